@@ -188,24 +188,26 @@ class CommandMaker:
         return command
 
     @staticmethod
-    def run_fermi_client(address, validator_key_path, rate, nodes):
+    def run_fermi_client(address, jsonrpc_address, validator_key_path, rate, nodes):
         assert isinstance(address, str)
+        assert isinstance(jsonrpc_address, str)
         assert isinstance(rate, int) and rate >= 0
         assert isinstance(nodes, list)
         assert all(isinstance(x, str) for x in nodes)
         nodes = f'--nodes {" ".join(nodes)}' if nodes else ""
-        command = f"./benchmark_fermi_client {address} --validator_key_fpath {validator_key_path} --rate {rate} {nodes}"
+        command = f"./benchmark_fermi_client {address} --jsonrpc {jsonrpc_address} --validator_key_fpath {validator_key_path} --rate {rate} {nodes}"
         print("Returning execution command = ", command)
         return command
 
     @staticmethod
-    def run_fermi_orderbook_client(address, validator_key_path, rate, nodes):
+    def run_fermi_orderbook_client(address, jsonrpc_address, validator_key_path, rate, nodes):
         assert isinstance(address, str)
+        assert isinstance(jsonrpc_address, str)
         assert isinstance(rate, int) and rate >= 0
         assert isinstance(nodes, list)
         assert all(isinstance(x, str) for x in nodes)
         nodes = f'{" ".join(nodes)}' if nodes else ""
-        command = f"./benchmark_orderbook_client {address} --validator_key_fpath {validator_key_path} --rate {rate}  --nodes {nodes}"
+        command = f"./benchmark_orderbook_client {address} --jsonrpc {jsonrpc_address} --validator_key_fpath {validator_key_path} --rate {rate}  --nodes {nodes}"
         print("Returning execution command = ", command)
         return command
 
@@ -169,6 +169,7 @@ class GDEXBench:
                 if bench_parameters.order_bench:
                     cmd = CommandMaker.run_fermi_orderbook_client(
                         multiaddr_to_url_data(validator_grpc_address),
+                        multiaddr_to_url_data(validator_jsonrpc_address),
                         os.path.abspath(bench_parameters.key_dir + PathMaker.key_file(0)),
                         rate_share,
                         [multiaddr_to_url_data(node['grpc_address']) for node in committee.json['authorities'].values() if node['grpc_address'] != validator_grpc_address]
@@ -176,6 +177,7 @@ class GDEXBench:
                 else:
                     cmd = CommandMaker.run_fermi_client(
                         multiaddr_to_url_data(validator_grpc_address),
+                        multiaddr_to_url_data(validator_jsonrpc_address),
                         os.path.abspath(bench_parameters.key_dir + PathMaker.key_file(i)),
                         rate_share,
                         [multiaddr_to_url_data(node['grpc_address']) for node in committee.json['authorities'].values() if node['grpc_address'] != validator_grpc_address]
@@ -345,6 +345,7 @@ class Bench:
             if self.bench_parameters.order_bench:
                 cmd = CommandMaker.run_fermi_orderbook_client(
                     multiaddr_to_url_data(validator_grpc_address),
+                    multiaddr_to_url_data(validator_dict['jsonrpc_address']),
                     self.remote_proto_dir + PathMaker.key_file(0),
                     rate_share,
                     [multiaddr_to_url_data(node['grpc_address']) for node in committee.json['authorities'].values() if node['grpc_address'] != validator_grpc_address]
@@ -352,6 +353,7 @@ class Bench:
             else:
                 cmd = CommandMaker.run_fermi_client(
                     multiaddr_to_url_data(validator_grpc_address),
+                    multiaddr_to_url_data(validator_dict['jsonrpc_address']),
                     self.remote_proto_dir + PathMaker.key_file(i),
                     rate_share,
                     [multiaddr_to_url_data(node['grpc_address']) for node in committee.json['authorities'].values() if node['grpc_address'] != validator_grpc_address]
@@ -3,10 +3,11 @@ use fermi_controller::{
     bank::proto::{create_create_asset_transaction, create_payment_transaction},
     spot::proto::{create_create_orderbook_transaction, create_limit_order_transaction},
 };
+use fermi_core::client::NonceTracker;
 use fermi_types::{
     account::AccountKeyPair,
     block::{BlockDigest, BlockInfo},
-    order_book::OrderSide,
+    order_book::{OrderSide, TimeInForce},
     proto::{Empty, LatestBlockInfoRequest, ValidatorGrpcClient},
     transaction::{serialize_protobuf, SignedTransaction},
 };
@@ -29,9 +30,11 @@ fn create_signed_payment_transaction(
     asset_id: u64,
     amount: u64,
     block_digest: BlockDigest,
+    nonce: u64,
 ) -> SignedTransaction {
-    let transaction =
+    let mut transaction =
         create_payment_transaction(kp_sender.public(), block_digest, kp_receiver.public(), asset_id, amount);
+    transaction.set_nonce(nonce);
     transaction.sign(kp_sender).unwrap()
 }
 
@@ -39,8 +42,10 @@ fn create_signed_asset_creation_transaction(
     kp_sender: &AccountKeyPair,
     block_digest: BlockDigest,
     dummy: u8,
+    nonce: u64,
 ) -> SignedTransaction {
-    let transaction = create_create_asset_transaction(kp_sender.public(), block_digest, dummy as u64);
+    let mut transaction = create_create_asset_transaction(kp_sender.public(), block_digest, dummy as u64);
+    transaction.set_nonce(nonce);
     transaction.sign(kp_sender).unwrap()
 }
 
@@ -49,9 +54,11 @@ fn create_signed_orderbook_transaction(
     base_asset_id: u64,
     quote_asset_id: u64,
     block_digest: BlockDigest,
+    nonce: u64,
 ) -> SignedTransaction {
-    let transaction =
+    let mut transaction =
         create_create_orderbook_transaction(kp_sender.public(), block_digest, base_asset_id, quote_asset_id);
+    transaction.set_nonce(nonce);
     transaction.sign(kp_sender).unwrap()
 }
 
@@ -63,8 +70,9 @@ fn create_signed_limit_order_transaction(
     price: u64,
     amount: u64,
     block_digest: BlockDigest,
+    nonce: u64,
 ) -> SignedTransaction {
-    let transaction = create_limit_order_transaction(
+    let mut transaction = create_limit_order_transaction(
         kp_sender.public(),
         block_digest,
         base_asset_id,
@@ -72,7 +80,11 @@ fn create_signed_limit_order_transaction(
         order_side as u64,
         price,
         amount,
+        0,
+        TimeInForce::GoodTilCancelled as u64,
+        false,
     );
+    transaction.set_nonce(nonce);
     transaction.sign(kp_sender).unwrap()
 }
 
@@ -80,6 +92,7 @@ pub struct BenchHelper {
     primary_keypair: AccountKeyPair,
     accounts: Vec<AccountKeyPair>,
     validator_client: Option<ValidatorGrpcClient<Channel>>,
+    nonce_tracker: NonceTracker,
     base_asset_id: u64,
     quote_asset_id: u64,
 }
@@ -94,6 +107,7 @@ impl BenchHelper {
             primary_keypair,
             accounts: Vec::new(),
             validator_client: None::<ValidatorGrpcClient<Channel>>,
+            nonce_tracker: NonceTracker::default(),
             // TODO - https://github.com/fermiorg/fermi/issues/157 - avoid hard coding by directly calculating created assets...
             base_asset_id: 1,
             quote_asset_id: 2,
@@ -141,6 +155,9 @@ impl BenchHelper {
         let keypair_copy = self.primary_keypair.copy();
         let base_asset_id = self.base_asset_id;
         let quote_asset_id = self.quote_asset_id;
+        let nonces: Vec<u64> = (0..burst)
+            .map(|_| self.nonce_tracker.next_nonce(self.primary_keypair.public()))
+            .collect();
 
         let stream = tokio_stream::iter(0..burst).map(move |x| {
             let mut amount = rand::thread_rng().gen_range(1_u64..100_u64);
@@ -165,6 +182,7 @@ impl BenchHelper {
                 price,
                 amount,
                 recent_block_hash,
+                nonces[x as usize],
             );
 
             if x == 0 {
@@ -197,7 +215,9 @@ impl BenchHelper {
     // TODO - https://github.com/fermiorg/fermi/issues/157 - Fetch created asset number to build this stack properly.
     pub async fn create_new_asset(&mut self, dummy: u8) {
         let recent_block_hash = self.get_recent_block_digest().await;
-        let transaction = create_signed_asset_creation_transaction(&self.primary_keypair, recent_block_hash, dummy);
+        let nonce = self.nonce_tracker.next_nonce(self.primary_keypair.public());
+        let transaction =
+            create_signed_asset_creation_transaction(&self.primary_keypair, recent_block_hash, dummy, nonce);
         self.submit_transaction(transaction)
             .await
             .expect("Failed to successfully submit asset creation transaction");
@@ -207,11 +227,13 @@ impl BenchHelper {
     // TODO - https://github.com/fermiorg/fermi/issues/157 - Fetch created asset number to build this stack properly.
     pub async fn create_orderbook(&mut self) {
         let recent_block_hash = self.get_recent_block_digest().await;
+        let nonce = self.nonce_tracker.next_nonce(self.primary_keypair.public());
         let transaction = create_signed_orderbook_transaction(
             &self.primary_keypair,
             self.base_asset_id,
             self.quote_asset_id,
             recent_block_hash,
+            nonce,
         );
         self.submit_transaction(transaction)
             .await
@@ -232,6 +254,7 @@ impl BenchHelper {
                 0,
                 1_000_000,
                 recent_block_hash,
+                self.nonce_tracker.next_nonce(self.primary_keypair.public()),
             );
             transactions.push(transaction);
             let transaction = create_signed_payment_transaction(
@@ -240,6 +263,7 @@ impl BenchHelper {
                 self.base_asset_id,
                 Self::AMOUNT_TO_FUND,
                 recent_block_hash,
+                self.nonce_tracker.next_nonce(self.primary_keypair.public()),
             );
             transactions.push(transaction);
             let transaction = create_signed_payment_transaction(
@@ -248,6 +272,7 @@ impl BenchHelper {
                 self.quote_asset_id,
                 Self::AMOUNT_TO_FUND,
                 recent_block_hash,
+                self.nonce_tracker.next_nonce(self.primary_keypair.public()),
             );
             transactions.push(transaction);
         }
@@ -260,12 +285,22 @@ impl BenchHelper {
     }
 
     /// Initialize the bench helper
-    pub async fn initialize(&mut self, validator_url: Url, seed: [u8; 32], accounts_to_generate: u64) {
+    pub async fn initialize(
+        &mut self,
+        validator_url: Url,
+        jsonrpc_url: Url,
+        seed: [u8; 32],
+        accounts_to_generate: u64,
+    ) {
         self.validator_client = Some(
             ValidatorGrpcClient::connect(validator_url.as_str().to_owned())
                 .await
                 .unwrap(),
         );
+        self.nonce_tracker
+            .sync(jsonrpc_url.as_str(), self.primary_keypair.public())
+            .await
+            .expect("Failed to fetch the next nonce of the primary account");
         self.generate_accounts(seed, accounts_to_generate);
 
         // log the transaction size to help python client calculate throughput
@@ -273,7 +308,9 @@ impl BenchHelper {
         // note, no they don't
         let recent_block_hash = self.get_recent_block_digest().await;
 
-        let signed_transaction = create_signed_asset_creation_transaction(&self.primary_keypair, recent_block_hash, 0);
+        // the transaction is only measured, so it does not consume a nonce
+        let signed_transaction =
+            create_signed_asset_creation_transaction(&self.primary_keypair, recent_block_hash, 0, 0);
         let serialized_signed_transaction = match bincode::serialize(&signed_transaction) {
             Ok(t) => t,
             _ => panic!("Error serializing transaction"),
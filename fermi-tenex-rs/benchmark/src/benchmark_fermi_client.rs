@@ -1,5 +1,6 @@
 // fermi
 use fermi_controller::bank::proto::create_payment_transaction;
+use fermi_core::client::NonceTracker;
 use fermi_types::{
     account::{AccountKeyPair, ValidatorKeyPair},
     block::{BlockDigest, BlockInfo},
@@ -36,15 +37,17 @@ fn create_signed_transaction(
     kp_receiver: &AccountKeyPair,
     amount: u64,
     block_digest: BlockDigest,
+    nonce: u64,
 ) -> SignedTransaction {
     // use a dummy batch digest for initial benchmarking
-    let transaction = create_payment_transaction(
+    let mut transaction = create_payment_transaction(
         kp_sender.public(),
         block_digest,
         kp_receiver.public(),
         PRIMARY_ASSET_ID,
         amount,
     );
+    transaction.set_nonce(nonce);
     transaction.sign(kp_sender).unwrap()
 }
 
@@ -54,6 +57,7 @@ async fn main() -> Result<()> {
         .version(crate_version!())
         .about("Benchmark client for Narwhal and Tusk.")
         .args_from_usage("<ADDR> 'The network address of the node where to send txs'")
+        .args_from_usage("--jsonrpc=<ADDR> 'The JSON-RPC address of the node, used to fetch account nonces'")
         .args_from_usage("--rate=<INT> 'The rate (txs/s) at which to send the transactions'")
         .args_from_usage("--validator_key_fpath=<FILE> 'The validator key file'")
         .args_from_usage("--nodes=[ADDR]... 'Network addresses that must be reachable before starting the benchmark.'")
@@ -80,6 +84,10 @@ async fn main() -> Result<()> {
     let target = target_str
         .parse::<Url>()
         .with_context(|| format!("Invalid url format {target_str}"))?;
+    let jsonrpc_str = matches.value_of("jsonrpc").unwrap();
+    let jsonrpc = jsonrpc_str
+        .parse::<Url>()
+        .with_context(|| format!("Invalid url format {jsonrpc_str}"))?;
     let rate = matches
         .value_of("rate")
         .unwrap()
@@ -103,6 +111,7 @@ async fn main() -> Result<()> {
 
     let client = Client {
         target,
+        jsonrpc,
         rate,
         nodes,
         validator_key_fpath,
@@ -118,6 +127,7 @@ async fn main() -> Result<()> {
 /// TODO - https://github.com/fermiorg/fermi/issues/157 - cleanup client to use bench helper
 struct Client {
     target: Url,
+    jsonrpc: Url,
     rate: u64,
     nodes: Vec<Url>,
     validator_key_fpath: PathBuf,
@@ -143,6 +153,8 @@ impl Client {
         // send payments from validator with assets
         // read in private key of validator who will send payment txns
         let keypair: ValidatorKeyPair = read_keypair_from_file(self.validator_key_fpath.clone())?;
+        let mut nonce_tracker = NonceTracker::default();
+        nonce_tracker.sync(self.jsonrpc.as_str(), keypair.public()).await?;
 
         // NOTE: This log entry is used to compute performance.
         info!("Start sending transactions");
@@ -166,7 +178,8 @@ impl Client {
             let kp_receiver = keys([1; 32]).pop().unwrap();
 
             if counter == 0 {
-                let signed_transaction = create_signed_transaction(&keypair, &kp_receiver, 1, block_digest.clone());
+                // the transaction is only measured, so it does not consume a nonce
+                let signed_transaction = create_signed_transaction(&keypair, &kp_receiver, 1, block_digest.clone(), 0);
                 let serialized_signed_transaction = match bincode::serialize(&signed_transaction) {
                     Ok(t) => t,
                     _ => panic!("Error serializing transaction"),
@@ -175,6 +188,7 @@ impl Client {
                 info!("Transactions size: {transaction_size} B");
             }
 
+            let nonces: Vec<u64> = (0..burst).map(|_| nonce_tracker.next_nonce(keypair.public())).collect();
             let stream = tokio_stream::iter(0..burst).map(move |x| {
                 let amount = rand::thread_rng().gen_range(100_000 as u64..5_000_000 as u64);
                 if x == counter % burst {
@@ -182,7 +196,7 @@ impl Client {
                     info!("Sending sample transaction {counter}");
                 }
                 let signed_tranasction =
-                    create_signed_transaction(&keypair, &kp_receiver, amount, block_digest.clone());
+                    create_signed_transaction(&keypair, &kp_receiver, amount, block_digest.clone(), nonces[x as usize]);
                 signed_tranasction
             });
 
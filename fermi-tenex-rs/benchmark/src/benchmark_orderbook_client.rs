@@ -22,6 +22,7 @@ async fn main() -> Result<()> {
         .version(crate_version!())
         .about("Benchmark client for GDEX Orderbook.")
         .args_from_usage("<ADDR> 'The network address of the node where to send txs'")
+        .args_from_usage("--jsonrpc=<ADDR> 'The JSON-RPC address of the node, used to fetch account nonces'")
         .args_from_usage("--rate=<INT> 'The rate (txs/s) at which to send the transactions'")
         .args_from_usage("--validator_key_fpath=<FILE> 'The validator key file'")
         .args_from_usage("--nodes=[ADDR]... 'Network addresses that must be reachable before starting the benchmark.'")
@@ -48,6 +49,10 @@ async fn main() -> Result<()> {
     let validator_url = target_str
         .parse::<Url>()
         .with_context(|| format!("Invalid url format {target_str}"))?;
+    let jsonrpc_str = matches.value_of("jsonrpc").unwrap();
+    let jsonrpc_url = jsonrpc_str
+        .parse::<Url>()
+        .with_context(|| format!("Invalid url format {jsonrpc_str}"))?;
     let rate = matches
         .value_of("rate")
         .unwrap()
@@ -81,7 +86,7 @@ async fn main() -> Result<()> {
     client.wait().await;
 
     // initialize the orderbook if running validator 0
-    client.initialize(validator_url).await.unwrap();
+    client.initialize(validator_url, jsonrpc_url).await.unwrap();
 
     info!("Starting to send transactions...");
 
@@ -99,9 +104,9 @@ struct Client {
 }
 
 impl Client {
-    pub async fn initialize(&mut self, validator_url: Url) -> Result<()> {
+    pub async fn initialize(&mut self, validator_url: Url, jsonrpc_url: Url) -> Result<()> {
         self.bench_helper
-            .initialize(validator_url, [0u8; 32], ACCOUNTS_TO_GENERATE)
+            .initialize(validator_url, jsonrpc_url, [0u8; 32], ACCOUNTS_TO_GENERATE)
             .await;
 
         self.bench_helper.prepare_orderbook().await;
@@ -76,6 +76,12 @@ pub enum GDEXCommand {
     AddControllersGenesis {
         #[clap(value_parser, long, help = "Path to save genesis blob file")]
         path: Option<PathBuf>,
+        #[clap(
+            value_parser,
+            long,
+            help = "Hex encoded account allowed to change the batch parameters, defaults to the first validator"
+        )]
+        governance_account: Option<String>,
     },
     /// Build genesis blob
     #[clap(name = "build-genesis")]
@@ -202,10 +208,13 @@ impl GDEXCommand {
                 ceremony.run().unwrap();
                 Ok(())
             }
-            GDEXCommand::AddControllersGenesis { path } => {
+            GDEXCommand::AddControllersGenesis {
+                path,
+                governance_account,
+            } => {
                 let ceremony = Ceremony {
                     path,
-                    command: CeremonyCommand::AddControllers,
+                    command: CeremonyCommand::AddControllers { governance_account },
                 };
                 ceremony.run().unwrap();
                 Ok(())
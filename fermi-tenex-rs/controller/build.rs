@@ -2,8 +2,10 @@ type Result<T> = ::std::result::Result<T, Box<dyn std::error::Error + Send + Syn
 
 fn main() -> Result<()> {
     build_proto("bank")?;
+    build_proto("consensus")?;
     build_proto("spot")?;
     build_proto("futures")?;
+    build_proto("stake")?;
 
     Ok(())
 }
@@ -5,7 +5,7 @@ use crate::event_manager::{EventEmitter, EventManager};
 use crate::router::ControllerRouter;
 use fermi_types::{
     account::AccountPubKey,
-    asset::{Asset, AssetId},
+    asset::{Asset, AssetId, DEFAULT_ASSET_DECIMALS, MAX_ASSET_DECIMALS, PRIMARY_ASSET_ID},
     crypto::ToFromBytes,
     error::GDEXError,
     store::RPCStoreHandle,
@@ -16,7 +16,7 @@ use sui_json_rpc::SuiRpcModule;
 // external
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 
 // ENUMS
@@ -31,6 +31,10 @@ pub enum Modifier {
 
 // TODO - https://github.com/fermiorg/fermi/issues/169 - implement coherent system for controller account pubkeys
 pub const BANK_CONTROLLER_ACCOUNT_PUBKEY: &[u8] = b"STAKECONTROLLERAAAAAAAAAAAAAAAAA";
+// account which accrues trading fees and funds maker rebates
+pub const FEE_ACCOUNT_PUBKEY: &[u8] = b"FEEACCOUNTAAAAAAAAAAAAAAAAAAAAAA";
+// account which accrues transaction fees, paid out to stakers and delegators at each epoch boundary
+pub const REWARD_POOL_ACCOUNT_PUBKEY: &[u8] = b"REWARDPOOLACCOUNTAAAAAAAAAAAAAAA";
 
 // 10 billion w/ 6 decimals, e.g. ALGO creation specs.
 pub const CREATED_ASSET_BALANCE: u64 = 10_000_000_000_000_000;
@@ -42,21 +46,45 @@ pub const CREATED_ASSET_BALANCE: u64 = 10_000_000_000_000_000;
 pub struct BankController {
     // controller state
     controller_account: AccountPubKey,
-    asset_id_to_asset: HashMap<AssetId, Asset>,
-    bank_accounts: HashMap<AccountPubKey, BankAccount>,
+    fee_account: AccountPubKey,
+    reward_pool_account: AccountPubKey,
+    asset_id_to_asset: BTreeMap<AssetId, Asset>,
+    bank_accounts: BTreeMap<AccountPubKey, BankAccount>,
+    // next nonce of each account which has sent a transaction
+    account_nonces: BTreeMap<AccountPubKey, u64>,
     n_assets: u64,
+    // prior state of everything modified since begin_journal, restored if the request fails
+    #[serde(skip)]
+    journal: Option<BankJournal>,
+    // shared accounts as they were when this bank was split off to execute a lane, see split_lane
+    #[serde(skip)]
+    lane_shared_accounts: HashMap<AccountPubKey, Option<BankAccount>>,
     // shared
+    #[serde(skip)]
     event_manager: Arc<Mutex<EventManager>>,
 }
 
+/// Records the value each account and asset held before it was first modified by the journaled request
+#[derive(Clone, Debug, Default)]
+struct BankJournal {
+    bank_accounts: HashMap<AccountPubKey, Option<BankAccount>>,
+    assets: HashMap<AssetId, Option<Asset>>,
+    n_assets: u64,
+}
+
 impl Default for BankController {
     fn default() -> Self {
         Self {
             // controller state
             controller_account: AccountPubKey::from_bytes(BANK_CONTROLLER_ACCOUNT_PUBKEY).unwrap(),
-            asset_id_to_asset: HashMap::new(),
-            bank_accounts: HashMap::new(),
+            fee_account: AccountPubKey::from_bytes(FEE_ACCOUNT_PUBKEY).unwrap(),
+            reward_pool_account: AccountPubKey::from_bytes(REWARD_POOL_ACCOUNT_PUBKEY).unwrap(),
+            asset_id_to_asset: BTreeMap::new(),
+            bank_accounts: BTreeMap::new(),
+            account_nonces: BTreeMap::new(),
             n_assets: 0,
+            journal: None,
+            lane_shared_accounts: HashMap::new(),
             // shared state
             event_manager: Arc::new(Mutex::new(EventManager::new())), // TEMPORARY
         }
@@ -73,20 +101,58 @@ impl Controller<crate::bank::rpc_server::JSONRPCService> for BankController {
         Ok(())
     }
 
+    // every bank modification is covered by the journal
+    type Checkpoint = ();
+
+    fn checkpoint(&self, _transaction: &Transaction) -> Self::Checkpoint {}
+
+    fn rollback(&mut self, _checkpoint: Self::Checkpoint) {}
+
     fn handle_consensus_transaction(&mut self, transaction: &Transaction) -> Result<(), GDEXError> {
         let request_type: BankRequestType = transaction.get_request_type()?;
         match request_type {
             BankRequestType::CreateAsset => {
-                let _request: CreateAssetRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let request: CreateAssetRequest = deserialize_protobuf(&transaction.request_bytes)?;
                 let sender = transaction.get_sender()?;
-                self.create_asset(&sender)
+                self.create_asset_with_metadata(
+                    &sender,
+                    &request.name,
+                    &request.ticker,
+                    request.decimals,
+                    request.initial_supply,
+                    request.get_max_supply(),
+                )
             }
             BankRequestType::Payment => {
                 let request: PaymentRequest = deserialize_protobuf(&transaction.request_bytes)?;
                 let sender = transaction.get_sender()?;
                 let receiver = request.get_receiver()?;
+                if self.is_asset_frozen(request.asset_id) {
+                    return Err(GDEXError::AssetFrozen);
+                }
                 self.transfer(&sender, &receiver, request.asset_id, request.quantity)
             }
+            BankRequestType::Mint => {
+                let request: MintRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let sender = transaction.get_sender()?;
+                self.mint(&sender, request.asset_id, request.quantity)
+            }
+            BankRequestType::Burn => {
+                let request: BurnRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let sender = transaction.get_sender()?;
+                self.burn(&sender, request.asset_id, request.quantity)
+            }
+            BankRequestType::FreezeAsset => {
+                let request: FreezeAssetRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let sender = transaction.get_sender()?;
+                self.set_asset_frozen(&sender, request.asset_id, request.frozen)
+            }
+            BankRequestType::TransferAssetOwnership => {
+                let request: TransferAssetOwnershipRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let sender = transaction.get_sender()?;
+                let new_owner = request.get_new_owner()?;
+                self.transfer_asset_ownership(&sender, request.asset_id, &new_owner)
+            }
         }
     }
 
@@ -119,6 +185,7 @@ impl BankController {
         if self.check_account_exists(account_pub_key) {
             Err(GDEXError::AccountCreation)
         } else {
+            self.journal_account(account_pub_key);
             self.bank_accounts
                 .insert(account_pub_key.clone(), BankAccount::new(account_pub_key.clone()));
             Ok(())
@@ -141,6 +208,7 @@ impl BankController {
         quantity: u64,
         increment: Modifier,
     ) -> Result<(), GDEXError> {
+        self.journal_account(account_pub_key);
         let bank_account = self
             .bank_accounts
             .get_mut(account_pub_key)
@@ -191,7 +259,31 @@ impl BankController {
         Ok(())
     }
 
+    // creates an unnamed, uncapped asset and mints CREATED_ASSET_BALANCE to the owner
     pub fn create_asset(&mut self, owner_pub_key: &AccountPubKey) -> Result<(), GDEXError> {
+        self.create_asset_with_metadata(
+            owner_pub_key,
+            "",
+            "",
+            DEFAULT_ASSET_DECIMALS,
+            CREATED_ASSET_BALANCE,
+            None,
+        )
+    }
+
+    pub fn create_asset_with_metadata(
+        &mut self,
+        owner_pub_key: &AccountPubKey,
+        name: &str,
+        ticker: &str,
+        decimals: u32,
+        initial_supply: u64,
+        max_supply: Option<u64>,
+    ) -> Result<(), GDEXError> {
+        if decimals > MAX_ASSET_DECIMALS || max_supply.map_or(false, |max_supply| initial_supply > max_supply) {
+            return Err(GDEXError::InvalidAssetMetadata);
+        }
+
         // special handling for genesis
         // an account must be created in this instance
         // since account creation is gated by receipt and balance of primary blockchain asset
@@ -205,15 +297,22 @@ impl BankController {
         }
 
         // add asset id -> asset mapping to hashmap
+        self.journal_asset(self.n_assets);
         self.asset_id_to_asset.insert(
             self.n_assets,
             Asset {
                 asset_id: self.n_assets,
                 owner_pubkey: owner_pub_key.clone(),
+                name: name.to_string(),
+                ticker: ticker.to_string(),
+                decimals,
+                total_supply: initial_supply,
+                max_supply,
+                is_frozen: false,
             },
         );
 
-        self.update_balance(owner_pub_key, self.n_assets, CREATED_ASSET_BALANCE, Modifier::Increment)?;
+        self.update_balance(owner_pub_key, self.n_assets, initial_supply, Modifier::Increment)?;
 
         // emit event
         self.emit_event(&AssetCreatedEvent::new(self.n_assets));
@@ -224,13 +323,282 @@ impl BankController {
         Ok(())
     }
 
-    pub fn get_asset(&mut self, asset_id: AssetId) -> Result<&Asset, GDEXError> {
+    // returns the asset if the sender owns it
+    fn get_owned_asset_mut(&mut self, sender: &AccountPubKey, asset_id: AssetId) -> Result<&mut Asset, GDEXError> {
+        self.journal_asset(asset_id);
+        let asset = self
+            .asset_id_to_asset
+            .get_mut(&asset_id)
+            .ok_or(GDEXError::AssetLookup)?;
+        if &asset.owner_pubkey != sender {
+            return Err(GDEXError::AssetOwner);
+        }
+        Ok(asset)
+    }
+
+    pub fn is_asset_frozen(&self, asset_id: AssetId) -> bool {
+        self.asset_id_to_asset
+            .get(&asset_id)
+            .map_or(false, |asset| asset.is_frozen)
+    }
+
+    /// Mints new supply of an asset into the owner's account
+    pub fn mint(&mut self, sender: &AccountPubKey, asset_id: AssetId, quantity: u64) -> Result<(), GDEXError> {
+        let asset = self.get_owned_asset_mut(sender, asset_id)?;
+        if asset.is_frozen {
+            return Err(GDEXError::AssetFrozen);
+        }
+        let total_supply = asset
+            .total_supply
+            .checked_add(quantity)
+            .ok_or(GDEXError::AssetMaxSupply)?;
+        if asset.max_supply.map_or(false, |max_supply| total_supply > max_supply) {
+            return Err(GDEXError::AssetMaxSupply);
+        }
+
+        self.update_balance(sender, asset_id, quantity, Modifier::Increment)?;
+        self.get_owned_asset_mut(sender, asset_id)?.total_supply = total_supply;
+
+        self.emit_event(&AssetMintedEvent::new(asset_id, quantity));
+        Ok(())
+    }
+
+    /// Burns supply of an asset out of the owner's account
+    pub fn burn(&mut self, sender: &AccountPubKey, asset_id: AssetId, quantity: u64) -> Result<(), GDEXError> {
+        let asset = self.get_owned_asset_mut(sender, asset_id)?;
+        if asset.is_frozen {
+            return Err(GDEXError::AssetFrozen);
+        }
+
+        self.update_balance(sender, asset_id, quantity, Modifier::Decrement)?;
+        self.get_owned_asset_mut(sender, asset_id)?.total_supply -= quantity;
+
+        self.emit_event(&AssetBurnedEvent::new(asset_id, quantity));
+        Ok(())
+    }
+
+    pub fn set_asset_frozen(
+        &mut self,
+        sender: &AccountPubKey,
+        asset_id: AssetId,
+        frozen: bool,
+    ) -> Result<(), GDEXError> {
+        self.get_owned_asset_mut(sender, asset_id)?.is_frozen = frozen;
+
+        self.emit_event(&AssetFrozenEvent::new(asset_id, frozen));
+        Ok(())
+    }
+
+    pub fn transfer_asset_ownership(
+        &mut self,
+        sender: &AccountPubKey,
+        asset_id: AssetId,
+        new_owner: &AccountPubKey,
+    ) -> Result<(), GDEXError> {
+        if !self.check_account_exists(new_owner) {
+            return Err(GDEXError::AccountLookup);
+        }
+        self.get_owned_asset_mut(sender, asset_id)?.owner_pubkey = new_owner.clone();
+
+        self.emit_event(&AssetOwnershipTransferredEvent::new(asset_id, sender, new_owner));
+        Ok(())
+    }
+
+    pub fn get_fee_account(&self) -> &AccountPubKey {
+        &self.fee_account
+    }
+
+    /// Moves a trading fee from the payer into the fee account
+    pub fn collect_fee(&mut self, payer: &AccountPubKey, asset_id: AssetId, quantity: u64) -> Result<(), GDEXError> {
+        // the fee account is opened by the first fee it receives
+        let fee_account = self.fee_account.clone();
+        if !self.check_account_exists(&fee_account) {
+            self.create_account(&fee_account)?;
+        }
+        self.transfer(payer, &fee_account, asset_id, quantity)
+    }
+
+    /// Pays a maker rebate out of previously collected fees
+    ///
+    /// The taker fee of a fill is always collected before its maker rebate is paid, and a valid fee schedule never
+    /// rebates more than the taker fee, so the fee account covers a rebate even if it held nothing before the fill.
+    /// Lanes rely on this to pay rebates from their own copy of the fee account, see merge_lane
+    pub fn pay_rebate(&mut self, receiver: &AccountPubKey, asset_id: AssetId, quantity: u64) -> Result<(), GDEXError> {
+        let fee_account = self.fee_account.clone();
+        self.transfer(&fee_account, receiver, asset_id, quantity)
+    }
+
+    /// Returns the fees accrued in a given asset, net of rebates paid
+    pub fn get_accrued_fees(&self, asset_id: AssetId) -> u64 {
+        self.get_balance(&self.fee_account, asset_id).unwrap_or(0)
+    }
+
+    pub fn get_reward_pool_account(&self) -> &AccountPubKey {
+        &self.reward_pool_account
+    }
+
+    /// Debits the transaction fee in the primary asset and credits it to the validator reward pool
+    pub fn charge_transaction_fee(&mut self, sender: &AccountPubKey, fee: u64) -> Result<(), GDEXError> {
+        // nothing can be charged before genesis creates the primary asset
+        if !self.asset_id_to_asset.contains_key(&PRIMARY_ASSET_ID) {
+            return Ok(());
+        }
+        let balance = self
+            .get_balance(sender, PRIMARY_ASSET_ID)
+            .map_err(|_| GDEXError::TransactionFeeUnderfunded)?;
+        if balance < fee {
+            return Err(GDEXError::TransactionFeeUnderfunded);
+        }
+
+        let reward_pool_account = self.reward_pool_account.clone();
+        if !self.check_account_exists(&reward_pool_account) {
+            self.create_account(&reward_pool_account)?;
+        }
+        // fees are moved without a payment event so they do not show up among the request's own events
+        self.update_balance(sender, PRIMARY_ASSET_ID, fee, Modifier::Decrement)?;
+        self.update_balance(&reward_pool_account, PRIMARY_ASSET_ID, fee, Modifier::Increment)
+    }
+
+    /// Returns the nonce the next transaction of the account must carry
+    pub fn get_next_nonce(&self, account_pub_key: &AccountPubKey) -> u64 {
+        self.account_nonces.get(account_pub_key).copied().unwrap_or(0)
+    }
+
+    pub fn validate_nonce(&self, account_pub_key: &AccountPubKey, nonce: u64) -> Result<(), GDEXError> {
+        if nonce != self.get_next_nonce(account_pub_key) {
+            return Err(GDEXError::TransactionNonce);
+        }
+        Ok(())
+    }
+
+    pub fn increment_nonce(&mut self, account_pub_key: &AccountPubKey) {
+        *self.account_nonces.entry(account_pub_key.clone()).or_insert(0) += 1;
+    }
+
+    /// Returns the transaction fees held by the reward pool
+    pub fn get_reward_pool_balance(&self) -> u64 {
+        self.get_balance(&self.reward_pool_account, PRIMARY_ASSET_ID)
+            .unwrap_or(0)
+    }
+
+    pub fn get_asset(&self, asset_id: AssetId) -> Result<&Asset, GDEXError> {
         self.asset_id_to_asset.get(&asset_id).ok_or(GDEXError::AssetLookup)
     }
 
     pub fn get_num_assets(&mut self) -> u64 {
         self.n_assets
     }
+
+    // JOURNAL FUNCTIONS
+
+    /// Starts recording changes so that they can be reverted if the request being executed fails
+    pub fn begin_journal(&mut self) {
+        self.journal = Some(BankJournal {
+            n_assets: self.n_assets,
+            ..BankJournal::default()
+        });
+    }
+
+    /// Keeps every change made since begin_journal
+    pub fn commit_journal(&mut self) {
+        self.journal = None;
+    }
+
+    /// Restores every account and asset modified since begin_journal
+    pub fn revert_journal(&mut self) {
+        if let Some(journal) = self.journal.take() {
+            for (account_pub_key, bank_account) in journal.bank_accounts {
+                match bank_account {
+                    Some(bank_account) => self.bank_accounts.insert(account_pub_key, bank_account),
+                    None => self.bank_accounts.remove(&account_pub_key),
+                };
+            }
+            for (asset_id, asset) in journal.assets {
+                match asset {
+                    Some(asset) => self.asset_id_to_asset.insert(asset_id, asset),
+                    None => self.asset_id_to_asset.remove(&asset_id),
+                };
+            }
+            self.n_assets = journal.n_assets;
+        }
+    }
+
+    // only the first modification of an account is recorded, as it holds the value to revert to
+    fn journal_account(&mut self, account_pub_key: &AccountPubKey) {
+        if let Some(journal) = &mut self.journal {
+            journal
+                .bank_accounts
+                .entry(account_pub_key.clone())
+                .or_insert_with(|| self.bank_accounts.get(account_pub_key).cloned());
+        }
+    }
+
+    fn journal_asset(&mut self, asset_id: AssetId) {
+        if let Some(journal) = &mut self.journal {
+            journal
+                .assets
+                .entry(asset_id)
+                .or_insert_with(|| self.asset_id_to_asset.get(&asset_id).cloned());
+        }
+    }
+
+    // LANE FUNCTIONS
+
+    /// Moves the given accounts into a bank of their own, which executes a lane of transactions touching no other
+    /// account alongside other lanes. Shared accounts, e.g. the fee account, are copied into every lane instead
+    pub fn split_lane(&mut self, accounts: &[AccountPubKey], shared_accounts: &[AccountPubKey]) -> BankController {
+        let mut lane = BankController {
+            asset_id_to_asset: self.asset_id_to_asset.clone(),
+            n_assets: self.n_assets,
+            ..BankController::default()
+        };
+        for account_pub_key in accounts {
+            if let Some(bank_account) = self.bank_accounts.remove(account_pub_key) {
+                lane.bank_accounts.insert(account_pub_key.clone(), bank_account);
+            }
+            if let Some(nonce) = self.account_nonces.remove(account_pub_key) {
+                lane.account_nonces.insert(account_pub_key.clone(), nonce);
+            }
+        }
+        for account_pub_key in shared_accounts {
+            let bank_account = self.bank_accounts.get(account_pub_key).cloned();
+            if let Some(bank_account) = &bank_account {
+                lane.bank_accounts.insert(account_pub_key.clone(), bank_account.clone());
+            }
+            lane.lane_shared_accounts.insert(account_pub_key.clone(), bank_account);
+        }
+        lane
+    }
+
+    /// Moves the accounts of a lane back and adds the lane's net change of each shared account
+    ///
+    /// A lane only debits a shared account by what it credited earlier in the lane or what the account held for the
+    /// lane's own accounts at the split, e.g. rebates are funded by the taker fee of the same fill, so a debit fails
+    /// in a lane exactly when it fails in serial execution
+    pub fn merge_lane(&mut self, mut lane: BankController) {
+        for (account_pub_key, split_account) in std::mem::take(&mut lane.lane_shared_accounts) {
+            let lane_account = match lane.bank_accounts.remove(&account_pub_key) {
+                Some(lane_account) => lane_account,
+                None => continue,
+            };
+            let bank_account = self
+                .bank_accounts
+                .entry(account_pub_key.clone())
+                .or_insert_with(|| BankAccount::new(account_pub_key));
+            for (asset_id, lane_balance) in lane_account.get_balances() {
+                let split_balance = split_account
+                    .as_ref()
+                    .map_or(0, |split_account| split_account.get_balance(*asset_id));
+                // the merged balance is the one serial execution ends with, so it does not underflow
+                bank_account.set_balance(
+                    *asset_id,
+                    bank_account.get_balance(*asset_id) + lane_balance - split_balance,
+                );
+            }
+        }
+        self.bank_accounts.extend(lane.bank_accounts);
+        self.account_nonces.extend(lane.account_nonces);
+    }
 }
 
 // TESTS
@@ -243,6 +611,7 @@ pub mod spot_tests {
     // mysten
     use fastcrypto::{generate_production_keypair, traits::KeyPair as _};
     use narwhal_crypto::KeyPair;
+    use narwhal_types::CertificateDigest;
 
     #[test]
     fn create_and_check_accounts() {
@@ -355,6 +724,210 @@ pub mod spot_tests {
         assert!(bank_controller.get_num_assets() == 2, "Number of assets must be 2.");
     }
 
+    #[test]
+    fn collect_fee_and_pay_rebate() {
+        let mut bank_controller = BankController::default();
+        let user_kp = generate_production_keypair::<KeyPair>();
+        const TEST_ASSET_ID: u64 = 0;
+        bank_controller.create_asset(user_kp.public()).unwrap();
+
+        // nothing accrues before the first fee is collected
+        assert_eq!(bank_controller.get_accrued_fees(TEST_ASSET_ID), 0);
+        bank_controller
+            .collect_fee(user_kp.public(), TEST_ASSET_ID, 100)
+            .unwrap();
+        bank_controller.pay_rebate(user_kp.public(), TEST_ASSET_ID, 40).unwrap();
+        assert_eq!(bank_controller.get_accrued_fees(TEST_ASSET_ID), 60);
+        assert_eq!(
+            bank_controller.get_balance(user_kp.public(), TEST_ASSET_ID).unwrap(),
+            CREATED_ASSET_BALANCE - 60
+        );
+
+        // rebates cannot exceed the fees collected
+        assert!(bank_controller.pay_rebate(user_kp.public(), TEST_ASSET_ID, 61).is_err());
+    }
+
+    #[test]
+    fn asset_metadata_mint_burn_freeze_and_transfer_ownership() {
+        let mut bank_controller = BankController::default();
+        let owner_kp = generate_production_keypair::<KeyPair>();
+        let user_kp = generate_production_keypair::<KeyPair>();
+        const TEST_ASSET_ID: u64 = 0;
+        const MAX_SUPPLY: u64 = 1_000;
+
+        // decimals and initial supply are validated against the metadata limits
+        assert!(bank_controller
+            .create_asset_with_metadata(owner_kp.public(), "Fermi", "FRM", MAX_ASSET_DECIMALS + 1, 0, None)
+            .is_err());
+        assert!(bank_controller
+            .create_asset_with_metadata(owner_kp.public(), "Fermi", "FRM", 6, MAX_SUPPLY + 1, Some(MAX_SUPPLY))
+            .is_err());
+
+        bank_controller
+            .create_asset_with_metadata(owner_kp.public(), "Fermi", "FRM", 6, 600, Some(MAX_SUPPLY))
+            .unwrap();
+        bank_controller.create_account(user_kp.public()).unwrap();
+        let asset = bank_controller.get_asset(TEST_ASSET_ID).unwrap();
+        assert_eq!(asset.name, "Fermi");
+        assert_eq!(asset.ticker, "FRM");
+        assert_eq!(asset.decimals, 6);
+        assert_eq!(asset.total_supply, 600);
+        assert_eq!(asset.max_supply, Some(MAX_SUPPLY));
+        assert_eq!(
+            bank_controller.get_balance(owner_kp.public(), TEST_ASSET_ID).unwrap(),
+            600
+        );
+
+        // only the owner may mint, and never beyond the max supply
+        assert!(matches!(
+            bank_controller.mint(user_kp.public(), TEST_ASSET_ID, 100),
+            Err(GDEXError::AssetOwner)
+        ));
+        assert!(matches!(
+            bank_controller.mint(owner_kp.public(), TEST_ASSET_ID, 401),
+            Err(GDEXError::AssetMaxSupply)
+        ));
+        bank_controller.mint(owner_kp.public(), TEST_ASSET_ID, 400).unwrap();
+        assert_eq!(
+            bank_controller.get_asset(TEST_ASSET_ID).unwrap().total_supply,
+            MAX_SUPPLY
+        );
+
+        // burning reduces the supply and frees room to mint again
+        bank_controller.burn(owner_kp.public(), TEST_ASSET_ID, 300).unwrap();
+        assert_eq!(bank_controller.get_asset(TEST_ASSET_ID).unwrap().total_supply, 700);
+        assert_eq!(
+            bank_controller.get_balance(owner_kp.public(), TEST_ASSET_ID).unwrap(),
+            700
+        );
+        assert!(bank_controller.burn(owner_kp.public(), TEST_ASSET_ID, 701).is_err());
+
+        // a frozen asset cannot be minted, burned or paid
+        bank_controller
+            .set_asset_frozen(owner_kp.public(), TEST_ASSET_ID, true)
+            .unwrap();
+        assert!(bank_controller.is_asset_frozen(TEST_ASSET_ID));
+        assert!(matches!(
+            bank_controller.mint(owner_kp.public(), TEST_ASSET_ID, 1),
+            Err(GDEXError::AssetFrozen)
+        ));
+        let payment = create_payment_transaction(
+            owner_kp.public(),
+            CertificateDigest::new([0; 32]),
+            user_kp.public(),
+            TEST_ASSET_ID,
+            1,
+        );
+        assert!(matches!(
+            bank_controller.handle_consensus_transaction(&payment),
+            Err(GDEXError::AssetFrozen)
+        ));
+        bank_controller
+            .set_asset_frozen(owner_kp.public(), TEST_ASSET_ID, false)
+            .unwrap();
+        bank_controller.handle_consensus_transaction(&payment).unwrap();
+        assert_eq!(bank_controller.get_balance(user_kp.public(), TEST_ASSET_ID).unwrap(), 1);
+
+        // ownership moves to the new owner
+        bank_controller
+            .transfer_asset_ownership(owner_kp.public(), TEST_ASSET_ID, user_kp.public())
+            .unwrap();
+        assert_eq!(
+            &bank_controller.get_asset(TEST_ASSET_ID).unwrap().owner_pubkey,
+            user_kp.public()
+        );
+        assert!(matches!(
+            bank_controller.set_asset_frozen(owner_kp.public(), TEST_ASSET_ID, true),
+            Err(GDEXError::AssetOwner)
+        ));
+        bank_controller.mint(user_kp.public(), TEST_ASSET_ID, 100).unwrap();
+    }
+
+    #[test]
+    fn revert_journal_restores_accounts_and_assets() {
+        let mut bank_controller = BankController::default();
+        let user_kp = generate_production_keypair::<KeyPair>();
+        let receiver_kp = generate_production_keypair::<KeyPair>();
+        bank_controller.create_asset(user_kp.public()).unwrap();
+
+        bank_controller.begin_journal();
+        bank_controller
+            .transfer(user_kp.public(), receiver_kp.public(), PRIMARY_ASSET_ID, 100)
+            .unwrap();
+        bank_controller.create_asset(user_kp.public()).unwrap();
+        bank_controller.mint(user_kp.public(), PRIMARY_ASSET_ID, 100).unwrap();
+        bank_controller.revert_journal();
+
+        assert!(!bank_controller.check_account_exists(receiver_kp.public()));
+        assert!(bank_controller.get_asset(1).is_err());
+        assert_eq!(bank_controller.get_num_assets(), 1);
+        assert_eq!(
+            bank_controller.get_balance(user_kp.public(), PRIMARY_ASSET_ID).unwrap(),
+            CREATED_ASSET_BALANCE
+        );
+        assert_eq!(
+            bank_controller.get_asset(PRIMARY_ASSET_ID).unwrap().total_supply,
+            CREATED_ASSET_BALANCE
+        );
+
+        // committed changes are kept
+        bank_controller.begin_journal();
+        bank_controller
+            .transfer(user_kp.public(), receiver_kp.public(), PRIMARY_ASSET_ID, 100)
+            .unwrap();
+        bank_controller.commit_journal();
+        bank_controller.revert_journal();
+        assert_eq!(
+            bank_controller
+                .get_balance(receiver_kp.public(), PRIMARY_ASSET_ID)
+                .unwrap(),
+            100
+        );
+    }
+
+    #[test]
+    fn merged_lanes_match_serial_balances() {
+        let mut bank_controller = BankController::default();
+        let keypairs: Vec<KeyPair> = (0..4).map(|_| generate_production_keypair::<KeyPair>()).collect();
+        let accounts: Vec<AccountPubKey> = keypairs.iter().map(|keypair| keypair.public().clone()).collect();
+        bank_controller.create_asset(&accounts[0]).unwrap();
+        for account in &accounts[1..] {
+            bank_controller
+                .transfer(&accounts[0], account, PRIMARY_ASSET_ID, 1_000)
+                .unwrap();
+        }
+        bank_controller.collect_fee(&accounts[0], PRIMARY_ASSET_ID, 50).unwrap();
+        bank_controller.increment_nonce(&accounts[2]);
+        let fee_account = bank_controller.get_fee_account().clone();
+
+        let mut lane_0 = bank_controller.split_lane(&accounts[..2], &[fee_account.clone()]);
+        let mut lane_1 = bank_controller.split_lane(&accounts[2..], &[fee_account.clone()]);
+        assert!(!bank_controller.check_account_exists(&accounts[0]));
+        assert_eq!(bank_controller.get_next_nonce(&accounts[2]), 0);
+
+        // each lane changes the fee account starting from the same balance
+        lane_0
+            .transfer(&accounts[1], &accounts[0], PRIMARY_ASSET_ID, 10)
+            .unwrap();
+        lane_0.collect_fee(&accounts[1], PRIMARY_ASSET_ID, 5).unwrap();
+        lane_1.collect_fee(&accounts[2], PRIMARY_ASSET_ID, 7).unwrap();
+        lane_1.pay_rebate(&accounts[3], PRIMARY_ASSET_ID, 3).unwrap();
+        lane_1.increment_nonce(&accounts[2]);
+        bank_controller.merge_lane(lane_0);
+        bank_controller.merge_lane(lane_1);
+
+        assert_eq!(bank_controller.get_accrued_fees(PRIMARY_ASSET_ID), 50 + 5 + 7 - 3);
+        assert_eq!(
+            bank_controller.get_balance(&accounts[1], PRIMARY_ASSET_ID).unwrap(),
+            1_000 - 10 - 5
+        );
+        assert_eq!(
+            bank_controller.get_balance(&accounts[3], PRIMARY_ASSET_ID).unwrap(),
+            1_000 + 3
+        );
+        assert_eq!(bank_controller.get_next_nonce(&accounts[2]), 2);
+    }
+
     #[test]
     fn create_bank_catchup_state_default() {
         let bank_controller = BankController::default();
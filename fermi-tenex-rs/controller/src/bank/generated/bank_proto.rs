@@ -4,6 +4,17 @@
 pub struct CreateAssetRequest {
     #[prost(uint64, tag="1")]
     pub dummy: u64,
+    #[prost(string, tag="2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub ticker: ::prost::alloc::string::String,
+    #[prost(uint32, tag="4")]
+    pub decimals: u32,
+    #[prost(uint64, tag="5")]
+    pub initial_supply: u64,
+    /// zero leaves the supply uncapped
+    #[prost(uint64, tag="6")]
+    pub max_supply: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PaymentRequest {
@@ -14,6 +25,34 @@ pub struct PaymentRequest {
     #[prost(uint64, tag="3")]
     pub quantity: u64,
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MintRequest {
+    #[prost(uint64, tag="1")]
+    pub asset_id: u64,
+    #[prost(uint64, tag="2")]
+    pub quantity: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BurnRequest {
+    #[prost(uint64, tag="1")]
+    pub asset_id: u64,
+    #[prost(uint64, tag="2")]
+    pub quantity: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FreezeAssetRequest {
+    #[prost(uint64, tag="1")]
+    pub asset_id: u64,
+    #[prost(bool, tag="2")]
+    pub frozen: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransferAssetOwnershipRequest {
+    #[prost(uint64, tag="1")]
+    pub asset_id: u64,
+    #[prost(bytes="bytes", tag="2")]
+    pub new_owner: ::prost::bytes::Bytes,
+}
 // EVENTS
 
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -32,6 +71,36 @@ pub struct PaymentSuccessEvent {
     #[prost(uint64, tag="4")]
     pub quantity: u64,
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AssetMintedEvent {
+    #[prost(uint64, tag="1")]
+    pub asset_id: u64,
+    #[prost(uint64, tag="2")]
+    pub quantity: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AssetBurnedEvent {
+    #[prost(uint64, tag="1")]
+    pub asset_id: u64,
+    #[prost(uint64, tag="2")]
+    pub quantity: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AssetFrozenEvent {
+    #[prost(uint64, tag="1")]
+    pub asset_id: u64,
+    #[prost(bool, tag="2")]
+    pub frozen: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AssetOwnershipTransferredEvent {
+    #[prost(uint64, tag="1")]
+    pub asset_id: u64,
+    #[prost(bytes="bytes", tag="2")]
+    pub previous_owner: ::prost::bytes::Bytes,
+    #[prost(bytes="bytes", tag="3")]
+    pub new_owner: ::prost::bytes::Bytes,
+}
 // REQUEST ENUM
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
@@ -39,10 +108,18 @@ pub struct PaymentSuccessEvent {
 pub enum BankRequestType {
     CreateAsset = 0,
     Payment = 1,
+    Mint = 2,
+    Burn = 3,
+    FreezeAsset = 4,
+    TransferAssetOwnership = 5,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum BankEventType {
     AssetCreated = 0,
     PaymentSuccess = 1,
-}
+    AssetMinted = 2,
+    AssetBurned = 3,
+    AssetFrozen = 4,
+    AssetOwnershipTransferred = 5,
+}
\ No newline at end of file
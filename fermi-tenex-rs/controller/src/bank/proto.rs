@@ -1,10 +1,13 @@
 // fermi
-use crate::router::ControllerType;
+use crate::{bank::controller::CREATED_ASSET_BALANCE, router::ControllerType};
 use fermi_types::{
     account::AccountPubKey,
+    asset::{AssetId, DEFAULT_ASSET_DECIMALS},
     crypto::ToFromBytes,
     error::GDEXError,
-    transaction::{Event, EventTypeEnum, Request, RequestTypeEnum, Transaction},
+    event::DecodedEvent,
+    transaction::{ControllerRequest, Event, EventTypeEnum, Request, RequestTypeEnum, Transaction},
+    utils,
 };
 // mysten
 use narwhal_types::CertificateDigest;
@@ -27,6 +30,10 @@ impl RequestTypeEnum for BankRequestType {
         match value {
             0 => Ok(BankRequestType::CreateAsset),
             1 => Ok(BankRequestType::Payment),
+            2 => Ok(BankRequestType::Mint),
+            3 => Ok(BankRequestType::Burn),
+            4 => Ok(BankRequestType::FreezeAsset),
+            5 => Ok(BankRequestType::TransferAssetOwnership),
             _ => Err(GDEXError::DeserializationError),
         }
     }
@@ -37,6 +44,10 @@ impl EventTypeEnum for BankEventType {
         match value {
             0 => Ok(BankEventType::AssetCreated),
             1 => Ok(BankEventType::PaymentSuccess),
+            2 => Ok(BankEventType::AssetMinted),
+            3 => Ok(BankEventType::AssetBurned),
+            4 => Ok(BankEventType::AssetFrozen),
+            5 => Ok(BankEventType::AssetOwnershipTransferred),
             _ => Err(GDEXError::DeserializationError),
         }
     }
@@ -45,8 +56,29 @@ impl EventTypeEnum for BankEventType {
 // REQUESTS
 
 impl CreateAssetRequest {
-    pub fn new(dummy: u64) -> Self {
-        CreateAssetRequest { dummy }
+    pub fn new(
+        dummy: u64,
+        name: &str,
+        ticker: &str,
+        decimals: u32,
+        initial_supply: u64,
+        max_supply: Option<u64>,
+    ) -> Self {
+        CreateAssetRequest {
+            dummy,
+            name: name.to_string(),
+            ticker: ticker.to_string(),
+            decimals,
+            initial_supply,
+            max_supply: max_supply.unwrap_or(0),
+        }
+    }
+
+    pub fn get_max_supply(&self) -> Option<u64> {
+        match self.max_supply {
+            0 => None,
+            max_supply => Some(max_supply),
+        }
     }
 }
 
@@ -82,6 +114,73 @@ impl Request for PaymentRequest {
     }
 }
 
+impl MintRequest {
+    pub fn new(asset_id: AssetId, quantity: u64) -> Self {
+        MintRequest { asset_id, quantity }
+    }
+}
+
+impl Request for MintRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Bank as i32
+    }
+    fn get_request_type_id() -> i32 {
+        BankRequestType::Mint as i32
+    }
+}
+
+impl BurnRequest {
+    pub fn new(asset_id: AssetId, quantity: u64) -> Self {
+        BurnRequest { asset_id, quantity }
+    }
+}
+
+impl Request for BurnRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Bank as i32
+    }
+    fn get_request_type_id() -> i32 {
+        BankRequestType::Burn as i32
+    }
+}
+
+impl FreezeAssetRequest {
+    pub fn new(asset_id: AssetId, frozen: bool) -> Self {
+        FreezeAssetRequest { asset_id, frozen }
+    }
+}
+
+impl Request for FreezeAssetRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Bank as i32
+    }
+    fn get_request_type_id() -> i32 {
+        BankRequestType::FreezeAsset as i32
+    }
+}
+
+impl TransferAssetOwnershipRequest {
+    pub fn new(asset_id: AssetId, new_owner: &AccountPubKey) -> Self {
+        TransferAssetOwnershipRequest {
+            asset_id,
+            new_owner: Bytes::from(new_owner.as_ref().to_vec()),
+        }
+    }
+
+    pub fn get_new_owner(&self) -> Result<AccountPubKey, GDEXError> {
+        AccountPubKey::from_bytes(&self.new_owner).map_err(|_e| GDEXError::DeserializationError)
+    }
+}
+
+impl Request for TransferAssetOwnershipRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Bank as i32
+    }
+    fn get_request_type_id() -> i32 {
+        BankRequestType::TransferAssetOwnership as i32
+    }
+}
+
 // EVENTS
 
 impl AssetCreatedEvent {
@@ -99,6 +198,14 @@ impl Event for AssetCreatedEvent {
     }
 }
 
+impl From<AssetCreatedEvent> for DecodedEvent {
+    fn from(event: AssetCreatedEvent) -> Self {
+        DecodedEvent::AssetCreated {
+            asset_id: event.asset_id,
+        }
+    }
+}
+
 impl PaymentSuccessEvent {
     pub fn new(sender: &AccountPubKey, receiver: &AccountPubKey, asset_id: u64, quantity: u64) -> Self {
         PaymentSuccessEvent {
@@ -119,15 +226,147 @@ impl Event for PaymentSuccessEvent {
     }
 }
 
+impl From<PaymentSuccessEvent> for DecodedEvent {
+    fn from(event: PaymentSuccessEvent) -> Self {
+        DecodedEvent::PaymentSuccess {
+            sender: utils::encode_bytes_hex(&event.sender),
+            receiver: utils::encode_bytes_hex(&event.receiver),
+            asset_id: event.asset_id,
+            quantity: event.quantity,
+        }
+    }
+}
+
+impl AssetMintedEvent {
+    pub fn new(asset_id: AssetId, quantity: u64) -> Self {
+        AssetMintedEvent { asset_id, quantity }
+    }
+}
+
+impl Event for AssetMintedEvent {
+    fn get_controller_id() -> i32 {
+        ControllerType::Bank as i32
+    }
+    fn get_event_type_id() -> i32 {
+        BankEventType::AssetMinted as i32
+    }
+}
+
+impl From<AssetMintedEvent> for DecodedEvent {
+    fn from(event: AssetMintedEvent) -> Self {
+        DecodedEvent::AssetMinted {
+            asset_id: event.asset_id,
+            quantity: event.quantity,
+        }
+    }
+}
+
+impl AssetBurnedEvent {
+    pub fn new(asset_id: AssetId, quantity: u64) -> Self {
+        AssetBurnedEvent { asset_id, quantity }
+    }
+}
+
+impl Event for AssetBurnedEvent {
+    fn get_controller_id() -> i32 {
+        ControllerType::Bank as i32
+    }
+    fn get_event_type_id() -> i32 {
+        BankEventType::AssetBurned as i32
+    }
+}
+
+impl From<AssetBurnedEvent> for DecodedEvent {
+    fn from(event: AssetBurnedEvent) -> Self {
+        DecodedEvent::AssetBurned {
+            asset_id: event.asset_id,
+            quantity: event.quantity,
+        }
+    }
+}
+
+impl AssetFrozenEvent {
+    pub fn new(asset_id: AssetId, frozen: bool) -> Self {
+        AssetFrozenEvent { asset_id, frozen }
+    }
+}
+
+impl Event for AssetFrozenEvent {
+    fn get_controller_id() -> i32 {
+        ControllerType::Bank as i32
+    }
+    fn get_event_type_id() -> i32 {
+        BankEventType::AssetFrozen as i32
+    }
+}
+
+impl From<AssetFrozenEvent> for DecodedEvent {
+    fn from(event: AssetFrozenEvent) -> Self {
+        DecodedEvent::AssetFrozen {
+            asset_id: event.asset_id,
+            frozen: event.frozen,
+        }
+    }
+}
+
+impl AssetOwnershipTransferredEvent {
+    pub fn new(asset_id: AssetId, previous_owner: &AccountPubKey, new_owner: &AccountPubKey) -> Self {
+        AssetOwnershipTransferredEvent {
+            asset_id,
+            previous_owner: Bytes::from(previous_owner.as_ref().to_vec()),
+            new_owner: Bytes::from(new_owner.as_ref().to_vec()),
+        }
+    }
+}
+
+impl Event for AssetOwnershipTransferredEvent {
+    fn get_controller_id() -> i32 {
+        ControllerType::Bank as i32
+    }
+    fn get_event_type_id() -> i32 {
+        BankEventType::AssetOwnershipTransferred as i32
+    }
+}
+
+impl From<AssetOwnershipTransferredEvent> for DecodedEvent {
+    fn from(event: AssetOwnershipTransferredEvent) -> Self {
+        DecodedEvent::AssetOwnershipTransferred {
+            asset_id: event.asset_id,
+            previous_owner: utils::encode_bytes_hex(&event.previous_owner),
+            new_owner: utils::encode_bytes_hex(&event.new_owner),
+        }
+    }
+}
+
 // TRANSACTION BUILDERS
 
-// TODO - https://github.com/fermiorg/fermi/issues/168 - add real params
+// creates an unnamed, uncapped asset which mints CREATED_ASSET_BALANCE to the sender
 pub fn create_create_asset_transaction(
     sender: &AccountPubKey,
     recent_block_hash: CertificateDigest,
     dummy: u64,
 ) -> Transaction {
-    Transaction::new(sender, recent_block_hash, &CreateAssetRequest::new(dummy))
+    Transaction::new(
+        sender,
+        recent_block_hash,
+        &CreateAssetRequest::new(dummy, "", "", DEFAULT_ASSET_DECIMALS, CREATED_ASSET_BALANCE, None),
+    )
+}
+
+pub fn create_create_asset_with_metadata_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    name: &str,
+    ticker: &str,
+    decimals: u32,
+    initial_supply: u64,
+    max_supply: Option<u64>,
+) -> Transaction {
+    Transaction::new(
+        sender,
+        recent_block_hash,
+        &CreateAssetRequest::new(0, name, ticker, decimals, initial_supply, max_supply),
+    )
 }
 
 pub fn create_payment_transaction(
@@ -144,6 +383,87 @@ pub fn create_payment_transaction(
     )
 }
 
+pub fn create_mint_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    asset_id: AssetId,
+    quantity: u64,
+) -> Transaction {
+    Transaction::new(sender, recent_block_hash, &MintRequest::new(asset_id, quantity))
+}
+
+pub fn create_burn_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    asset_id: AssetId,
+    quantity: u64,
+) -> Transaction {
+    Transaction::new(sender, recent_block_hash, &BurnRequest::new(asset_id, quantity))
+}
+
+pub fn create_freeze_asset_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    asset_id: AssetId,
+    frozen: bool,
+) -> Transaction {
+    Transaction::new(sender, recent_block_hash, &FreezeAssetRequest::new(asset_id, frozen))
+}
+
+pub fn create_transfer_asset_ownership_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    asset_id: AssetId,
+    new_owner: &AccountPubKey,
+) -> Transaction {
+    Transaction::new(
+        sender,
+        recent_block_hash,
+        &TransferAssetOwnershipRequest::new(asset_id, new_owner),
+    )
+}
+
+// REQUEST BUILDERS
+
+// requests are combined into a single all-or-nothing transaction with Transaction::new_batch
+
+pub fn create_create_asset_with_metadata_request(
+    name: &str,
+    ticker: &str,
+    decimals: u32,
+    initial_supply: u64,
+    max_supply: Option<u64>,
+) -> ControllerRequest {
+    ControllerRequest::new(&CreateAssetRequest::new(
+        0,
+        name,
+        ticker,
+        decimals,
+        initial_supply,
+        max_supply,
+    ))
+}
+
+pub fn create_payment_request(receiver: &AccountPubKey, asset_id: u64, amount: u64) -> ControllerRequest {
+    ControllerRequest::new(&PaymentRequest::new(receiver, asset_id, amount))
+}
+
+pub fn create_mint_request(asset_id: AssetId, quantity: u64) -> ControllerRequest {
+    ControllerRequest::new(&MintRequest::new(asset_id, quantity))
+}
+
+pub fn create_burn_request(asset_id: AssetId, quantity: u64) -> ControllerRequest {
+    ControllerRequest::new(&BurnRequest::new(asset_id, quantity))
+}
+
+pub fn create_freeze_asset_request(asset_id: AssetId, frozen: bool) -> ControllerRequest {
+    ControllerRequest::new(&FreezeAssetRequest::new(asset_id, frozen))
+}
+
+pub fn create_transfer_asset_ownership_request(asset_id: AssetId, new_owner: &AccountPubKey) -> ControllerRequest {
+    ControllerRequest::new(&TransferAssetOwnershipRequest::new(asset_id, new_owner))
+}
+
 /// Begin externally available testing functions
 #[cfg(any(test, feature = "testing"))]
 pub mod bank_controller_test_functions {
@@ -157,16 +477,18 @@ pub mod bank_controller_test_functions {
         kp_sender: &AccountKeyPair,
         kp_receiver: &AccountKeyPair,
         amount: u64,
+        nonce: u64,
     ) -> SignedTransaction {
         let dummy_batch_digest = CertificateDigest::new([0; DIGEST_LEN]);
 
-        let transaction = create_payment_transaction(
+        let mut transaction = create_payment_transaction(
             kp_sender.public(),
             dummy_batch_digest,
             kp_receiver.public(),
             PRIMARY_ASSET_ID,
             amount,
         );
+        transaction.set_nonce(nonce);
 
         transaction.sign(kp_sender).unwrap()
     }
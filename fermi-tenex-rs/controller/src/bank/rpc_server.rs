@@ -1,4 +1,5 @@
 // local
+use crate::bank::types::AssetResponse;
 use crate::router::ControllerRouter;
 use fermi_types::store::RPCStoreHandle;
 use fermi_types::{account::AccountPubKey, crypto::ToFromBytes, utils};
@@ -22,6 +23,15 @@ use std::sync::{Arc, Mutex};
 pub trait ControllerData {
     #[method(name = "getAccountBalance")]
     async fn get_account_balance(&self, account: String, asset_id: u64) -> RpcResult<u64>;
+
+    #[method(name = "getAccruedFees")]
+    async fn get_accrued_fees(&self, asset_id: u64) -> RpcResult<u64>;
+
+    #[method(name = "getAsset")]
+    async fn get_asset(&self, asset_id: u64) -> RpcResult<AssetResponse>;
+
+    #[method(name = "getNextNonce")]
+    async fn get_next_nonce(&self, account: String) -> RpcResult<u64>;
 }
 
 // The JSONRPCService struct will implement the RPC server
@@ -56,6 +66,35 @@ impl ControllerDataServer for JSONRPCService {
             .get_balance(&account, asset_id)
             .unwrap_or(0))
     }
+
+    async fn get_accrued_fees(&self, asset_id: u64) -> RpcResult<u64> {
+        Ok(self
+            .state_manager
+            .lock()
+            .unwrap()
+            .bank_controller
+            .lock()
+            .unwrap()
+            .get_accrued_fees(asset_id))
+    }
+
+    async fn get_asset(&self, asset_id: u64) -> RpcResult<AssetResponse> {
+        let state_manager = self.state_manager.lock().unwrap();
+        let bank_controller = state_manager.bank_controller.lock().unwrap();
+        let asset = bank_controller
+            .get_asset(asset_id)
+            .map_err(|_| Error::Custom("Failed to find asset".to_string()))?;
+
+        Ok(AssetResponse::from(asset))
+    }
+
+    async fn get_next_nonce(&self, account: String) -> RpcResult<u64> {
+        let account_bytes: Vec<u8> = utils::decode_bytes_hex(&account)?;
+        let account: AccountPubKey = AccountPubKey::from_bytes(account_bytes.as_slice())
+            .map_err(|_| Error::Custom("Failed to decode account".to_string()))?;
+
+        Ok(self.state_manager.lock().unwrap().get_next_nonce(&account))
+    }
 }
 
 // The SuiRPCModule allows us to generate an OpenRPC document for the RPC server.
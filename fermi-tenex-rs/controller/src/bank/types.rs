@@ -1,23 +1,25 @@
 // fermi
 use fermi_types::{
     account::{AccountBalance, AccountPubKey},
-    asset::AssetId,
+    asset::{Asset, AssetId},
+    utils,
 };
 // external
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 /// Bank account specifies the format of user accounts in the bank controller
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BankAccount {
     account_pub_key: AccountPubKey,
-    balances: HashMap<AssetId, AccountBalance>,
+    balances: BTreeMap<AssetId, AccountBalance>,
 }
 impl BankAccount {
     pub fn new(account_pub_key: AccountPubKey) -> Self {
         BankAccount {
             account_pub_key,
-            balances: HashMap::new(),
+            balances: BTreeMap::new(),
         }
     }
 
@@ -25,7 +27,7 @@ impl BankAccount {
         &self.account_pub_key
     }
 
-    pub fn get_balances(&self) -> &HashMap<AssetId, AccountBalance> {
+    pub fn get_balances(&self) -> &BTreeMap<AssetId, AccountBalance> {
         &self.balances
     }
 
@@ -37,3 +39,32 @@ impl BankAccount {
         self.balances.insert(asset_id, amount);
     }
 }
+
+// JSON RPC Response structs
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AssetResponse {
+    pub asset_id: AssetId,
+    pub owner: String,
+    pub name: String,
+    pub ticker: String,
+    pub decimals: u32,
+    pub total_supply: u64,
+    pub max_supply: Option<u64>,
+    pub is_frozen: bool,
+}
+
+impl From<&Asset> for AssetResponse {
+    fn from(asset: &Asset) -> Self {
+        AssetResponse {
+            asset_id: asset.asset_id,
+            owner: utils::encode_bytes_hex(&asset.owner_pubkey),
+            name: asset.name.clone(),
+            ticker: asset.ticker.clone(),
+            decimals: asset.decimals,
+            total_supply: asset.total_supply,
+            max_supply: asset.max_supply,
+            is_frozen: asset.is_frozen,
+        }
+    }
+}
@@ -1,11 +1,17 @@
 // fermi
 use crate::{
-    consensus::rpc_server::UnimplementedRPC,
+    consensus::{proto::*, rpc_server::UnimplementedRPC},
     controller::Controller,
     event_manager::{EventEmitter, EventManager},
     router::ControllerRouter,
 };
-use fermi_types::{account::AccountPubKey, crypto::ToFromBytes, error::GDEXError, transaction::Transaction};
+use fermi_types::{
+    account::AccountPubKey,
+    crypto::ToFromBytes,
+    error::GDEXError,
+    store::CriticalPathStore,
+    transaction::{deserialize_protobuf, Transaction},
+};
 // external
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -14,7 +20,7 @@ use std::time::Duration;
 
 // CONSTANTS
 pub const CONSENSUS_CONTROLLER_ACCOUNT_PUBKEY: &[u8] = b"CONSENSUSCONTROLLERAAAAAAAAAAAAA";
-const DEFAULT_BATCH_SIZE: usize = 500_000;
+const DEFAULT_BATCH_SIZE: usize = 100;
 const DEFAULT_MAX_DELAY_MILLIS: u64 = 200; // .2 sec
 
 // INTERFACE
@@ -25,16 +31,24 @@ pub struct ConsensusController {
     controller_account: AccountPubKey,
     pub batch_size: usize,
     pub max_batch_delay: Duration,
+    // account allowed to change the batch parameters, set at genesis as the keyless controller account cannot sign
+    governance_account: AccountPubKey,
+    // batch parameters requested during the block, applied at the end of the block
+    pending_batch_params: Option<(usize, Duration)>,
     // shared
+    #[serde(skip)]
     event_manager: Arc<Mutex<EventManager>>,
 }
 
 impl Default for ConsensusController {
     fn default() -> Self {
+        let controller_account = AccountPubKey::from_bytes(CONSENSUS_CONTROLLER_ACCOUNT_PUBKEY).unwrap();
         Self {
-            controller_account: AccountPubKey::from_bytes(CONSENSUS_CONTROLLER_ACCOUNT_PUBKEY).unwrap(),
+            governance_account: controller_account.clone(),
+            controller_account,
             batch_size: DEFAULT_BATCH_SIZE,
             max_batch_delay: Duration::from_millis(DEFAULT_MAX_DELAY_MILLIS),
+            pending_batch_params: None,
             // shared state
             event_manager: Arc::new(Mutex::new(EventManager::new())), // TEMPORARY
         }
@@ -51,14 +65,78 @@ impl Controller<UnimplementedRPC> for ConsensusController {
         Ok(())
     }
 
-    fn handle_consensus_transaction(&mut self, _transaction: &Transaction) -> Result<(), GDEXError> {
-        Err(GDEXError::InvalidRequestTypeError)
+    type Checkpoint = Option<(usize, Duration)>;
+
+    fn checkpoint(&self, _transaction: &Transaction) -> Self::Checkpoint {
+        self.pending_batch_params
+    }
+
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        self.pending_batch_params = checkpoint;
+    }
+
+    fn handle_consensus_transaction(&mut self, transaction: &Transaction) -> Result<(), GDEXError> {
+        let request_type: ConsensusRequestType = transaction.get_request_type()?;
+        match request_type {
+            ConsensusRequestType::UpdateBatchParams => {
+                let request: UpdateBatchParamsRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let sender = transaction.get_sender()?;
+                self.update_batch_params(&sender, request.batch_size, request.max_batch_delay_millis)
+            }
+        }
+    }
+
+    fn critical_process_end_of_block(&mut self, _critical_path_store: &CriticalPathStore, _block_number: u64) {
+        self.apply_pending_batch_params();
+    }
+}
+
+impl ConsensusController {
+    pub fn get_governance_account(&self) -> &AccountPubKey {
+        &self.governance_account
+    }
+
+    pub fn set_governance_account(&mut self, governance_account: AccountPubKey) {
+        self.governance_account = governance_account;
+    }
+
+    pub fn get_pending_batch_params(&self) -> Option<(usize, Duration)> {
+        self.pending_batch_params
+    }
+
+    // schedule new batch parameters, consensus picks them up once the current block has been processed
+    pub fn update_batch_params(
+        &mut self,
+        sender: &AccountPubKey,
+        batch_size: u64,
+        max_batch_delay_millis: u64,
+    ) -> Result<(), GDEXError> {
+        if sender != &self.governance_account {
+            return Err(GDEXError::GovernanceAccount);
+        }
+        if batch_size == 0 || max_batch_delay_millis == 0 {
+            return Err(GDEXError::InvalidBatchParams);
+        }
+        self.pending_batch_params = Some((batch_size as usize, Duration::from_millis(max_batch_delay_millis)));
+        self.emit_event(&BatchParamsUpdatedEvent::new(batch_size, max_batch_delay_millis));
+        Ok(())
+    }
+
+    pub fn apply_pending_batch_params(&mut self) {
+        if let Some((batch_size, max_batch_delay)) = self.pending_batch_params.take() {
+            self.batch_size = batch_size;
+            self.max_batch_delay = max_batch_delay;
+        }
     }
 }
 
 #[cfg(test)]
 pub mod consensus_tests {
     use super::*;
+    use fastcrypto::DIGEST_LEN;
+    use fermi_types::account::account_test_functions::generate_keypair_vec;
+    use fermi_types::crypto::KeypairTraits;
+    use narwhal_types::CertificateDigest;
 
     #[test]
     fn create_consensus_catchup_state_default() {
@@ -75,6 +153,38 @@ pub mod consensus_tests {
             Err(_) => panic!("deserializing catchup_state_default failed"),
         }
     }
+
+    #[test]
+    fn batch_params_applied_at_end_of_block() {
+        let governance = generate_keypair_vec([0; 32]).pop().unwrap();
+
+        let mut consensus_controller = ConsensusController::default();
+        assert_eq!(
+            consensus_controller.update_batch_params(governance.public(), 10, 50),
+            Err(GDEXError::GovernanceAccount)
+        );
+
+        consensus_controller.set_governance_account(governance.public().clone());
+        assert_eq!(
+            consensus_controller.update_batch_params(governance.public(), 0, 50),
+            Err(GDEXError::InvalidBatchParams)
+        );
+        consensus_controller
+            .handle_consensus_transaction(&create_update_batch_params_transaction(
+                governance.public(),
+                CertificateDigest::new([0; DIGEST_LEN]),
+                10,
+                50,
+            ))
+            .unwrap();
+
+        // parameters only change once the block has been processed
+        assert_eq!(consensus_controller.batch_size, DEFAULT_BATCH_SIZE);
+        consensus_controller.apply_pending_batch_params();
+        assert_eq!(consensus_controller.batch_size, 10);
+        assert_eq!(consensus_controller.max_batch_delay, Duration::from_millis(50));
+        assert!(consensus_controller.get_pending_batch_params().is_none());
+    }
 }
 
 impl EventEmitter for ConsensusController {
@@ -0,0 +1,30 @@
+// REQUESTS
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateBatchParamsRequest {
+    #[prost(uint64, tag="1")]
+    pub batch_size: u64,
+    #[prost(uint64, tag="2")]
+    pub max_batch_delay_millis: u64,
+}
+// EVENTS
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchParamsUpdatedEvent {
+    #[prost(uint64, tag="1")]
+    pub batch_size: u64,
+    #[prost(uint64, tag="2")]
+    pub max_batch_delay_millis: u64,
+}
+// REQUEST ENUM
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ConsensusRequestType {
+    UpdateBatchParams = 0,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ConsensusEventType {
+    BatchParamsUpdated = 0,
+}
@@ -1,2 +1,3 @@
 pub mod controller;
+pub mod proto;
 pub mod rpc_server;
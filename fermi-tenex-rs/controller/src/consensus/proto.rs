@@ -0,0 +1,110 @@
+// fermi
+use crate::router::ControllerType;
+use fermi_types::{
+    account::AccountPubKey,
+    error::GDEXError,
+    event::DecodedEvent,
+    transaction::{ControllerRequest, Event, EventTypeEnum, Request, RequestTypeEnum, Transaction},
+    utils,
+};
+// mysten
+use narwhal_types::CertificateDigest;
+
+// MODULE IMPORTS
+
+#[path = "./generated/consensus_proto.rs"]
+#[rustfmt::skip]
+#[allow(clippy::all)]
+mod consensus_proto;
+
+pub use consensus_proto::*;
+
+// ENUMS
+
+impl RequestTypeEnum for ConsensusRequestType {
+    fn request_type_from_i32(value: i32) -> Result<Self, GDEXError> {
+        match value {
+            0 => Ok(ConsensusRequestType::UpdateBatchParams),
+            _ => Err(GDEXError::DeserializationError),
+        }
+    }
+}
+
+impl EventTypeEnum for ConsensusEventType {
+    fn event_type_from_i32(value: i32) -> Result<Self, GDEXError> {
+        match value {
+            0 => Ok(ConsensusEventType::BatchParamsUpdated),
+            _ => Err(GDEXError::DeserializationError),
+        }
+    }
+}
+
+// REQUESTS
+
+impl UpdateBatchParamsRequest {
+    pub fn new(batch_size: u64, max_batch_delay_millis: u64) -> Self {
+        UpdateBatchParamsRequest {
+            batch_size,
+            max_batch_delay_millis,
+        }
+    }
+}
+
+impl Request for UpdateBatchParamsRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Consensus as i32
+    }
+    fn get_request_type_id() -> i32 {
+        ConsensusRequestType::UpdateBatchParams as i32
+    }
+}
+
+// EVENTS
+
+impl BatchParamsUpdatedEvent {
+    pub fn new(batch_size: u64, max_batch_delay_millis: u64) -> Self {
+        BatchParamsUpdatedEvent {
+            batch_size,
+            max_batch_delay_millis,
+        }
+    }
+}
+
+impl Event for BatchParamsUpdatedEvent {
+    fn get_controller_id() -> i32 {
+        ControllerType::Consensus as i32
+    }
+    fn get_event_type_id() -> i32 {
+        ConsensusEventType::BatchParamsUpdated as i32
+    }
+}
+
+impl From<BatchParamsUpdatedEvent> for DecodedEvent {
+    fn from(event: BatchParamsUpdatedEvent) -> Self {
+        DecodedEvent::BatchParamsUpdated {
+            batch_size: event.batch_size,
+            max_batch_delay_millis: event.max_batch_delay_millis,
+        }
+    }
+}
+
+// TRANSACTION BUILDERS
+
+pub fn create_update_batch_params_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    batch_size: u64,
+    max_batch_delay_millis: u64,
+) -> Transaction {
+    Transaction::new(
+        sender,
+        recent_block_hash,
+        &UpdateBatchParamsRequest::new(batch_size, max_batch_delay_millis),
+    )
+}
+
+// REQUEST BUILDERS
+
+pub fn create_update_batch_params_request(batch_size: u64, max_batch_delay_millis: u64) -> ControllerRequest {
+    ControllerRequest::new(&UpdateBatchParamsRequest::new(batch_size, max_batch_delay_millis))
+}
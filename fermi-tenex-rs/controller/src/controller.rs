@@ -26,11 +26,20 @@ where
 
     fn initialize_controller_account(&self) -> Result<(), GDEXError>;
 
+    /// State a request may modify, saved before the request executes
+    /// bank balances are not part of it, the router journals them on the bank controller instead
+    type Checkpoint: Send;
+
+    fn checkpoint(&self, transaction: &Transaction) -> Self::Checkpoint;
+
+    /// Restores the state saved before a request which failed partway through
+    fn rollback(&mut self, checkpoint: Self::Checkpoint);
+
     fn handle_consensus_transaction(&mut self, transaction: &Transaction) -> Result<(), GDEXError>;
 
-    fn critical_process_end_of_block(&self, _critical_path_store: &CriticalPathStore, _block_number: u64) {}
+    fn critical_process_end_of_block(&mut self, _critical_path_store: &CriticalPathStore, _block_number: u64) {}
 
-    fn non_critical_process_end_of_block(&self, _rpc_store: &RPCStore, _block_number: u64) {}
+    fn non_critical_process_end_of_block(&mut self, _rpc_store: &RPCStore, _block_number: u64) {}
 
     fn get_catchup_state(&self) -> Result<Vec<u8>, GDEXError> {
         match bincode::serialize(&self.clone()) {
@@ -0,0 +1,176 @@
+// IMPORTS
+
+// crate
+use crate::{bank::proto::*, consensus::proto::*, futures::proto::*, spot::proto::*, stake::proto::*};
+
+// fermi
+use fermi_types::{
+    error::GDEXError,
+    event::{DecodedEvent, EventDecoder},
+    transaction::{deserialize_protobuf, ControllerType, Event, ExecutionEvent},
+};
+
+// external
+use prost::Message;
+use std::collections::HashMap;
+
+// INTERFACE
+
+type DecodeFn = fn(&[u8]) -> Result<DecodedEvent, GDEXError>;
+
+/// Maps each (controller, event type) pair to the decoder of its protobuf event
+pub struct EventRegistry {
+    decoders: HashMap<(ControllerType, i32), DecodeFn>,
+}
+
+impl Default for EventRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventRegistry {
+    pub fn new() -> Self {
+        let mut registry = EventRegistry {
+            decoders: HashMap::new(),
+        };
+        // bank
+        registry.register::<AssetCreatedEvent>();
+        registry.register::<PaymentSuccessEvent>();
+        registry.register::<AssetMintedEvent>();
+        registry.register::<AssetBurnedEvent>();
+        registry.register::<AssetFrozenEvent>();
+        registry.register::<AssetOwnershipTransferredEvent>();
+        // stake
+        registry.register::<StakedEvent>();
+        registry.register::<UnstakeStartedEvent>();
+        registry.register::<DelegatedEvent>();
+        // spot
+        registry.register::<SpotOrderNewEvent>();
+        registry.register::<SpotOrderFillEvent>();
+        registry.register::<SpotOrderPartialFillEvent>();
+        registry.register::<SpotOrderUpdateEvent>();
+        registry.register::<SpotOrderCancelEvent>();
+        // consensus
+        registry.register::<BatchParamsUpdatedEvent>();
+        // futures
+        registry.register::<FuturesOrderNewEvent>();
+        registry.register::<FuturesOrderFillEvent>();
+        registry.register::<FuturesOrderPartialFillEvent>();
+        registry.register::<FuturesOrderUpdateEvent>();
+        registry.register::<FuturesOrderCancelEvent>();
+        registry.register::<FuturesLiquidateEvent>();
+        registry
+    }
+
+    pub fn register<T>(&mut self)
+    where
+        T: Event + Message + Default + Into<DecodedEvent>,
+    {
+        let controller_type =
+            ControllerType::from_i32(T::get_controller_id()).expect("Events must be emitted by a known controller");
+        self.decoders
+            .insert((controller_type, T::get_event_type_id()), decode::<T>);
+    }
+
+    pub fn is_registered(&self, controller_type: ControllerType, event_type: i32) -> bool {
+        self.decoders.contains_key(&(controller_type, event_type))
+    }
+}
+
+impl EventDecoder for EventRegistry {
+    fn decode_event(&self, event: &ExecutionEvent) -> Result<DecodedEvent, GDEXError> {
+        let controller_type = ControllerType::from_i32(event.controller_id)?;
+        let decoder = self
+            .decoders
+            .get(&(controller_type, event.event_type))
+            .ok_or(GDEXError::UnknownEventType)?;
+        decoder(&event.event_bytes)
+    }
+}
+
+fn decode<T>(event_bytes: &[u8]) -> Result<DecodedEvent, GDEXError>
+where
+    T: Message + Default + Into<DecodedEvent>,
+{
+    Ok(deserialize_protobuf::<T>(event_bytes)?.into())
+}
+
+#[cfg(test)]
+pub mod event_registry_tests {
+    use super::*;
+    use fermi_types::{
+        account::account_test_functions::generate_keypair_vec, crypto::KeypairTraits, transaction::EventTypeEnum, utils,
+    };
+
+    // counts the event types of a controller by walking its enum until the first unknown value
+    fn count_event_types<T: EventTypeEnum>() -> i32 {
+        (0..)
+            .take_while(|event_type| T::event_type_from_i32(*event_type).is_ok())
+            .count() as i32
+    }
+
+    #[test]
+    fn every_event_type_is_registered() {
+        let registry = EventRegistry::new();
+        let controller_event_types = [
+            (ControllerType::Bank, count_event_types::<BankEventType>()),
+            (ControllerType::Stake, count_event_types::<StakeEventType>()),
+            (ControllerType::Spot, count_event_types::<SpotEventType>()),
+            (ControllerType::Consensus, count_event_types::<ConsensusEventType>()),
+            (ControllerType::Futures, count_event_types::<FuturesEventType>()),
+        ];
+        for (controller_type, event_types) in controller_event_types {
+            for event_type in 0..event_types {
+                assert!(registry.is_registered(controller_type, event_type));
+            }
+        }
+        assert!(!registry.is_registered(ControllerType::Bank, count_event_types::<BankEventType>()));
+    }
+
+    #[test]
+    fn decodes_events_with_hex_account_keys() {
+        let registry = EventRegistry::new();
+        let sender = generate_keypair_vec([0; 32]).pop().unwrap();
+        let receiver = generate_keypair_vec([1; 32]).pop().unwrap();
+
+        let event = ExecutionEvent::new(&PaymentSuccessEvent::new(sender.public(), receiver.public(), 0, 10));
+        assert_eq!(
+            registry.decode_event(&event).unwrap(),
+            DecodedEvent::PaymentSuccess {
+                sender: utils::encode_bytes_hex(sender.public()),
+                receiver: utils::encode_bytes_hex(receiver.public()),
+                asset_id: 0,
+                quantity: 10,
+            }
+        );
+
+        let event = ExecutionEvent::new(&FuturesLiquidateEvent::new(
+            sender.public(),
+            receiver.public(),
+            1,
+            100,
+            5,
+        ));
+        let decoded_event = serde_json::to_value(registry.decode_event(&event).unwrap()).unwrap();
+        assert_eq!(decoded_event["event"], "FuturesLiquidate");
+        assert_eq!(
+            decoded_event["target_account"],
+            utils::encode_bytes_hex(receiver.public())
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_events() {
+        let registry = EventRegistry::new();
+        let event = ExecutionEvent {
+            controller_id: ControllerType::Bank as i32,
+            event_type: 100,
+            event_bytes: Vec::new(),
+        };
+        assert!(matches!(
+            registry.decode_event(&event),
+            Err(GDEXError::UnknownEventType)
+        ));
+    }
+}
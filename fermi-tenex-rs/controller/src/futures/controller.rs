@@ -5,13 +5,13 @@ use crate::event_manager::{EventEmitter, EventManager};
 use crate::futures::{proto::*, types::*, utils::*};
 use crate::router::ControllerRouter;
 use crate::spot::proto::*;
-use crate::utils::engine::order_book::{OrderBookWrapper, OrderId, Orderbook};
+use crate::utils::engine::order_book::{validate_order_id_assets, OrderBookWrapper, OrderId, Orderbook};
 use fermi_types::{
     account::AccountPubKey,
     asset::{AssetId, FuturesOrderbookKey},
     crypto::ToFromBytes,
     error::GDEXError,
-    order_book::{OrderSide, OrderbookDepth},
+    order_book::{ClientOrderId, FeeSchedule, Liquidity, OrderSide, OrderbookDepth, Success},
     store::{RPCStore, RPCStoreHandle},
     transaction::{deserialize_protobuf, Transaction},
     utils,
@@ -24,7 +24,7 @@ use fermi_types::transaction::parse_order_side;
 use serde::{Deserialize, Serialize};
 use std::borrow::BorrowMut;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     convert::TryInto,
     sync::{Arc, Mutex},
 };
@@ -41,20 +41,29 @@ pub const ORDERBOOK_DEPTH_FREQUENCY: u64 = 1;
 pub struct FuturesController {
     // controller state
     pub controller_account: AccountPubKey,
+    #[serde(skip)]
     bank_controller: Arc<Mutex<BankController>>,
     // A market_place is created by an admin
     // and is a collection of futures market interfaces
-    market_places: HashMap<AccountPubKey, Marketplace>,
+    market_places: BTreeMap<AccountPubKey, Marketplace>,
     // shared
+    #[serde(skip)]
     event_manager: Arc<Mutex<EventManager>>,
 }
 
+/// Copy of the one marketplace a request may modify and of its deposits, or None if it did not exist yet
+#[derive(Clone, Debug)]
+pub struct FuturesCheckpoint {
+    market_admin: AccountPubKey,
+    market_place: Option<(Marketplace, BTreeMap<AccountPubKey, i64>)>,
+}
+
 impl Default for FuturesController {
     fn default() -> Self {
         Self {
             controller_account: AccountPubKey::from_bytes(FUTURES_CONTROLLER_ACCOUNT_PUBKEY).unwrap(),
             bank_controller: Arc::new(Mutex::new(BankController::default())), // TEMPORARY
-            market_places: HashMap::new(),
+            market_places: BTreeMap::new(),
             // shared state
             event_manager: Arc::new(Mutex::new(EventManager::new())), // TEMPORARY
         }
@@ -68,7 +77,7 @@ impl FuturesController {
         Self {
             controller_account,
             bank_controller,
-            market_places: HashMap::new(),
+            market_places: BTreeMap::new(),
             // shared state
             event_manager: Arc::new(Mutex::new(EventManager::new())), // TEMPORARY
         }
@@ -94,10 +103,10 @@ impl FuturesController {
         self.market_places.insert(
             market_admin,
             Marketplace {
-                deposits: Arc::new(Mutex::new(HashMap::new())),
+                deposits: Arc::new(Mutex::new(BTreeMap::new())),
                 quote_asset_id: request.quote_asset_id,
                 latest_time: 0,
-                markets: HashMap::new(),
+                markets: BTreeMap::new(),
             },
         );
         Ok(())
@@ -111,6 +120,10 @@ impl FuturesController {
             if market_place.markets.get(&request.base_asset_id).is_some() {
                 return Err(GDEXError::MarketExistence);
             }
+            // order ids of the market carry its asset pair
+            if validate_order_id_assets(request.base_asset_id, market_place.quote_asset_id).is_err() {
+                return Err(GDEXError::FuturesInitialization);
+            }
             market_place.markets.insert(
                 request.base_asset_id,
                 FuturesMarket {
@@ -120,11 +133,14 @@ impl FuturesController {
                     max_leverage: DEFAULT_MAX_LEVERAGE,
                     base_asset_id: request.base_asset_id,
                     quote_asset_id: market_place.quote_asset_id,
-                    accounts: HashMap::new(),
-                    order_to_account: HashMap::new(),
+                    accounts: BTreeMap::new(),
+                    order_to_account: BTreeMap::new(),
                     orderbook: Orderbook::new(request.base_asset_id, market_place.quote_asset_id),
                     marketplace_deposits: Arc::downgrade(&market_place.deposits),
                     liquidation_fee_percent: 1,
+                    fee_schedule: FeeSchedule::default(),
+                    controller_account: self.controller_account.clone(),
+                    bank_controller: Arc::clone(&self.bank_controller),
                     event_manager: Arc::clone(&self.event_manager),
                 },
             );
@@ -157,6 +173,27 @@ impl FuturesController {
         Ok(())
     }
 
+    fn update_market_fees(
+        &mut self,
+        market_admin: AccountPubKey,
+        request: UpdateMarketFeesRequest,
+    ) -> Result<(), GDEXError> {
+        if let Some(market_place) = self.market_places.get_mut(&market_admin) {
+            if let Some(market) = market_place.markets.get_mut(&request.base_asset_id) {
+                let fee_schedule = FeeSchedule::new(request.maker_fee_bps, request.taker_fee_bps);
+                if !fee_schedule.is_valid() {
+                    return Err(GDEXError::InvalidFeeSchedule);
+                }
+                market.fee_schedule = fee_schedule;
+            } else {
+                return Err(GDEXError::MarketExistence);
+            }
+        } else {
+            return Err(GDEXError::MarketplaceExistence);
+        }
+        Ok(())
+    }
+
     fn update_time(&mut self, market_admin: AccountPubKey, request: UpdateTimeRequest) -> Result<(), GDEXError> {
         if let Some(market_place) = self.market_places.get_mut(&market_admin) {
             market_place.latest_time = request.latest_time;
@@ -327,17 +364,74 @@ impl FuturesController {
                 .get_mut(&request.base_asset_id)
                 .ok_or(GDEXError::MarketExistence)?;
 
-            market.place_limit_order(&sender, &LimitOrderRequest::from(request))?;
+            // a reduce-only order must trade against an opposite position and never past it,
+            // together with the reduce-only orders of the same side which are still open
+            let reduce_only = request.reduce_only;
+            if reduce_only {
+                let futures_account = market.accounts.get(&sender);
+                let open_reduce_only_quantity: u64 = futures_account
+                    .map(|futures_account| {
+                        futures_account
+                            .open_orders
+                            .iter()
+                            .filter(|order| order.reduce_only && order.side == request.side)
+                            .map(|order| order.quantity)
+                            .sum()
+                    })
+                    .unwrap_or(0);
+                let reduced_quantity = request
+                    .quantity
+                    .checked_add(open_reduce_only_quantity)
+                    .ok_or(GDEXError::ReduceOnlyIncreasesPosition)?;
+                match futures_account.and_then(|futures_account| futures_account.position.as_ref()) {
+                    Some(position) if position.side != request.side && position.quantity >= reduced_quantity => {}
+                    _ => return Err(GDEXError::ReduceOnlyIncreasesPosition),
+                }
+            }
+
+            let results = market.place_limit_order(&sender, &LimitOrderRequest::from(request))?;
+
+            // mark the remainder which rests on the book as reduce-only
+            if reduce_only {
+                if let Some(Ok(Success::Accepted { order_id, .. })) = results.first() {
+                    if let Some(order) = market.accounts.get_mut(&sender).and_then(|futures_account| {
+                        futures_account
+                            .open_orders
+                            .iter_mut()
+                            .find(|order| order.order_id == *order_id)
+                    }) {
+                        order.reduce_only = true;
+                    }
+                }
+            }
         } else {
             return Err(GDEXError::MarketplaceExistence);
         };
         Ok(())
     }
 
-    pub fn get_marketplaces(&self) -> &HashMap<AccountPubKey, Marketplace> {
+    pub fn get_marketplaces(&self) -> &BTreeMap<AccountPubKey, Marketplace> {
         &self.market_places
     }
 
+    /// Moves the marketplaces of the given admins into a controller of their own, which executes a lane of
+    /// transactions alongside others, their markets are linked to the lane's bank once the lane router is initialized
+    pub fn split_lane(&mut self, market_admins: &[AccountPubKey]) -> FuturesController {
+        FuturesController {
+            controller_account: self.controller_account.clone(),
+            market_places: market_admins
+                .iter()
+                .filter_map(|market_admin| self.market_places.remove_entry(market_admin))
+                .collect(),
+            ..FuturesController::default()
+        }
+    }
+
+    /// Moves the marketplaces of a lane back, they must be initialized again to link them to this controller's bank
+    pub fn merge_lane(&mut self, lane: FuturesController) {
+        self.market_places.extend(lane.market_places);
+    }
+
     fn liquidate(
         &mut self,
         sender: AccountPubKey,
@@ -441,22 +535,56 @@ impl FuturesController {
         market_admin: AccountPubKey,
         request: CancelOrderRequest,
     ) -> Result<(), GDEXError> {
-        if let Some(market_place) = self.market_places.get_mut(&market_admin) {
-            let market = market_place
-                .markets
-                .get_mut(&request.base_asset_id)
-                .ok_or(GDEXError::MarketExistence)?;
-            let is_owned = market
-                .order_to_account
-                .get(&request.order_id)
-                .ok_or(GDEXError::OrderRequest)
-                .unwrap()
-                .eq(&sender);
-            if is_owned {
-                market.place_cancel_order(&sender, &request)?;
-            }
+        let market_place = self
+            .market_places
+            .get_mut(&market_admin)
+            .ok_or(GDEXError::MarketplaceExistence)?;
+        let market = market_place
+            .markets
+            .get_mut(&request.base_asset_id)
+            .ok_or(GDEXError::MarketExistence)?;
+        // orders can only be cancelled by the account which placed them
+        let owner = market
+            .order_to_account
+            .get(&request.order_id)
+            .ok_or(GDEXError::OrderRequest)?;
+        if owner != &sender {
+            return Err(GDEXError::OrderRequest);
         }
-        Err(GDEXError::MarketplaceExistence)
+        market.place_cancel_order(&sender, &request)?;
+        Ok(())
+    }
+
+    /// Admin of the marketplace a request is addressed to, admin requests are addressed to the sender's own
+    pub fn get_request_market_admin(&self, transaction: &Transaction) -> Result<AccountPubKey, GDEXError> {
+        let request_type: FuturesRequestType = transaction.get_request_type()?;
+        let market_admin = match request_type {
+            FuturesRequestType::CreateMarketplace
+            | FuturesRequestType::CreateMarket
+            | FuturesRequestType::UpdateMarketParams
+            | FuturesRequestType::UpdateTime
+            | FuturesRequestType::UpdatePrices
+            | FuturesRequestType::UpdateMarketFees => return transaction.get_sender(),
+            FuturesRequestType::AccountDeposit => {
+                deserialize_protobuf::<AccountDepositRequest>(&transaction.request_bytes)?.market_admin
+            }
+            FuturesRequestType::AccountWithdrawal => {
+                deserialize_protobuf::<AccountWithdrawalRequest>(&transaction.request_bytes)?.market_admin
+            }
+            FuturesRequestType::FuturesLimitOrder => {
+                deserialize_protobuf::<FuturesLimitOrderRequest>(&transaction.request_bytes)?.market_admin
+            }
+            FuturesRequestType::CancelOrder => {
+                deserialize_protobuf::<CancelOrderRequest>(&transaction.request_bytes)?.market_admin
+            }
+            FuturesRequestType::CancelAll => {
+                deserialize_protobuf::<CancelAllRequest>(&transaction.request_bytes)?.market_admin
+            }
+            FuturesRequestType::Liquidate => {
+                deserialize_protobuf::<LiquidateRequest>(&transaction.request_bytes)?.market_admin
+            }
+        };
+        AccountPubKey::from_bytes(&market_admin).map_err(|_| GDEXError::InvalidAddress)
     }
 
     pub fn get_marketplace_state(&self, market_admin: &AccountPubKey) -> Result<MarketplaceState, GDEXError> {
@@ -580,6 +708,14 @@ impl Controller<crate::futures::rpc_server::JSONRPCService> for FuturesControlle
     fn initialize(&mut self, controller_router: &ControllerRouter) {
         self.bank_controller = Arc::clone(&controller_router.bank_controller);
         self.event_manager = Arc::clone(&controller_router.event_manager);
+        // markets restored from a catchup state must be linked to the shared state, which is not serialized
+        for market_place in self.market_places.values_mut() {
+            for market in market_place.markets.values_mut() {
+                market.marketplace_deposits = Arc::downgrade(&market_place.deposits);
+                market.bank_controller = Arc::clone(&self.bank_controller);
+                market.event_manager = Arc::clone(&self.event_manager);
+            }
+        }
     }
 
     fn initialize_controller_account(&self) -> Result<(), GDEXError> {
@@ -590,6 +726,36 @@ impl Controller<crate::futures::rpc_server::JSONRPCService> for FuturesControlle
         Ok(())
     }
 
+    // a request which cannot be decoded fails before it modifies any marketplace, so it needs no checkpoint
+    type Checkpoint = Option<FuturesCheckpoint>;
+
+    fn checkpoint(&self, transaction: &Transaction) -> Self::Checkpoint {
+        let market_admin = self.get_request_market_admin(transaction).ok()?;
+        // markets hold a reference to the deposits of their marketplace, so those are copied out separately
+        let market_place = self.market_places.get(&market_admin).map(|market_place| {
+            let deposits = market_place.deposits.lock().unwrap().clone();
+            (market_place.clone(), deposits)
+        });
+        Some(FuturesCheckpoint {
+            market_admin,
+            market_place,
+        })
+    }
+
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        if let Some(checkpoint) = checkpoint {
+            match checkpoint.market_place {
+                Some((market_place, deposits)) => {
+                    *market_place.deposits.lock().unwrap() = deposits;
+                    self.market_places.insert(checkpoint.market_admin, market_place);
+                }
+                None => {
+                    self.market_places.remove(&checkpoint.market_admin);
+                }
+            }
+        }
+    }
+
     fn handle_consensus_transaction(&mut self, transaction: &Transaction) -> Result<(), GDEXError> {
         let sender = transaction.get_sender()?;
         let request_type: FuturesRequestType = transaction.get_request_type()?;
@@ -649,11 +815,15 @@ impl Controller<crate::futures::rpc_server::JSONRPCService> for FuturesControlle
                     AccountPubKey::from_bytes(&request.market_admin).map_err(|_| GDEXError::InvalidAddress)?;
                 self.liquidate(sender, market_admin, request)?;
             }
+            FuturesRequestType::UpdateMarketFees => {
+                let request: UpdateMarketFeesRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                self.update_market_fees(sender, request)?;
+            }
         }
         Ok(())
     }
 
-    fn non_critical_process_end_of_block(&self, rpc_store: &RPCStore, block_number: u64) {
+    fn non_critical_process_end_of_block(&mut self, rpc_store: &RPCStore, block_number: u64) {
         // write out orderbook depth every ORDERBOOK_DEPTH_FREQUENCY
         if block_number % ORDERBOOK_DEPTH_FREQUENCY == 0 {
             let orderbook_depths = self.generate_orderbook_depths();
@@ -709,7 +879,12 @@ impl OrderBookWrapper for FuturesMarket {
     }
 
     // SETTERS
-    fn set_order(&mut self, order_id: OrderId, account: AccountPubKey) -> Result<(), GDEXError> {
+    fn set_order(
+        &mut self,
+        order_id: OrderId,
+        account: AccountPubKey,
+        _client_order_id: ClientOrderId,
+    ) -> Result<(), GDEXError> {
         // order id should be constantly increasing
         if self.order_to_account.contains_key(&order_id) {
             return Err(GDEXError::OrderRequest);
@@ -758,6 +933,7 @@ impl OrderBookWrapper for FuturesMarket {
                 side: side as u64,
                 price,
                 quantity,
+                reduce_only: false,
             });
         Ok(())
     }
@@ -817,6 +993,58 @@ impl OrderBookWrapper for FuturesMarket {
         Ok(())
     }
 
+    // positions are margined rather than escrowed, so a market fill settles like any other fill
+    fn update_state_on_market_fill(
+        &mut self,
+        account: &AccountPubKey,
+        order_id: u64,
+        side: OrderSide,
+        price: u64,
+        quantity: u64,
+    ) -> Result<(), GDEXError> {
+        self.update_state_on_fill(account, order_id, side, price, quantity)
+    }
+
+    fn charge_fill_fee(
+        &mut self,
+        account: &AccountPubKey,
+        _order_id: u64,
+        liquidity: Liquidity,
+        price: u64,
+        quantity: u64,
+    ) -> Result<i64, GDEXError> {
+        let fee = self
+            .fee_schedule
+            .compute_fee(liquidity, price, quantity)
+            .ok_or(GDEXError::Conversion)?;
+        if fee == 0 {
+            return Ok(fee);
+        }
+
+        // fees are taken from the deposit, the quote backing it moves between the controller and fee accounts
+        let marketplace_deposits = self
+            .marketplace_deposits
+            .upgrade()
+            .ok_or(GDEXError::MarketplaceExistence)?;
+        let mut deposits_lock = marketplace_deposits.lock().unwrap();
+        let account_deposit = deposits_lock.get_mut(account).ok_or(GDEXError::AccountLookup)?;
+
+        // a fee may not be taken from a deposit which cannot cover it
+        let remaining_deposit = account_deposit.checked_sub(fee).ok_or(GDEXError::Conversion)?;
+        if fee > 0 && remaining_deposit < 0 {
+            return Err(GDEXError::InsufficientCollateral);
+        }
+
+        let mut bank_controller = self.bank_controller.lock().unwrap();
+        if fee > 0 {
+            bank_controller.collect_fee(&self.controller_account, self.quote_asset_id, fee.unsigned_abs())?;
+        } else {
+            bank_controller.pay_rebate(&self.controller_account, self.quote_asset_id, fee.unsigned_abs())?;
+        }
+        *account_deposit = remaining_deposit;
+        Ok(fee)
+    }
+
     #[allow(clippy::collapsible_else_if)]
     fn update_state_on_update(
         &mut self,
@@ -859,13 +1087,24 @@ impl OrderBookWrapper for FuturesMarket {
         side: u64,
         price: u64,
         quantity: u64,
+        fee: i64,
     ) {
-        self.emit_event(&FuturesOrderFillEvent::new(account, order_id, side, price, quantity));
+        self.emit_event(&FuturesOrderPartialFillEvent::new(
+            account, order_id, side, price, quantity, fee,
+        ));
     }
 
-    fn emit_order_fill_event(&mut self, account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64) {
-        self.emit_event(&FuturesOrderPartialFillEvent::new(
-            account, order_id, side, price, quantity,
+    fn emit_order_fill_event(
+        &mut self,
+        account: &AccountPubKey,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        fee: i64,
+    ) {
+        self.emit_event(&FuturesOrderFillEvent::new(
+            account, order_id, side, price, quantity, fee,
         ));
     }
 
@@ -18,6 +18,15 @@ pub struct UpdateMarketParamsRequest {
     pub max_leverage: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateMarketFeesRequest {
+    #[prost(uint64, tag="1")]
+    pub base_asset_id: u64,
+    #[prost(sint64, tag="2")]
+    pub maker_fee_bps: i64,
+    #[prost(sint64, tag="3")]
+    pub taker_fee_bps: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateTimeRequest {
     #[prost(uint64, tag="1")]
     pub latest_time: u64,
@@ -62,6 +71,12 @@ pub struct FuturesLimitOrderRequest {
     pub quantity: u64,
     #[prost(bytes="bytes", tag="6")]
     pub market_admin: ::prost::bytes::Bytes,
+    #[prost(uint64, tag="7")]
+    pub time_in_force: u64,
+    #[prost(bool, tag="8")]
+    pub post_only: bool,
+    #[prost(bool, tag="9")]
+    pub reduce_only: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CancelAllRequest {
@@ -110,6 +125,8 @@ pub struct FuturesOrderFillEvent {
     pub price: u64,
     #[prost(uint64, tag="5")]
     pub quantity: u64,
+    #[prost(int64, tag="6")]
+    pub fee: i64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FuturesOrderPartialFillEvent {
@@ -123,6 +140,8 @@ pub struct FuturesOrderPartialFillEvent {
     pub price: u64,
     #[prost(uint64, tag="5")]
     pub quantity: u64,
+    #[prost(int64, tag="6")]
+    pub fee: i64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FuturesOrderUpdateEvent {
@@ -173,6 +192,7 @@ pub enum FuturesRequestType {
     CancelOrder = 8,
     CancelAll = 9,
     Liquidate = 10,
+    UpdateMarketFees = 11,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -7,7 +7,9 @@ use crate::router::ControllerType;
 use fermi_types::{
     account::AccountPubKey,
     error::GDEXError,
-    transaction::{Event, EventTypeEnum, Request, RequestTypeEnum},
+    event::DecodedEvent,
+    transaction::{ControllerRequest, Event, EventTypeEnum, Request, RequestTypeEnum},
+    utils,
 };
 
 // external
@@ -32,6 +34,9 @@ impl From<FuturesLimitOrderRequest> for LimitOrderRequest {
             side: request.side,
             price: request.price,
             quantity: request.quantity,
+            client_order_id: 0,
+            time_in_force: request.time_in_force,
+            post_only: request.post_only,
         }
     }
 }
@@ -52,6 +57,7 @@ impl RequestTypeEnum for FuturesRequestType {
             8 => Ok(FuturesRequestType::CancelOrder),
             9 => Ok(FuturesRequestType::CancelAll),
             10 => Ok(FuturesRequestType::Liquidate),
+            11 => Ok(FuturesRequestType::UpdateMarketFees),
             _ => Err(GDEXError::DeserializationError),
         }
     }
@@ -65,6 +71,7 @@ impl EventTypeEnum for FuturesEventType {
             2 => Ok(FuturesEventType::OrderPartialFill),
             3 => Ok(FuturesEventType::OrderUpdate),
             4 => Ok(FuturesEventType::OrderCancel),
+            5 => Ok(FuturesEventType::LiquidateEvent),
             _ => Err(GDEXError::DeserializationError),
         }
     }
@@ -126,6 +133,27 @@ impl Request for UpdateMarketParamsRequest {
     }
 }
 
+// update market fees
+
+impl UpdateMarketFeesRequest {
+    pub fn new(base_asset_id: u64, maker_fee_bps: i64, taker_fee_bps: i64) -> Self {
+        UpdateMarketFeesRequest {
+            base_asset_id,
+            maker_fee_bps,
+            taker_fee_bps,
+        }
+    }
+}
+
+impl Request for UpdateMarketFeesRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Futures as i32
+    }
+    fn get_request_type_id() -> i32 {
+        FuturesRequestType::UpdateMarketFees as i32
+    }
+}
+
 // update time
 
 impl UpdateTimeRequest {
@@ -211,6 +239,9 @@ impl FuturesLimitOrderRequest {
         price: u64,
         quantity: u64,
         market_admin: &AccountPubKey,
+        time_in_force: u64,
+        post_only: bool,
+        reduce_only: bool,
     ) -> Self {
         FuturesLimitOrderRequest {
             base_asset_id,
@@ -219,6 +250,9 @@ impl FuturesLimitOrderRequest {
             price,
             quantity,
             market_admin: Bytes::from(market_admin.as_ref().to_vec()),
+            time_in_force,
+            post_only,
+            reduce_only,
         }
     }
 }
@@ -304,16 +338,29 @@ impl Event for FuturesOrderNewEvent {
     }
 }
 
+impl From<FuturesOrderNewEvent> for DecodedEvent {
+    fn from(event: FuturesOrderNewEvent) -> Self {
+        DecodedEvent::FuturesOrderNew {
+            account: utils::encode_bytes_hex(&event.account),
+            order_id: event.order_id,
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+        }
+    }
+}
+
 // order fill
 
 impl FuturesOrderFillEvent {
-    pub fn new(account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64) -> Self {
+    pub fn new(account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64, fee: i64) -> Self {
         FuturesOrderFillEvent {
             account: Bytes::from(account.as_ref().to_vec()),
             order_id,
             side,
             price,
             quantity,
+            fee,
         }
     }
 }
@@ -327,16 +374,30 @@ impl Event for FuturesOrderFillEvent {
     }
 }
 
+impl From<FuturesOrderFillEvent> for DecodedEvent {
+    fn from(event: FuturesOrderFillEvent) -> Self {
+        DecodedEvent::FuturesOrderFill {
+            account: utils::encode_bytes_hex(&event.account),
+            order_id: event.order_id,
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+            fee: event.fee,
+        }
+    }
+}
+
 // order partial fill
 
 impl FuturesOrderPartialFillEvent {
-    pub fn new(account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64) -> Self {
+    pub fn new(account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64, fee: i64) -> Self {
         FuturesOrderPartialFillEvent {
             account: Bytes::from(account.as_ref().to_vec()),
             order_id,
             side,
             price,
             quantity,
+            fee,
         }
     }
 }
@@ -350,6 +411,19 @@ impl Event for FuturesOrderPartialFillEvent {
     }
 }
 
+impl From<FuturesOrderPartialFillEvent> for DecodedEvent {
+    fn from(event: FuturesOrderPartialFillEvent) -> Self {
+        DecodedEvent::FuturesOrderPartialFill {
+            account: utils::encode_bytes_hex(&event.account),
+            order_id: event.order_id,
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+            fee: event.fee,
+        }
+    }
+}
+
 // order update
 
 impl FuturesOrderUpdateEvent {
@@ -373,6 +447,18 @@ impl Event for FuturesOrderUpdateEvent {
     }
 }
 
+impl From<FuturesOrderUpdateEvent> for DecodedEvent {
+    fn from(event: FuturesOrderUpdateEvent) -> Self {
+        DecodedEvent::FuturesOrderUpdate {
+            account: utils::encode_bytes_hex(&event.account),
+            order_id: event.order_id,
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+        }
+    }
+}
+
 // order cancel
 
 impl FuturesOrderCancelEvent {
@@ -405,6 +491,18 @@ impl Event for FuturesLiquidateEvent {
     }
 }
 
+impl From<FuturesLiquidateEvent> for DecodedEvent {
+    fn from(event: FuturesLiquidateEvent) -> Self {
+        DecodedEvent::FuturesLiquidate {
+            sender: utils::encode_bytes_hex(&event.sender),
+            target_account: utils::encode_bytes_hex(&event.target_account),
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+        }
+    }
+}
+
 impl Event for FuturesOrderCancelEvent {
     fn get_controller_id() -> i32 {
         ControllerType::Futures as i32
@@ -414,6 +512,104 @@ impl Event for FuturesOrderCancelEvent {
     }
 }
 
+impl From<FuturesOrderCancelEvent> for DecodedEvent {
+    fn from(event: FuturesOrderCancelEvent) -> Self {
+        DecodedEvent::FuturesOrderCancel {
+            account: utils::encode_bytes_hex(&event.account),
+            order_id: event.order_id,
+        }
+    }
+}
+
+// REQUEST BUILDERS
+
+pub fn create_create_marketplace_request(quote_asset_id: u64) -> ControllerRequest {
+    ControllerRequest::new(&CreateMarketplaceRequest::new(quote_asset_id))
+}
+
+pub fn create_create_market_request(base_asset_id: u64) -> ControllerRequest {
+    ControllerRequest::new(&CreateMarketRequest::new(base_asset_id))
+}
+
+pub fn create_update_market_params_request(base_asset_id: u64, max_leverage: u64) -> ControllerRequest {
+    ControllerRequest::new(&UpdateMarketParamsRequest::new(base_asset_id, max_leverage))
+}
+
+pub fn create_update_market_fees_request(
+    base_asset_id: u64,
+    maker_fee_bps: i64,
+    taker_fee_bps: i64,
+) -> ControllerRequest {
+    ControllerRequest::new(&UpdateMarketFeesRequest::new(
+        base_asset_id,
+        maker_fee_bps,
+        taker_fee_bps,
+    ))
+}
+
+pub fn create_update_time_request(latest_time: u64) -> ControllerRequest {
+    ControllerRequest::new(&UpdateTimeRequest::new(latest_time))
+}
+
+pub fn create_update_prices_request(price_entries: Vec<PriceEntry>) -> ControllerRequest {
+    ControllerRequest::new(&UpdatePricesRequest::new(price_entries))
+}
+
+pub fn create_account_deposit_request(quantity: i64, market_admin: &AccountPubKey) -> ControllerRequest {
+    ControllerRequest::new(&AccountDepositRequest::new(quantity, market_admin))
+}
+
+pub fn create_account_withdrawal_request(quantity: u64, market_admin: &AccountPubKey) -> ControllerRequest {
+    ControllerRequest::new(&AccountWithdrawalRequest::new(quantity, market_admin))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_futures_limit_order_request(
+    base_asset_id: u64,
+    quote_asset_id: u64,
+    side: u64,
+    price: u64,
+    quantity: u64,
+    market_admin: &AccountPubKey,
+    time_in_force: u64,
+    post_only: bool,
+    reduce_only: bool,
+) -> ControllerRequest {
+    ControllerRequest::new(&FuturesLimitOrderRequest::new(
+        base_asset_id,
+        quote_asset_id,
+        side,
+        price,
+        quantity,
+        market_admin,
+        time_in_force,
+        post_only,
+        reduce_only,
+    ))
+}
+
+pub fn create_cancel_all_request(target: &AccountPubKey, market_admin: &AccountPubKey) -> ControllerRequest {
+    ControllerRequest::new(&CancelAllRequest::new(target, market_admin))
+}
+
+pub fn create_liquidate_request(
+    base_asset_id: u64,
+    quote_asset_id: u64,
+    side: u64,
+    quantity: u64,
+    market_admin: &AccountPubKey,
+    target: &AccountPubKey,
+) -> ControllerRequest {
+    ControllerRequest::new(&LiquidateRequest::new(
+        base_asset_id,
+        quote_asset_id,
+        side,
+        quantity,
+        market_admin,
+        target,
+    ))
+}
+
 /// Begin externally available testing functions
 #[cfg(any(test, feature = "testing"))]
 pub mod futures_controller_test_functions {
@@ -442,6 +638,9 @@ pub mod futures_controller_test_functions {
             price,
             quantity,
             market_admin: bytes::Bytes::from(kp_admin.public().as_bytes().to_vec()),
+            time_in_force: 0,
+            post_only: false,
+            reduce_only: false,
         };
 
         let dummy_batch_digest = CertificateDigest::new([0; DIGEST_LEN]);
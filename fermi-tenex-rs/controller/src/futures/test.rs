@@ -15,7 +15,7 @@ pub mod futures_tests {
         asset::AssetId,
         crypto::KeypairTraits,
         error::GDEXError,
-        order_book::OrderSide,
+        order_book::{OrderSide, TimeInForce},
         transaction::{ExecutionEvents, Transaction},
     };
     // mysten
@@ -95,6 +95,13 @@ pub mod futures_tests {
             Ok(())
         }
 
+        // executes the transaction with the next nonce of its sender
+        fn execute(&self, mut transaction: Transaction) -> Result<ExecutionEvents, GDEXError> {
+            let controller_router = self.controller_router.lock().unwrap();
+            transaction.set_nonce(controller_router.get_next_nonce(&transaction.get_sender()?));
+            controller_router.handle_consensus_transaction(&transaction)
+        }
+
         pub fn create_marketplace(&self) -> Result<ExecutionEvents, GDEXError> {
             let request = CreateMarketplaceRequest::new(self.quote_asset_id);
             let transaction = Transaction::new(
@@ -102,10 +109,7 @@ pub mod futures_tests {
                 CertificateDigest::new([0; fastcrypto::DIGEST_LEN]),
                 &request,
             );
-            self.controller_router
-                .lock()
-                .unwrap()
-                .handle_consensus_transaction(&transaction)
+            self.execute(transaction)
         }
 
         pub fn create_market(&self) -> Result<ExecutionEvents, GDEXError> {
@@ -115,10 +119,7 @@ pub mod futures_tests {
                 CertificateDigest::new([0; fastcrypto::DIGEST_LEN]),
                 &request,
             );
-            self.controller_router
-                .lock()
-                .unwrap()
-                .handle_consensus_transaction(&transaction)
+            self.execute(transaction)
         }
 
         pub fn update_market_params(&self) -> Result<ExecutionEvents, GDEXError> {
@@ -128,10 +129,17 @@ pub mod futures_tests {
                 CertificateDigest::new([0; fastcrypto::DIGEST_LEN]),
                 &request,
             );
-            self.controller_router
-                .lock()
-                .unwrap()
-                .handle_consensus_transaction(&transaction)
+            self.execute(transaction)
+        }
+
+        pub fn update_market_fees(&self, maker_fee_bps: i64, taker_fee_bps: i64) -> Result<ExecutionEvents, GDEXError> {
+            let request = UpdateMarketFeesRequest::new(self.base_asset_id, maker_fee_bps, taker_fee_bps);
+            let transaction = Transaction::new(
+                self.admin_key.public(),
+                CertificateDigest::new([0; fastcrypto::DIGEST_LEN]),
+                &request,
+            );
+            self.execute(transaction)
         }
 
         pub fn update_time(&self, latest_time: u64) -> Result<ExecutionEvents, GDEXError> {
@@ -141,10 +149,7 @@ pub mod futures_tests {
                 CertificateDigest::new([0; fastcrypto::DIGEST_LEN]),
                 &request,
             );
-            self.controller_router
-                .lock()
-                .unwrap()
-                .handle_consensus_transaction(&transaction)
+            self.execute(transaction)
         }
 
         pub fn update_prices(&self, price_entries: Vec<PriceEntry>) -> Result<ExecutionEvents, GDEXError> {
@@ -154,10 +159,7 @@ pub mod futures_tests {
                 CertificateDigest::new([0; fastcrypto::DIGEST_LEN]),
                 &request,
             );
-            self.controller_router
-                .lock()
-                .unwrap()
-                .handle_consensus_transaction(&transaction)
+            self.execute(transaction)
         }
 
         pub fn account_deposit(&self, quantity: u64, sender: AccountPubKey) -> Result<ExecutionEvents, GDEXError> {
@@ -166,10 +168,7 @@ pub mod futures_tests {
                 self.admin_key.public(),
             );
             let transaction = Transaction::new(&sender, CertificateDigest::new([0; fastcrypto::DIGEST_LEN]), &request);
-            self.controller_router
-                .lock()
-                .unwrap()
-                .handle_consensus_transaction(&transaction)
+            self.execute(transaction)
         }
 
         pub fn initialize_futures_controller(&self) -> Result<(), GDEXError> {
@@ -192,6 +191,28 @@ pub mod futures_tests {
             side: u64,
             price: u64,
             quantity: u64,
+        ) -> Result<ExecutionEvents, GDEXError> {
+            self.futures_limit_order_with_flags(
+                user_index,
+                side,
+                price,
+                quantity,
+                TimeInForce::GoodTilCancelled as u64,
+                false,
+                false,
+            )
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        pub fn futures_limit_order_with_flags(
+            &self,
+            user_index: usize,
+            side: u64,
+            price: u64,
+            quantity: u64,
+            time_in_force: u64,
+            post_only: bool,
+            reduce_only: bool,
         ) -> Result<ExecutionEvents, GDEXError> {
             let request = FuturesLimitOrderRequest::new(
                 self.base_asset_id,
@@ -200,6 +221,9 @@ pub mod futures_tests {
                 price,
                 quantity,
                 self.admin_key.public(),
+                time_in_force,
+                post_only,
+                reduce_only,
             );
 
             let transaction = Transaction::new(
@@ -207,10 +231,7 @@ pub mod futures_tests {
                 CertificateDigest::new([0; fastcrypto::DIGEST_LEN]),
                 &request,
             );
-            self.controller_router
-                .lock()
-                .unwrap()
-                .handle_consensus_transaction(&transaction)
+            self.execute(transaction)
         }
 
         pub fn cancel_open_orders(
@@ -225,10 +246,7 @@ pub mod futures_tests {
                 CertificateDigest::new([0; fastcrypto::DIGEST_LEN]),
                 &request,
             );
-            self.controller_router
-                .lock()
-                .unwrap()
-                .handle_consensus_transaction(&transaction)
+            self.execute(transaction)
         }
 
         pub fn liquidate(
@@ -252,10 +270,7 @@ pub mod futures_tests {
                 CertificateDigest::new([0; fastcrypto::DIGEST_LEN]),
                 &request,
             );
-            self.controller_router
-                .lock()
-                .unwrap()
-                .handle_consensus_transaction(&transaction)
+            self.execute(transaction)
         }
 
         // UTIILITY FUNCTIONS
@@ -433,6 +448,49 @@ pub mod futures_tests {
         );
     }
 
+    #[test]
+    fn fees_charged_on_fills() {
+        let futures_tester = FuturesControllerTester::new();
+        futures_tester.initialize();
+        // a maker rebate larger than the taker fee is rejected
+        assert_eq!(
+            futures_tester.update_market_fees(-40, 30).unwrap_err(),
+            GDEXError::InvalidFeeSchedule
+        );
+        futures_tester.update_market_fees(-10, 30).unwrap();
+
+        let (maker_index, maker_side, maker_price, maker_quantity) = (0, OrderSide::Bid as u64, 10_000_000, 100);
+        let (taker_index, taker_side, taker_price, taker_quantity) = (1, OrderSide::Ask as u64, 10_000_000 - 1, 10);
+        let maker_deposit = futures_tester.get_account_deposit(maker_index).unwrap();
+        let taker_deposit = futures_tester.get_account_deposit(taker_index).unwrap();
+
+        futures_tester
+            .futures_limit_order(maker_index, maker_side, maker_price, maker_quantity)
+            .unwrap();
+        futures_tester
+            .futures_limit_order(taker_index, taker_side, taker_price, taker_quantity)
+            .unwrap();
+
+        // the fill notional is 100_000_000, the taker pays 30 bps and the maker is rebated 10 bps
+        assert_eq!(
+            futures_tester.get_account_deposit(maker_index).unwrap(),
+            maker_deposit + 100_000
+        );
+        assert_eq!(
+            futures_tester.get_account_deposit(taker_index).unwrap(),
+            taker_deposit - 300_000
+        );
+        let accrued_fees = futures_tester
+            .controller_router
+            .lock()
+            .unwrap()
+            .bank_controller
+            .lock()
+            .unwrap()
+            .get_accrued_fees(QUOTE_ASSET_ID);
+        assert_eq!(accrued_fees, 200_000);
+    }
+
     #[test]
     fn exceed_collateral() {
         let futures_tester = FuturesControllerTester::new();
@@ -445,6 +503,126 @@ pub mod futures_tests {
         assert_eq!(result.unwrap_err(), GDEXError::InsufficientCollateral);
     }
 
+    #[test]
+    fn reduce_only() {
+        let futures_tester = FuturesControllerTester::new();
+        futures_tester.initialize();
+        let (maker_index, maker_price, maker_quantity) = (0, 10_000_000, 100);
+        let (taker_index, taker_price, taker_quantity) = (1, 10_000_000 - 1, 10);
+        let gtc = TimeInForce::GoodTilCancelled as u64;
+
+        // without a position there is nothing to reduce
+        let result = futures_tester.futures_limit_order_with_flags(
+            taker_index,
+            OrderSide::Ask as u64,
+            taker_price,
+            taker_quantity,
+            gtc,
+            false,
+            true,
+        );
+        assert_eq!(result.unwrap_err(), GDEXError::ReduceOnlyIncreasesPosition);
+
+        // open a short position for the taker
+        futures_tester
+            .futures_limit_order(maker_index, OrderSide::Bid as u64, maker_price, maker_quantity)
+            .unwrap();
+        futures_tester
+            .futures_limit_order(taker_index, OrderSide::Ask as u64, taker_price, taker_quantity)
+            .unwrap();
+
+        // adding to the short is rejected
+        let result = futures_tester.futures_limit_order_with_flags(
+            taker_index,
+            OrderSide::Ask as u64,
+            taker_price,
+            taker_quantity,
+            gtc,
+            false,
+            true,
+        );
+        assert_eq!(result.unwrap_err(), GDEXError::ReduceOnlyIncreasesPosition);
+
+        // flipping the short into a long is rejected
+        let result = futures_tester.futures_limit_order_with_flags(
+            taker_index,
+            OrderSide::Bid as u64,
+            taker_price,
+            taker_quantity + 1,
+            gtc,
+            false,
+            true,
+        );
+        assert_eq!(result.unwrap_err(), GDEXError::ReduceOnlyIncreasesPosition);
+
+        // closing the short is accepted
+        futures_tester
+            .futures_limit_order_with_flags(
+                taker_index,
+                OrderSide::Bid as u64,
+                taker_price,
+                taker_quantity,
+                gtc,
+                false,
+                true,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn stacked_reduce_only_orders() {
+        let futures_tester = FuturesControllerTester::new();
+        futures_tester.initialize();
+        let (maker_index, maker_price, maker_quantity) = (0, 10_000_000, 100);
+        let (taker_index, taker_price, taker_quantity) = (1, 10_000_000 - 1, 10);
+        let gtc = TimeInForce::GoodTilCancelled as u64;
+
+        // open a short position for the taker
+        futures_tester
+            .futures_limit_order(maker_index, OrderSide::Bid as u64, maker_price, maker_quantity)
+            .unwrap();
+        futures_tester
+            .futures_limit_order(taker_index, OrderSide::Ask as u64, taker_price, taker_quantity)
+            .unwrap();
+
+        // the first reduce-only bid rests on the book, there are no asks to cross
+        futures_tester
+            .futures_limit_order_with_flags(taker_index, OrderSide::Bid as u64, taker_price, 6, gtc, false, true)
+            .unwrap();
+
+        // a second one which together with the first would flip the short is rejected
+        let result = futures_tester.futures_limit_order_with_flags(
+            taker_index,
+            OrderSide::Bid as u64,
+            taker_price,
+            5,
+            gtc,
+            false,
+            true,
+        );
+        assert_eq!(result.unwrap_err(), GDEXError::ReduceOnlyIncreasesPosition);
+
+        // the rest of the short may still be reduced
+        futures_tester
+            .futures_limit_order_with_flags(taker_index, OrderSide::Bid as u64, taker_price, 4, gtc, false, true)
+            .unwrap();
+        let result = futures_tester.futures_limit_order_with_flags(
+            taker_index,
+            OrderSide::Bid as u64,
+            taker_price,
+            1,
+            gtc,
+            false,
+            true,
+        );
+        assert_eq!(result.unwrap_err(), GDEXError::ReduceOnlyIncreasesPosition);
+
+        // orders without the flag are not limited by the position
+        futures_tester
+            .futures_limit_order(taker_index, OrderSide::Bid as u64, taker_price, 1)
+            .unwrap();
+    }
+
     #[test]
     fn liquidate_long_full() {
         let trade_size = 960;
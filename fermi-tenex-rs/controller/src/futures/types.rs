@@ -1,15 +1,16 @@
 // crate
+use crate::bank::controller::BankController;
 use crate::event_manager::EventManager;
 use crate::utils::engine::order_book::{OrderId, Orderbook};
 
 // fermi
-use fermi_types::{account::AccountPubKey, asset::AssetId};
+use fermi_types::{account::AccountPubKey, asset::AssetId, order_book::FeeSchedule};
 
 // external
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::BTreeMap,
     sync::{Arc, Mutex, Weak},
 };
 
@@ -27,6 +28,8 @@ pub struct FuturesOrder {
     pub side: u64,
     pub quantity: u64,
     pub price: u64,
+    // resting reduce-only orders count against the position they may close
+    pub reduce_only: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
@@ -85,13 +88,20 @@ pub struct FuturesMarket {
     pub open_interest: u64,
     pub last_traded_price: AssetPrice,
     pub oracle_price: AssetPrice,
-    pub order_to_account: HashMap<OrderId, AccountPubKey>,
-    pub accounts: HashMap<AccountPubKey, FuturesAccount>,
+    pub order_to_account: BTreeMap<OrderId, AccountPubKey>,
+    pub accounts: BTreeMap<AccountPubKey, FuturesAccount>,
     pub orderbook: Orderbook,
     // reference to parent Marketplace deposits
-    pub marketplace_deposits: Weak<Mutex<HashMap<AccountPubKey, i64>>>,
+    #[serde(skip)]
+    pub marketplace_deposits: Weak<Mutex<BTreeMap<AccountPubKey, i64>>>,
     pub liquidation_fee_percent: u64,
+    pub fee_schedule: FeeSchedule,
+    // futures controller account which holds marketplace deposits in the bank
+    pub controller_account: AccountPubKey,
     // shared
+    #[serde(skip)]
+    pub bank_controller: Arc<Mutex<BankController>>,
+    #[serde(skip)]
     pub event_manager: Arc<Mutex<EventManager>>,
 }
 
@@ -99,10 +109,10 @@ pub struct FuturesMarket {
 pub struct Marketplace {
     pub quote_asset_id: u64,
     pub latest_time: u64,
-    pub markets: HashMap<AssetId, FuturesMarket>,
+    pub markets: BTreeMap<AssetId, FuturesMarket>,
     // i64 is necessary because deposits can go negative given inadequate liquidations
     // Arc + Mutex wrapper is necessary as a reference to deposits must be passed to each FuturesMarket
-    pub deposits: Arc<Mutex<HashMap<AccountPubKey, i64>>>,
+    pub deposits: Arc<Mutex<BTreeMap<AccountPubKey, i64>>>,
 }
 
 // market base asset id, open orders, position
@@ -4,6 +4,8 @@ pub mod controller;
 
 pub mod event_manager;
 
+pub mod event_registry;
+
 pub mod bank;
 
 pub mod consensus;
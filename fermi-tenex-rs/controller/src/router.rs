@@ -2,16 +2,26 @@
 
 // crate
 use crate::{
-    bank::controller::BankController, consensus::controller::ConsensusController, controller::Controller,
-    event_manager::EventManager, futures::controller::FuturesController, spot::controller::SpotController,
-    stake::controller::StakeController,
+    bank::{
+        controller::BankController,
+        proto::{BankRequestType, PaymentRequest},
+    },
+    consensus::controller::ConsensusController,
+    controller::Controller,
+    event_manager::EventManager,
+    futures::controller::{FuturesCheckpoint, FuturesController},
+    spot::controller::{SpotCheckpoint, SpotController},
+    stake::controller::{StakeCheckpoint, StakeController},
 };
 
 // fermi
 use fermi_types::{
+    account::AccountPubKey,
+    asset::AssetPairKey,
+    block::{BlockDigest, BlockNumber},
     error::GDEXError,
     store::{CatchupState, CriticalPathStore, RPCStore, RPCStoreHandle},
-    transaction::{ExecutionEvents, Transaction},
+    transaction::{deserialize_protobuf, ExecutionEvents, Transaction},
 };
 
 // mysten
@@ -20,38 +30,37 @@ use fermi_types::{
 use jsonrpsee::RpcModule;
 
 // constants
-const CATCHUP_STATE_FREQUENCY: u64 = 100;
+pub const CATCHUP_STATE_FREQUENCY: u64 = 100;
 
 // external
 use serde::{Deserialize, Serialize};
+use std::mem;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 // ENUMS
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[repr(i32)]
-pub enum ControllerType {
-    Bank = 0,
-    Stake = 1,
-    Spot = 2,
-    Consensus = 3,
-    Futures = 4,
-}
+pub use fermi_types::transaction::ControllerType;
 
-impl ControllerType {
-    pub fn from_i32(value: i32) -> Result<Self, GDEXError> {
-        match value {
-            0 => Ok(ControllerType::Bank),
-            1 => Ok(ControllerType::Stake),
-            2 => Ok(ControllerType::Spot),
-            3 => Ok(ControllerType::Consensus),
-            4 => Ok(ControllerType::Futures),
-            _ => Err(GDEXError::DeserializationError),
-        }
-    }
+// INTERFACE
+
+// state saved by a controller before executing a request, copies of books and marketplaces are boxed
+enum RequestCheckpoint {
+    Consensus(Option<(usize, Duration)>),
+    Stake(StakeCheckpoint),
+    Spot(Box<Option<SpotCheckpoint>>),
+    Futures(Box<Option<FuturesCheckpoint>>),
 }
 
-// INTERFACE
+/// Accounts, orderbooks and futures marketplaces a transaction may modify,
+/// transactions with disjoint footprints may execute in any order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionFootprint {
+    pub accounts: Vec<AccountPubKey>,
+    pub orderbook_keys: Vec<AssetPairKey>,
+    // marketplaces are keyed by their admin, a marketplace holds the deposits and positions of all of its users
+    pub market_admins: Vec<AccountPubKey>,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ControllerRouter {
@@ -129,46 +138,230 @@ impl ControllerRouter {
     // TODO - Change the return signature of this to "ExecutionEvents" and roll the error into ExecutionEvents
     // This will also remove the need for the type ExecutionResult
     pub fn handle_consensus_transaction(&self, transaction: &Transaction) -> Result<ExecutionEvents, GDEXError> {
-        // reset execution result
-        // TODO - https://github.com/fermiorg/fermi/issues/176 - elimintate the need for reset by more properly handling errors
-        // eventually we wont need this as we will have strong guarantees that this
-        // val gets rest on each iteration but for now we can fail halfway though (in theory) so must be reset
+        // controllers may be called outside of a transaction, e.g. by genesis, so start from a clean set of events
         self.event_manager.lock().unwrap().reset();
 
-        let target_controller = ControllerType::from_i32(transaction.target_controller)?;
+        // a batched transaction is executed as its requests in order, each request is routed on its own
+        let requests = transaction.get_requests()?;
+        let target_controllers = requests
+            .iter()
+            .map(|request| ControllerType::from_i32(request.target_controller))
+            .collect::<Result<Vec<ControllerType>, GDEXError>>()?;
+
+        // the nonce and fee are settled before dispatch, both are kept even if a request fails
+        // a transaction which is replayed or cannot pay its fee is rejected without consuming the nonce
+        transaction.validate_fee()?;
+        let sender = transaction.get_sender()?;
+        {
+            let mut bank_controller = self.bank_controller.lock().unwrap();
+            bank_controller.validate_nonce(&sender, transaction.nonce)?;
+            bank_controller.charge_transaction_fee(&sender, transaction.get_total_fee()?)?;
+            bank_controller.increment_nonce(&sender);
+            bank_controller.begin_journal();
+        }
+
+        // if any request fails every request of the transaction is rolled back
+        // so that balances, books and events are exactly as before the transaction
+        let mut checkpoints = Vec::new();
+        let result = requests
+            .iter()
+            .zip(target_controllers)
+            .try_for_each(|(request, target_controller)| {
+                self.execute_request(target_controller, request, &mut checkpoints)
+            });
+        match result {
+            Ok(()) => {
+                self.bank_controller.lock().unwrap().commit_journal();
+                Ok(self.event_manager.lock().unwrap().emit())
+            }
+            Err(err) => {
+                self.rollback_requests(checkpoints);
+                self.bank_controller.lock().unwrap().revert_journal();
+                self.event_manager.lock().unwrap().reset();
+                Err(err)
+            }
+        }
+    }
+
+    // saves the state the request may modify in checkpoints before dispatching it to its controller
+    fn execute_request(
+        &self,
+        target_controller: ControllerType,
+        transaction: &Transaction,
+        checkpoints: &mut Vec<RequestCheckpoint>,
+    ) -> Result<(), GDEXError> {
         match target_controller {
             ControllerType::Consensus => {
-                self.consensus_controller
-                    .lock()
-                    .unwrap()
-                    .handle_consensus_transaction(transaction)?;
-            }
-            ControllerType::Bank => {
-                self.bank_controller
-                    .lock()
-                    .unwrap()
-                    .handle_consensus_transaction(transaction)?;
+                let mut consensus_controller = self.consensus_controller.lock().unwrap();
+                checkpoints.push(RequestCheckpoint::Consensus(
+                    consensus_controller.checkpoint(transaction),
+                ));
+                consensus_controller.handle_consensus_transaction(transaction)
             }
+            // bank modifications are reverted through the journal
+            ControllerType::Bank => self
+                .bank_controller
+                .lock()
+                .unwrap()
+                .handle_consensus_transaction(transaction),
             ControllerType::Stake => {
-                self.stake_controller
-                    .lock()
-                    .unwrap()
-                    .handle_consensus_transaction(transaction)?;
+                let mut stake_controller = self.stake_controller.lock().unwrap();
+                checkpoints.push(RequestCheckpoint::Stake(stake_controller.checkpoint(transaction)));
+                stake_controller.handle_consensus_transaction(transaction)
             }
             ControllerType::Spot => {
-                self.spot_controller
-                    .lock()
-                    .unwrap()
-                    .handle_consensus_transaction(transaction)?;
+                let mut spot_controller = self.spot_controller.lock().unwrap();
+                checkpoints.push(RequestCheckpoint::Spot(Box::new(
+                    spot_controller.checkpoint(transaction),
+                )));
+                spot_controller.handle_consensus_transaction(transaction)
             }
             ControllerType::Futures => {
-                self.futures_controller
-                    .lock()
-                    .unwrap()
-                    .handle_consensus_transaction(transaction)?;
+                let mut futures_controller = self.futures_controller.lock().unwrap();
+                checkpoints.push(RequestCheckpoint::Futures(Box::new(
+                    futures_controller.checkpoint(transaction),
+                )));
+                futures_controller.handle_consensus_transaction(transaction)
             }
         }
-        Ok(self.event_manager.lock().unwrap().emit())
+    }
+
+    // restores controllers from the checkpoints of executed requests, latest first
+    fn rollback_requests(&self, checkpoints: Vec<RequestCheckpoint>) {
+        for checkpoint in checkpoints.into_iter().rev() {
+            match checkpoint {
+                RequestCheckpoint::Consensus(checkpoint) => {
+                    self.consensus_controller.lock().unwrap().rollback(checkpoint)
+                }
+                RequestCheckpoint::Stake(checkpoint) => self.stake_controller.lock().unwrap().rollback(checkpoint),
+                RequestCheckpoint::Spot(checkpoint) => self.spot_controller.lock().unwrap().rollback(*checkpoint),
+                RequestCheckpoint::Futures(checkpoint) => self.futures_controller.lock().unwrap().rollback(*checkpoint),
+            }
+        }
+    }
+
+    // LANE FUNCTIONS
+
+    /// Returns the accounts and orderbooks a transaction may modify, or None if it may modify any other state,
+    /// e.g. stakes or asset metadata, in which case it must execute on its own
+    pub fn get_footprint(&self, transaction: &Transaction) -> Option<TransactionFootprint> {
+        let mut footprint = TransactionFootprint {
+            accounts: vec![transaction.get_sender().ok()?],
+            ..TransactionFootprint::default()
+        };
+        for request in transaction.get_requests().ok()? {
+            match ControllerType::from_i32(request.target_controller).ok()? {
+                ControllerType::Bank => {
+                    if !matches!(
+                        request.get_request_type::<BankRequestType>(),
+                        Ok(BankRequestType::Payment)
+                    ) {
+                        return None;
+                    }
+                    let payment: PaymentRequest = deserialize_protobuf(&request.request_bytes).ok()?;
+                    footprint.accounts.push(payment.get_receiver().ok()?);
+                }
+                ControllerType::Spot => footprint.orderbook_keys.push(
+                    self.spot_controller
+                        .lock()
+                        .unwrap()
+                        .get_request_orderbook_key(&request)
+                        .ok()?,
+                ),
+                ControllerType::Futures => footprint.market_admins.push(
+                    self.futures_controller
+                        .lock()
+                        .unwrap()
+                        .get_request_market_admin(&request)
+                        .ok()?,
+                ),
+                _ => return None,
+            }
+        }
+        // shared accounts are merged back from lanes as net changes, which only holds for their own credits and debits
+        let shared_accounts = self.get_shared_accounts();
+        if footprint
+            .accounts
+            .iter()
+            .any(|account| shared_accounts.contains(account))
+        {
+            return None;
+        }
+        Some(footprint)
+    }
+
+    /// Returns the accounts with orders resting on a book, filling an order on the book may credit any of them
+    pub fn get_resting_accounts(&self, orderbook_key: &AssetPairKey) -> Vec<AccountPubKey> {
+        self.spot_controller
+            .lock()
+            .unwrap()
+            .get_resting_accounts(orderbook_key)
+            .into_iter()
+            .collect()
+    }
+
+    /// Moves the accounts, orderbooks and marketplaces of a lane into a router of its own, which executes the lane's
+    /// transactions alongside other lanes until merge_lane moves them back
+    pub fn split_lane(&self, footprint: &TransactionFootprint) -> ControllerRouter {
+        let shared_accounts = self.get_shared_accounts();
+        let lane_router = ControllerRouter::default();
+        *lane_router.bank_controller.lock().unwrap() = self
+            .bank_controller
+            .lock()
+            .unwrap()
+            .split_lane(&footprint.accounts, &shared_accounts);
+        *lane_router.spot_controller.lock().unwrap() = self
+            .spot_controller
+            .lock()
+            .unwrap()
+            .split_lane(&footprint.orderbook_keys);
+        *lane_router.futures_controller.lock().unwrap() = self
+            .futures_controller
+            .lock()
+            .unwrap()
+            .split_lane(&footprint.market_admins);
+        lane_router.initialize_controllers();
+        lane_router
+    }
+
+    pub fn merge_lane(&self, lane_router: ControllerRouter) {
+        let lane_bank_controller = mem::take(&mut *lane_router.bank_controller.lock().unwrap());
+        self.bank_controller.lock().unwrap().merge_lane(lane_bank_controller);
+        let lane_spot_controller = mem::take(&mut *lane_router.spot_controller.lock().unwrap());
+        let mut spot_controller = self.spot_controller.lock().unwrap();
+        spot_controller.merge_lane(lane_spot_controller);
+        // the merged orderbooks and markets are still linked to the lane's bank
+        spot_controller.initialize(self);
+        drop(spot_controller);
+        let lane_futures_controller = mem::take(&mut *lane_router.futures_controller.lock().unwrap());
+        let mut futures_controller = self.futures_controller.lock().unwrap();
+        futures_controller.merge_lane(lane_futures_controller);
+        futures_controller.initialize(self);
+    }
+
+    /// Returns the accounts which transactions of every lane may modify, e.g. the spot escrow,
+    /// each lane works on a copy of them
+    pub fn get_shared_accounts(&self) -> Vec<AccountPubKey> {
+        let (fee_account, reward_pool_account) = {
+            let bank_controller = self.bank_controller.lock().unwrap();
+            (
+                bank_controller.get_fee_account().clone(),
+                bank_controller.get_reward_pool_account().clone(),
+            )
+        };
+        let spot_controller_account = self.spot_controller.lock().unwrap().get_controller_account().clone();
+        let futures_controller_account = self.futures_controller.lock().unwrap().controller_account.clone();
+        vec![
+            fee_account,
+            reward_pool_account,
+            spot_controller_account,
+            futures_controller_account,
+        ]
+    }
+
+    /// Returns the nonce the next transaction of the account must carry
+    pub fn get_next_nonce(&self, account: &AccountPubKey) -> u64 {
+        self.bank_controller.lock().unwrap().get_next_nonce(account)
     }
 
     pub fn critical_process_end_of_block(
@@ -203,29 +396,59 @@ impl ControllerRouter {
         Ok(())
     }
 
+    /// Captures every controller in a versioned catchup state taken at the given block
+    pub fn get_catchup_state(
+        &self,
+        block_number: BlockNumber,
+        block_digest: BlockDigest,
+    ) -> Result<CatchupState, GDEXError> {
+        let mut catchup_state = CatchupState::new(block_number, block_digest);
+        catchup_state.insert_controller_state(
+            ControllerType::Consensus,
+            self.consensus_controller.lock().unwrap().get_catchup_state()?,
+        );
+        catchup_state.insert_controller_state(
+            ControllerType::Bank,
+            self.bank_controller.lock().unwrap().get_catchup_state()?,
+        );
+        catchup_state.insert_controller_state(
+            ControllerType::Stake,
+            self.stake_controller.lock().unwrap().get_catchup_state()?,
+        );
+        catchup_state.insert_controller_state(
+            ControllerType::Spot,
+            self.spot_controller.lock().unwrap().get_catchup_state()?,
+        );
+        catchup_state.insert_controller_state(
+            ControllerType::Futures,
+            self.futures_controller.lock().unwrap().get_catchup_state()?,
+        );
+        Ok(catchup_state)
+    }
+
+    /// Overwrites every controller with a verified catchup state
+    pub fn restore_catchup_state(&self, catchup_state: &CatchupState) -> Result<(), GDEXError> {
+        catchup_state.verify()?;
+        let consensus_controller: ConsensusController =
+            deserialize_catchup_state(catchup_state, ControllerType::Consensus)?;
+        let bank_controller: BankController = deserialize_catchup_state(catchup_state, ControllerType::Bank)?;
+        let stake_controller: StakeController = deserialize_catchup_state(catchup_state, ControllerType::Stake)?;
+        let spot_controller: SpotController = deserialize_catchup_state(catchup_state, ControllerType::Spot)?;
+        let futures_controller: FuturesController = deserialize_catchup_state(catchup_state, ControllerType::Futures)?;
+
+        *self.consensus_controller.lock().unwrap() = consensus_controller;
+        *self.bank_controller.lock().unwrap() = bank_controller;
+        *self.stake_controller.lock().unwrap() = stake_controller;
+        *self.spot_controller.lock().unwrap() = spot_controller;
+        *self.futures_controller.lock().unwrap() = futures_controller;
+
+        // shared state is not part of the catchup state, so link the controllers back to the router
+        self.initialize_controllers();
+        Ok(())
+    }
+
     // Same todo previously mentioned, need to move away from using non-async mutex's for controller related functionality
     pub fn non_critical_process_end_of_block(&self, rpc_store: &RPCStore, block_number: u64) -> Result<(), GDEXError> {
-        if block_number % CATCHUP_STATE_FREQUENCY == 0 {
-            // TODO - It is quite gross and potentially error prone to just stick the catch-up states into
-            // A vector and then write them to the store. We should probably have a more structured way of
-            // doing this. Moreover, it should fit into the Controller workflow more directly.
-            let consensus_controller_state = self.consensus_controller.lock().unwrap().get_catchup_state()?;
-            let bank_controller_state = self.bank_controller.lock().unwrap().get_catchup_state()?;
-            let stake_controller_state = self.stake_controller.lock().unwrap().get_catchup_state()?;
-            let spot_controller_state = self.spot_controller.lock().unwrap().get_catchup_state()?;
-            let futures_controller_state = self.futures_controller.lock().unwrap().get_catchup_state()?;
-
-            let state = vec![
-                consensus_controller_state,
-                bank_controller_state,
-                stake_controller_state,
-                spot_controller_state,
-                futures_controller_state,
-            ];
-
-            rpc_store.catchup_state_store.try_write(0, CatchupState { state });
-        }
-
         self.consensus_controller
             .lock()
             .unwrap()
@@ -329,3 +552,706 @@ impl ControllerRouter {
         module
     }
 }
+
+fn deserialize_catchup_state<T: serde::de::DeserializeOwned>(
+    catchup_state: &CatchupState,
+    controller_type: ControllerType,
+) -> Result<T, GDEXError> {
+    bincode::deserialize(catchup_state.get_controller_state(controller_type)?)
+        .map_err(|_| GDEXError::DeserializationError)
+}
+
+#[cfg(test)]
+pub mod router_tests {
+    // crate
+    use super::*;
+    use crate::{
+        bank::{
+            controller::CREATED_ASSET_BALANCE,
+            proto::{create_create_asset_transaction, create_payment_request, create_payment_transaction},
+        },
+        futures::proto::{AccountDepositRequest, CreateMarketplaceRequest},
+        spot::{
+            controller::SPOT_CONTROLLER_ACCOUNT_PUBKEY,
+            proto::{
+                create_cancel_order_transaction, create_create_orderbook_request, create_create_orderbook_transaction,
+                create_limit_order_transaction,
+            },
+        },
+        stake::proto::create_stake_transaction,
+        utils::engine::order_book::{get_first_order_id, OrderBookWrapper},
+    };
+
+    // fermi
+    use fermi_types::{
+        account::{account_test_functions::generate_keypair_vec, AccountKeyPair},
+        asset::AssetId,
+        block::BlockDigest,
+        crypto::{KeypairTraits, ToFromBytes},
+        order_book::{OrderSide, TimeInForce},
+        transaction::{DEFAULT_TRANSACTION_FEE, MAX_TRANSACTION_REQUESTS},
+    };
+
+    // mysten
+    use fastcrypto::DIGEST_LEN;
+
+    // constants
+
+    const BASE_ASSET_ID: AssetId = 0;
+    const QUOTE_ASSET_ID: AssetId = 1;
+    const TRANSFER_AMOUNT: u64 = 1_000_000;
+
+    // test helpers
+
+    fn create_router() -> ControllerRouter {
+        let controller_router = ControllerRouter::default();
+        controller_router.initialize_controllers();
+        controller_router.initialize_controller_accounts();
+        controller_router
+    }
+
+    // builds a block which rests two asks at the same price and crosses them with a single bid
+    fn create_test_block() -> Vec<Transaction> {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, maker_0, maker_1, taker) = (&keys[0], &keys[1], &keys[2], &keys[3]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+
+        let mut transactions = vec![
+            create_create_asset_transaction(admin.public(), recent_block_hash, 0),
+            create_create_asset_transaction(admin.public(), recent_block_hash, 0),
+            create_create_orderbook_transaction(admin.public(), recent_block_hash, BASE_ASSET_ID, QUOTE_ASSET_ID),
+        ];
+        for user in [maker_0, maker_1, taker] {
+            for asset_id in [BASE_ASSET_ID, QUOTE_ASSET_ID] {
+                transactions.push(create_payment_transaction(
+                    admin.public(),
+                    recent_block_hash,
+                    user.public(),
+                    asset_id,
+                    TRANSFER_AMOUNT,
+                ));
+            }
+        }
+        for maker in [maker_0, maker_1] {
+            transactions.push(create_limit_order_transaction(
+                maker.public(),
+                recent_block_hash,
+                BASE_ASSET_ID,
+                QUOTE_ASSET_ID,
+                OrderSide::Ask as u64,
+                10,
+                100,
+                0,
+                TimeInForce::GoodTilCancelled as u64,
+                false,
+            ));
+        }
+        transactions.push(create_limit_order_transaction(
+            taker.public(),
+            recent_block_hash,
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            OrderSide::Bid as u64,
+            10,
+            150,
+            0,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
+        ));
+        transactions.push(create_cancel_order_transaction(
+            maker_1.public(),
+            recent_block_hash,
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            OrderSide::Ask as u64,
+            get_first_order_id(BASE_ASSET_ID, QUOTE_ASSET_ID) + 1,
+        ));
+        transactions
+    }
+
+    // executes the transaction with the next nonce of its sender
+    fn execute(
+        controller_router: &ControllerRouter,
+        mut transaction: Transaction,
+    ) -> Result<ExecutionEvents, GDEXError> {
+        transaction.set_nonce(controller_router.get_next_nonce(&transaction.get_sender().unwrap()));
+        controller_router.handle_consensus_transaction(&transaction)
+    }
+
+    fn replay_block(controller_router: &ControllerRouter, transactions: &[Transaction]) -> Vec<ExecutionEvents> {
+        transactions
+            .iter()
+            .map(|transaction| execute(controller_router, transaction.clone()).unwrap())
+            .collect()
+    }
+
+    fn serialize_orderbook(controller_router: &ControllerRouter) -> Vec<u8> {
+        let mut spot_controller = controller_router.spot_controller.lock().unwrap();
+        let orderbook = spot_controller.get_orderbook(BASE_ASSET_ID, QUOTE_ASSET_ID).unwrap();
+        bincode::serialize(orderbook.get_orderbook()).unwrap()
+    }
+
+    #[test]
+    fn replayed_block_is_deterministic() {
+        let transactions = create_test_block();
+
+        let router_0 = create_router();
+        let router_1 = create_router();
+        let events_0 = replay_block(&router_0, &transactions);
+        let events_1 = replay_block(&router_1, &transactions);
+
+        assert_eq!(
+            bincode::serialize(&events_0).unwrap(),
+            bincode::serialize(&events_1).unwrap()
+        );
+        assert_eq!(serialize_orderbook(&router_0), serialize_orderbook(&router_1));
+
+        let keys = generate_keypair_vec([0; 32]);
+        for key in &keys {
+            for asset_id in [BASE_ASSET_ID, QUOTE_ASSET_ID] {
+                assert_eq!(
+                    router_0
+                        .bank_controller
+                        .lock()
+                        .unwrap()
+                        .get_balance(key.public(), asset_id),
+                    router_1
+                        .bank_controller
+                        .lock()
+                        .unwrap()
+                        .get_balance(key.public(), asset_id)
+                );
+            }
+        }
+
+        // the first resting ask has priority, so the second maker has a partial fill which it then cancels
+        let maker_1 = &keys[2];
+        assert_eq!(
+            router_0
+                .bank_controller
+                .lock()
+                .unwrap()
+                .get_balance(maker_1.public(), BASE_ASSET_ID)
+                .unwrap(),
+            TRANSFER_AMOUNT - 50 - 2 * DEFAULT_TRANSACTION_FEE
+        );
+    }
+
+    #[test]
+    fn restored_catchup_state_matches_and_stays_linked() {
+        let router_0 = create_router();
+        replay_block(&router_0, &create_test_block());
+
+        let router_1 = create_router();
+        let catchup_state = router_0
+            .get_catchup_state(1, BlockDigest::new([0; DIGEST_LEN]))
+            .unwrap();
+        router_1.restore_catchup_state(&catchup_state).unwrap();
+        assert_eq!(serialize_orderbook(&router_0), serialize_orderbook(&router_1));
+
+        // an order placed after the restore must settle against the router's bank
+        let keys = generate_keypair_vec([0; 32]);
+        let taker = &keys[3];
+        let transaction = create_limit_order_transaction(
+            taker.public(),
+            BlockDigest::new([0; DIGEST_LEN]),
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            OrderSide::Bid as u64,
+            10,
+            100,
+            0,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
+        );
+        let events_0 = replay_block(&router_0, &[transaction.clone()]);
+        let events_1 = replay_block(&router_1, &[transaction]);
+        assert_eq!(
+            bincode::serialize(&events_0).unwrap(),
+            bincode::serialize(&events_1).unwrap()
+        );
+        for asset_id in [BASE_ASSET_ID, QUOTE_ASSET_ID] {
+            assert_eq!(
+                router_0
+                    .bank_controller
+                    .lock()
+                    .unwrap()
+                    .get_balance(taker.public(), asset_id),
+                router_1
+                    .bank_controller
+                    .lock()
+                    .unwrap()
+                    .get_balance(taker.public(), asset_id)
+            );
+        }
+        assert_eq!(serialize_orderbook(&router_0), serialize_orderbook(&router_1));
+    }
+
+    #[test]
+    fn catchup_manifest_detects_changes() {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, user) = (&keys[0], &keys[1]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let controller_router = create_router();
+        execute(
+            &controller_router,
+            create_create_asset_transaction(admin.public(), recent_block_hash, 0),
+        )
+        .unwrap();
+        let catchup_state_0 = controller_router.get_catchup_state(1, recent_block_hash).unwrap();
+        assert!(catchup_state_0.verify().is_ok());
+
+        // a payment only touches the bank
+        execute(
+            &controller_router,
+            create_payment_transaction(
+                admin.public(),
+                recent_block_hash,
+                user.public(),
+                BASE_ASSET_ID,
+                TRANSFER_AMOUNT,
+            ),
+        )
+        .unwrap();
+        let catchup_state_1 = controller_router.get_catchup_state(2, recent_block_hash).unwrap();
+        assert_eq!(
+            catchup_state_0.manifest.diff(&catchup_state_1.manifest),
+            vec![ControllerType::Bank]
+        );
+        assert_ne!(catchup_state_0.manifest.root(), catchup_state_1.manifest.root());
+
+        // tampered or incompatible snapshots are not restored
+        let mut tampered_state = catchup_state_1.clone();
+        tampered_state
+            .controller_states
+            .get_mut(&ControllerType::Bank)
+            .unwrap()
+            .push(0);
+        assert_eq!(
+            create_router().restore_catchup_state(&tampered_state),
+            Err(GDEXError::CatchupStateVerification)
+        );
+        let mut incompatible_state = catchup_state_1;
+        incompatible_state.manifest.version += 1;
+        assert_eq!(
+            create_router().restore_catchup_state(&incompatible_state),
+            Err(GDEXError::CatchupStateVersion)
+        );
+    }
+
+    #[test]
+    fn catchup_root_independent_of_insertion_order() {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, user_0, user_1) = (&keys[0], &keys[1], &keys[2]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+
+        // each user funds, trades, stakes and deposits into its own markets, so the two sets of transactions commute
+        let user_transactions = |user: &AccountKeyPair, quote_asset_id: AssetId| {
+            vec![
+                create_payment_transaction(
+                    admin.public(),
+                    recent_block_hash,
+                    user.public(),
+                    BASE_ASSET_ID,
+                    TRANSFER_AMOUNT,
+                ),
+                create_payment_transaction(
+                    admin.public(),
+                    recent_block_hash,
+                    user.public(),
+                    quote_asset_id,
+                    TRANSFER_AMOUNT,
+                ),
+                create_create_orderbook_transaction(user.public(), recent_block_hash, BASE_ASSET_ID, quote_asset_id),
+                create_limit_order_transaction(
+                    user.public(),
+                    recent_block_hash,
+                    BASE_ASSET_ID,
+                    quote_asset_id,
+                    OrderSide::Bid as u64,
+                    10,
+                    100,
+                    0,
+                    TimeInForce::GoodTilCancelled as u64,
+                    false,
+                ),
+                create_stake_transaction(user.public(), recent_block_hash, 1_000),
+                Transaction::new(
+                    user.public(),
+                    recent_block_hash,
+                    &CreateMarketplaceRequest::new(quote_asset_id),
+                ),
+                Transaction::new(
+                    user.public(),
+                    recent_block_hash,
+                    &AccountDepositRequest::new(1_000, user.public()),
+                ),
+            ]
+        };
+        let create_assets: Vec<Transaction> = (0..3)
+            .map(|_| create_create_asset_transaction(admin.public(), recent_block_hash, 0))
+            .collect();
+
+        let router_0 = create_router();
+        replay_block(&router_0, &create_assets);
+        replay_block(&router_0, &user_transactions(user_0, 1));
+        replay_block(&router_0, &user_transactions(user_1, 2));
+
+        let router_1 = create_router();
+        replay_block(&router_1, &create_assets);
+        replay_block(&router_1, &user_transactions(user_1, 2));
+        replay_block(&router_1, &user_transactions(user_0, 1));
+
+        let catchup_state_0 = router_0.get_catchup_state(1, recent_block_hash).unwrap();
+        let catchup_state_1 = router_1.get_catchup_state(1, recent_block_hash).unwrap();
+        assert!(catchup_state_0.manifest.diff(&catchup_state_1.manifest).is_empty());
+        assert_eq!(catchup_state_0.manifest.root(), catchup_state_1.manifest.root());
+    }
+
+    #[test]
+    fn transaction_fee_charged_to_reward_pool() {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, user) = (&keys[0], &keys[1]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let controller_router = create_router();
+
+        // genesis asset creation is free since the primary asset does not exist yet
+        execute(
+            &controller_router,
+            create_create_asset_transaction(admin.public(), recent_block_hash, 0),
+        )
+        .unwrap();
+        execute(
+            &controller_router,
+            create_payment_transaction(
+                admin.public(),
+                recent_block_hash,
+                user.public(),
+                BASE_ASSET_ID,
+                TRANSFER_AMOUNT,
+            ),
+        )
+        .unwrap();
+
+        // a failing request still pays its fee
+        let failing_transaction = create_cancel_order_transaction(
+            user.public(),
+            recent_block_hash,
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            OrderSide::Ask as u64,
+            1,
+        );
+        assert!(execute(&controller_router, failing_transaction).is_err());
+
+        let bank_controller = controller_router.bank_controller.lock().unwrap();
+        assert_eq!(
+            bank_controller.get_balance(admin.public(), BASE_ASSET_ID).unwrap(),
+            CREATED_ASSET_BALANCE - TRANSFER_AMOUNT - DEFAULT_TRANSACTION_FEE
+        );
+        assert_eq!(
+            bank_controller.get_balance(user.public(), BASE_ASSET_ID).unwrap(),
+            TRANSFER_AMOUNT - DEFAULT_TRANSACTION_FEE
+        );
+        assert_eq!(bank_controller.get_reward_pool_balance(), 2 * DEFAULT_TRANSACTION_FEE);
+    }
+
+    #[test]
+    fn underfunded_transaction_rejected() {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, user) = (&keys[0], &keys[1]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let controller_router = create_router();
+        execute(
+            &controller_router,
+            create_create_asset_transaction(admin.public(), recent_block_hash, 0),
+        )
+        .unwrap();
+
+        // the user holds no primary asset to pay the fee with
+        let transaction = create_create_orderbook_transaction(user.public(), recent_block_hash, 0, 1);
+        assert_eq!(
+            execute(&controller_router, transaction),
+            Err(GDEXError::TransactionFeeUnderfunded)
+        );
+        // the rejected transaction does not consume the nonce
+        assert_eq!(controller_router.get_next_nonce(user.public()), 0);
+        assert!(!controller_router
+            .spot_controller
+            .lock()
+            .unwrap()
+            .validate_controllerbook_exists(0, 1));
+    }
+
+    #[test]
+    fn zero_fee_transaction_rejected() {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, user) = (&keys[0], &keys[1]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let controller_router = create_router();
+        execute(
+            &controller_router,
+            create_create_asset_transaction(admin.public(), recent_block_hash, 0),
+        )
+        .unwrap();
+
+        let mut transaction =
+            create_payment_transaction(admin.public(), recent_block_hash, user.public(), BASE_ASSET_ID, 1);
+        transaction.set_fee(0);
+        assert_eq!(
+            execute(&controller_router, transaction),
+            Err(GDEXError::TransactionFeeTooLow)
+        );
+        // the rejected transaction neither consumes the nonce nor moves funds
+        assert_eq!(controller_router.get_next_nonce(admin.public()), 1);
+        assert_eq!(
+            controller_router
+                .bank_controller
+                .lock()
+                .unwrap()
+                .get_balance(admin.public(), BASE_ASSET_ID)
+                .unwrap(),
+            CREATED_ASSET_BALANCE
+        );
+    }
+
+    #[test]
+    fn replayed_transaction_rejected_by_nonce() {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, user) = (&keys[0], &keys[1]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let controller_router = create_router();
+        execute(
+            &controller_router,
+            create_create_asset_transaction(admin.public(), recent_block_hash, 0),
+        )
+        .unwrap();
+
+        let mut payment_transaction = create_payment_transaction(
+            admin.public(),
+            recent_block_hash,
+            user.public(),
+            BASE_ASSET_ID,
+            TRANSFER_AMOUNT,
+        );
+        payment_transaction.set_nonce(1);
+        controller_router
+            .handle_consensus_transaction(&payment_transaction)
+            .unwrap();
+        assert_eq!(controller_router.get_next_nonce(admin.public()), 2);
+
+        // neither a replayed nor a skipped nonce is executed or charged
+        let reward_pool_balance = controller_router
+            .bank_controller
+            .lock()
+            .unwrap()
+            .get_reward_pool_balance();
+        assert_eq!(
+            controller_router.handle_consensus_transaction(&payment_transaction),
+            Err(GDEXError::TransactionNonce)
+        );
+        payment_transaction.set_nonce(3);
+        assert_eq!(
+            controller_router.handle_consensus_transaction(&payment_transaction),
+            Err(GDEXError::TransactionNonce)
+        );
+        let bank_controller = controller_router.bank_controller.lock().unwrap();
+        assert_eq!(
+            bank_controller.get_balance(user.public(), BASE_ASSET_ID).unwrap(),
+            TRANSFER_AMOUNT
+        );
+        assert_eq!(bank_controller.get_reward_pool_balance(), reward_pool_balance);
+        drop(bank_controller);
+
+        // nonces are part of the catchup state
+        let restored_router = create_router();
+        restored_router
+            .restore_catchup_state(&controller_router.get_catchup_state(1, recent_block_hash).unwrap())
+            .unwrap();
+        assert_eq!(restored_router.get_next_nonce(admin.public()), 2);
+    }
+
+    #[test]
+    fn failed_request_rolled_back() {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, maker, taker) = (&keys[0], &keys[1], &keys[3]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let controller_router = create_router();
+        // fund the users and rest the first maker's ask for the taker to cross
+        let transactions = create_test_block();
+        replay_block(&controller_router, &transactions[..transactions.len() - 3]);
+        let spot_account = AccountPubKey::from_bytes(SPOT_CONTROLLER_ACCOUNT_PUBKEY).unwrap();
+
+        // draining the escrowed base asset makes the fill fail after the bid was escrowed and matched
+        controller_router
+            .bank_controller
+            .lock()
+            .unwrap()
+            .transfer(&spot_account, admin.public(), BASE_ASSET_ID, 10)
+            .unwrap();
+        let orderbook = serialize_orderbook(&controller_router);
+        let balances = |account: &AccountPubKey| {
+            let bank_controller = controller_router.bank_controller.lock().unwrap();
+            (
+                bank_controller.get_balance(account, BASE_ASSET_ID).unwrap(),
+                bank_controller.get_balance(account, QUOTE_ASSET_ID).unwrap(),
+            )
+        };
+        let (maker_balances, taker_balances) = (balances(maker.public()), balances(taker.public()));
+        let taker_bid = create_limit_order_transaction(
+            taker.public(),
+            recent_block_hash,
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            OrderSide::Bid as u64,
+            10,
+            100,
+            0,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
+        );
+        assert!(execute(&controller_router, taker_bid.clone()).is_err());
+
+        // only the fee and nonce of the failed transaction are kept
+        assert_eq!(serialize_orderbook(&controller_router), orderbook);
+        assert_eq!(balances(maker.public()), maker_balances);
+        assert_eq!(
+            balances(taker.public()),
+            (taker_balances.0 - DEFAULT_TRANSACTION_FEE, taker_balances.1)
+        );
+        assert_eq!(controller_router.get_next_nonce(taker.public()), 1);
+        assert!(controller_router
+            .event_manager
+            .lock()
+            .unwrap()
+            .current_execution_result
+            .is_empty());
+
+        // once the escrow is restored the same order fills
+        controller_router
+            .bank_controller
+            .lock()
+            .unwrap()
+            .transfer(admin.public(), &spot_account, BASE_ASSET_ID, 10)
+            .unwrap();
+        execute(&controller_router, taker_bid).unwrap();
+        assert_eq!(
+            balances(taker.public()),
+            (
+                taker_balances.0 - 2 * DEFAULT_TRANSACTION_FEE + 10,
+                taker_balances.1 - 10 * 100
+            )
+        );
+    }
+
+    #[test]
+    fn batched_requests_execute_atomically() {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, user) = (&keys[0], &keys[1]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let controller_router = create_router();
+        for _ in [BASE_ASSET_ID, QUOTE_ASSET_ID] {
+            execute(
+                &controller_router,
+                create_create_asset_transaction(admin.public(), recent_block_hash, 0),
+            )
+            .unwrap();
+        }
+        let balances = |account: &AccountPubKey| {
+            let bank_controller = controller_router.bank_controller.lock().unwrap();
+            (
+                bank_controller.get_balance(account, BASE_ASSET_ID).unwrap(),
+                bank_controller.get_balance(account, QUOTE_ASSET_ID).unwrap(),
+                bank_controller.get_reward_pool_balance(),
+            )
+        };
+        let (admin_base, admin_quote, reward_pool_balance) = balances(admin.public());
+
+        // every request of the batch takes effect and the fee is paid once per request
+        let transaction = Transaction::new_batch(
+            admin.public(),
+            recent_block_hash,
+            vec![
+                create_create_orderbook_request(BASE_ASSET_ID, QUOTE_ASSET_ID),
+                create_payment_request(user.public(), BASE_ASSET_ID, TRANSFER_AMOUNT),
+                create_payment_request(user.public(), QUOTE_ASSET_ID, TRANSFER_AMOUNT),
+            ],
+        );
+        let events = execute(&controller_router, transaction).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(controller_router
+            .spot_controller
+            .lock()
+            .unwrap()
+            .validate_controllerbook_exists(BASE_ASSET_ID, QUOTE_ASSET_ID));
+        assert_eq!(
+            balances(admin.public()),
+            (
+                admin_base - TRANSFER_AMOUNT - 3 * DEFAULT_TRANSACTION_FEE,
+                admin_quote - TRANSFER_AMOUNT,
+                reward_pool_balance + 3 * DEFAULT_TRANSACTION_FEE
+            )
+        );
+        assert_eq!(controller_router.get_next_nonce(admin.public()), 3);
+
+        // the orderbook already exists, so the payment before it is rolled back
+        let user_balances = balances(user.public());
+        let transaction = Transaction::new_batch(
+            admin.public(),
+            recent_block_hash,
+            vec![
+                create_payment_request(user.public(), BASE_ASSET_ID, TRANSFER_AMOUNT),
+                create_create_orderbook_request(BASE_ASSET_ID, QUOTE_ASSET_ID),
+            ],
+        );
+        assert!(execute(&controller_router, transaction).is_err());
+        assert_eq!(
+            balances(user.public()),
+            (
+                user_balances.0,
+                user_balances.1,
+                user_balances.2 + 2 * DEFAULT_TRANSACTION_FEE
+            )
+        );
+        assert_eq!(controller_router.get_next_nonce(admin.public()), 4);
+        assert!(controller_router
+            .event_manager
+            .lock()
+            .unwrap()
+            .current_execution_result
+            .is_empty());
+    }
+
+    #[test]
+    fn oversized_batch_rejected() {
+        let keys = generate_keypair_vec([0; 32]);
+        let (admin, user) = (&keys[0], &keys[1]);
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let controller_router = create_router();
+        execute(
+            &controller_router,
+            create_create_asset_transaction(admin.public(), recent_block_hash, 0),
+        )
+        .unwrap();
+
+        let transaction = Transaction::new_batch(
+            admin.public(),
+            recent_block_hash,
+            vec![create_payment_request(user.public(), BASE_ASSET_ID, 1); MAX_TRANSACTION_REQUESTS + 1],
+        );
+        assert_eq!(
+            execute(&controller_router, transaction),
+            Err(GDEXError::TransactionRequestLimit)
+        );
+        // the rejected transaction is neither charged nor does it consume the nonce
+        assert_eq!(controller_router.get_next_nonce(admin.public()), 1);
+        assert_eq!(
+            controller_router
+                .bank_controller
+                .lock()
+                .unwrap()
+                .get_reward_pool_balance(),
+            0
+        );
+    }
+}
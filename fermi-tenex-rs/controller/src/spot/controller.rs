@@ -4,12 +4,11 @@
 
 // crate
 use crate::bank::controller::BankController;
-use crate::consensus::rpc_server::UnimplementedRPC;
 use crate::controller::Controller;
 use crate::event_manager::{EventEmitter, EventManager};
 use crate::router::ControllerRouter;
-use crate::spot::proto::*;
-use crate::utils::engine::order_book::{OrderBookWrapper, OrderId, Orderbook};
+use crate::spot::{proto::*, rpc_server::JSONRPCService, types::SpotOrder};
+use crate::utils::engine::order_book::{validate_order_id_assets, OrderBookWrapper, OrderId, Orderbook};
 
 // fermi
 use fermi_types::{
@@ -17,37 +16,55 @@ use fermi_types::{
     asset::{AssetId, AssetPairKey},
     crypto::ToFromBytes,
     error::GDEXError,
-    order_book::{OrderSide, OrderbookDepth},
+    order_book::{ClientOrderId, FeeSchedule, Liquidity, OrderSide, OrderbookDepth, Trade, TradeRecord},
+    store::{CriticalPathStore, RPCStore, RPCStoreHandle},
     transaction::{deserialize_protobuf, Transaction},
 };
 
+// mysten
+use sui_json_rpc::SuiRpcModule;
+
 // external
 use async_trait::async_trait;
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex};
 
 // CONSTANTS
 
 pub const SPOT_CONTROLLER_ACCOUNT_PUBKEY: &[u8] = b"SPOTCONTROLLERAAAAAAAAAAAAAAAAAA";
+// number of trades kept per orderbook for the recent trades rpc
+pub const MAX_RECENT_TRADES: usize = 100;
+// number of trades kept per orderbook on the persisted trade tape
+pub const MAX_TRADE_HISTORY: u64 = 10_000;
 
 // INTERFACE
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SpotController {
     // controller state
     controller_account: AccountPubKey,
-    orderbooks: HashMap<AssetPairKey, SpotOrderbook>,
+    orderbooks: BTreeMap<AssetPairKey, SpotOrderbook>,
+    #[serde(skip)]
     bank_controller: Arc<Mutex<BankController>>,
     // shared
+    #[serde(skip)]
     event_manager: Arc<Mutex<EventManager>>,
 }
 
+/// Copy of the one orderbook a request may modify, or None if the book did not exist yet
+#[derive(Clone, Debug)]
+pub struct SpotCheckpoint {
+    orderbook_key: AssetPairKey,
+    orderbook: Option<SpotOrderbook>,
+}
+
 impl Default for SpotController {
     fn default() -> Self {
         Self {
             controller_account: AccountPubKey::from_bytes(SPOT_CONTROLLER_ACCOUNT_PUBKEY).unwrap(),
-            orderbooks: HashMap::new(),
+            orderbooks: BTreeMap::new(),
             bank_controller: Arc::new(Mutex::new(BankController::default())), // TEMPORARY
             // shared state
             event_manager: Arc::new(Mutex::new(EventManager::new())), // TEMPORARY
@@ -56,10 +73,15 @@ impl Default for SpotController {
 }
 
 #[async_trait]
-impl Controller<UnimplementedRPC> for SpotController {
+impl Controller<JSONRPCService> for SpotController {
     fn initialize(&mut self, controller_router: &ControllerRouter) {
         self.bank_controller = Arc::clone(&controller_router.bank_controller);
         self.event_manager = Arc::clone(&controller_router.event_manager);
+        // orderbooks restored from a catchup state must be linked to the shared state, which is not serialized
+        for orderbook in self.orderbooks.values_mut() {
+            orderbook.bank_controller = Arc::clone(&self.bank_controller);
+            orderbook.event_manager = Arc::clone(&self.event_manager);
+        }
     }
 
     fn initialize_controller_account(&self) -> Result<(), GDEXError> {
@@ -70,34 +92,46 @@ impl Controller<UnimplementedRPC> for SpotController {
         Ok(())
     }
 
+    // a request which cannot be decoded fails before it modifies any orderbook, so it needs no checkpoint
+    type Checkpoint = Option<SpotCheckpoint>;
+
+    fn checkpoint(&self, transaction: &Transaction) -> Self::Checkpoint {
+        let orderbook_key = self.get_request_orderbook_key(transaction).ok()?;
+        Some(SpotCheckpoint {
+            orderbook: self.orderbooks.get(&orderbook_key).cloned(),
+            orderbook_key,
+        })
+    }
+
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        if let Some(checkpoint) = checkpoint {
+            match checkpoint.orderbook {
+                Some(orderbook) => self.orderbooks.insert(checkpoint.orderbook_key, orderbook),
+                None => self.orderbooks.remove(&checkpoint.orderbook_key),
+            };
+        }
+    }
+
     fn handle_consensus_transaction(&mut self, transaction: &Transaction) -> Result<(), GDEXError> {
         let sender = transaction.get_sender()?;
         let request_type: SpotRequestType = transaction.get_request_type()?;
         match request_type {
             SpotRequestType::CreateOrderbook => {
                 let request: CreateOrderbookRequest = deserialize_protobuf(&transaction.request_bytes)?;
-                self.create_orderbook(request.base_asset_id, request.quote_asset_id)
+                self.create_orderbook(&sender, request.base_asset_id, request.quote_asset_id)
             }
-            // TODO - https://github.com/fermiorg/fermi/issues/170 - add support for market orders
             SpotRequestType::MarketOrder => {
                 let request: MarketOrderRequest = deserialize_protobuf(&transaction.request_bytes)?;
-                match self
-                    .get_orderbook(request.base_asset_id, request.quote_asset_id)?
-                    .place_market_order(&sender, &request)
-                {
-                    Ok(_ordering_processing_result) => Ok(()),
-                    Err(_err) => Err(GDEXError::OrderRequest),
-                }
+                // surface the specific rejection, e.g. a missing or violated slippage guard
+                self.get_orderbook(request.base_asset_id, request.quote_asset_id)?
+                    .place_market_order(&sender, &request)?;
+                Ok(())
             }
             SpotRequestType::LimitOrder => {
                 let request: LimitOrderRequest = deserialize_protobuf(&transaction.request_bytes)?;
-                match self
-                    .get_orderbook(request.base_asset_id, request.quote_asset_id)?
-                    .place_limit_order(&sender, &request)
-                {
-                    Ok(_ordering_processing_result) => Ok(()),
-                    Err(_err) => Err(GDEXError::OrderRequest),
-                }
+                self.get_orderbook(request.base_asset_id, request.quote_asset_id)?
+                    .place_limit_order(&sender, &request)?;
+                Ok(())
             }
             SpotRequestType::UpdateOrder => {
                 let request: UpdateOrderRequest = deserialize_protobuf(&transaction.request_bytes)?;
@@ -119,8 +153,68 @@ impl Controller<UnimplementedRPC> for SpotController {
                     Err(_err) => Err(GDEXError::OrderRequest),
                 }
             }
+            SpotRequestType::UpdateFeeSchedule => {
+                let request: UpdateFeeScheduleRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let fee_schedule = FeeSchedule::new(request.maker_fee_bps, request.taker_fee_bps);
+                self.get_orderbook(request.base_asset_id, request.quote_asset_id)?
+                    .update_fee_schedule(&sender, fee_schedule)
+            }
+        }
+    }
+
+    fn critical_process_end_of_block(&mut self, _critical_path_store: &CriticalPathStore, _block_number: u64) {
+        // trades are only persisted by nodes serving rpc, which do not run the critical path
+        for orderbook in self.orderbooks.values_mut() {
+            orderbook.unpersisted_trades.clear();
+        }
+    }
+
+    fn non_critical_process_end_of_block(&mut self, rpc_store: &RPCStore, block_number: u64) {
+        for (orderbook_key, orderbook) in self.orderbooks.iter_mut() {
+            rpc_store
+                .latest_orderbook_depth_store
+                .try_write(orderbook_key.clone(), orderbook.get_orderbook_depth());
+            if orderbook.unpersisted_trades.is_empty() {
+                continue;
+            }
+            rpc_store
+                .latest_trades_store
+                .try_write(orderbook_key.clone(), orderbook.recent_trades.iter().cloned().collect());
+
+            // append the trades of this block to the tape and drop those which fall off its end
+            let trades = std::mem::take(&mut orderbook.unpersisted_trades);
+            let expired_trade_ids: Vec<u64> = trades
+                .iter()
+                .filter(|trade| trade.trade_id > MAX_TRADE_HISTORY)
+                .map(|trade| trade.trade_id - MAX_TRADE_HISTORY)
+                .collect();
+            rpc_store.trade_history_store.try_write_all(trades.iter().map(|trade| {
+                (
+                    (orderbook_key.clone(), trade.trade_id),
+                    TradeRecord::new(trade, block_number),
+                )
+            }));
+            if !expired_trade_ids.is_empty() {
+                rpc_store.trade_history_store.try_remove_all(
+                    expired_trade_ids
+                        .into_iter()
+                        .map(|trade_id| (orderbook_key.clone(), trade_id)),
+                );
+            }
         }
     }
+
+    fn rpc_is_implemented() -> bool {
+        true
+    }
+
+    fn generate_json_rpc_module(
+        state_manager: Arc<Mutex<ControllerRouter>>,
+        rpc_store_handle: Arc<RPCStoreHandle>,
+    ) -> Result<jsonrpsee::RpcModule<JSONRPCService>, GDEXError> {
+        let result = JSONRPCService::new(state_manager, rpc_store_handle).rpc();
+        Ok(result)
+    }
 }
 
 impl EventEmitter for SpotController {
@@ -132,7 +226,7 @@ impl EventEmitter for SpotController {
 impl SpotController {
     pub fn new(
         controller_account: AccountPubKey,
-        orderbooks: HashMap<AssetPairKey, SpotOrderbook>,
+        orderbooks: BTreeMap<AssetPairKey, SpotOrderbook>,
         bank_controller: Arc<Mutex<BankController>>,
         event_manager: Arc<Mutex<EventManager>>,
     ) -> Self {
@@ -150,6 +244,38 @@ impl SpotController {
         format!("{}_{}", base_asset_id, quote_asset_id)
     }
 
+    /// Returns the key of the orderbook a request is addressed to
+    pub fn get_request_orderbook_key(&self, transaction: &Transaction) -> Result<AssetPairKey, GDEXError> {
+        let request_type: SpotRequestType = transaction.get_request_type()?;
+        let (base_asset_id, quote_asset_id) = match request_type {
+            SpotRequestType::CreateOrderbook => {
+                let request: CreateOrderbookRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                (request.base_asset_id, request.quote_asset_id)
+            }
+            SpotRequestType::MarketOrder => {
+                let request: MarketOrderRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                (request.base_asset_id, request.quote_asset_id)
+            }
+            SpotRequestType::LimitOrder => {
+                let request: LimitOrderRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                (request.base_asset_id, request.quote_asset_id)
+            }
+            SpotRequestType::UpdateOrder => {
+                let request: UpdateOrderRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                (request.base_asset_id, request.quote_asset_id)
+            }
+            SpotRequestType::CancelOrder => {
+                let request: CancelOrderRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                (request.base_asset_id, request.quote_asset_id)
+            }
+            SpotRequestType::UpdateFeeSchedule => {
+                let request: UpdateFeeScheduleRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                (request.base_asset_id, request.quote_asset_id)
+            }
+        };
+        Ok(self.get_orderbook_key(base_asset_id, quote_asset_id))
+    }
+
     pub fn validate_controllerbook_exists(&self, base_asset_id: AssetId, quote_asset_id: AssetId) -> bool {
         let lookup_string = self.get_orderbook_key(base_asset_id, quote_asset_id);
         self.orderbooks.contains_key(&lookup_string)
@@ -164,8 +290,49 @@ impl SpotController {
         orderbook_depths
     }
 
+    pub fn get_controller_account(&self) -> &AccountPubKey {
+        &self.controller_account
+    }
+
+    /// Returns the accounts with orders resting on a book, a fill settles with them although they did not send it
+    pub fn get_resting_accounts(&self, orderbook_key: &AssetPairKey) -> HashSet<AccountPubKey> {
+        self.orderbooks
+            .get(orderbook_key)
+            .map(|orderbook| {
+                orderbook
+                    .orderbook
+                    .get_open_orders()
+                    .filter_map(|order| orderbook.order_to_account.get(&order.order_id).cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // LANE FUNCTIONS
+
+    /// Moves the given orderbooks into a controller of their own, which executes a lane of transactions alongside others
+    /// the books are linked to the lane's bank once the lane router is initialized
+    pub fn split_lane(&mut self, orderbook_keys: &[AssetPairKey]) -> SpotController {
+        SpotController {
+            orderbooks: orderbook_keys
+                .iter()
+                .filter_map(|orderbook_key| self.orderbooks.remove_entry(orderbook_key))
+                .collect(),
+            ..SpotController::default()
+        }
+    }
+
+    /// Moves the orderbooks of a lane back, they must be initialized again to link them to this controller's bank
+    pub fn merge_lane(&mut self, lane: SpotController) {
+        self.orderbooks.extend(lane.orderbooks);
+    }
+
     // METRIC FUNCTIONS
 
+    pub fn get_orderbooks(&self) -> &BTreeMap<AssetPairKey, SpotOrderbook> {
+        &self.orderbooks
+    }
+
     pub fn get_orderbook(
         &mut self,
         base_asset_id: AssetId,
@@ -177,19 +344,26 @@ impl SpotController {
 
     // USER FUNCTIONS
 
-    pub fn create_orderbook(&mut self, base_asset_id: AssetId, quote_asset_id: AssetId) -> Result<(), GDEXError> {
+    /// Creates a new orderbook, the creator becomes its admin and may adjust the fee schedule
+    pub fn create_orderbook(
+        &mut self,
+        admin: &AccountPubKey,
+        base_asset_id: AssetId,
+        quote_asset_id: AssetId,
+    ) -> Result<(), GDEXError> {
+        // order ids of the book carry its asset pair
+        validate_order_id_assets(base_asset_id, quote_asset_id)?;
         let lookup_string = self.get_orderbook_key(base_asset_id, quote_asset_id);
         if !self.validate_controllerbook_exists(base_asset_id, quote_asset_id) {
-            self.orderbooks.insert(
-                lookup_string,
-                SpotOrderbook::new(
-                    base_asset_id,
-                    quote_asset_id,
-                    self.controller_account.clone(),
-                    Arc::clone(&self.bank_controller),
-                    Arc::clone(&self.event_manager),
-                ),
+            let mut orderbook = SpotOrderbook::new(
+                base_asset_id,
+                quote_asset_id,
+                self.controller_account.clone(),
+                Arc::clone(&self.bank_controller),
+                Arc::clone(&self.event_manager),
             );
+            orderbook.admin = admin.clone();
+            self.orderbooks.insert(lookup_string, orderbook);
             Ok(())
         } else {
             Err(GDEXError::OrderBookCreation)
@@ -205,10 +379,24 @@ pub struct SpotOrderbook {
     base_asset_id: AssetId,
     quote_asset_id: AssetId,
     controller_account: AccountPubKey,
+    // account allowed to adjust the fee schedule
+    admin: AccountPubKey,
+    fee_schedule: FeeSchedule,
+    #[serde(skip)]
     bank_controller: Arc<Mutex<BankController>>,
     orderbook: Orderbook,
-    order_to_account: HashMap<OrderId, AccountPubKey>,
+    order_to_account: BTreeMap<OrderId, AccountPubKey>,
+    order_to_client_order_id: BTreeMap<OrderId, ClientOrderId>,
+    // maker fee held back from resting bids, asks pay their maker fee out of the quote a fill pays them
+    order_to_fee_escrow: BTreeMap<OrderId, u64>,
+    // most recent fills of the book, oldest first
+    recent_trades: VecDeque<Trade>,
+    trade_count: u64,
+    // trades executed since the end of the last block
+    #[serde(skip)]
+    unpersisted_trades: Vec<Trade>,
     // shared
+    #[serde(skip)]
     event_manager: Arc<Mutex<EventManager>>,
 }
 
@@ -225,10 +413,17 @@ impl SpotOrderbook {
         SpotOrderbook {
             base_asset_id,
             quote_asset_id,
+            admin: controller_account.clone(),
             controller_account,
+            fee_schedule: FeeSchedule::default(),
             bank_controller,
             orderbook,
-            order_to_account: HashMap::new(),
+            order_to_account: BTreeMap::new(),
+            order_to_client_order_id: BTreeMap::new(),
+            order_to_fee_escrow: BTreeMap::new(),
+            recent_trades: VecDeque::new(),
+            trade_count: 0,
+            unpersisted_trades: Vec::new(),
             event_manager,
         }
     }
@@ -239,14 +434,107 @@ impl SpotOrderbook {
         self.orderbook.get_orderbook_depth()
     }
 
+    pub fn get_base_asset_id(&self) -> AssetId {
+        self.base_asset_id
+    }
+
+    pub fn get_quote_asset_id(&self) -> AssetId {
+        self.quote_asset_id
+    }
+
+    pub fn get_recent_trades(&self) -> &VecDeque<Trade> {
+        &self.recent_trades
+    }
+
+    /// Returns the resting orders of an account with their remaining quantity
+    pub fn get_account_open_orders(&self, account: &AccountPubKey) -> Vec<SpotOrder> {
+        self.orderbook
+            .get_open_orders()
+            .filter(|order| self.order_to_account.get(&order.order_id) == Some(account))
+            .map(|order| SpotOrder {
+                base_asset_id: self.base_asset_id,
+                quote_asset_id: self.quote_asset_id,
+                order_id: order.order_id,
+                client_order_id: self.get_client_order_id(order.order_id),
+                side: order.side as u64,
+                price: order.price,
+                quantity: order.quantity,
+            })
+            .collect()
+    }
+
+    pub fn get_admin(&self) -> &AccountPubKey {
+        &self.admin
+    }
+
+    pub fn get_fee_schedule(&self) -> FeeSchedule {
+        self.fee_schedule
+    }
+
+    // ADMIN FUNCTIONS
+
+    pub fn update_fee_schedule(&mut self, sender: &AccountPubKey, fee_schedule: FeeSchedule) -> Result<(), GDEXError> {
+        if *sender != self.admin {
+            return Err(GDEXError::OrderbookAdmin);
+        }
+        if !fee_schedule.is_valid() {
+            return Err(GDEXError::InvalidFeeSchedule);
+        }
+        self.fee_schedule = fee_schedule;
+        Ok(())
+    }
+
     // TODO - https://github.com/fermiorg/fermi/issues/172 - Restrict overwrite_orderbook to benchmark only
     pub fn overwrite_orderbook(&mut self, new_orderbook: Orderbook) {
-        self.order_to_account = HashMap::new();
+        self.order_to_account = BTreeMap::new();
+        self.order_to_client_order_id = BTreeMap::new();
+        self.order_to_fee_escrow = BTreeMap::new();
         self.orderbook = new_orderbook;
     }
 
     // HELPER FUNCTIONS
 
+    fn get_client_order_id(&self, order_id: OrderId) -> ClientOrderId {
+        self.order_to_client_order_id
+            .get(&order_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // quote reserved on top of a bid, the maker fee escrowed while it rests plus the fee it may pay as a taker
+    fn get_fee_headroom(&self, notional: u64) -> u64 {
+        [Liquidity::Maker, Liquidity::Taker]
+            .iter()
+            .filter_map(|liquidity| self.fee_schedule.compute_fee(*liquidity, 1, notional))
+            .map(|fee| fee.max(0) as u64)
+            .sum()
+    }
+
+    // moves quote between a bid's owner and its fee escrow so that the escrow covers the maker fee of the remaining size
+    fn reset_fee_escrow(
+        &mut self,
+        account: &AccountPubKey,
+        order_id: OrderId,
+        price: u64,
+        quantity: u64,
+    ) -> Result<(), GDEXError> {
+        let fee_escrow = self
+            .fee_schedule
+            .compute_fee(Liquidity::Maker, price, quantity)
+            .ok_or(GDEXError::Conversion)?
+            .max(0) as u64;
+        let previous_fee_escrow = self.order_to_fee_escrow.remove(&order_id).unwrap_or(0);
+        match fee_escrow.cmp(&previous_fee_escrow) {
+            Ordering::Greater => self.receive_quote_asset(account, fee_escrow - previous_fee_escrow)?,
+            Ordering::Less => self.send_quote_asset(account, previous_fee_escrow - fee_escrow)?,
+            Ordering::Equal => {}
+        }
+        if fee_escrow > 0 {
+            self.order_to_fee_escrow.insert(order_id, fee_escrow);
+        }
+        Ok(())
+    }
+
     fn get_base_asset_balance(&self, account: &AccountPubKey) -> Result<u64, GDEXError> {
         self.bank_controller
             .lock()
@@ -325,12 +613,29 @@ impl OrderBookWrapper for SpotOrderbook {
     }
 
     // SETTERS
-    fn set_order(&mut self, order_id: OrderId, account: AccountPubKey) -> Result<(), GDEXError> {
-        // // order id should be constantly increasing
+    fn set_order(
+        &mut self,
+        order_id: OrderId,
+        account: AccountPubKey,
+        client_order_id: ClientOrderId,
+    ) -> Result<(), GDEXError> {
+        // order ids are never reused within a book
         if self.order_to_account.contains_key(&order_id) {
             return Err(GDEXError::OrderRequest);
         }
         self.order_to_account.insert(order_id, account);
+        self.order_to_client_order_id.insert(order_id, client_order_id);
+        Ok(())
+    }
+
+    fn remove_order(&mut self, order_id: OrderId) -> Result<(), GDEXError> {
+        // the account is kept, see https://github.com/fermiorg/fermi/issues/175
+        self.order_to_client_order_id.remove(&order_id);
+        // return whatever maker fee escrow the order did not use
+        if self.order_to_fee_escrow.contains_key(&order_id) {
+            let account = self.get_pub_key_from_order_id(&order_id);
+            self.reset_fee_escrow(&account, order_id, 0, 0)?;
+        }
         Ok(())
     }
 
@@ -352,10 +657,11 @@ impl OrderBookWrapper for SpotOrderbook {
                 }
             }
         } else {
-            // if bid, buying base asset with quantity*price of quote asset
-            if previous_quantity * previous_price < quantity * previous_price {
+            // if bid, buying base asset with quantity*price of quote asset plus the fee it may be charged
+            if previous_quantity * previous_price < quantity * price {
                 let quote_asset_balance = self.get_quote_asset_balance(account)?;
-                if quote_asset_balance < (quantity * price - previous_quantity * previous_price) {
+                let notional = quantity * price - previous_quantity * previous_price;
+                if quote_asset_balance < notional + self.get_fee_headroom(notional) {
                     return Err(GDEXError::OrderExceedsBalance);
                 }
             }
@@ -368,7 +674,7 @@ impl OrderBookWrapper for SpotOrderbook {
     fn update_state_on_limit_order_creation(
         &mut self,
         account: &AccountPubKey,
-        _order_id: u64,
+        order_id: u64,
         side: OrderSide,
         price: u64,
         quantity: u64,
@@ -377,6 +683,7 @@ impl OrderBookWrapper for SpotOrderbook {
             self.receive_base_asset(account, quantity)?;
         } else {
             self.receive_quote_asset(account, quantity * price)?;
+            self.reset_fee_escrow(account, order_id, price, quantity)?;
         }
         Ok(())
     }
@@ -397,11 +704,76 @@ impl OrderBookWrapper for SpotOrderbook {
         Ok(())
     }
 
+    fn update_state_on_market_fill(
+        &mut self,
+        account: &AccountPubKey,
+        order_id: u64,
+        side: OrderSide,
+        price: u64,
+        quantity: u64,
+    ) -> Result<(), GDEXError> {
+        // market orders are not escrowed up front, so collect the taker's side of each fill as it happens
+        if matches!(side, OrderSide::Ask) {
+            self.receive_base_asset(account, quantity)?;
+        } else {
+            self.receive_quote_asset(account, quantity * price)?;
+        }
+        self.update_state_on_fill(account, order_id, side, price, quantity)
+    }
+
+    fn charge_fill_fee(
+        &mut self,
+        account: &AccountPubKey,
+        order_id: u64,
+        liquidity: Liquidity,
+        price: u64,
+        quantity: u64,
+    ) -> Result<i64, GDEXError> {
+        // fees are settled in the quote asset
+        let fee = self
+            .fee_schedule
+            .compute_fee(liquidity, price, quantity)
+            .ok_or(GDEXError::Conversion)?;
+        let mut bank_controller = self.bank_controller.lock().unwrap();
+
+        // a resting bid pays from its escrow, which was sized at placement and so caps the fee
+        if fee > 0 && liquidity == Liquidity::Maker {
+            if let Some(fee_escrow) = self.order_to_fee_escrow.get_mut(&order_id) {
+                let charged = fee.unsigned_abs().min(*fee_escrow);
+                *fee_escrow -= charged;
+                bank_controller.collect_fee(&self.controller_account, self.quote_asset_id, charged)?;
+                return Ok(charged as i64);
+            }
+        }
+
+        match fee.cmp(&0) {
+            Ordering::Greater => bank_controller.collect_fee(account, self.quote_asset_id, fee.unsigned_abs())?,
+            Ordering::Less => bank_controller.pay_rebate(account, self.quote_asset_id, fee.unsigned_abs())?,
+            Ordering::Equal => {}
+        }
+        Ok(fee)
+    }
+
+    fn record_trade(&mut self, taker_side: OrderSide, price: u64, quantity: u64) {
+        self.trade_count += 1;
+        let trade = Trade {
+            trade_id: self.trade_count,
+            taker_side: taker_side as u64,
+            price,
+            quantity,
+        };
+        if self.recent_trades.len() == MAX_RECENT_TRADES {
+            self.recent_trades.pop_front();
+        }
+        self.recent_trades.push_back(trade.clone());
+        self.unpersisted_trades.push(trade);
+    }
+
     #[allow(clippy::collapsible_else_if)]
     fn update_state_on_update(
         &mut self,
         account: &AccountPubKey,
-        _order_id: u64,
+        order_id: u64,
         side: OrderSide,
         previous_price: u64,
         previous_quantity: u64,
@@ -420,6 +792,7 @@ impl OrderBookWrapper for SpotOrderbook {
             } else {
                 self.send_quote_asset(account, previous_quantity * previous_price - quantity * price)?;
             }
+            self.reset_fee_escrow(account, order_id, price, quantity)?;
         }
         Ok(())
     }
@@ -443,7 +816,17 @@ impl OrderBookWrapper for SpotOrderbook {
     // event emitters
 
     fn emit_order_new_event(&mut self, account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64) {
-        self.emit_event(&SpotOrderNewEvent::new(account, order_id, side, price, quantity));
+        let client_order_id = self.get_client_order_id(order_id);
+        self.emit_event(&SpotOrderNewEvent::new(
+            account,
+            self.base_asset_id,
+            self.quote_asset_id,
+            order_id,
+            side,
+            price,
+            quantity,
+            client_order_id,
+        ));
     }
 
     fn emit_order_partial_fill_event(
@@ -453,13 +836,42 @@ impl OrderBookWrapper for SpotOrderbook {
         side: u64,
         price: u64,
         quantity: u64,
+        fee: i64,
     ) {
-        self.emit_event(&SpotOrderFillEvent::new(account, order_id, side, price, quantity));
+        let client_order_id = self.get_client_order_id(order_id);
+        self.emit_event(&SpotOrderPartialFillEvent::new(
+            account,
+            self.base_asset_id,
+            self.quote_asset_id,
+            order_id,
+            side,
+            price,
+            quantity,
+            client_order_id,
+            fee,
+        ));
     }
 
-    fn emit_order_fill_event(&mut self, account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64) {
-        self.emit_event(&SpotOrderPartialFillEvent::new(
-            account, order_id, side, price, quantity,
+    fn emit_order_fill_event(
+        &mut self,
+        account: &AccountPubKey,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        fee: i64,
+    ) {
+        let client_order_id = self.get_client_order_id(order_id);
+        self.emit_event(&SpotOrderFillEvent::new(
+            account,
+            self.base_asset_id,
+            self.quote_asset_id,
+            order_id,
+            side,
+            price,
+            quantity,
+            client_order_id,
+            fee,
         ));
     }
 
@@ -471,11 +883,28 @@ impl OrderBookWrapper for SpotOrderbook {
         price: u64,
         quantity: u64,
     ) {
-        self.emit_event(&SpotOrderUpdateEvent::new(account, order_id, side, price, quantity));
+        let client_order_id = self.get_client_order_id(order_id);
+        self.emit_event(&SpotOrderUpdateEvent::new(
+            account,
+            self.base_asset_id,
+            self.quote_asset_id,
+            order_id,
+            side,
+            price,
+            quantity,
+            client_order_id,
+        ));
     }
 
     fn emit_order_cancel_event(&mut self, account: &AccountPubKey, order_id: u64) {
-        self.emit_event(&SpotOrderCancelEvent::new(account, order_id));
+        let client_order_id = self.get_client_order_id(order_id);
+        self.emit_event(&SpotOrderCancelEvent::new(
+            account,
+            self.base_asset_id,
+            self.quote_asset_id,
+            order_id,
+            client_order_id,
+        ));
     }
 
     fn emit_liquidate_event(
@@ -497,13 +926,14 @@ pub mod spot_tests {
     // crate
     use super::*;
     use crate::bank::controller::{BankController, CREATED_ASSET_BALANCE};
+    use crate::utils::engine::order_book::get_first_order_id;
 
     // fermi
     use fermi_types::crypto::KeypairTraits;
     use fermi_types::{
         account::account_test_functions::generate_keypair_vec,
         block::BlockDigest,
-        order_book::{OrderProcessingResult, OrderSide, Success},
+        order_book::{OrderProcessingResult, OrderSide, Success, TimeInForce},
     };
 
     // mysten
@@ -513,6 +943,8 @@ pub mod spot_tests {
 
     const BASE_ASSET_ID: AssetId = 0;
     const QUOTE_ASSET_ID: AssetId = 1;
+    const ORDER_ID_1: OrderId = get_first_order_id(BASE_ASSET_ID, QUOTE_ASSET_ID);
+    const ORDER_ID_2: OrderId = ORDER_ID_1 + 1;
     const TRANSFER_AMOUNT: u64 = 1_000_000;
 
     // test helpers
@@ -524,7 +956,16 @@ pub mod spot_tests {
         price: u64,
         quantity: u64,
     ) -> OrderProcessingResult {
-        let limit_order_request = LimitOrderRequest::new(BASE_ASSET_ID, QUOTE_ASSET_ID, side as u64, price, quantity);
+        let limit_order_request = LimitOrderRequest::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            side as u64,
+            price,
+            quantity,
+            0,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
+        );
         orderbook_interface
             .place_limit_order(account, &limit_order_request)
             .unwrap()
@@ -633,7 +1074,7 @@ pub mod spot_tests {
             .spot_controller
             .lock()
             .unwrap()
-            .create_orderbook(BASE_ASSET_ID, QUOTE_ASSET_ID)
+            .create_orderbook(account.public(), BASE_ASSET_ID, QUOTE_ASSET_ID)
             .unwrap();
 
         let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
@@ -647,6 +1088,9 @@ pub mod spot_tests {
             OrderSide::Bid as u64,
             bid_price,
             bid_size,
+            0,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
         );
         controller_router
             .spot_controller
@@ -1070,6 +1514,429 @@ pub mod spot_tests {
         }
     }
 
+    #[test]
+    fn client_order_id_echoed_in_events() {
+        let account_0 = generate_keypair_vec([0; 32]).pop().unwrap();
+        let account_1 = generate_keypair_vec([1; 32]).pop().unwrap();
+
+        let mut bank_controller = BankController::default();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller
+            .transfer(account_0.public(), account_1.public(), BASE_ASSET_ID, TRANSFER_AMOUNT)
+            .unwrap();
+        let bank_controller_ref = Arc::new(Mutex::new(bank_controller));
+
+        let event_manager = EventManager::new();
+        let event_manager_ref = Arc::new(Mutex::new(event_manager));
+
+        let controller_account = AccountPubKey::from_bytes(SPOT_CONTROLLER_ACCOUNT_PUBKEY).unwrap();
+        let _create_account_result = bank_controller_ref.lock().unwrap().create_account(&controller_account);
+
+        let mut orderbook_interface = SpotOrderbook::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            controller_account,
+            Arc::clone(&bank_controller_ref),
+            Arc::clone(&event_manager_ref),
+        );
+
+        let ask_client_order_id = 7;
+        let bid_client_order_id = 9;
+        let ask_request = LimitOrderRequest::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            OrderSide::Ask as u64,
+            100,
+            10,
+            ask_client_order_id,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
+        );
+        orderbook_interface
+            .place_limit_order(account_1.public(), &ask_request)
+            .unwrap();
+        let bid_request = LimitOrderRequest::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            OrderSide::Bid as u64,
+            100,
+            4,
+            bid_client_order_id,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
+        );
+        orderbook_interface
+            .place_limit_order(account_0.public(), &bid_request)
+            .unwrap();
+
+        let events = event_manager_ref.lock().unwrap().emit();
+        assert_eq!(events.len(), 4);
+
+        // order ids are assigned sequentially from the start of the book
+        let ask_new: SpotOrderNewEvent = deserialize_protobuf(&events[0].event_bytes).unwrap();
+        assert_eq!(
+            (ask_new.order_id, ask_new.client_order_id),
+            (ORDER_ID_1, ask_client_order_id)
+        );
+        assert_eq!(
+            (ask_new.base_asset_id, ask_new.quote_asset_id),
+            (BASE_ASSET_ID, QUOTE_ASSET_ID)
+        );
+        let bid_new: SpotOrderNewEvent = deserialize_protobuf(&events[1].event_bytes).unwrap();
+        assert_eq!(
+            (bid_new.order_id, bid_new.client_order_id),
+            (ORDER_ID_2, bid_client_order_id)
+        );
+
+        // the incoming bid is filled and the resting ask is partially filled
+        assert_eq!(events[2].event_type, SpotEventType::OrderFill as i32);
+        let bid_fill: SpotOrderFillEvent = deserialize_protobuf(&events[2].event_bytes).unwrap();
+        assert_eq!(
+            (bid_fill.order_id, bid_fill.client_order_id),
+            (ORDER_ID_2, bid_client_order_id)
+        );
+        assert_eq!(events[3].event_type, SpotEventType::OrderPartialFill as i32);
+        let ask_fill: SpotOrderPartialFillEvent = deserialize_protobuf(&events[3].event_bytes).unwrap();
+        assert_eq!(
+            (ask_fill.order_id, ask_fill.client_order_id),
+            (ORDER_ID_1, ask_client_order_id)
+        );
+        assert_eq!(
+            (ask_fill.base_asset_id, ask_fill.quote_asset_id),
+            (BASE_ASSET_ID, QUOTE_ASSET_ID)
+        );
+
+        // client order ids are dropped once an order leaves the book, by a fill or a cancel
+        assert!(!orderbook_interface.order_to_client_order_id.contains_key(&ORDER_ID_2));
+        let cancel_request = CancelOrderRequest::new(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask as u64, ORDER_ID_1);
+        orderbook_interface
+            .place_cancel_order(account_1.public(), &cancel_request)
+            .unwrap();
+        let events = event_manager_ref.lock().unwrap().emit();
+        let ask_cancel: SpotOrderCancelEvent = deserialize_protobuf(&events[0].event_bytes).unwrap();
+        assert_eq!(ask_cancel.client_order_id, ask_client_order_id);
+        assert!(orderbook_interface.order_to_client_order_id.is_empty());
+    }
+
+    #[test]
+    fn fees_charged_on_fills() {
+        let account_0 = generate_keypair_vec([0; 32]).pop().unwrap();
+        let account_1 = generate_keypair_vec([1; 32]).pop().unwrap();
+
+        let mut bank_controller = BankController::default();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller
+            .transfer(account_0.public(), account_1.public(), BASE_ASSET_ID, TRANSFER_AMOUNT)
+            .unwrap();
+        let bank_controller_ref = Arc::new(Mutex::new(bank_controller));
+
+        let event_manager = EventManager::new();
+        let event_manager_ref = Arc::new(Mutex::new(event_manager));
+
+        let controller_account = AccountPubKey::from_bytes(SPOT_CONTROLLER_ACCOUNT_PUBKEY).unwrap();
+        let _create_account_result = bank_controller_ref.lock().unwrap().create_account(&controller_account);
+
+        let mut orderbook_interface = SpotOrderbook::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            controller_account.clone(),
+            Arc::clone(&bank_controller_ref),
+            Arc::clone(&event_manager_ref),
+        );
+
+        // only the admin may adjust fees and a rebate may not exceed the taker fee
+        assert_eq!(
+            orderbook_interface.update_fee_schedule(account_0.public(), FeeSchedule::new(-10, 30)),
+            Err(GDEXError::OrderbookAdmin)
+        );
+        assert_eq!(
+            orderbook_interface.update_fee_schedule(&controller_account, FeeSchedule::new(-40, 30)),
+            Err(GDEXError::InvalidFeeSchedule)
+        );
+        orderbook_interface
+            .update_fee_schedule(&controller_account, FeeSchedule::new(-10, 30))
+            .unwrap();
+
+        let price = 1_000;
+        let quantity = 10;
+        place_limit_order_helper(
+            &mut orderbook_interface,
+            account_1.public(),
+            OrderSide::Ask,
+            price,
+            quantity,
+        );
+        place_limit_order_helper(
+            &mut orderbook_interface,
+            account_0.public(),
+            OrderSide::Bid,
+            price,
+            quantity,
+        );
+
+        // the taker pays 30 bps of the notional and the maker is rebated 10 bps
+        let events = event_manager_ref.lock().unwrap().emit();
+        let taker_fill: SpotOrderFillEvent = deserialize_protobuf(&events[2].event_bytes).unwrap();
+        assert_eq!((taker_fill.order_id, taker_fill.fee), (ORDER_ID_2, 30));
+        let maker_fill: SpotOrderFillEvent = deserialize_protobuf(&events[3].event_bytes).unwrap();
+        assert_eq!((maker_fill.order_id, maker_fill.fee), (ORDER_ID_1, -10));
+
+        let bank_controller = bank_controller_ref.lock().unwrap();
+        assert_eq!(
+            bank_controller.get_balance(account_0.public(), QUOTE_ASSET_ID).unwrap(),
+            CREATED_ASSET_BALANCE - price * quantity - 30
+        );
+        assert_eq!(
+            bank_controller.get_balance(account_1.public(), QUOTE_ASSET_ID).unwrap(),
+            price * quantity + 10
+        );
+        assert_eq!(bank_controller.get_accrued_fees(QUOTE_ASSET_ID), 20);
+    }
+
+    #[test]
+    fn maker_fee_escrowed_while_bid_rests() {
+        let account_0 = generate_keypair_vec([0; 32]).pop().unwrap();
+        let account_1 = generate_keypair_vec([1; 32]).pop().unwrap();
+        let account_2 = generate_keypair_vec([2; 32]).pop().unwrap();
+
+        let mut bank_controller = BankController::default();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller
+            .transfer(account_0.public(), account_1.public(), BASE_ASSET_ID, TRANSFER_AMOUNT)
+            .unwrap();
+        let bank_controller_ref = Arc::new(Mutex::new(bank_controller));
+
+        let event_manager = EventManager::new();
+        let event_manager_ref = Arc::new(Mutex::new(event_manager));
+
+        let controller_account = AccountPubKey::from_bytes(SPOT_CONTROLLER_ACCOUNT_PUBKEY).unwrap();
+        let _create_account_result = bank_controller_ref.lock().unwrap().create_account(&controller_account);
+
+        let mut orderbook_interface = SpotOrderbook::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            controller_account.clone(),
+            Arc::clone(&bank_controller_ref),
+            Arc::clone(&event_manager_ref),
+        );
+        orderbook_interface
+            .update_fee_schedule(&controller_account, FeeSchedule::new(10, 30))
+            .unwrap();
+
+        // the resting bid holds back its notional and a 10 bps maker fee
+        let price = 1_000;
+        let quantity = 10;
+        place_limit_order_helper(
+            &mut orderbook_interface,
+            account_0.public(),
+            OrderSide::Bid,
+            price,
+            quantity,
+        );
+        let free_quote = CREATED_ASSET_BALANCE - price * quantity - 10;
+        assert_eq!(
+            bank_controller_ref
+                .lock()
+                .unwrap()
+                .get_balance(account_0.public(), QUOTE_ASSET_ID)
+                .unwrap(),
+            free_quote
+        );
+
+        // the maker withdraws everything it has not committed to the book
+        bank_controller_ref
+            .lock()
+            .unwrap()
+            .transfer(account_0.public(), account_2.public(), QUOTE_ASSET_ID, free_quote)
+            .unwrap();
+
+        // a taker crossing the bid still fills and the maker fee comes out of the escrow
+        place_limit_order_helper(&mut orderbook_interface, account_1.public(), OrderSide::Ask, price, 4);
+        let events = event_manager_ref.lock().unwrap().emit();
+        let taker_fill: SpotOrderFillEvent = deserialize_protobuf(&events[2].event_bytes).unwrap();
+        assert_eq!((taker_fill.order_id, taker_fill.fee), (ORDER_ID_2, 12));
+        let maker_fill: SpotOrderPartialFillEvent = deserialize_protobuf(&events[3].event_bytes).unwrap();
+        assert_eq!((maker_fill.order_id, maker_fill.fee), (ORDER_ID_1, 4));
+
+        // cancelling the remainder returns its notional along with the unused escrow
+        place_cancel_order_helper(&mut orderbook_interface, account_0.public(), OrderSide::Bid, ORDER_ID_1);
+        let bank_controller = bank_controller_ref.lock().unwrap();
+        assert_eq!(
+            bank_controller.get_balance(account_0.public(), QUOTE_ASSET_ID).unwrap(),
+            price * 6 + 6
+        );
+        assert_eq!(
+            bank_controller.get_balance(account_1.public(), QUOTE_ASSET_ID).unwrap(),
+            price * 4 - 12
+        );
+        assert_eq!(bank_controller.get_accrued_fees(QUOTE_ASSET_ID), 16);
+        assert_eq!(
+            bank_controller
+                .get_balance(&controller_account, QUOTE_ASSET_ID)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn fills_recorded_as_recent_trades() {
+        let account_0 = generate_keypair_vec([0; 32]).pop().unwrap();
+        let account_1 = generate_keypair_vec([1; 32]).pop().unwrap();
+
+        let mut bank_controller = BankController::default();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller
+            .transfer(account_0.public(), account_1.public(), BASE_ASSET_ID, TRANSFER_AMOUNT)
+            .unwrap();
+        let bank_controller_ref = Arc::new(Mutex::new(bank_controller));
+
+        let event_manager = EventManager::new();
+        let event_manager_ref = Arc::new(Mutex::new(event_manager));
+
+        let controller_account = AccountPubKey::from_bytes(SPOT_CONTROLLER_ACCOUNT_PUBKEY).unwrap();
+        let _create_account_result = bank_controller_ref.lock().unwrap().create_account(&controller_account);
+
+        let mut orderbook_interface = SpotOrderbook::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            controller_account,
+            Arc::clone(&bank_controller_ref),
+            Arc::clone(&event_manager_ref),
+        );
+
+        let price = 1_000;
+        place_limit_order_helper(&mut orderbook_interface, account_1.public(), OrderSide::Ask, price, 10);
+        place_limit_order_helper(&mut orderbook_interface, account_0.public(), OrderSide::Bid, price, 4);
+        place_limit_order_helper(&mut orderbook_interface, account_0.public(), OrderSide::Bid, price, 4);
+
+        // each fill is recorded once, from the side of the taker
+        let trades: Vec<Trade> = orderbook_interface.get_recent_trades().iter().cloned().collect();
+        assert_eq!(
+            trades,
+            vec![
+                Trade {
+                    trade_id: 1,
+                    taker_side: OrderSide::Bid as u64,
+                    price,
+                    quantity: 4
+                },
+                Trade {
+                    trade_id: 2,
+                    taker_side: OrderSide::Bid as u64,
+                    price,
+                    quantity: 4
+                },
+            ]
+        );
+
+        // the partially filled ask rests with its remaining quantity
+        let open_orders = orderbook_interface.get_account_open_orders(account_1.public());
+        assert_eq!(open_orders.len(), 1);
+        assert_eq!((open_orders[0].order_id, open_orders[0].quantity), (ORDER_ID_1, 2));
+        assert!(orderbook_interface
+            .get_account_open_orders(account_0.public())
+            .is_empty());
+
+        // the tape only keeps the most recent trades
+        for _ in 0..MAX_RECENT_TRADES {
+            place_limit_order_helper(&mut orderbook_interface, account_1.public(), OrderSide::Ask, price, 1);
+            place_limit_order_helper(&mut orderbook_interface, account_0.public(), OrderSide::Bid, price, 1);
+        }
+        let recent_trades = orderbook_interface.get_recent_trades();
+        assert_eq!(recent_trades.len(), MAX_RECENT_TRADES);
+        assert_eq!(recent_trades.back().unwrap().trade_id, MAX_RECENT_TRADES as u64 + 2);
+    }
+
+    #[test]
+    fn market_order_settles_fills() {
+        let account_0 = generate_keypair_vec([0; 32]).pop().unwrap();
+        let account_1 = generate_keypair_vec([1; 32]).pop().unwrap();
+
+        let mut bank_controller = BankController::default();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller.create_asset(account_0.public()).unwrap();
+        bank_controller
+            .transfer(account_0.public(), account_1.public(), BASE_ASSET_ID, TRANSFER_AMOUNT)
+            .unwrap();
+        bank_controller
+            .transfer(account_0.public(), account_1.public(), QUOTE_ASSET_ID, TRANSFER_AMOUNT)
+            .unwrap();
+        let bank_controller_ref = Arc::new(Mutex::new(bank_controller));
+
+        let event_manager = EventManager::new();
+        let event_manager_ref = Arc::new(Mutex::new(event_manager));
+
+        let controller_account = AccountPubKey::from_bytes(SPOT_CONTROLLER_ACCOUNT_PUBKEY).unwrap();
+        let _create_account_result = bank_controller_ref.lock().unwrap().create_account(&controller_account);
+
+        let mut orderbook_interface = SpotOrderbook::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            controller_account,
+            Arc::clone(&bank_controller_ref),
+            Arc::clone(&event_manager_ref),
+        );
+
+        let ask_size = 10;
+        let ask_price = 100;
+        place_limit_order_helper(
+            &mut orderbook_interface,
+            account_1.public(),
+            OrderSide::Ask,
+            ask_price,
+            ask_size,
+        );
+
+        // a bid without a slippage guard has no upper bound on cost
+        let unguarded_request =
+            MarketOrderRequest::new(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid as u64, 15, 0, 0, 0);
+        assert_eq!(
+            orderbook_interface
+                .place_market_order(account_0.public(), &unguarded_request)
+                .unwrap_err(),
+            GDEXError::MarketOrderUnguarded
+        );
+
+        // only the resting ask can be taken, the rest of the order is cancelled
+        let market_request = MarketOrderRequest::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            OrderSide::Bid as u64,
+            15,
+            0,
+            ask_price,
+            0,
+        );
+        orderbook_interface
+            .place_market_order(account_0.public(), &market_request)
+            .unwrap();
+
+        let events = event_manager_ref.lock().unwrap().emit();
+        assert_eq!(events.last().unwrap().event_type, SpotEventType::OrderCancel as i32);
+
+        let bank_controller = bank_controller_ref.lock().unwrap();
+        assert_eq!(
+            bank_controller.get_balance(account_0.public(), BASE_ASSET_ID).unwrap(),
+            CREATED_ASSET_BALANCE - TRANSFER_AMOUNT + ask_size
+        );
+        assert_eq!(
+            bank_controller.get_balance(account_0.public(), QUOTE_ASSET_ID).unwrap(),
+            CREATED_ASSET_BALANCE - TRANSFER_AMOUNT - ask_size * ask_price
+        );
+        assert_eq!(
+            bank_controller.get_balance(account_1.public(), BASE_ASSET_ID).unwrap(),
+            TRANSFER_AMOUNT - ask_size
+        );
+        assert_eq!(
+            bank_controller.get_balance(account_1.public(), QUOTE_ASSET_ID).unwrap(),
+            TRANSFER_AMOUNT + ask_size * ask_price
+        );
+    }
+
     #[test]
     fn place_update() {
         let account = generate_keypair_vec([0; 32]).pop().unwrap();
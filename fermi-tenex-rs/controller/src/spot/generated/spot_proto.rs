@@ -17,6 +17,12 @@ pub struct MarketOrderRequest {
     pub side: u64,
     #[prost(uint64, tag="4")]
     pub quantity: u64,
+    #[prost(uint64, tag="5")]
+    pub client_order_id: u64,
+    #[prost(uint64, tag="6")]
+    pub worst_price: u64,
+    #[prost(uint64, tag="7")]
+    pub max_quote_spend: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct LimitOrderRequest {
@@ -30,6 +36,12 @@ pub struct LimitOrderRequest {
     pub price: u64,
     #[prost(uint64, tag="5")]
     pub quantity: u64,
+    #[prost(uint64, tag="6")]
+    pub client_order_id: u64,
+    #[prost(uint64, tag="7")]
+    pub time_in_force: u64,
+    #[prost(bool, tag="8")]
+    pub post_only: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateOrderRequest {
@@ -59,6 +71,17 @@ pub struct CancelOrderRequest {
     #[prost(bytes="bytes", tag="5")]
     pub market_admin: ::prost::bytes::Bytes,
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateFeeScheduleRequest {
+    #[prost(uint64, tag="1")]
+    pub base_asset_id: u64,
+    #[prost(uint64, tag="2")]
+    pub quote_asset_id: u64,
+    #[prost(sint64, tag="3")]
+    pub maker_fee_bps: i64,
+    #[prost(sint64, tag="4")]
+    pub taker_fee_bps: i64,
+}
 // EVENTS
 
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -73,6 +96,12 @@ pub struct SpotOrderNewEvent {
     pub price: u64,
     #[prost(uint64, tag="5")]
     pub quantity: u64,
+    #[prost(uint64, tag="6")]
+    pub client_order_id: u64,
+    #[prost(uint64, tag="7")]
+    pub base_asset_id: u64,
+    #[prost(uint64, tag="8")]
+    pub quote_asset_id: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SpotOrderFillEvent {
@@ -86,6 +115,14 @@ pub struct SpotOrderFillEvent {
     pub price: u64,
     #[prost(uint64, tag="5")]
     pub quantity: u64,
+    #[prost(uint64, tag="6")]
+    pub client_order_id: u64,
+    #[prost(int64, tag="7")]
+    pub fee: i64,
+    #[prost(uint64, tag="8")]
+    pub base_asset_id: u64,
+    #[prost(uint64, tag="9")]
+    pub quote_asset_id: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SpotOrderPartialFillEvent {
@@ -99,6 +136,14 @@ pub struct SpotOrderPartialFillEvent {
     pub price: u64,
     #[prost(uint64, tag="5")]
     pub quantity: u64,
+    #[prost(uint64, tag="6")]
+    pub client_order_id: u64,
+    #[prost(int64, tag="7")]
+    pub fee: i64,
+    #[prost(uint64, tag="8")]
+    pub base_asset_id: u64,
+    #[prost(uint64, tag="9")]
+    pub quote_asset_id: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SpotOrderUpdateEvent {
@@ -112,6 +157,12 @@ pub struct SpotOrderUpdateEvent {
     pub price: u64,
     #[prost(uint64, tag="5")]
     pub quantity: u64,
+    #[prost(uint64, tag="6")]
+    pub client_order_id: u64,
+    #[prost(uint64, tag="7")]
+    pub base_asset_id: u64,
+    #[prost(uint64, tag="8")]
+    pub quote_asset_id: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SpotOrderCancelEvent {
@@ -119,6 +170,12 @@ pub struct SpotOrderCancelEvent {
     pub account: ::prost::bytes::Bytes,
     #[prost(uint64, tag="2")]
     pub order_id: u64,
+    #[prost(uint64, tag="3")]
+    pub client_order_id: u64,
+    #[prost(uint64, tag="4")]
+    pub base_asset_id: u64,
+    #[prost(uint64, tag="5")]
+    pub quote_asset_id: u64,
 }
 // ENUMS
 
@@ -130,6 +187,7 @@ pub enum SpotRequestType {
     LimitOrder = 2,
     UpdateOrder = 3,
     CancelOrder = 4,
+    UpdateFeeSchedule = 5,
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
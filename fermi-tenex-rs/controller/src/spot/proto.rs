@@ -6,7 +6,9 @@ use crate::router::ControllerType;
 use fermi_types::{
     account::AccountPubKey,
     error::GDEXError,
-    transaction::{Event, EventTypeEnum, Request, RequestTypeEnum, Transaction},
+    event::DecodedEvent,
+    transaction::{ControllerRequest, Event, EventTypeEnum, Request, RequestTypeEnum, Transaction},
+    utils,
 };
 
 // mysten
@@ -34,6 +36,7 @@ impl RequestTypeEnum for SpotRequestType {
             2 => Ok(SpotRequestType::LimitOrder),
             3 => Ok(SpotRequestType::UpdateOrder),
             4 => Ok(SpotRequestType::CancelOrder),
+            5 => Ok(SpotRequestType::UpdateFeeSchedule),
             _ => Err(GDEXError::DeserializationError),
         }
     }
@@ -77,12 +80,23 @@ impl Request for CreateOrderbookRequest {
 // market order
 
 impl MarketOrderRequest {
-    pub fn new(base_asset_id: u64, quote_asset_id: u64, side: u64, quantity: u64) -> Self {
+    pub fn new(
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        side: u64,
+        quantity: u64,
+        client_order_id: u64,
+        worst_price: u64,
+        max_quote_spend: u64,
+    ) -> Self {
         MarketOrderRequest {
             base_asset_id,
             quote_asset_id,
             side,
             quantity,
+            client_order_id,
+            worst_price,
+            max_quote_spend,
         }
     }
 }
@@ -99,13 +113,25 @@ impl Request for MarketOrderRequest {
 // limit order
 
 impl LimitOrderRequest {
-    pub fn new(base_asset_id: u64, quote_asset_id: u64, side: u64, price: u64, quantity: u64) -> Self {
+    pub fn new(
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+        time_in_force: u64,
+        post_only: bool,
+    ) -> Self {
         LimitOrderRequest {
             base_asset_id,
             quote_asset_id,
             side,
             price,
             quantity,
+            client_order_id,
+            time_in_force,
+            post_only,
         }
     }
 }
@@ -166,18 +192,55 @@ impl Request for CancelOrderRequest {
     }
 }
 
+// update fee schedule
+
+impl UpdateFeeScheduleRequest {
+    pub fn new(base_asset_id: u64, quote_asset_id: u64, maker_fee_bps: i64, taker_fee_bps: i64) -> Self {
+        UpdateFeeScheduleRequest {
+            base_asset_id,
+            quote_asset_id,
+            maker_fee_bps,
+            taker_fee_bps,
+        }
+    }
+}
+
+impl Request for UpdateFeeScheduleRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Spot as i32
+    }
+    fn get_request_type_id() -> i32 {
+        SpotRequestType::UpdateFeeSchedule as i32
+    }
+}
+
 // EVENTS
 
+// order ids encode the pair of their book, order events also carry the pair so subscribers can filter by market
+
 // order new
 
 impl SpotOrderNewEvent {
-    pub fn new(account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account: &AccountPubKey,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+    ) -> Self {
         SpotOrderNewEvent {
             account: Bytes::from(account.as_ref().to_vec()),
+            base_asset_id,
+            quote_asset_id,
             order_id,
             side,
             price,
             quantity,
+            client_order_id,
         }
     }
 }
@@ -191,16 +254,46 @@ impl Event for SpotOrderNewEvent {
     }
 }
 
+impl From<SpotOrderNewEvent> for DecodedEvent {
+    fn from(event: SpotOrderNewEvent) -> Self {
+        DecodedEvent::SpotOrderNew {
+            account: utils::encode_bytes_hex(&event.account),
+            base_asset_id: event.base_asset_id,
+            quote_asset_id: event.quote_asset_id,
+            order_id: event.order_id,
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+            client_order_id: event.client_order_id,
+        }
+    }
+}
+
 // order fill
 
 impl SpotOrderFillEvent {
-    pub fn new(account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account: &AccountPubKey,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+        fee: i64,
+    ) -> Self {
         SpotOrderFillEvent {
             account: Bytes::from(account.as_ref().to_vec()),
+            base_asset_id,
+            quote_asset_id,
             order_id,
             side,
             price,
             quantity,
+            client_order_id,
+            fee,
         }
     }
 }
@@ -214,16 +307,47 @@ impl Event for SpotOrderFillEvent {
     }
 }
 
+impl From<SpotOrderFillEvent> for DecodedEvent {
+    fn from(event: SpotOrderFillEvent) -> Self {
+        DecodedEvent::SpotOrderFill {
+            account: utils::encode_bytes_hex(&event.account),
+            base_asset_id: event.base_asset_id,
+            quote_asset_id: event.quote_asset_id,
+            order_id: event.order_id,
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+            client_order_id: event.client_order_id,
+            fee: event.fee,
+        }
+    }
+}
+
 // order partial fill
 
 impl SpotOrderPartialFillEvent {
-    pub fn new(account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account: &AccountPubKey,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+        fee: i64,
+    ) -> Self {
         SpotOrderPartialFillEvent {
             account: Bytes::from(account.as_ref().to_vec()),
+            base_asset_id,
+            quote_asset_id,
             order_id,
             side,
             price,
             quantity,
+            client_order_id,
+            fee,
         }
     }
 }
@@ -237,16 +361,45 @@ impl Event for SpotOrderPartialFillEvent {
     }
 }
 
+impl From<SpotOrderPartialFillEvent> for DecodedEvent {
+    fn from(event: SpotOrderPartialFillEvent) -> Self {
+        DecodedEvent::SpotOrderPartialFill {
+            account: utils::encode_bytes_hex(&event.account),
+            base_asset_id: event.base_asset_id,
+            quote_asset_id: event.quote_asset_id,
+            order_id: event.order_id,
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+            client_order_id: event.client_order_id,
+            fee: event.fee,
+        }
+    }
+}
+
 // order update
 
 impl SpotOrderUpdateEvent {
-    pub fn new(account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account: &AccountPubKey,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+    ) -> Self {
         SpotOrderUpdateEvent {
             account: Bytes::from(account.as_ref().to_vec()),
+            base_asset_id,
+            quote_asset_id,
             order_id,
             side,
             price,
             quantity,
+            client_order_id,
         }
     }
 }
@@ -260,13 +413,37 @@ impl Event for SpotOrderUpdateEvent {
     }
 }
 
+impl From<SpotOrderUpdateEvent> for DecodedEvent {
+    fn from(event: SpotOrderUpdateEvent) -> Self {
+        DecodedEvent::SpotOrderUpdate {
+            account: utils::encode_bytes_hex(&event.account),
+            base_asset_id: event.base_asset_id,
+            quote_asset_id: event.quote_asset_id,
+            order_id: event.order_id,
+            side: event.side,
+            price: event.price,
+            quantity: event.quantity,
+            client_order_id: event.client_order_id,
+        }
+    }
+}
+
 // order cancel
 
 impl SpotOrderCancelEvent {
-    pub fn new(account: &AccountPubKey, order_id: u64) -> Self {
+    pub fn new(
+        account: &AccountPubKey,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        client_order_id: u64,
+    ) -> Self {
         SpotOrderCancelEvent {
             account: Bytes::from(account.as_ref().to_vec()),
+            base_asset_id,
+            quote_asset_id,
             order_id,
+            client_order_id,
         }
     }
 }
@@ -280,6 +457,18 @@ impl Event for SpotOrderCancelEvent {
     }
 }
 
+impl From<SpotOrderCancelEvent> for DecodedEvent {
+    fn from(event: SpotOrderCancelEvent) -> Self {
+        DecodedEvent::SpotOrderCancel {
+            account: utils::encode_bytes_hex(&event.account),
+            base_asset_id: event.base_asset_id,
+            quote_asset_id: event.quote_asset_id,
+            order_id: event.order_id,
+            client_order_id: event.client_order_id,
+        }
+    }
+}
+
 // TRANSACTION BUILDERS
 
 pub fn create_create_orderbook_transaction(
@@ -303,11 +492,22 @@ pub fn create_market_order_transaction(
     quote_asset_id: u64,
     side: u64,
     quantity: u64,
+    client_order_id: u64,
+    worst_price: u64,
+    max_quote_spend: u64,
 ) -> Transaction {
     Transaction::new(
         sender,
         recent_block_hash,
-        &MarketOrderRequest::new(base_asset_id, quote_asset_id, side, quantity),
+        &MarketOrderRequest::new(
+            base_asset_id,
+            quote_asset_id,
+            side,
+            quantity,
+            client_order_id,
+            worst_price,
+            max_quote_spend,
+        ),
     )
 }
 
@@ -320,11 +520,23 @@ pub fn create_limit_order_transaction(
     side: u64,
     price: u64,
     quantity: u64,
+    client_order_id: u64,
+    time_in_force: u64,
+    post_only: bool,
 ) -> Transaction {
     Transaction::new(
         sender,
         recent_block_hash,
-        &LimitOrderRequest::new(base_asset_id, quote_asset_id, side, price, quantity),
+        &LimitOrderRequest::new(
+            base_asset_id,
+            quote_asset_id,
+            side,
+            price,
+            quantity,
+            client_order_id,
+            time_in_force,
+            post_only,
+        ),
     )
 }
 
@@ -361,3 +573,108 @@ pub fn create_cancel_order_transaction(
         &CancelOrderRequest::new(base_asset_id, quote_asset_id, side, order_id),
     )
 }
+
+pub fn create_update_fee_schedule_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    base_asset_id: u64,
+    quote_asset_id: u64,
+    maker_fee_bps: i64,
+    taker_fee_bps: i64,
+) -> Transaction {
+    Transaction::new(
+        sender,
+        recent_block_hash,
+        &UpdateFeeScheduleRequest::new(base_asset_id, quote_asset_id, maker_fee_bps, taker_fee_bps),
+    )
+}
+
+// REQUEST BUILDERS
+
+pub fn create_create_orderbook_request(base_asset_id: u64, quote_asset_id: u64) -> ControllerRequest {
+    ControllerRequest::new(&CreateOrderbookRequest::new(base_asset_id, quote_asset_id))
+}
+
+pub fn create_market_order_request(
+    base_asset_id: u64,
+    quote_asset_id: u64,
+    side: u64,
+    quantity: u64,
+    client_order_id: u64,
+    worst_price: u64,
+    max_quote_spend: u64,
+) -> ControllerRequest {
+    ControllerRequest::new(&MarketOrderRequest::new(
+        base_asset_id,
+        quote_asset_id,
+        side,
+        quantity,
+        client_order_id,
+        worst_price,
+        max_quote_spend,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_limit_order_request(
+    base_asset_id: u64,
+    quote_asset_id: u64,
+    side: u64,
+    price: u64,
+    quantity: u64,
+    client_order_id: u64,
+    time_in_force: u64,
+    post_only: bool,
+) -> ControllerRequest {
+    ControllerRequest::new(&LimitOrderRequest::new(
+        base_asset_id,
+        quote_asset_id,
+        side,
+        price,
+        quantity,
+        client_order_id,
+        time_in_force,
+        post_only,
+    ))
+}
+
+pub fn create_update_order_request(
+    base_asset_id: u64,
+    quote_asset_id: u64,
+    side: u64,
+    price: u64,
+    quantity: u64,
+    order_id: u64,
+) -> ControllerRequest {
+    ControllerRequest::new(&UpdateOrderRequest::new(
+        base_asset_id,
+        quote_asset_id,
+        side,
+        price,
+        quantity,
+        order_id,
+    ))
+}
+
+pub fn create_cancel_order_request(
+    base_asset_id: u64,
+    quote_asset_id: u64,
+    side: u64,
+    order_id: u64,
+) -> ControllerRequest {
+    ControllerRequest::new(&CancelOrderRequest::new(base_asset_id, quote_asset_id, side, order_id))
+}
+
+pub fn create_update_fee_schedule_request(
+    base_asset_id: u64,
+    quote_asset_id: u64,
+    maker_fee_bps: i64,
+    taker_fee_bps: i64,
+) -> ControllerRequest {
+    ControllerRequest::new(&UpdateFeeScheduleRequest::new(
+        base_asset_id,
+        quote_asset_id,
+        maker_fee_bps,
+        taker_fee_bps,
+    ))
+}
@@ -1 +1,242 @@
-pub struct UnimplementedRPC;
+// IMPORTS
+
+// local
+use crate::router::ControllerRouter;
+use crate::spot::{
+    controller::MAX_RECENT_TRADES,
+    types::{SpotOrder, SpotOrderbookResponse},
+};
+use fermi_types::asset::{AssetId, AssetPairKey};
+use fermi_types::order_book::{OrderbookDepth, Trade, TradeRecord};
+use fermi_types::store::RPCStoreHandle;
+use fermi_types::{account::AccountPubKey, crypto::ToFromBytes, utils};
+
+// mysten
+use sui_json_rpc::SuiRpcModule;
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+
+// external
+use jsonrpsee::core::{async_trait, Error, RpcResult};
+use jsonrpsee::RpcModule;
+use jsonrpsee_proc_macros::rpc;
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of trades returned by a single getSpotTradeHistory call
+pub const MAX_TRADE_HISTORY_PAGE: u64 = 100;
+
+// To implement a custom RPC server, one starts with a trait that defines RPC methods
+// The trait must be annotated with the `rpc` decorator.
+// The methods must return a `Result` type.
+// The `#[method(name = "foo")]` attribute is optional and results in a method named `{namespace}_foo`.
+// If the attribute is not present, the method name is `{namespace}_{method_name}`.
+#[open_rpc(namespace = "tenex", tag = "Primary RPC API")]
+#[rpc(server, client, namespace = "tenex")]
+pub trait ControllerData {
+    #[method(name = "getSpotOrderbooks")]
+    async fn get_spot_orderbooks(&self) -> RpcResult<Vec<SpotOrderbookResponse>>;
+    #[method(name = "getSpotOrderbookDepth")]
+    async fn get_spot_orderbook_depth(
+        &self,
+        base_asset_id: AssetId,
+        quote_asset_id: AssetId,
+        depth: usize, // max depth of 100
+    ) -> RpcResult<OrderbookDepth>;
+    #[method(name = "getSpotOpenOrders")]
+    async fn get_spot_open_orders(&self, account: String) -> RpcResult<Vec<SpotOrder>>;
+    #[method(name = "getSpotRecentTrades")]
+    async fn get_spot_recent_trades(
+        &self,
+        base_asset_id: AssetId,
+        quote_asset_id: AssetId,
+        limit: usize, // max limit of 100
+    ) -> RpcResult<Vec<Trade>>;
+    #[method(name = "getSpotTradeHistory")]
+    async fn get_spot_trade_history(
+        &self,
+        base_asset_id: AssetId,
+        quote_asset_id: AssetId,
+        before_trade_id: Option<u64>,
+        limit: u64, // max limit of 100
+    ) -> RpcResult<Vec<TradeRecord>>;
+}
+
+// The JSONRPCService struct will implement the RPC server
+// To do so, it must implement the trait `{TraitName}Server`
+// this trait is generated by the rpc method above
+// TODO -  - use RWLock
+pub struct JSONRPCService {
+    state_manager: Arc<Mutex<ControllerRouter>>,
+    rpc_store_handle: Arc<RPCStoreHandle>,
+}
+
+impl JSONRPCService {
+    #[allow(clippy::new_without_default)]
+    pub fn new(state_manager: Arc<Mutex<ControllerRouter>>, rpc_store_handle: Arc<RPCStoreHandle>) -> Self {
+        Self {
+            state_manager,
+            rpc_store_handle,
+        }
+    }
+
+    fn get_orderbook_key(&self, base_asset_id: AssetId, quote_asset_id: AssetId) -> AssetPairKey {
+        self.state_manager
+            .lock()
+            .unwrap()
+            .spot_controller
+            .lock()
+            .unwrap()
+            .get_orderbook_key(base_asset_id, quote_asset_id)
+    }
+}
+
+#[async_trait]
+impl ControllerDataServer for JSONRPCService {
+    async fn get_spot_orderbooks(&self) -> RpcResult<Vec<SpotOrderbookResponse>> {
+        let locked_state_manager = self.state_manager.lock().unwrap();
+        let locked_spot_controller = locked_state_manager.spot_controller.lock().unwrap();
+
+        let mut orderbooks_vec: Vec<SpotOrderbookResponse> = locked_spot_controller
+            .get_orderbooks()
+            .values()
+            .map(|orderbook| {
+                let fee_schedule = orderbook.get_fee_schedule();
+                SpotOrderbookResponse {
+                    base_asset_id: orderbook.get_base_asset_id(),
+                    quote_asset_id: orderbook.get_quote_asset_id(),
+                    admin: utils::encode_bytes_hex(orderbook.get_admin()),
+                    maker_fee_bps: fee_schedule.maker_fee_bps,
+                    taker_fee_bps: fee_schedule.taker_fee_bps,
+                }
+            })
+            .collect();
+        orderbooks_vec.sort_by_key(|orderbook| (orderbook.base_asset_id, orderbook.quote_asset_id));
+        Ok(orderbooks_vec)
+    }
+
+    async fn get_spot_orderbook_depth(
+        &self,
+        base_asset_id: AssetId,
+        quote_asset_id: AssetId,
+        depth: usize,
+    ) -> RpcResult<OrderbookDepth> {
+        // do not allow large snapshots to be returned
+        if depth > 100 {
+            return Err(Error::Custom("Depth exceeds 100 which is not allowed".to_string()));
+        }
+
+        let orderbook_depth = self
+            .rpc_store_handle
+            .rpc_store
+            .latest_orderbook_depth_store
+            .read(self.get_orderbook_key(base_asset_id, quote_asset_id))
+            .await
+            .map_err(|_| Error::Custom("Could not load orderbook depth".to_string()))?
+            .ok_or_else(|| Error::Custom("Orderbook does not exist".to_string()))?;
+
+        // reduce each depth level to the requested depth, best prices first
+        Ok(OrderbookDepth {
+            bids: orderbook_depth.bids.into_iter().rev().take(depth).collect(),
+            asks: orderbook_depth.asks.into_iter().take(depth).collect(),
+        })
+    }
+
+    async fn get_spot_open_orders(&self, account: String) -> RpcResult<Vec<SpotOrder>> {
+        let account_bytes: Vec<u8> = utils::decode_bytes_hex(&account)?;
+        let account: AccountPubKey = AccountPubKey::from_bytes(account_bytes.as_slice())
+            .map_err(|_| Error::Custom("Failed to decode account".to_string()))?;
+
+        let locked_state_manager = self.state_manager.lock().unwrap();
+        let locked_spot_controller = locked_state_manager.spot_controller.lock().unwrap();
+
+        let mut open_orders: Vec<SpotOrder> = locked_spot_controller
+            .get_orderbooks()
+            .values()
+            .flat_map(|orderbook| orderbook.get_account_open_orders(&account))
+            .collect();
+        open_orders.sort_by_key(|order| (order.base_asset_id, order.quote_asset_id, order.order_id));
+        Ok(open_orders)
+    }
+
+    async fn get_spot_recent_trades(
+        &self,
+        base_asset_id: AssetId,
+        quote_asset_id: AssetId,
+        limit: usize,
+    ) -> RpcResult<Vec<Trade>> {
+        if limit > MAX_RECENT_TRADES {
+            return Err(Error::Custom(format!(
+                "Limit exceeds {} which is not allowed",
+                MAX_RECENT_TRADES
+            )));
+        }
+
+        let trades = self
+            .rpc_store_handle
+            .rpc_store
+            .latest_trades_store
+            .read(self.get_orderbook_key(base_asset_id, quote_asset_id))
+            .await
+            .map_err(|_| Error::Custom("Could not load recent trades".to_string()))?
+            .unwrap_or_default();
+
+        // most recent trades first
+        Ok(trades.into_iter().rev().take(limit).collect())
+    }
+
+    async fn get_spot_trade_history(
+        &self,
+        base_asset_id: AssetId,
+        quote_asset_id: AssetId,
+        before_trade_id: Option<u64>,
+        limit: u64,
+    ) -> RpcResult<Vec<TradeRecord>> {
+        if limit > MAX_TRADE_HISTORY_PAGE {
+            return Err(Error::Custom(format!(
+                "Limit exceeds {} which is not allowed",
+                MAX_TRADE_HISTORY_PAGE
+            )));
+        }
+
+        let orderbook_key = self.get_orderbook_key(base_asset_id, quote_asset_id);
+        let rpc_store = &self.rpc_store_handle.rpc_store;
+
+        // without a cursor the page starts after the latest trade of the book
+        let end_trade_id = match before_trade_id {
+            Some(before_trade_id) => before_trade_id,
+            None => rpc_store
+                .latest_trades_store
+                .read(orderbook_key.clone())
+                .await
+                .map_err(|_| Error::Custom("Could not load recent trades".to_string()))?
+                .and_then(|trades| trades.last().map(|trade| trade.trade_id + 1))
+                .unwrap_or(0),
+        };
+        // trade ids start at 1
+        let start_trade_id = end_trade_id.saturating_sub(limit).max(1);
+
+        let trades = rpc_store
+            .trade_history_store
+            .read_all(
+                (start_trade_id..end_trade_id)
+                    .rev()
+                    .map(|trade_id| (orderbook_key.clone(), trade_id)),
+            )
+            .await
+            .map_err(|_| Error::Custom("Could not load trade history".to_string()))?;
+
+        // most recent trades first, trades which fell off the tape are skipped
+        Ok(trades.into_iter().flatten().collect())
+    }
+}
+
+// The SuiRPCModule allows us to generate an OpenRPC document for the RPC server.
+impl SuiRpcModule for JSONRPCService {
+    fn rpc(self) -> RpcModule<Self> {
+        ControllerDataServer::into_rpc(self)
+    }
+
+    fn rpc_doc_module() -> Module {
+        ControllerDataOpenRpc::module_doc()
+    }
+}
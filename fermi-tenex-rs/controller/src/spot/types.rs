@@ -0,0 +1,27 @@
+// fermi
+use fermi_types::order_book::ClientOrderId;
+
+// external
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SpotOrderbookResponse {
+    pub base_asset_id: u64,
+    pub quote_asset_id: u64,
+    pub admin: String,
+    pub maker_fee_bps: i64,
+    pub taker_fee_bps: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SpotOrder {
+    pub base_asset_id: u64,
+    pub quote_asset_id: u64,
+    pub order_id: u64,
+    pub client_order_id: ClientOrderId,
+    pub side: u64,
+    pub price: u64,
+    // quantity which is still resting on the book
+    pub quantity: u64,
+}
@@ -6,10 +6,19 @@ use crate::consensus::rpc_server::UnimplementedRPC;
 use crate::controller::Controller;
 use crate::event_manager::{EventEmitter, EventManager};
 use crate::router::ControllerRouter;
-use crate::stake::types::StakeAccount;
+use crate::stake::{
+    proto::*,
+    types::{PendingUnstake, StakeAccount},
+};
 // fermi
 use fermi_types::{
-    account::AccountPubKey, asset::PRIMARY_ASSET_ID, crypto::ToFromBytes, error::GDEXError, transaction::Transaction,
+    account::{AccountPubKey, ValidatorPubKeyBytes},
+    asset::PRIMARY_ASSET_ID,
+    committee::{EpochId, StakeUnit, ValidatorName},
+    crypto::ToFromBytes,
+    error::GDEXError,
+    store::CriticalPathStore,
+    transaction::{deserialize_protobuf, Transaction},
 };
 
 // mysten
@@ -17,12 +26,17 @@ use fermi_types::{
 // external
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::sync::{Arc, Mutex};
+use tracing::warn;
 
 // CONSTANTS
 
 pub const STAKE_CONTROLLER_ACCOUNT_PUBKEY: &[u8] = b"STAKECONTROLLERAAAAAAAAAAAAAAAAA";
+// number of blocks withdrawn stake stays locked before it is returned to the owner
+pub const DEFAULT_UNBONDING_PERIOD: u64 = 100;
+// number of blocks between committee reconfigurations
+pub const DEFAULT_EPOCH_LENGTH: u64 = 1_000;
 
 // INTERFACE
 
@@ -31,19 +45,46 @@ pub const STAKE_CONTROLLER_ACCOUNT_PUBKEY: &[u8] = b"STAKECONTROLLERAAAAAAAAAAAA
 pub struct StakeController {
     // controller state
     controller_account: AccountPubKey,
-    stake_accounts: HashMap<AccountPubKey, StakeAccount>,
+    stake_accounts: BTreeMap<AccountPubKey, StakeAccount>,
+    #[serde(skip)]
     bank_controller: Arc<Mutex<BankController>>,
     total_staked: u64,
+    // unbonding state, pending unstakes are ordered by release block
+    unbonding_period: u64,
+    latest_block_number: u64,
+    pending_unstakes: VecDeque<PendingUnstake>,
+    // epoch state, the committee is rebuilt from stake every epoch_length blocks
+    epoch: EpochId,
+    epoch_length: u64,
+    epoch_start_block: u64,
+    // accounts whose network addresses consensus knows, unset outside of a genesis so that any account may stake
+    validator_set: Option<BTreeSet<AccountPubKey>>,
     // shared
+    #[serde(skip)]
     event_manager: Arc<Mutex<EventManager>>,
 }
 
+/// Stake state a request may modify, the sender's and validator's accounts are the only ones it touches
+#[derive(Clone, Debug)]
+pub struct StakeCheckpoint {
+    stake_accounts: Vec<(AccountPubKey, Option<StakeAccount>)>,
+    total_staked: u64,
+    n_pending_unstakes: usize,
+}
+
 impl Default for StakeController {
     fn default() -> Self {
         Self {
             controller_account: AccountPubKey::from_bytes(STAKE_CONTROLLER_ACCOUNT_PUBKEY).unwrap(),
-            stake_accounts: HashMap::new(),
+            stake_accounts: BTreeMap::new(),
             total_staked: 0,
+            unbonding_period: DEFAULT_UNBONDING_PERIOD,
+            latest_block_number: 0,
+            pending_unstakes: VecDeque::new(),
+            epoch: 0,
+            epoch_length: DEFAULT_EPOCH_LENGTH,
+            epoch_start_block: 0,
+            validator_set: None,
             bank_controller: Arc::new(Mutex::new(BankController::default())), // TEMPORARY
             // shared state
             event_manager: Arc::new(Mutex::new(EventManager::new())), // TEMPORARY
@@ -66,8 +107,69 @@ impl Controller<UnimplementedRPC> for StakeController {
         Ok(())
     }
 
-    fn handle_consensus_transaction(&mut self, _transaction: &Transaction) -> Result<(), GDEXError> {
-        Err(GDEXError::InvalidRequestTypeError)
+    type Checkpoint = StakeCheckpoint;
+
+    fn checkpoint(&self, transaction: &Transaction) -> Self::Checkpoint {
+        // a request which cannot be decoded fails before it modifies any account
+        let mut touched_accounts = Vec::new();
+        if let Ok(sender) = transaction.get_sender() {
+            touched_accounts.push(sender);
+        }
+        if let Ok(Some(validator)) = self.get_request_validator(transaction) {
+            touched_accounts.push(validator);
+        }
+        StakeCheckpoint {
+            stake_accounts: touched_accounts
+                .into_iter()
+                .map(|account_pub_key| {
+                    let stake_account = self.stake_accounts.get(&account_pub_key).cloned();
+                    (account_pub_key, stake_account)
+                })
+                .collect(),
+            total_staked: self.total_staked,
+            n_pending_unstakes: self.pending_unstakes.len(),
+        }
+    }
+
+    fn rollback(&mut self, checkpoint: Self::Checkpoint) {
+        for (account_pub_key, stake_account) in checkpoint.stake_accounts {
+            match stake_account {
+                Some(stake_account) => self.stake_accounts.insert(account_pub_key, stake_account),
+                None => self.stake_accounts.remove(&account_pub_key),
+            };
+        }
+        self.total_staked = checkpoint.total_staked;
+        self.pending_unstakes.truncate(checkpoint.n_pending_unstakes);
+    }
+
+    fn handle_consensus_transaction(&mut self, transaction: &Transaction) -> Result<(), GDEXError> {
+        let request_type: StakeRequestType = transaction.get_request_type()?;
+        match request_type {
+            StakeRequestType::Stake => {
+                let request: StakeRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let sender = transaction.get_sender()?;
+                self.stake(&sender, request.amount)
+            }
+            StakeRequestType::Unstake => {
+                let request: UnstakeRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let sender = transaction.get_sender()?;
+                match request.get_validator()? {
+                    Some(validator) => self.undelegate(&sender, &validator, request.amount),
+                    None => self.unstake(&sender, request.amount),
+                }
+            }
+            StakeRequestType::Delegate => {
+                let request: DelegateRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                let sender = transaction.get_sender()?;
+                let validator = request.get_validator()?;
+                self.delegate(&sender, &validator, request.amount)
+            }
+        }
+    }
+
+    fn critical_process_end_of_block(&mut self, _critical_path_store: &CriticalPathStore, block_number: u64) {
+        self.process_unbonding(block_number);
+        self.process_epoch(block_number);
     }
 }
 
@@ -78,6 +180,22 @@ impl EventEmitter for StakeController {
 }
 
 impl StakeController {
+    // validator whose delegated stake the request changes, if any
+    fn get_request_validator(&self, transaction: &Transaction) -> Result<Option<AccountPubKey>, GDEXError> {
+        let request_type: StakeRequestType = transaction.get_request_type()?;
+        match request_type {
+            StakeRequestType::Stake => Ok(None),
+            StakeRequestType::Unstake => {
+                let request: UnstakeRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                request.get_validator()
+            }
+            StakeRequestType::Delegate => {
+                let request: DelegateRequest = deserialize_protobuf(&transaction.request_bytes)?;
+                Ok(Some(request.get_validator()?))
+            }
+        }
+    }
+
     pub fn create_account(&mut self, account_pub_key: &AccountPubKey) -> Result<(), GDEXError> {
         if self.stake_accounts.contains_key(account_pub_key) {
             Err(GDEXError::AccountCreation)
@@ -96,8 +214,28 @@ impl StakeController {
         Ok(stake_account.get_staked_amount())
     }
 
+    pub fn get_delegated(&self, delegator: &AccountPubKey, validator: &AccountPubKey) -> Result<u64, GDEXError> {
+        let stake_account = self.stake_accounts.get(delegator).ok_or(GDEXError::AccountLookup)?;
+        Ok(stake_account.get_delegation(validator))
+    }
+
+    // own stake plus the stake delegated to the account
+    pub fn get_voting_stake(&self, account_pub_key: &AccountPubKey) -> Result<u64, GDEXError> {
+        let stake_account = self
+            .stake_accounts
+            .get(account_pub_key)
+            .ok_or(GDEXError::AccountLookup)?;
+        Ok(stake_account.get_staked_amount() + stake_account.get_delegated_amount())
+    }
+
     // stake funds to participate in consensus
     pub fn stake(&mut self, account_pub_key: &AccountPubKey, amount: u64) -> Result<(), GDEXError> {
+        // stake from outside the validator set could never be counted towards a committee
+        if let Some(validator_set) = &self.validator_set {
+            if !validator_set.contains(account_pub_key) {
+                return Err(GDEXError::StakeIneligible);
+            }
+        }
         self.bank_controller.lock().unwrap().transfer(
             account_pub_key,
             &self.controller_account,
@@ -105,44 +243,220 @@ impl StakeController {
             amount,
         )?;
         self.total_staked += amount;
-        let lookup = self.stake_accounts.get_mut(account_pub_key);
-        match lookup {
-            Some(stake_account) => {
-                stake_account.set_staked_amount(stake_account.get_staked_amount() + amount as u64);
-                Ok(())
-            }
-            None => {
-                let mut new_stake_account = StakeAccount::new(account_pub_key.clone());
-                new_stake_account.set_staked_amount(amount);
-                self.stake_accounts.insert(account_pub_key.clone(), new_stake_account);
-                Ok(())
-            }
-        }
+        let stake_account = self
+            .stake_accounts
+            .entry(account_pub_key.clone())
+            .or_insert_with(|| StakeAccount::new(account_pub_key.clone()));
+        stake_account.set_staked_amount(stake_account.get_staked_amount() + amount);
+        self.emit_event(&StakedEvent::new(account_pub_key, amount));
+        Ok(())
     }
 
+    // withdraw own stake, funds are returned once the unbonding period has elapsed
     pub fn unstake(&mut self, account_pub_key: &AccountPubKey, amount: u64) -> Result<(), GDEXError> {
-        self.total_staked -= amount;
-        self.bank_controller.lock().unwrap().transfer(
-            &self.controller_account,
-            account_pub_key,
-            PRIMARY_ASSET_ID,
-            amount,
-        )?;
         let stake_account = self
             .stake_accounts
             .get_mut(account_pub_key)
             .ok_or(GDEXError::AccountLookup)?;
-        stake_account.set_staked_amount(stake_account.get_staked_amount() - amount);
+        let staked_amount = *stake_account.get_staked_amount();
+        if staked_amount < amount {
+            return Err(GDEXError::InsufficientStake);
+        }
+        stake_account.set_staked_amount(staked_amount - amount);
+        self.total_staked -= amount;
+        self.begin_unbonding(account_pub_key, amount);
         Ok(())
     }
 
-    pub fn get_accounts(&self) -> &HashMap<AccountPubKey, StakeAccount> {
+    // delegate funds to a staked validator, which adds to the validator's voting stake
+    pub fn delegate(
+        &mut self,
+        delegator: &AccountPubKey,
+        validator: &AccountPubKey,
+        amount: u64,
+    ) -> Result<(), GDEXError> {
+        if delegator == validator {
+            return Err(GDEXError::DelegationTarget);
+        }
+        match self.stake_accounts.get(validator) {
+            Some(validator_account) if *validator_account.get_staked_amount() > 0 => {}
+            _ => return Err(GDEXError::DelegationTarget),
+        }
+        self.bank_controller
+            .lock()
+            .unwrap()
+            .transfer(delegator, &self.controller_account, PRIMARY_ASSET_ID, amount)?;
+        self.total_staked += amount;
+
+        let delegator_account = self
+            .stake_accounts
+            .entry(delegator.clone())
+            .or_insert_with(|| StakeAccount::new(delegator.clone()));
+        delegator_account.set_delegation(validator, delegator_account.get_delegation(validator) + amount);
+
+        let validator_account = self.stake_accounts.get_mut(validator).ok_or(GDEXError::AccountLookup)?;
+        validator_account.set_delegated_amount(validator_account.get_delegated_amount() + amount);
+
+        self.emit_event(&DelegatedEvent::new(delegator, validator, amount));
+        Ok(())
+    }
+
+    // withdraw a delegation, funds are returned once the unbonding period has elapsed
+    pub fn undelegate(
+        &mut self,
+        delegator: &AccountPubKey,
+        validator: &AccountPubKey,
+        amount: u64,
+    ) -> Result<(), GDEXError> {
+        let delegated_amount = self
+            .stake_accounts
+            .get(validator)
+            .ok_or(GDEXError::AccountLookup)?
+            .get_delegated_amount()
+            .checked_sub(amount)
+            .ok_or(GDEXError::InsufficientStake)?;
+
+        let delegator_account = self.stake_accounts.get_mut(delegator).ok_or(GDEXError::AccountLookup)?;
+        let delegation = delegator_account.get_delegation(validator);
+        if delegation < amount {
+            return Err(GDEXError::InsufficientStake);
+        }
+        delegator_account.set_delegation(validator, delegation - amount);
+
+        let validator_account = self.stake_accounts.get_mut(validator).ok_or(GDEXError::AccountLookup)?;
+        validator_account.set_delegated_amount(delegated_amount);
+
+        self.total_staked -= amount;
+        self.begin_unbonding(delegator, amount);
+        Ok(())
+    }
+
+    fn begin_unbonding(&mut self, account_pub_key: &AccountPubKey, amount: u64) {
+        let release_block = self.latest_block_number + self.unbonding_period;
+        self.pending_unstakes.push_back(PendingUnstake {
+            account_pub_key: account_pub_key.clone(),
+            amount,
+            release_block,
+        });
+        self.emit_event(&UnstakeStartedEvent::new(account_pub_key, amount, release_block));
+    }
+
+    // return every pending unstake whose release block has been reached
+    pub fn process_unbonding(&mut self, block_number: u64) {
+        self.latest_block_number = block_number;
+        let mut failed_unstakes = VecDeque::new();
+        while let Some(pending_unstake) = self.pending_unstakes.front() {
+            if pending_unstake.release_block > block_number {
+                break;
+            }
+            let pending_unstake = self.pending_unstakes.pop_front().unwrap();
+            if let Err(err) = self.bank_controller.lock().unwrap().transfer(
+                &self.controller_account,
+                &pending_unstake.account_pub_key,
+                PRIMARY_ASSET_ID,
+                pending_unstake.amount,
+            ) {
+                warn!(
+                    "Failed to release unstake of {} to {:?}, retrying next block: {:?}",
+                    pending_unstake.amount, pending_unstake.account_pub_key, err
+                );
+                failed_unstakes.push_back(pending_unstake);
+            }
+        }
+        // failed entries are skipped rather than holding up the rest of the queue, they stay due for a retry
+        while let Some(pending_unstake) = failed_unstakes.pop_back() {
+            self.pending_unstakes.push_front(pending_unstake);
+        }
+    }
+
+    // advance the epoch when block_number lands on an epoch boundary, paying out the epoch's rewards
+    pub fn process_epoch(&mut self, block_number: u64) {
+        if self.epoch_length > 0 && block_number > 0 && block_number % self.epoch_length == 0 {
+            self.distribute_rewards();
+            self.epoch += 1;
+            self.epoch_start_block = block_number;
+        }
+    }
+
+    // pay the transaction fees held by the reward pool out pro rata to own and delegated stake,
+    // the rounding remainder stays in the pool for the next epoch
+    pub fn distribute_rewards(&mut self) {
+        if self.total_staked == 0 {
+            return;
+        }
+        let mut bank_controller = self.bank_controller.lock().unwrap();
+        let reward_pool_account = bank_controller.get_reward_pool_account().clone();
+        let reward_pool_balance = bank_controller.get_reward_pool_balance();
+        if reward_pool_balance == 0 {
+            return;
+        }
+        for (account_pub_key, stake_account) in &self.stake_accounts {
+            let stake = stake_account.get_staked_amount() + stake_account.get_delegations().values().sum::<u64>();
+            let reward = (reward_pool_balance as u128 * stake as u128 / self.total_staked as u128) as u64;
+            if reward == 0 {
+                continue;
+            }
+            if let Err(err) = bank_controller.transfer(&reward_pool_account, account_pub_key, PRIMARY_ASSET_ID, reward)
+            {
+                warn!("Failed to pay reward of {reward} to {:?}: {:?}", account_pub_key, err);
+            }
+        }
+    }
+
+    // voting rights of the next committee, accounts without their own stake are excluded
+    pub fn get_voting_rights(&self) -> BTreeMap<ValidatorName, StakeUnit> {
+        self.stake_accounts
+            .iter()
+            .filter(|(_, stake_account)| *stake_account.get_staked_amount() > 0)
+            .map(|(account_pub_key, stake_account)| {
+                (
+                    ValidatorPubKeyBytes::from(account_pub_key),
+                    stake_account.get_staked_amount() + stake_account.get_delegated_amount(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn get_accounts(&self) -> &BTreeMap<AccountPubKey, StakeAccount> {
         &self.stake_accounts
     }
 
     pub fn get_total_staked(&self) -> u64 {
         self.total_staked
     }
+
+    pub fn get_pending_unstakes(&self) -> &VecDeque<PendingUnstake> {
+        &self.pending_unstakes
+    }
+
+    pub fn get_unbonding_period(&self) -> u64 {
+        self.unbonding_period
+    }
+
+    pub fn set_unbonding_period(&mut self, unbonding_period: u64) {
+        self.unbonding_period = unbonding_period;
+    }
+
+    pub fn get_epoch(&self) -> EpochId {
+        self.epoch
+    }
+
+    /// Returns the last block of the previous epoch, blocks after it are sequenced by the current committee
+    pub fn get_epoch_start_block(&self) -> u64 {
+        self.epoch_start_block
+    }
+
+    pub fn get_epoch_length(&self) -> u64 {
+        self.epoch_length
+    }
+
+    pub fn set_epoch_length(&mut self, epoch_length: u64) {
+        self.epoch_length = epoch_length;
+    }
+
+    pub fn set_validator_set(&mut self, validator_set: BTreeSet<AccountPubKey>) {
+        self.validator_set = Some(validator_set);
+    }
 }
 
 /// Begin the testing suite for account
@@ -150,31 +464,36 @@ impl StakeController {
 pub mod stake_tests {
     use super::*;
     use crate::bank::controller::CREATED_ASSET_BALANCE;
+    use fastcrypto::DIGEST_LEN;
     use fermi_types::account::account_test_functions::generate_keypair_vec;
     use fermi_types::crypto::KeypairTraits;
+    use narwhal_types::CertificateDigest;
 
     const STAKE_AMOUNT: u64 = 1_000;
-    #[test]
-    fn stake() {
-        let sender = generate_keypair_vec([0; 32]).pop().unwrap();
 
+    // router whose primary asset is created by the owner, who funds each of the listed accounts
+    fn create_router(owner: &AccountPubKey, funded_accounts: &[(&AccountPubKey, u64)]) -> ControllerRouter {
         let controller_router = ControllerRouter::default();
         controller_router.initialize_controllers();
         controller_router.initialize_controller_accounts();
-        let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
-
-        controller_router
-            .bank_controller
-            .lock()
-            .unwrap()
-            .create_asset(sender.public())
-            .unwrap();
+        {
+            let mut bank_controller = controller_router.bank_controller.lock().unwrap();
+            bank_controller.create_asset(owner).unwrap();
+            for (account, amount) in funded_accounts {
+                bank_controller
+                    .transfer(owner, account, PRIMARY_ASSET_ID, *amount)
+                    .unwrap();
+            }
+        }
         controller_router
-            .bank_controller
-            .lock()
-            .unwrap()
-            .create_asset(sender.public())
-            .unwrap();
+    }
+
+    #[test]
+    fn stake() {
+        let sender = generate_keypair_vec([0; 32]).pop().unwrap();
+
+        let controller_router = create_router(sender.public(), &[]);
+        let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
 
         controller_router
             .stake_controller
@@ -221,24 +540,9 @@ pub mod stake_tests {
     fn stake_empty() {
         let sender = generate_keypair_vec([0; 32]).pop().unwrap();
 
-        let controller_router = ControllerRouter::default();
-        controller_router.initialize_controllers();
-        controller_router.initialize_controller_accounts();
+        let controller_router = create_router(sender.public(), &[]);
         let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
 
-        controller_router
-            .bank_controller
-            .lock()
-            .unwrap()
-            .create_asset(sender.public())
-            .unwrap();
-        controller_router
-            .bank_controller
-            .lock()
-            .unwrap()
-            .create_asset(sender.public())
-            .unwrap();
-
         controller_router
             .stake_controller
             .lock()
@@ -290,24 +594,9 @@ pub mod stake_tests {
     fn failed_stake() {
         let sender = generate_keypair_vec([0; 32]).pop().unwrap();
 
-        let controller_router = ControllerRouter::default();
-        controller_router.initialize_controllers();
-        controller_router.initialize_controller_accounts();
+        let controller_router = create_router(sender.public(), &[]);
         let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
 
-        controller_router
-            .bank_controller
-            .lock()
-            .unwrap()
-            .create_asset(sender.public())
-            .unwrap();
-        controller_router
-            .bank_controller
-            .lock()
-            .unwrap()
-            .create_asset(sender.public())
-            .unwrap();
-
         assert!(
             bank_controller_ref
                 .lock()
@@ -343,4 +632,318 @@ pub mod stake_tests {
             Err(_) => panic!("deserializing catchup_state_default failed"),
         }
     }
+
+    #[test]
+    fn unstake_without_stake_fails() {
+        let sender = generate_keypair_vec([0; 32]).pop().unwrap();
+
+        let controller_router = create_router(sender.public(), &[]);
+
+        let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+        assert_eq!(
+            stake_controller.unstake(sender.public(), STAKE_AMOUNT),
+            Err(GDEXError::AccountLookup)
+        );
+
+        stake_controller.stake(sender.public(), STAKE_AMOUNT).unwrap();
+        assert_eq!(
+            stake_controller.unstake(sender.public(), STAKE_AMOUNT + 1),
+            Err(GDEXError::InsufficientStake)
+        );
+        assert_eq!(*stake_controller.get_staked(sender.public()).unwrap(), STAKE_AMOUNT);
+        assert_eq!(stake_controller.get_total_staked(), STAKE_AMOUNT);
+        assert!(stake_controller.get_pending_unstakes().is_empty());
+    }
+
+    #[test]
+    fn unstake_released_after_unbonding_period() {
+        let sender = generate_keypair_vec([0; 32]).pop().unwrap();
+
+        let controller_router = create_router(sender.public(), &[]);
+        let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
+
+        let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+        stake_controller.set_unbonding_period(2);
+        stake_controller.stake(sender.public(), STAKE_AMOUNT).unwrap();
+        stake_controller.unstake(sender.public(), STAKE_AMOUNT).unwrap();
+
+        assert_eq!(*stake_controller.get_staked(sender.public()).unwrap(), 0);
+        assert_eq!(stake_controller.get_total_staked(), 0);
+        assert_eq!(stake_controller.get_pending_unstakes().len(), 1);
+
+        // funds stay locked until the release block is reached
+        stake_controller.process_unbonding(1);
+        assert_eq!(stake_controller.get_pending_unstakes().len(), 1);
+        assert_eq!(
+            bank_controller_ref
+                .lock()
+                .unwrap()
+                .get_balance(sender.public(), PRIMARY_ASSET_ID)
+                .unwrap(),
+            CREATED_ASSET_BALANCE - STAKE_AMOUNT
+        );
+
+        stake_controller.process_unbonding(2);
+        assert!(stake_controller.get_pending_unstakes().is_empty());
+        assert_eq!(
+            bank_controller_ref
+                .lock()
+                .unwrap()
+                .get_balance(sender.public(), PRIMARY_ASSET_ID)
+                .unwrap(),
+            CREATED_ASSET_BALANCE
+        );
+    }
+
+    #[test]
+    fn failed_unstake_release_does_not_block_queue() {
+        let mut keypairs = generate_keypair_vec([0; 32]);
+        let first = keypairs.pop().unwrap();
+        let second = keypairs.pop().unwrap();
+
+        let controller_router = create_router(first.public(), &[(second.public(), STAKE_AMOUNT)]);
+        let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
+
+        let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+        stake_controller.set_unbonding_period(2);
+        stake_controller.stake(first.public(), STAKE_AMOUNT).unwrap();
+        stake_controller.stake(second.public(), STAKE_AMOUNT / 2).unwrap();
+        stake_controller.unstake(first.public(), STAKE_AMOUNT).unwrap();
+        stake_controller.unstake(second.public(), STAKE_AMOUNT / 2).unwrap();
+
+        // leave the controller only enough to release the second unstake
+        let controller_account = AccountPubKey::from_bytes(STAKE_CONTROLLER_ACCOUNT_PUBKEY).unwrap();
+        bank_controller_ref
+            .lock()
+            .unwrap()
+            .transfer(&controller_account, first.public(), PRIMARY_ASSET_ID, STAKE_AMOUNT)
+            .unwrap();
+
+        stake_controller.process_unbonding(2);
+        let pending_unstakes = stake_controller.get_pending_unstakes();
+        assert_eq!(pending_unstakes.len(), 1);
+        assert_eq!(pending_unstakes[0].account_pub_key, *first.public());
+        assert_eq!(
+            bank_controller_ref
+                .lock()
+                .unwrap()
+                .get_balance(second.public(), PRIMARY_ASSET_ID)
+                .unwrap(),
+            STAKE_AMOUNT
+        );
+    }
+
+    #[test]
+    fn delegate_and_undelegate() {
+        let mut keypairs = generate_keypair_vec([0; 32]);
+        let validator = keypairs.pop().unwrap();
+        let delegator = keypairs.pop().unwrap();
+        const DELEGATE_AMOUNT: u64 = 500;
+
+        let controller_router = create_router(validator.public(), &[(delegator.public(), DELEGATE_AMOUNT)]);
+        let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
+
+        let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+
+        // delegating to an account without stake is rejected
+        assert_eq!(
+            stake_controller.delegate(delegator.public(), validator.public(), DELEGATE_AMOUNT),
+            Err(GDEXError::DelegationTarget)
+        );
+
+        stake_controller.stake(validator.public(), STAKE_AMOUNT).unwrap();
+        stake_controller
+            .delegate(delegator.public(), validator.public(), DELEGATE_AMOUNT)
+            .unwrap();
+
+        assert_eq!(
+            stake_controller.get_voting_stake(validator.public()).unwrap(),
+            STAKE_AMOUNT + DELEGATE_AMOUNT
+        );
+        assert_eq!(
+            stake_controller
+                .get_delegated(delegator.public(), validator.public())
+                .unwrap(),
+            DELEGATE_AMOUNT
+        );
+        assert_eq!(stake_controller.get_total_staked(), STAKE_AMOUNT + DELEGATE_AMOUNT);
+
+        assert_eq!(
+            stake_controller.undelegate(delegator.public(), validator.public(), DELEGATE_AMOUNT + 1),
+            Err(GDEXError::InsufficientStake)
+        );
+        stake_controller
+            .undelegate(delegator.public(), validator.public(), DELEGATE_AMOUNT)
+            .unwrap();
+        assert_eq!(
+            stake_controller.get_voting_stake(validator.public()).unwrap(),
+            STAKE_AMOUNT
+        );
+
+        let release_block = stake_controller.get_unbonding_period();
+        stake_controller.process_unbonding(release_block);
+        assert_eq!(
+            bank_controller_ref
+                .lock()
+                .unwrap()
+                .get_balance(delegator.public(), PRIMARY_ASSET_ID)
+                .unwrap(),
+            DELEGATE_AMOUNT
+        );
+    }
+
+    #[test]
+    fn stake_requests_through_consensus() {
+        let mut keypairs = generate_keypair_vec([0; 32]);
+        let validator = keypairs.pop().unwrap();
+        let delegator = keypairs.pop().unwrap();
+        let recent_block_hash = CertificateDigest::new([0; DIGEST_LEN]);
+
+        let controller_router = create_router(validator.public(), &[(delegator.public(), STAKE_AMOUNT)]);
+        let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
+
+        let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+        stake_controller
+            .handle_consensus_transaction(&create_stake_transaction(
+                validator.public(),
+                recent_block_hash,
+                STAKE_AMOUNT,
+            ))
+            .unwrap();
+        stake_controller
+            .handle_consensus_transaction(&create_delegate_transaction(
+                delegator.public(),
+                recent_block_hash,
+                validator.public(),
+                STAKE_AMOUNT,
+            ))
+            .unwrap();
+        assert_eq!(
+            stake_controller.get_voting_stake(validator.public()).unwrap(),
+            2 * STAKE_AMOUNT
+        );
+
+        stake_controller
+            .handle_consensus_transaction(&create_unstake_transaction(
+                delegator.public(),
+                recent_block_hash,
+                STAKE_AMOUNT,
+                Some(validator.public()),
+            ))
+            .unwrap();
+        stake_controller
+            .handle_consensus_transaction(&create_unstake_transaction(
+                validator.public(),
+                recent_block_hash,
+                STAKE_AMOUNT,
+                None,
+            ))
+            .unwrap();
+        assert_eq!(stake_controller.get_total_staked(), 0);
+        assert_eq!(stake_controller.get_pending_unstakes().len(), 2);
+    }
+
+    #[test]
+    fn epoch_advances_with_voting_rights() {
+        let mut keypairs = generate_keypair_vec([0; 32]);
+        let validator = keypairs.pop().unwrap();
+        let delegator = keypairs.pop().unwrap();
+
+        let controller_router = create_router(validator.public(), &[(delegator.public(), STAKE_AMOUNT)]);
+        let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
+
+        let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+        stake_controller.set_epoch_length(10);
+        stake_controller.stake(validator.public(), STAKE_AMOUNT).unwrap();
+        stake_controller
+            .delegate(delegator.public(), validator.public(), STAKE_AMOUNT)
+            .unwrap();
+
+        // delegators do not join the committee themselves
+        let voting_rights = stake_controller.get_voting_rights();
+        assert_eq!(voting_rights.len(), 1);
+        assert_eq!(
+            voting_rights.get(&ValidatorPubKeyBytes::from(validator.public())),
+            Some(&(2 * STAKE_AMOUNT))
+        );
+
+        stake_controller.process_epoch(9);
+        assert_eq!(stake_controller.get_epoch(), 0);
+        stake_controller.process_epoch(10);
+        assert_eq!(stake_controller.get_epoch(), 1);
+        assert_eq!(stake_controller.get_epoch_start_block(), 10);
+
+        // a validator leaves the committee once its own stake is withdrawn
+        stake_controller.unstake(validator.public(), STAKE_AMOUNT).unwrap();
+        assert!(stake_controller.get_voting_rights().is_empty());
+    }
+
+    #[test]
+    fn rewards_distributed_at_epoch_boundary() {
+        let mut keypairs = generate_keypair_vec([0; 32]);
+        let validator = keypairs.pop().unwrap();
+        let delegator = keypairs.pop().unwrap();
+        const DELEGATE_AMOUNT: u64 = 500;
+        const COLLECTED_FEES: u64 = 301;
+
+        let controller_router = create_router(validator.public(), &[(delegator.public(), DELEGATE_AMOUNT)]);
+        let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
+        let balance = |account_pub_key: &AccountPubKey| {
+            bank_controller_ref
+                .lock()
+                .unwrap()
+                .get_balance(account_pub_key, PRIMARY_ASSET_ID)
+                .unwrap()
+        };
+
+        let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+        stake_controller.set_epoch_length(10);
+        stake_controller.stake(validator.public(), STAKE_AMOUNT).unwrap();
+        stake_controller
+            .delegate(delegator.public(), validator.public(), DELEGATE_AMOUNT)
+            .unwrap();
+        bank_controller_ref
+            .lock()
+            .unwrap()
+            .charge_transaction_fee(validator.public(), COLLECTED_FEES)
+            .unwrap();
+        let (validator_balance, delegator_balance) = (balance(validator.public()), balance(delegator.public()));
+
+        // rewards are held until the epoch ends
+        stake_controller.process_epoch(9);
+        assert_eq!(
+            bank_controller_ref.lock().unwrap().get_reward_pool_balance(),
+            COLLECTED_FEES
+        );
+
+        // own stake and delegations are rewarded pro rata, the remainder stays in the pool
+        stake_controller.process_epoch(10);
+        assert_eq!(balance(validator.public()), validator_balance + 200);
+        assert_eq!(balance(delegator.public()), delegator_balance + 100);
+        assert_eq!(bank_controller_ref.lock().unwrap().get_reward_pool_balance(), 1);
+    }
+
+    #[test]
+    fn stake_restricted_to_validator_set() {
+        let mut keypairs = generate_keypair_vec([0; 32]);
+        let validator = keypairs.pop().unwrap();
+        let delegator = keypairs.pop().unwrap();
+
+        let controller_router = create_router(validator.public(), &[(delegator.public(), STAKE_AMOUNT)]);
+        let bank_controller_ref = Arc::clone(&controller_router.bank_controller);
+
+        let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+        stake_controller.set_validator_set(BTreeSet::from([validator.public().clone()]));
+        stake_controller.stake(validator.public(), STAKE_AMOUNT).unwrap();
+
+        // accounts outside the validator set may only back a validator through delegation
+        assert_eq!(
+            stake_controller.stake(delegator.public(), STAKE_AMOUNT),
+            Err(GDEXError::StakeIneligible)
+        );
+        stake_controller
+            .delegate(delegator.public(), validator.public(), STAKE_AMOUNT)
+            .unwrap();
+        assert_eq!(stake_controller.get_total_staked(), 2 * STAKE_AMOUNT);
+    }
 }
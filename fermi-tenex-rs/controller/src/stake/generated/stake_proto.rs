@@ -0,0 +1,65 @@
+// REQUESTS
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StakeRequest {
+    #[prost(uint64, tag="1")]
+    pub amount: u64,
+}
+/// an empty validator unstakes the sender's own stake, otherwise the delegation to validator is withdrawn
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnstakeRequest {
+    #[prost(uint64, tag="1")]
+    pub amount: u64,
+    #[prost(bytes="bytes", tag="2")]
+    pub validator: ::prost::bytes::Bytes,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DelegateRequest {
+    #[prost(bytes="bytes", tag="1")]
+    pub validator: ::prost::bytes::Bytes,
+    #[prost(uint64, tag="2")]
+    pub amount: u64,
+}
+// EVENTS
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StakedEvent {
+    #[prost(bytes="bytes", tag="1")]
+    pub account: ::prost::bytes::Bytes,
+    #[prost(uint64, tag="2")]
+    pub amount: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnstakeStartedEvent {
+    #[prost(bytes="bytes", tag="1")]
+    pub account: ::prost::bytes::Bytes,
+    #[prost(uint64, tag="2")]
+    pub amount: u64,
+    #[prost(uint64, tag="3")]
+    pub release_block: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DelegatedEvent {
+    #[prost(bytes="bytes", tag="1")]
+    pub delegator: ::prost::bytes::Bytes,
+    #[prost(bytes="bytes", tag="2")]
+    pub validator: ::prost::bytes::Bytes,
+    #[prost(uint64, tag="3")]
+    pub amount: u64,
+}
+// REQUEST ENUM
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum StakeRequestType {
+    Stake = 0,
+    Unstake = 1,
+    Delegate = 2,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum StakeEventType {
+    Staked = 0,
+    UnstakeStarted = 1,
+    Delegated = 2,
+}
@@ -1,3 +1,4 @@
 pub mod controller;
+pub mod proto;
 pub mod rpc_server;
 pub mod types;
@@ -0,0 +1,243 @@
+// fermi
+use crate::router::ControllerType;
+use fermi_types::{
+    account::AccountPubKey,
+    crypto::ToFromBytes,
+    error::GDEXError,
+    event::DecodedEvent,
+    transaction::{ControllerRequest, Event, EventTypeEnum, Request, RequestTypeEnum, Transaction},
+    utils,
+};
+// mysten
+use narwhal_types::CertificateDigest;
+// external
+use prost::bytes::Bytes;
+
+// MODULE IMPORTS
+
+#[path = "./generated/stake_proto.rs"]
+#[rustfmt::skip]
+#[allow(clippy::all)]
+mod stake_proto;
+
+pub use stake_proto::*;
+
+// ENUMS
+
+impl RequestTypeEnum for StakeRequestType {
+    fn request_type_from_i32(value: i32) -> Result<Self, GDEXError> {
+        match value {
+            0 => Ok(StakeRequestType::Stake),
+            1 => Ok(StakeRequestType::Unstake),
+            2 => Ok(StakeRequestType::Delegate),
+            _ => Err(GDEXError::DeserializationError),
+        }
+    }
+}
+
+impl EventTypeEnum for StakeEventType {
+    fn event_type_from_i32(value: i32) -> Result<Self, GDEXError> {
+        match value {
+            0 => Ok(StakeEventType::Staked),
+            1 => Ok(StakeEventType::UnstakeStarted),
+            2 => Ok(StakeEventType::Delegated),
+            _ => Err(GDEXError::DeserializationError),
+        }
+    }
+}
+
+// REQUESTS
+
+impl StakeRequest {
+    pub fn new(amount: u64) -> Self {
+        StakeRequest { amount }
+    }
+}
+
+impl Request for StakeRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Stake as i32
+    }
+    fn get_request_type_id() -> i32 {
+        StakeRequestType::Stake as i32
+    }
+}
+
+impl UnstakeRequest {
+    pub fn new(amount: u64, validator: Option<&AccountPubKey>) -> Self {
+        UnstakeRequest {
+            amount,
+            validator: validator.map_or_else(Bytes::new, |validator| Bytes::from(validator.as_ref().to_vec())),
+        }
+    }
+
+    // returns None when the sender is withdrawing its own stake
+    pub fn get_validator(&self) -> Result<Option<AccountPubKey>, GDEXError> {
+        if self.validator.is_empty() {
+            return Ok(None);
+        }
+        AccountPubKey::from_bytes(&self.validator)
+            .map(Some)
+            .map_err(|_e| GDEXError::DeserializationError)
+    }
+}
+
+impl Request for UnstakeRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Stake as i32
+    }
+    fn get_request_type_id() -> i32 {
+        StakeRequestType::Unstake as i32
+    }
+}
+
+impl DelegateRequest {
+    pub fn new(validator: &AccountPubKey, amount: u64) -> Self {
+        DelegateRequest {
+            validator: Bytes::from(validator.as_ref().to_vec()),
+            amount,
+        }
+    }
+
+    pub fn get_validator(&self) -> Result<AccountPubKey, GDEXError> {
+        AccountPubKey::from_bytes(&self.validator).map_err(|_e| GDEXError::DeserializationError)
+    }
+}
+
+impl Request for DelegateRequest {
+    fn get_controller_id() -> i32 {
+        ControllerType::Stake as i32
+    }
+    fn get_request_type_id() -> i32 {
+        StakeRequestType::Delegate as i32
+    }
+}
+
+// EVENTS
+
+impl StakedEvent {
+    pub fn new(account: &AccountPubKey, amount: u64) -> Self {
+        StakedEvent {
+            account: Bytes::from(account.as_ref().to_vec()),
+            amount,
+        }
+    }
+}
+
+impl Event for StakedEvent {
+    fn get_controller_id() -> i32 {
+        ControllerType::Stake as i32
+    }
+    fn get_event_type_id() -> i32 {
+        StakeEventType::Staked as i32
+    }
+}
+
+impl From<StakedEvent> for DecodedEvent {
+    fn from(event: StakedEvent) -> Self {
+        DecodedEvent::Staked {
+            account: utils::encode_bytes_hex(&event.account),
+            amount: event.amount,
+        }
+    }
+}
+
+impl UnstakeStartedEvent {
+    pub fn new(account: &AccountPubKey, amount: u64, release_block: u64) -> Self {
+        UnstakeStartedEvent {
+            account: Bytes::from(account.as_ref().to_vec()),
+            amount,
+            release_block,
+        }
+    }
+}
+
+impl Event for UnstakeStartedEvent {
+    fn get_controller_id() -> i32 {
+        ControllerType::Stake as i32
+    }
+    fn get_event_type_id() -> i32 {
+        StakeEventType::UnstakeStarted as i32
+    }
+}
+
+impl From<UnstakeStartedEvent> for DecodedEvent {
+    fn from(event: UnstakeStartedEvent) -> Self {
+        DecodedEvent::UnstakeStarted {
+            account: utils::encode_bytes_hex(&event.account),
+            amount: event.amount,
+            release_block: event.release_block,
+        }
+    }
+}
+
+impl DelegatedEvent {
+    pub fn new(delegator: &AccountPubKey, validator: &AccountPubKey, amount: u64) -> Self {
+        DelegatedEvent {
+            delegator: Bytes::from(delegator.as_ref().to_vec()),
+            validator: Bytes::from(validator.as_ref().to_vec()),
+            amount,
+        }
+    }
+}
+
+impl Event for DelegatedEvent {
+    fn get_controller_id() -> i32 {
+        ControllerType::Stake as i32
+    }
+    fn get_event_type_id() -> i32 {
+        StakeEventType::Delegated as i32
+    }
+}
+
+impl From<DelegatedEvent> for DecodedEvent {
+    fn from(event: DelegatedEvent) -> Self {
+        DecodedEvent::Delegated {
+            delegator: utils::encode_bytes_hex(&event.delegator),
+            validator: utils::encode_bytes_hex(&event.validator),
+            amount: event.amount,
+        }
+    }
+}
+
+// TRANSACTION BUILDERS
+
+pub fn create_stake_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    amount: u64,
+) -> Transaction {
+    Transaction::new(sender, recent_block_hash, &StakeRequest::new(amount))
+}
+
+pub fn create_unstake_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    amount: u64,
+    validator: Option<&AccountPubKey>,
+) -> Transaction {
+    Transaction::new(sender, recent_block_hash, &UnstakeRequest::new(amount, validator))
+}
+
+pub fn create_delegate_transaction(
+    sender: &AccountPubKey,
+    recent_block_hash: CertificateDigest,
+    validator: &AccountPubKey,
+    amount: u64,
+) -> Transaction {
+    Transaction::new(sender, recent_block_hash, &DelegateRequest::new(validator, amount))
+}
+
+// REQUEST BUILDERS
+
+pub fn create_stake_request(amount: u64) -> ControllerRequest {
+    ControllerRequest::new(&StakeRequest::new(amount))
+}
+
+pub fn create_unstake_request(amount: u64, validator: Option<&AccountPubKey>) -> ControllerRequest {
+    ControllerRequest::new(&UnstakeRequest::new(amount, validator))
+}
+
+pub fn create_delegate_request(validator: &AccountPubKey, amount: u64) -> ControllerRequest {
+    ControllerRequest::new(&DelegateRequest::new(validator, amount))
+}
@@ -2,18 +2,25 @@
 use fermi_types::account::AccountPubKey;
 // external
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// StakeAccount is consumed by the StakeController
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StakeAccount {
     account_pub_key: AccountPubKey,
     staked_amount: u64,
+    // stake this account has delegated out, keyed by validator
+    delegations: BTreeMap<AccountPubKey, u64>,
+    // stake other accounts have delegated to this account
+    delegated_amount: u64,
 }
 impl StakeAccount {
     pub fn new(account_pub_key: AccountPubKey) -> Self {
         StakeAccount {
             account_pub_key,
             staked_amount: 0,
+            delegations: BTreeMap::new(),
+            delegated_amount: 0,
         }
     }
 
@@ -28,4 +35,36 @@ impl StakeAccount {
     pub fn set_staked_amount(&mut self, new_amount: u64) {
         self.staked_amount = new_amount;
     }
+
+    pub fn get_delegations(&self) -> &BTreeMap<AccountPubKey, u64> {
+        &self.delegations
+    }
+
+    pub fn get_delegation(&self, validator: &AccountPubKey) -> u64 {
+        *self.delegations.get(validator).unwrap_or(&0)
+    }
+
+    pub fn set_delegation(&mut self, validator: &AccountPubKey, new_amount: u64) {
+        if new_amount == 0 {
+            self.delegations.remove(validator);
+        } else {
+            self.delegations.insert(validator.clone(), new_amount);
+        }
+    }
+
+    pub fn get_delegated_amount(&self) -> &u64 {
+        &self.delegated_amount
+    }
+
+    pub fn set_delegated_amount(&mut self, new_amount: u64) {
+        self.delegated_amount = new_amount;
+    }
+}
+
+/// PendingUnstake holds withdrawn stake until its unbonding period has elapsed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingUnstake {
+    pub account_pub_key: AccountPubKey,
+    pub amount: u64,
+    pub release_block: u64,
 }
@@ -1,5 +1,8 @@
 use super::order_queues::OrderQueue;
-use super::orders::{create_cancel_order_request, create_limit_order_request, create_update_order_request};
+use super::orders::{
+    create_cancel_order_request, create_limit_order_request_with_flags, create_market_order_request_with_guard,
+    create_update_order_request,
+};
 use super::sequence;
 use super::validation::OrderRequestValidator;
 // TODO - https://github.com/fermiorg/fermi/issues/164 - we should not depend on or import anything from specific controllers
@@ -10,20 +13,48 @@ use fermi_types::{
     asset::AssetId,
     error::GDEXError,
     order_book::{
-        Depth, Failed, Order, OrderProcessingResult, OrderRequest, OrderSide, OrderType, OrderbookDepth, Success,
+        ClientOrderId, Depth, Failed, Liquidity, Order, OrderProcessingResult, OrderRequest, OrderSide, OrderType,
+        OrderbookDepth, SequenceNumber, Success, TimeInForce,
     },
-    transaction::parse_order_side,
+    transaction::{parse_order_side, parse_time_in_force},
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::SystemTime;
 
 pub type OrderId = u64;
 
-const MIN_SEQUENCE_ID: u64 = 1;
-const MAX_SEQUENCE_ID: u64 = 1_000_000;
+const FIRST_ORDER_ID: u64 = 1;
 const MAX_STALLED_INDICES_IN_QUEUE: u64 = 10;
 const ORDER_QUEUE_INIT_CAPACITY: usize = 500;
+// order ids carry the asset pair of their book above the per-book sequence, so an id is unique across books
+const ORDER_ID_ASSET_BITS: u32 = 16;
+const ORDER_ID_SEQUENCE_BITS: u32 = u64::BITS - 2 * ORDER_ID_ASSET_BITS;
+
+/// Checks that both assets of a pair fit in an order id, books may only be created for such pairs
+pub fn validate_order_id_assets(base_asset: AssetId, quote_asset: AssetId) -> Result<(), GDEXError> {
+    if base_asset >> ORDER_ID_ASSET_BITS != 0 || quote_asset >> ORDER_ID_ASSET_BITS != 0 {
+        return Err(GDEXError::OrderBookCreation);
+    }
+    Ok(())
+}
+
+/// Returns the first order id issued by the book of an asset pair
+pub const fn get_first_order_id(base_asset: AssetId, quote_asset: AssetId) -> OrderId {
+    get_order_id_prefix(base_asset, quote_asset) | FIRST_ORDER_ID
+}
+
+/// Returns the base and quote asset of the book which issued an order id
+pub fn get_order_id_assets(order_id: OrderId) -> (AssetId, AssetId) {
+    let asset_mask = (1 << ORDER_ID_ASSET_BITS) - 1;
+    (
+        order_id >> (ORDER_ID_SEQUENCE_BITS + ORDER_ID_ASSET_BITS),
+        (order_id >> ORDER_ID_SEQUENCE_BITS) & asset_mask,
+    )
+}
+
+const fn get_order_id_prefix(base_asset: AssetId, quote_asset: AssetId) -> OrderId {
+    (base_asset << (ORDER_ID_SEQUENCE_BITS + ORDER_ID_ASSET_BITS)) | (quote_asset << ORDER_ID_SEQUENCE_BITS)
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Orderbook {
@@ -32,19 +63,35 @@ pub struct Orderbook {
     bid_queue: OrderQueue<Order>,
     ask_queue: OrderQueue<Order>,
     seq: sequence::TradeSequence,
+    // asset pair bits shared by every order id of the book
+    order_id_prefix: OrderId,
+    // logical clock used for price-time priority, advanced once per processed request
+    sequence_number: SequenceNumber,
     order_validator: OrderRequestValidator,
 }
 
 impl Orderbook {
     pub fn new(base_asset: AssetId, quote_asset: AssetId) -> Self {
+        assert!(validate_order_id_assets(base_asset, quote_asset).is_ok());
         Orderbook {
             base_asset,
             quote_asset,
             bid_queue: OrderQueue::new(OrderSide::Bid, MAX_STALLED_INDICES_IN_QUEUE, ORDER_QUEUE_INIT_CAPACITY),
             ask_queue: OrderQueue::new(OrderSide::Ask, MAX_STALLED_INDICES_IN_QUEUE, ORDER_QUEUE_INIT_CAPACITY),
-            seq: sequence::new_sequence_gen(MIN_SEQUENCE_ID, MAX_SEQUENCE_ID),
-            order_validator: OrderRequestValidator::new(base_asset, quote_asset, MIN_SEQUENCE_ID, MAX_SEQUENCE_ID),
+            seq: sequence::new_sequence_gen(FIRST_ORDER_ID),
+            order_id_prefix: get_order_id_prefix(base_asset, quote_asset),
+            sequence_number: 0,
+            order_validator: OrderRequestValidator::new(base_asset, quote_asset),
+        }
+    }
+
+    fn next_order_id(&mut self) -> Result<OrderId, GDEXError> {
+        let sequence_id = self.seq.next_id()?;
+        // the sequence may not spill into the asset pair bits
+        if sequence_id >> ORDER_ID_SEQUENCE_BITS != 0 {
+            return Err(GDEXError::OrderIdExhausted);
         }
+        Ok(self.order_id_prefix | sequence_id)
     }
 
     pub fn get_orderbook_depth(&self) -> OrderbookDepth {
@@ -97,26 +144,37 @@ impl Orderbook {
         OrderbookDepth { bids, asks }
     }
 
-    pub fn process_order(&mut self, order: OrderRequest) -> OrderProcessingResult {
+    /// Returns the resting orders of the book, bids before asks
+    pub fn get_open_orders(&self) -> impl Iterator<Item = &Order> {
+        self.bid_queue.orders.values().chain(self.ask_queue.orders.values())
+    }
+
+    pub fn process_order(&mut self, order: OrderRequest) -> Result<OrderProcessingResult, GDEXError> {
         // processing result accumulator
         let mut process_result: OrderProcessingResult = vec![];
 
         // validate request
         if let Err(reason) = self.order_validator.validate(&order) {
             process_result.push(Err(Failed::Validation(String::from(reason))));
-            return process_result;
+            return Ok(process_result);
         }
 
+        // requests reach the book in consensus order, so this sequence is identical across replicas
+        self.sequence_number += 1;
+        let sequence_number = self.sequence_number;
+
         match order {
             OrderRequest::Market {
                 base_asset_id,
                 quote_asset_id,
                 side,
                 quantity,
-                ..
+                client_order_id,
+                worst_price,
+                max_quote_spend,
             } => {
                 // generate new ID for order
-                let order_id = self.seq.next_id();
+                let order_id = self.next_order_id()?;
                 let price: u64 = 0;
                 process_result.push(Ok(Success::Accepted {
                     order_id,
@@ -124,7 +182,8 @@ impl Orderbook {
                     price,
                     quantity,
                     order_type: OrderType::Market,
-                    timestamp: SystemTime::now(),
+                    client_order_id,
+                    sequence_number,
                 }));
 
                 self.process_market_order(
@@ -134,7 +193,10 @@ impl Orderbook {
                     quote_asset_id,
                     side,
                     quantity,
-                );
+                    worst_price,
+                    max_quote_spend,
+                    sequence_number,
+                )?;
             }
 
             OrderRequest::Limit {
@@ -143,16 +205,29 @@ impl Orderbook {
                 side,
                 price,
                 quantity,
-                local_timestamp,
+                client_order_id,
+                time_in_force,
+                post_only,
             } => {
-                let order_id = self.seq.next_id();
+                // flags which reject the whole order are checked before an id is issued
+                if post_only && self.crosses_book(side, price) {
+                    process_result.push(Err(Failed::PostOnlyWouldTake));
+                    return Ok(process_result);
+                }
+                if time_in_force == TimeInForce::FillOrKill && self.available_liquidity(side, price) < quantity {
+                    process_result.push(Err(Failed::FillOrKillUnfilled));
+                    return Ok(process_result);
+                }
+
+                let order_id = self.next_order_id()?;
                 process_result.push(Ok(Success::Accepted {
                     order_id,
                     side,
                     price,
                     quantity,
                     order_type: OrderType::Limit,
-                    timestamp: SystemTime::now(),
+                    client_order_id,
+                    sequence_number,
                 }));
 
                 self.process_limit_order(
@@ -163,7 +238,8 @@ impl Orderbook {
                     side,
                     price,
                     quantity,
-                    local_timestamp,
+                    time_in_force,
+                    sequence_number,
                 );
             }
 
@@ -172,20 +248,19 @@ impl Orderbook {
                 side,
                 price,
                 quantity,
-                local_timestamp,
                 ..
             } => {
-                self.process_order_update(&mut process_result, order_id, side, price, quantity, local_timestamp);
+                self.process_order_update(&mut process_result, order_id, side, price, quantity, sequence_number);
             }
 
             OrderRequest::Cancel { order_id, side, .. } => {
-                self.process_order_cancel(&mut process_result, order_id, side);
+                self.process_order_cancel(&mut process_result, order_id, side, sequence_number);
             }
         }
 
         // return collected processing results
 
-        process_result
+        Ok(process_result)
     }
 
     /// Get current spread as a tuple: (bid, ask)
@@ -197,6 +272,7 @@ impl Orderbook {
 
     /* Processing logic */
 
+    #[allow(clippy::too_many_arguments)]
     fn process_market_order(
         &mut self,
         results: &mut OrderProcessingResult,
@@ -205,7 +281,10 @@ impl Orderbook {
         quote_asset: AssetId,
         side: OrderSide,
         quantity: u64,
-    ) {
+        worst_price: Option<u64>,
+        max_quote_spend: Option<u64>,
+        sequence_number: SequenceNumber,
+    ) -> Result<(), GDEXError> {
         // get copy of the current limit order
         let opposite_order_result = {
             let opposite_queue = match side {
@@ -215,8 +294,22 @@ impl Orderbook {
             opposite_queue.peek().cloned()
         };
 
-        if let Some(opposite_order) = opposite_order_result {
-            let matching_complete = self.order_matching(
+        // only match against liquidity which sits inside both slippage guards
+        let fillable = opposite_order_result.and_then(|opposite_order| {
+            if let Some(worst_price) = worst_price {
+                if !prices_cross(side, worst_price, opposite_order.price) {
+                    return None;
+                }
+            }
+            let affordable_quantity = match max_quote_spend {
+                Some(max_quote_spend) => quantity.min(max_quote_spend / opposite_order.price),
+                None => quantity,
+            };
+            (affordable_quantity > 0).then_some((opposite_order, affordable_quantity))
+        });
+
+        if let Some((opposite_order, fill_quantity)) = fillable {
+            self.order_matching(
                 results,
                 &opposite_order,
                 order_id,
@@ -224,10 +317,22 @@ impl Orderbook {
                 quote_asset,
                 OrderType::Market,
                 side,
-                quantity,
+                fill_quantity,
+                sequence_number,
             );
 
-            if !matching_complete {
+            let filled_quantity = fill_quantity.min(opposite_order.quantity);
+            if filled_quantity < quantity {
+                // carry the unspent quote budget over to the next level
+                let remaining_quote_spend = max_quote_spend
+                    .map(|max_quote_spend| {
+                        filled_quantity
+                            .checked_mul(opposite_order.price)
+                            .and_then(|spent| max_quote_spend.checked_sub(spent))
+                            .ok_or(GDEXError::OrderValueOverflow)
+                    })
+                    .transpose()?;
+
                 // match the rest
                 self.process_market_order(
                     results,
@@ -235,13 +340,25 @@ impl Orderbook {
                     base_asset,
                     quote_asset,
                     side,
-                    quantity - opposite_order.quantity,
-                );
+                    quantity - filled_quantity,
+                    worst_price,
+                    remaining_quote_spend,
+                    sequence_number,
+                )?;
             }
         } else {
-            // no limit orders found
-            results.push(Err(Failed::NoMatch(order_id)));
+            // market orders never rest, report the unfilled remainder as cancelled
+            results.push(Ok(Success::Cancelled {
+                order_id,
+                side,
+                order_type: OrderType::Market,
+                price: 0,
+                quantity,
+                sequence_number,
+            }));
         }
+
+        Ok(())
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -254,7 +371,8 @@ impl Orderbook {
         side: OrderSide,
         price: u64,
         quantity: u64,
-        timestamp: SystemTime,
+        time_in_force: TimeInForce,
+        sequence_number: SequenceNumber,
     ) {
         // take a look at current opposite limit order
         let opposite_order_result = {
@@ -265,14 +383,9 @@ impl Orderbook {
             opposite_queue.peek().cloned()
         };
 
-        if let Some(opposite_order) = opposite_order_result {
-            let could_be_matched = match side {
-                // verify bid/ask price overlap
-                OrderSide::Bid => price >= opposite_order.price,
-                OrderSide::Ask => price <= opposite_order.price,
-            };
-
-            if could_be_matched {
+        match opposite_order_result {
+            // verify bid/ask price overlap
+            Some(opposite_order) if prices_cross(side, price, opposite_order.price) => {
                 // match immediately
                 let matching_complete = self.order_matching(
                     results,
@@ -283,6 +396,7 @@ impl Orderbook {
                     OrderType::Limit,
                     side,
                     quantity,
+                    sequence_number,
                 );
 
                 if !matching_complete {
@@ -295,33 +409,36 @@ impl Orderbook {
                         side,
                         price,
                         quantity - opposite_order.quantity,
-                        timestamp,
+                        time_in_force,
+                        sequence_number,
                     );
                 }
-            } else {
-                // just insert new order in queue
-                self.store_new_limit_order(
-                    results,
-                    order_id,
-                    base_asset,
-                    quote_asset,
-                    side,
-                    price,
-                    quantity,
-                    timestamp,
-                );
             }
-        } else {
-            self.store_new_limit_order(
-                results,
-                order_id,
-                base_asset,
-                quote_asset,
-                side,
-                price,
-                quantity,
-                timestamp,
-            );
+            _ => {
+                if time_in_force == TimeInForce::GoodTilCancelled {
+                    // just insert new order in queue
+                    self.store_new_limit_order(
+                        results,
+                        order_id,
+                        base_asset,
+                        quote_asset,
+                        side,
+                        price,
+                        quantity,
+                        sequence_number,
+                    );
+                } else {
+                    // immediate orders never rest, report the unmatched remainder as cancelled
+                    results.push(Ok(Success::Cancelled {
+                        order_id,
+                        side,
+                        order_type: OrderType::Limit,
+                        price,
+                        quantity,
+                        sequence_number,
+                    }));
+                }
+            }
         }
     }
 
@@ -332,21 +449,27 @@ impl Orderbook {
         side: OrderSide,
         price: u64,
         quantity: u64,
-        timestamp: SystemTime,
+        sequence_number: SequenceNumber,
     ) {
         let order_queue = match side {
             OrderSide::Bid => &mut self.bid_queue,
             OrderSide::Ask => &mut self.ask_queue,
         };
 
-        let current_order = order_queue.get_order(order_id).unwrap();
+        let current_order = match order_queue.get_order(order_id) {
+            Some(order) => order,
+            None => {
+                results.push(Err(Failed::OrderNotFound(order_id)));
+                return;
+            }
+        };
         let previous_quantity = current_order.get_quantity();
         let previous_price = current_order.get_price();
 
         if order_queue.update(
             order_id,
             price,
-            timestamp,
+            sequence_number,
             Order {
                 order_id,
                 base_asset: self.base_asset,
@@ -363,14 +486,20 @@ impl Orderbook {
                 previous_price,
                 price,
                 quantity,
-                timestamp: SystemTime::now(),
+                sequence_number,
             }));
         } else {
             results.push(Err(Failed::OrderNotFound(order_id)));
         }
     }
 
-    fn process_order_cancel(&mut self, results: &mut OrderProcessingResult, order_id: u64, side: OrderSide) {
+    fn process_order_cancel(
+        &mut self,
+        results: &mut OrderProcessingResult,
+        order_id: u64,
+        side: OrderSide,
+        sequence_number: SequenceNumber,
+    ) {
         let order_queue = match side {
             OrderSide::Bid => &mut self.bid_queue,
             OrderSide::Ask => &mut self.ask_queue,
@@ -386,9 +515,10 @@ impl Orderbook {
                 results.push(Ok(Success::Cancelled {
                     order_id,
                     side,
+                    order_type: OrderType::Limit,
                     price,
                     quantity,
-                    timestamp: SystemTime::now(),
+                    sequence_number,
                 }));
             }
         } else {
@@ -398,6 +528,31 @@ impl Orderbook {
 
     /* Helpers */
 
+    /// Returns true if a limit order at the given price would match the best opposite order
+    fn crosses_book(&mut self, side: OrderSide, price: u64) -> bool {
+        let opposite_queue = match side {
+            OrderSide::Bid => &mut self.ask_queue,
+            OrderSide::Ask => &mut self.bid_queue,
+        };
+        opposite_queue
+            .peek()
+            .map_or(false, |opposite_order| prices_cross(side, price, opposite_order.price))
+    }
+
+    /// Total opposite quantity a limit order at the given price could match against
+    fn available_liquidity(&self, side: OrderSide, price: u64) -> u64 {
+        let opposite_queue = match side {
+            OrderSide::Bid => &self.ask_queue,
+            OrderSide::Ask => &self.bid_queue,
+        };
+        opposite_queue
+            .orders
+            .values()
+            .filter(|opposite_order| prices_cross(side, price, opposite_order.price))
+            .map(|opposite_order| opposite_order.quantity)
+            .sum()
+    }
+
     pub fn get_order(&mut self, side: OrderSide, order_id: u64) -> Result<&Order, Failed> {
         let order_queue = match side {
             OrderSide::Bid => &mut self.bid_queue,
@@ -417,7 +572,7 @@ impl Orderbook {
         side: OrderSide,
         price: u64,
         quantity: u64,
-        timestamp: SystemTime,
+        sequence_number: SequenceNumber,
     ) {
         let order_queue = match side {
             OrderSide::Bid => &mut self.bid_queue,
@@ -426,7 +581,7 @@ impl Orderbook {
         if !order_queue.insert(
             order_id,
             price,
-            timestamp,
+            sequence_number,
             Order {
                 order_id,
                 base_asset,
@@ -451,9 +606,8 @@ impl Orderbook {
         order_type: OrderType,
         side: OrderSide,
         quantity: u64,
+        sequence_number: SequenceNumber,
     ) -> bool {
-        // real processing time
-        let deal_time = SystemTime::now();
         // match immediately
         match quantity {
             x if x < opposite_order.quantity => {
@@ -464,9 +618,10 @@ impl Orderbook {
                     order_id,
                     side,
                     order_type,
+                    liquidity: Liquidity::Taker,
                     price: opposite_order.price,
                     quantity,
-                    timestamp: deal_time,
+                    sequence_number,
                 }));
 
                 // report partially filled opposite limit order
@@ -474,9 +629,10 @@ impl Orderbook {
                     order_id: opposite_order.order_id,
                     side: opposite_order.side,
                     order_type: OrderType::Limit,
+                    liquidity: Liquidity::Maker,
                     price: opposite_order.price,
                     quantity,
-                    timestamp: deal_time,
+                    sequence_number,
                 }));
 
                 // modify unmatched part of the opposite limit order
@@ -502,9 +658,10 @@ impl Orderbook {
                     order_id,
                     side,
                     order_type,
+                    liquidity: Liquidity::Taker,
                     price: opposite_order.price,
                     quantity: opposite_order.quantity,
-                    timestamp: deal_time,
+                    sequence_number,
                 }));
 
                 // report filled opposite limit order
@@ -512,9 +669,10 @@ impl Orderbook {
                     order_id: opposite_order.order_id,
                     side: opposite_order.side,
                     order_type: OrderType::Limit,
+                    liquidity: Liquidity::Maker,
                     price: opposite_order.price,
                     quantity: opposite_order.quantity,
-                    timestamp: deal_time,
+                    sequence_number,
                 }));
 
                 // remove filled limit order from the queue
@@ -536,18 +694,20 @@ impl Orderbook {
                     order_id,
                     side,
                     order_type,
+                    liquidity: Liquidity::Taker,
                     price: opposite_order.price,
                     quantity,
-                    timestamp: deal_time,
+                    sequence_number,
                 }));
                 // report filled opposite limit order
                 results.push(Ok(Success::Filled {
                     order_id: opposite_order.order_id,
                     side: opposite_order.side,
                     order_type: OrderType::Limit,
+                    liquidity: Liquidity::Maker,
                     price: opposite_order.price,
                     quantity,
-                    timestamp: deal_time,
+                    sequence_number,
                 }));
 
                 // remove filled limit order from the queue
@@ -565,6 +725,13 @@ impl Orderbook {
     }
 }
 
+fn prices_cross(side: OrderSide, price: u64, opposite_price: u64) -> bool {
+    match side {
+        OrderSide::Bid => price >= opposite_price,
+        OrderSide::Ask => price <= opposite_price,
+    }
+}
+
 pub trait OrderBookWrapper {
     // HELPER FUNCTIONS
 
@@ -574,7 +741,17 @@ pub trait OrderBookWrapper {
     fn get_pub_key_from_order_id(&self, order_id: &OrderId) -> AccountPubKey;
 
     // SETTERS
-    fn set_order(&mut self, order_id: OrderId, account: AccountPubKey) -> Result<(), GDEXError>;
+    fn set_order(
+        &mut self,
+        order_id: OrderId,
+        account: AccountPubKey,
+        client_order_id: ClientOrderId,
+    ) -> Result<(), GDEXError>;
+
+    /// Drops the state kept for an order once it has left the book, i.e. it was filled or cancelled
+    fn remove_order(&mut self, _order_id: OrderId) -> Result<(), GDEXError> {
+        Ok(())
+    }
 
     // TODO - https://github.com/fermiorg/fermi/issues/174 - remove gating from the order_book level
     // this creates awkward tension in any instance of cross-margin
@@ -591,10 +768,43 @@ pub trait OrderBookWrapper {
     // PLACERS [ORDERS]
 
     // PLACE MARKET ORDER
-    // TODO - https://github.com/fermiorg/fermi/issues/170 - implement market orders
 
-    fn place_market_order(&mut self, _account: &AccountPubKey, _request: &MarketOrderRequest) -> Result<(), GDEXError> {
-        Ok(())
+    fn place_market_order(
+        &mut self,
+        account: &AccountPubKey,
+        request: &MarketOrderRequest,
+    ) -> Result<OrderProcessingResult, GDEXError> {
+        // parse side
+        let side = parse_order_side(request.side)?;
+
+        // a zero guard is treated as unset
+        let worst_price = (request.worst_price > 0).then_some(request.worst_price);
+        let max_quote_spend = (request.max_quote_spend > 0).then_some(request.max_quote_spend);
+
+        // check balances against the most the order could cost, which is unbounded for a bid without a guard
+        match (side, worst_price, max_quote_spend) {
+            (OrderSide::Ask, _, _) => self.validate_controller(account, side, request.quantity, 0, 0, 0)?,
+            (OrderSide::Bid, _, Some(max_quote_spend)) => {
+                self.validate_controller(account, side, max_quote_spend, 1, 0, 0)?
+            }
+            (OrderSide::Bid, Some(worst_price), None) => {
+                self.validate_controller(account, side, request.quantity, worst_price, 0, 0)?
+            }
+            (OrderSide::Bid, None, None) => return Err(GDEXError::MarketOrderUnguarded),
+        }
+
+        // create and process market order
+        let order = create_market_order_request_with_guard(
+            request.base_asset_id,
+            request.quote_asset_id,
+            side,
+            request.quantity,
+            request.client_order_id,
+            worst_price,
+            max_quote_spend,
+        );
+        let res = self.get_orderbook().process_order(order)?;
+        self.process_order_result(account, res)
     }
 
     // PLACE LIMIT ORDER
@@ -611,16 +821,21 @@ pub trait OrderBookWrapper {
         // check balances before placing order
         self.validate_controller(account, side, request.quantity, request.price, 0, 0)?;
 
+        // parse execution flags
+        let time_in_force = parse_time_in_force(request.time_in_force)?;
+
         // create and process limit order
-        let order = create_limit_order_request(
+        let order = create_limit_order_request_with_flags(
             request.base_asset_id,
             request.quote_asset_id,
             side,
             request.price,
             request.quantity,
-            SystemTime::now(),
+            request.client_order_id,
+            time_in_force,
+            request.post_only,
         );
-        let res = self.get_orderbook().process_order(order);
+        let res = self.get_orderbook().process_order(order)?;
         self.process_order_result(account, res)
     }
 
@@ -634,14 +849,8 @@ pub trait OrderBookWrapper {
         let side = parse_order_side(request.side)?;
 
         // create and process limit order
-        let order = create_cancel_order_request(
-            request.base_asset_id,
-            request.quote_asset_id,
-            request.order_id,
-            side,
-            SystemTime::now(),
-        );
-        let res = self.get_orderbook().process_order(order);
+        let order = create_cancel_order_request(request.base_asset_id, request.quote_asset_id, request.order_id, side);
+        let res = self.get_orderbook().process_order(order)?;
         self.process_order_result(account, res)
     }
 
@@ -656,7 +865,10 @@ pub trait OrderBookWrapper {
         let side = parse_order_side(request.side)?;
 
         // check updates against user's balances
-        let current_order = self.get_orderbook().get_order(side, request.order_id).unwrap();
+        let current_order = self
+            .get_orderbook()
+            .get_order(side, request.order_id)
+            .map_err(|_| GDEXError::OrderRequest)?;
         let current_quantity = current_order.get_quantity();
         let current_price = current_order.get_price();
 
@@ -678,9 +890,8 @@ pub trait OrderBookWrapper {
             side,
             request.price,
             request.quantity,
-            SystemTime::now(),
         );
-        let res = self.get_orderbook().process_order(order);
+        let res = self.get_orderbook().process_order(order)?;
         self.process_order_result(account, res)
     }
 
@@ -704,6 +915,28 @@ pub trait OrderBookWrapper {
         quantity: u64,
     ) -> Result<(), GDEXError>;
 
+    fn update_state_on_market_fill(
+        &mut self,
+        account: &AccountPubKey,
+        order_id: u64,
+        side: OrderSide,
+        price: u64,
+        quantity: u64,
+    ) -> Result<(), GDEXError>;
+
+    /// Charges the fee owed on a fill, or pays the rebate when negative, and returns the signed amount
+    fn charge_fill_fee(
+        &mut self,
+        account: &AccountPubKey,
+        order_id: u64,
+        liquidity: Liquidity,
+        price: u64,
+        quantity: u64,
+    ) -> Result<i64, GDEXError>;
+
+    /// Records a fill on the trade tape of the book, called once per fill from the side of the taker
+    fn record_trade(&mut self, _taker_side: OrderSide, _price: u64, _quantity: u64) {}
+
     #[allow(clippy::too_many_arguments)]
     fn update_state_on_update(
         &mut self,
@@ -736,9 +969,18 @@ pub trait OrderBookWrapper {
         side: u64,
         price: u64,
         quantity: u64,
+        fee: i64,
     );
 
-    fn emit_order_fill_event(&mut self, account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64);
+    fn emit_order_fill_event(
+        &mut self,
+        account: &AccountPubKey,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        fee: i64,
+    );
 
     fn emit_order_update_event(&mut self, account: &AccountPubKey, order_id: u64, side: u64, price: u64, quantity: u64);
 
@@ -769,6 +1011,7 @@ pub trait OrderBookWrapper {
                     price,
                     quantity,
                     order_type,
+                    client_order_id,
                     ..
                 }) => {
                     // update user's balances if it is a limit order
@@ -776,7 +1019,7 @@ pub trait OrderBookWrapper {
                         self.update_state_on_limit_order_creation(account, *order_id, *side, *price, *quantity)?;
                     }
                     // insert new order to map
-                    self.set_order(*order_id, account.clone())?;
+                    self.set_order(*order_id, account.clone(), *client_order_id)?;
                     // emit order new event
                     self.emit_order_new_event(account, *order_id, *side as u64, *price, *quantity);
                 }
@@ -784,31 +1027,61 @@ pub trait OrderBookWrapper {
                 Ok(Success::PartiallyFilled {
                     order_id,
                     side,
+                    order_type,
+                    liquidity,
                     price,
                     quantity,
                     ..
                 }) => {
                     // update user balances
                     let existing_pub_key = self.get_pub_key_from_order_id(order_id);
-                    self.update_state_on_fill(&existing_pub_key, *order_id, *side, *price, *quantity)?;
+                    if *order_type == OrderType::Market {
+                        self.update_state_on_market_fill(&existing_pub_key, *order_id, *side, *price, *quantity)?;
+                    } else {
+                        self.update_state_on_fill(&existing_pub_key, *order_id, *side, *price, *quantity)?;
+                    }
+                    // the taker is reported first, so its fee is collected before the maker rebate is paid
+                    let fee = self.charge_fill_fee(&existing_pub_key, *order_id, *liquidity, *price, *quantity)?;
+                    if *liquidity == Liquidity::Taker {
+                        self.record_trade(*side, *price, *quantity);
+                    }
                     // emit order partial fill event
-                    self.emit_order_partial_fill_event(&existing_pub_key, *order_id, *side as u64, *price, *quantity);
+                    self.emit_order_partial_fill_event(
+                        &existing_pub_key,
+                        *order_id,
+                        *side as u64,
+                        *price,
+                        *quantity,
+                        fee,
+                    );
                 }
                 Ok(Success::Filled {
                     order_id,
                     side,
+                    order_type,
+                    liquidity,
                     price,
                     quantity,
                     ..
                 }) => {
                     // update user balances
                     let existing_pub_key = self.get_pub_key_from_order_id(order_id);
-                    self.update_state_on_fill(&existing_pub_key, *order_id, *side, *price, *quantity)?;
+                    if *order_type == OrderType::Market {
+                        self.update_state_on_market_fill(&existing_pub_key, *order_id, *side, *price, *quantity)?;
+                    } else {
+                        self.update_state_on_fill(&existing_pub_key, *order_id, *side, *price, *quantity)?;
+                    }
+                    // the taker is reported first, so its fee is collected before the maker rebate is paid
+                    let fee = self.charge_fill_fee(&existing_pub_key, *order_id, *liquidity, *price, *quantity)?;
+                    if *liquidity == Liquidity::Taker {
+                        self.record_trade(*side, *price, *quantity);
+                    }
                     // TODO - https://github.com/fermiorg/fermi/issues/175 - Uncomment remove below after diagnosing how this can cause failures
                     // remove order from map
                     //self.order_to_account.remove(order_id).ok_or(GDEXError::OrderRequest)?;
                     // emit order fill event
-                    self.emit_order_fill_event(&existing_pub_key, *order_id, *side as u64, *price, *quantity);
+                    self.emit_order_fill_event(&existing_pub_key, *order_id, *side as u64, *price, *quantity, fee);
+                    self.remove_order(*order_id)?;
                 }
                 Ok(Success::Updated {
                     order_id,
@@ -835,15 +1108,26 @@ pub trait OrderBookWrapper {
                 Ok(Success::Cancelled {
                     order_id,
                     side,
+                    order_type,
                     price,
                     quantity,
                     ..
                 }) => {
                     // order has been cancelled from order book, update states
                     let existing_pub_key = self.get_pub_key_from_order_id(order_id);
-                    self.update_state_on_cancel(&existing_pub_key, *order_id, *side, *price, *quantity)?;
+                    // market orders hold no escrow, so there is nothing to release
+                    if *order_type == OrderType::Limit {
+                        self.update_state_on_cancel(&existing_pub_key, *order_id, *side, *price, *quantity)?;
+                    }
                     // emit order cancel event
                     self.emit_order_cancel_event(&existing_pub_key, *order_id);
+                    self.remove_order(*order_id)?;
+                }
+                Err(Failed::PostOnlyWouldTake) => {
+                    return Err(GDEXError::PostOnlyWouldTake);
+                }
+                Err(Failed::FillOrKillUnfilled) => {
+                    return Err(GDEXError::FillOrKillUnfilled);
                 }
                 Err(_failure) => {
                     return Err(GDEXError::OrderRequest);
@@ -859,18 +1143,21 @@ mod test_order_book {
 
     use super::*;
     use crate::utils::engine::orders::{
-        create_cancel_order_request, create_limit_order_request, create_market_order_request,
-        create_update_order_request,
+        create_cancel_order_request, create_limit_order_request, create_limit_order_request_with_flags,
+        create_market_order_request, create_market_order_request_with_guard, create_update_order_request,
     };
 
     const BASE_ASSET: u64 = 0;
     const QUOTE_ASSET: u64 = 1;
+    const ORDER_ID_1: OrderId = get_first_order_id(BASE_ASSET, QUOTE_ASSET);
+    const ORDER_ID_2: OrderId = ORDER_ID_1 + 1;
+    const ORDER_ID_3: OrderId = ORDER_ID_1 + 2;
 
     #[test]
     fn failed_cancel() {
         let mut orderbook = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
-        let request = create_cancel_order_request(BASE_ASSET, QUOTE_ASSET, 1, OrderSide::Bid, SystemTime::now());
-        let mut result = orderbook.process_order(request);
+        let request = create_cancel_order_request(BASE_ASSET, QUOTE_ASSET, 1, OrderSide::Bid);
+        let mut result = orderbook.process_order(request).unwrap();
 
         assert_eq!(result.len(), 1);
         match result.pop().unwrap() {
@@ -885,8 +1172,8 @@ mod test_order_book {
         let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
 
         // create and process limit order
-        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET + 1, OrderSide::Ask, 10, 1, SystemTime::now());
-        let results = order_book.process_order(order);
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET + 1, OrderSide::Ask, 10, 1, 0);
+        let results = order_book.process_order(order).unwrap();
         for result in results {
             result.unwrap();
         }
@@ -897,12 +1184,12 @@ mod test_order_book {
         let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
 
         // create and process limit order
-        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Bid, 10, 100, SystemTime::now());
-        order_book.process_order(order);
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Bid, 10, 100, 0);
+        order_book.process_order(order).unwrap();
 
         // create and process limit order
-        let order = create_market_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, SystemTime::now());
-        let results = order_book.process_order(order);
+        let order = create_market_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 0);
+        let results = order_book.process_order(order).unwrap();
         for result in results {
             result.unwrap();
         }
@@ -913,23 +1200,16 @@ mod test_order_book {
         let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
 
         // create and process limit order
-        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Bid, 10, 100, SystemTime::now());
-        let mut results = order_book.process_order(order);
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Bid, 10, 100, 0);
+        let mut results = order_book.process_order(order).unwrap();
 
         let order_result = results.pop().unwrap().unwrap();
 
         match order_result {
             Success::Accepted { order_id, .. } => {
-                let update_order = create_update_order_request(
-                    BASE_ASSET,
-                    QUOTE_ASSET,
-                    order_id,
-                    OrderSide::Bid,
-                    100,
-                    100,
-                    SystemTime::now(),
-                );
-                order_book.process_order(update_order).pop().unwrap().unwrap();
+                let update_order =
+                    create_update_order_request(BASE_ASSET, QUOTE_ASSET, order_id, OrderSide::Bid, 100, 100);
+                order_book.process_order(update_order).unwrap().pop().unwrap().unwrap();
             }
             _ => {
                 panic!("unexpected match result");
@@ -942,14 +1222,302 @@ mod test_order_book {
         let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
 
         // create and process limit order
-        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Bid, 10, 100, SystemTime::now());
-        order_book.process_order(order);
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Bid, 10, 100, 0);
+        order_book.process_order(order).unwrap();
+
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 5, 10, 0);
+        let results = order_book.process_order(order).unwrap();
+
+        for result in results {
+            result.unwrap();
+        }
+    }
+
+    #[test]
+    pub fn equal_price_matches_in_sequence_order() {
+        let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
+
+        // rest two asks at the same price, the first should have priority
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 100, 0);
+        order_book.process_order(order).unwrap();
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 100, 0);
+        order_book.process_order(order).unwrap();
+
+        let order = create_market_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Bid, 150, 0);
+        let results = order_book.process_order(order).unwrap();
+
+        if !matches!(
+            results[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_3,
+                sequence_number: 3,
+                ..
+            })
+        ) || !matches!(
+            results[2],
+            Ok(Success::Filled {
+                order_id: ORDER_ID_1,
+                sequence_number: 3,
+                ..
+            })
+        ) || !matches!(
+            results[4],
+            Ok(Success::PartiallyFilled {
+                order_id: ORDER_ID_2,
+                sequence_number: 3,
+                ..
+            })
+        ) {
+            panic!("unexpected event sequence: {:?}", results)
+        }
+        assert_eq!(
+            order_book.get_order(OrderSide::Ask, ORDER_ID_2).unwrap().get_quantity(),
+            50
+        );
+        assert!(order_book.get_order(OrderSide::Ask, ORDER_ID_1).is_err());
+    }
+
+    #[test]
+    pub fn post_only_rejected_when_crossing() {
+        let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
 
-        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 5, 10, SystemTime::now());
-        let results = order_book.process_order(order);
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 100, 0);
+        order_book.process_order(order).unwrap();
+
+        // a crossing post-only bid is rejected without being assigned an id
+        let order = create_limit_order_request_with_flags(
+            BASE_ASSET,
+            QUOTE_ASSET,
+            OrderSide::Bid,
+            10,
+            100,
+            0,
+            TimeInForce::GoodTilCancelled,
+            true,
+        );
+        let results = order_book.process_order(order).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(Failed::PostOnlyWouldTake)));
+
+        // a non-crossing post-only bid rests on the book
+        let order = create_limit_order_request_with_flags(
+            BASE_ASSET,
+            QUOTE_ASSET,
+            OrderSide::Bid,
+            9,
+            100,
+            0,
+            TimeInForce::GoodTilCancelled,
+            true,
+        );
+        let results = order_book.process_order(order).unwrap();
+        assert!(matches!(
+            results[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_2,
+                ..
+            })
+        ));
+        assert_eq!(order_book.current_spread(), Some((9, 10)));
+    }
 
+    #[test]
+    pub fn immediate_or_cancel_cancels_remainder() {
+        let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
+
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 50, 0);
+        order_book.process_order(order).unwrap();
+
+        let order = create_limit_order_request_with_flags(
+            BASE_ASSET,
+            QUOTE_ASSET,
+            OrderSide::Bid,
+            10,
+            80,
+            0,
+            TimeInForce::ImmediateOrCancel,
+            false,
+        );
+        let results = order_book.process_order(order).unwrap();
+
+        if !matches!(
+            results.last(),
+            Some(Ok(Success::Cancelled {
+                order_id: ORDER_ID_2,
+                quantity: 30,
+                ..
+            }))
+        ) {
+            panic!("unexpected event sequence: {:?}", results)
+        }
+        // nothing rests on either side of the book
+        assert_eq!(order_book.current_spread(), None);
+        assert!(order_book.get_order(OrderSide::Bid, ORDER_ID_2).is_err());
+    }
+
+    #[test]
+    pub fn fill_or_kill_requires_full_liquidity() {
+        let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
+
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 50, 0);
+        order_book.process_order(order).unwrap();
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 11, 50, 0);
+        order_book.process_order(order).unwrap();
+
+        // only 50 is available at or below the limit price
+        let order = create_limit_order_request_with_flags(
+            BASE_ASSET,
+            QUOTE_ASSET,
+            OrderSide::Bid,
+            10,
+            80,
+            0,
+            TimeInForce::FillOrKill,
+            false,
+        );
+        let results = order_book.process_order(order).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(Failed::FillOrKillUnfilled)));
+        assert_eq!(
+            order_book.get_order(OrderSide::Ask, ORDER_ID_1).unwrap().get_quantity(),
+            50
+        );
+
+        // raising the limit makes enough liquidity available
+        let order = create_limit_order_request_with_flags(
+            BASE_ASSET,
+            QUOTE_ASSET,
+            OrderSide::Bid,
+            11,
+            80,
+            0,
+            TimeInForce::FillOrKill,
+            false,
+        );
+        let results = order_book.process_order(order).unwrap();
         for result in results {
             result.unwrap();
         }
+        assert!(order_book.get_order(OrderSide::Ask, ORDER_ID_1).is_err());
+        assert_eq!(
+            order_book.get_order(OrderSide::Ask, ORDER_ID_2).unwrap().get_quantity(),
+            20
+        );
+    }
+
+    #[test]
+    pub fn market_order_stops_at_worst_price() {
+        let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
+
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 50, 0);
+        order_book.process_order(order).unwrap();
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 12, 50, 0);
+        order_book.process_order(order).unwrap();
+
+        let order =
+            create_market_order_request_with_guard(BASE_ASSET, QUOTE_ASSET, OrderSide::Bid, 100, 0, Some(10), None);
+        let results = order_book.process_order(order).unwrap();
+
+        if !matches!(
+            results.last(),
+            Some(Ok(Success::Cancelled {
+                order_id: ORDER_ID_3,
+                order_type: OrderType::Market,
+                quantity: 50,
+                ..
+            }))
+        ) {
+            panic!("unexpected event sequence: {:?}", results)
+        }
+        assert!(order_book.get_order(OrderSide::Ask, ORDER_ID_1).is_err());
+        assert_eq!(
+            order_book.get_order(OrderSide::Ask, ORDER_ID_2).unwrap().get_quantity(),
+            50
+        );
+    }
+
+    #[test]
+    pub fn market_order_respects_max_quote_spend() {
+        let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
+
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 50, 0);
+        order_book.process_order(order).unwrap();
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 12, 50, 0);
+        order_book.process_order(order).unwrap();
+
+        // 500 buys the first level and the remaining 300 buys 25 of the second
+        let order =
+            create_market_order_request_with_guard(BASE_ASSET, QUOTE_ASSET, OrderSide::Bid, 100, 0, None, Some(800));
+        let results = order_book.process_order(order).unwrap();
+
+        if !matches!(
+            results.last(),
+            Some(Ok(Success::Cancelled {
+                order_id: ORDER_ID_3,
+                quantity: 25,
+                ..
+            }))
+        ) {
+            panic!("unexpected event sequence: {:?}", results)
+        }
+        assert_eq!(
+            order_book.get_order(OrderSide::Ask, ORDER_ID_2).unwrap().get_quantity(),
+            25
+        );
+    }
+
+    #[test]
+    pub fn order_ids_carry_asset_pair() {
+        let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
+        let mut other_order_book = Orderbook::new(QUOTE_ASSET, BASE_ASSET);
+
+        // the first order of each book has the same sequence but a different id
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 100, 0);
+        let results = order_book.process_order(order).unwrap();
+        assert!(matches!(
+            results[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_1,
+                ..
+            })
+        ));
+        let order = create_limit_order_request(QUOTE_ASSET, BASE_ASSET, OrderSide::Ask, 10, 100, 0);
+        let results = other_order_book.process_order(order).unwrap();
+        let other_order_id = match results[0] {
+            Ok(Success::Accepted { order_id, .. }) => order_id,
+            _ => panic!("unexpected event sequence: {:?}", results),
+        };
+        assert_ne!(other_order_id, ORDER_ID_1);
+
+        assert_eq!(get_order_id_assets(ORDER_ID_1), (BASE_ASSET, QUOTE_ASSET));
+        assert_eq!(get_order_id_assets(other_order_id), (QUOTE_ASSET, BASE_ASSET));
+    }
+
+    #[test]
+    pub fn order_id_assets_must_fit() {
+        assert!(validate_order_id_assets(u16::MAX as u64, QUOTE_ASSET).is_ok());
+        assert_eq!(
+            validate_order_id_assets(1 << ORDER_ID_ASSET_BITS, QUOTE_ASSET),
+            Err(GDEXError::OrderBookCreation)
+        );
+        assert_eq!(
+            validate_order_id_assets(BASE_ASSET, 1 << ORDER_ID_ASSET_BITS),
+            Err(GDEXError::OrderBookCreation)
+        );
+    }
+
+    #[test]
+    pub fn order_id_sequence_does_not_spill_into_asset_pair() {
+        let mut order_book = Orderbook::new(BASE_ASSET, QUOTE_ASSET);
+        order_book.seq = sequence::new_sequence_gen((1 << ORDER_ID_SEQUENCE_BITS) - 1);
+
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 100, 0);
+        order_book.process_order(order).unwrap()[0].as_ref().unwrap();
+
+        let order = create_limit_order_request(BASE_ASSET, QUOTE_ASSET, OrderSide::Ask, 10, 100, 0);
+        assert!(matches!(
+            order_book.process_order(order),
+            Err(GDEXError::OrderIdExhausted)
+        ));
     }
 }
@@ -1,20 +1,19 @@
-use fermi_types::order_book::OrderSide;
+use fermi_types::order_book::{OrderSide, SequenceNumber};
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
-    collections::{BinaryHeap, HashMap},
-    time,
+    collections::{BTreeMap, BinaryHeap},
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 struct OrderIndex {
     id: u64,
     price: u64,
-    timestamp: time::SystemTime,
+    sequence_number: SequenceNumber,
     order_side: OrderSide,
 }
 
-/// Arrange at first by price and after that by time
+/// Arrange at first by price and after that by sequence number
 impl Ord for OrderIndex {
     fn cmp(&self, other: &Self) -> Ordering {
         match self.price {
@@ -28,7 +27,7 @@ impl Ord for OrderIndex {
             },
             _ => {
                 // FIFO
-                other.timestamp.cmp(&self.timestamp)
+                other.sequence_number.cmp(&self.sequence_number)
             }
         }
     }
@@ -45,7 +44,7 @@ impl PartialEq for OrderIndex {
         if self.price != other.price {
             false
         } else {
-            self.timestamp == other.timestamp
+            self.sequence_number == other.sequence_number
         }
     }
 }
@@ -57,7 +56,8 @@ impl Eq for OrderIndex {}
 pub struct OrderQueue<T> {
     // use Option in order to replace heap in mutable borrow
     idx_queue: Option<BinaryHeap<OrderIndex>>,
-    pub orders: HashMap<u64, T>,
+    // ordered map keeps iteration and serialization identical across replicas
+    pub orders: BTreeMap<u64, T>,
     op_counter: u64,
     max_stalled: u64,
     queue_side: OrderSide,
@@ -70,7 +70,7 @@ impl<T> OrderQueue<T> {
     pub fn new(side: OrderSide, max_stalled: u64, capacity: usize) -> Self {
         OrderQueue {
             idx_queue: Some(BinaryHeap::with_capacity(capacity)),
-            orders: HashMap::with_capacity(capacity),
+            orders: BTreeMap::new(),
             op_counter: 0,
             max_stalled,
             queue_side: side,
@@ -102,7 +102,7 @@ impl<T> OrderQueue<T> {
     }
 
     // Add new limit order to the queue
-    pub fn insert(&mut self, id: u64, price: u64, timestamp: time::SystemTime, order: T) -> bool {
+    pub fn insert(&mut self, id: u64, price: u64, sequence_number: SequenceNumber, order: T) -> bool {
         if self.orders.contains_key(&id) {
             // do not update existing order
             return false;
@@ -112,7 +112,7 @@ impl<T> OrderQueue<T> {
         self.idx_queue.as_mut().unwrap().push(OrderIndex {
             id,
             price,
-            timestamp,
+            sequence_number,
             order_side: self.queue_side,
         });
         self.orders.insert(id, order);
@@ -124,11 +124,11 @@ impl<T> OrderQueue<T> {
     }
 
     // use it when price was changed
-    pub fn update(&mut self, id: u64, price: u64, timestamp: time::SystemTime, order: T) -> bool {
-        if let std::collections::hash_map::Entry::Occupied(mut e) = self.orders.entry(id) {
+    pub fn update(&mut self, id: u64, price: u64, sequence_number: SequenceNumber, order: T) -> bool {
+        if let std::collections::btree_map::Entry::Occupied(mut e) = self.orders.entry(id) {
             // store new order data
             e.insert(order);
-            self.rebuild_idx(id, price, timestamp);
+            self.rebuild_idx(id, price, sequence_number);
             true
         } else {
             false
@@ -149,10 +149,10 @@ impl<T> OrderQueue<T> {
 
     /// Used internally when current order is partially matched.
     ///
-    /// Note: do not modify price or time, cause index doesn't change!
+    /// Note: do not modify price or sequence number, cause index doesn't change!
     pub fn modify_current_order(&mut self, new_order: T) -> bool {
         if let Some(order_id) = self.get_current_order_id() {
-            if let std::collections::hash_map::Entry::Occupied(mut e) = self.orders.entry(order_id) {
+            if let std::collections::btree_map::Entry::Occupied(mut e) = self.orders.entry(order_id) {
                 e.insert(new_order);
                 return true;
             }
@@ -180,7 +180,7 @@ impl<T> OrderQueue<T> {
     }
 
     /// Recreate order-index queue with changed index info
-    fn rebuild_idx(&mut self, id: u64, price: u64, timestamp: time::SystemTime) {
+    fn rebuild_idx(&mut self, id: u64, price: u64, sequence_number: SequenceNumber) {
         if let Some(idx_queue) = self.idx_queue.take() {
             // deconstruct queue
             let mut active_orders = idx_queue.into_vec();
@@ -190,7 +190,7 @@ impl<T> OrderQueue<T> {
             active_orders.push(OrderIndex {
                 id,
                 price,
-                timestamp,
+                sequence_number,
                 order_side: self.queue_side,
             });
             // construct new queue
@@ -222,13 +222,13 @@ mod test {
     fn get_queue_bids() -> OrderQueue<TestOrder> {
         let mut bid_queue = get_queue_empty(OrderSide::Bid);
 
-        assert!(bid_queue.insert(1, 101, time::SystemTime::now(), TestOrder { name: "low bid" },));
-        assert!(bid_queue.insert(2, 102, time::SystemTime::now(), TestOrder { name: "high bid first" },));
+        assert!(bid_queue.insert(1, 101, 1, TestOrder { name: "low bid" },));
+        assert!(bid_queue.insert(2, 102, 2, TestOrder { name: "high bid first" },));
         // same price but later
         assert!(bid_queue.insert(
             3,
             102,
-            time::SystemTime::now(),
+            3,
             TestOrder {
                 name: "high bid second"
             },
@@ -240,9 +240,9 @@ mod test {
 
     fn get_queue_asks() -> OrderQueue<TestOrder> {
         let mut ask_queue = get_queue_empty(OrderSide::Ask);
-        assert!(ask_queue.insert(1, 101, time::SystemTime::now(), TestOrder { name: "low ask first" },));
-        assert!(ask_queue.insert(2, 102, time::SystemTime::now(), TestOrder { name: "high ask" },));
-        assert!(ask_queue.insert(3, 101, time::SystemTime::now(), TestOrder { name: "low ask second" },));
+        assert!(ask_queue.insert(1, 101, 1, TestOrder { name: "low ask first" },));
+        assert!(ask_queue.insert(2, 102, 2, TestOrder { name: "high ask" },));
+        assert!(ask_queue.insert(3, 101, 3, TestOrder { name: "low ask second" },));
         assert_eq!(ask_queue.peek().unwrap().name, "low ask first");
 
         ask_queue
@@ -254,13 +254,13 @@ mod test {
         assert_eq!(bid_queue.peek(), None);
 
         // insert unique
-        assert!(bid_queue.insert(1, 101, time::SystemTime::now(), TestOrder { name: "first bid" },));
+        assert!(bid_queue.insert(1, 101, 1, TestOrder { name: "first bid" },));
 
         // discard order with existing ID
         assert!(!bid_queue.insert(
             1,
             102,
-            time::SystemTime::now(),
+            2,
             TestOrder {
                 name: "another first bid"
             },
@@ -303,10 +303,10 @@ mod test {
         let mut ask_queue = get_queue_asks();
 
         // update two orders in the queue
-        assert!(ask_queue.update(2, 99, time::SystemTime::now(), TestOrder { name: "new first" },));
-        assert!(ask_queue.update(1, 101, time::SystemTime::now(), TestOrder { name: "new last" },));
+        assert!(ask_queue.update(2, 99, 4, TestOrder { name: "new first" },));
+        assert!(ask_queue.update(1, 101, 5, TestOrder { name: "new last" },));
         // non-exist order
-        assert!(!ask_queue.update(4, 303, time::SystemTime::now(), TestOrder { name: "nonexistent" },));
+        assert!(!ask_queue.update(4, 303, 6, TestOrder { name: "nonexistent" },));
 
         assert_eq!(ask_queue.pop().unwrap().name, "new first");
         assert_eq!(ask_queue.pop().unwrap().name, "low ask second");
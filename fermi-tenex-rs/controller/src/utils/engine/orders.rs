@@ -1,27 +1,47 @@
 use fermi_types::{
     asset::AssetId,
-    order_book::{OrderRequest, OrderSide},
+    order_book::{ClientOrderId, OrderRequest, OrderSide, TimeInForce},
 };
-use std::time::SystemTime;
 
 /* Constructors */
 
-// TODO - https://github.com/fermiorg/fermi/issues/173 - replace timestamp as a means of sorting, it is not sufficient (or really possible) in our current construction
-
 /// Create request for the new market order
 pub fn create_market_order_request(
     base_asset_id: AssetId,
     quote_asset_id: AssetId,
     side: OrderSide,
     quantity: u64,
-    local_timestamp: SystemTime,
+    client_order_id: ClientOrderId,
+) -> OrderRequest {
+    create_market_order_request_with_guard(
+        base_asset_id,
+        quote_asset_id,
+        side,
+        quantity,
+        client_order_id,
+        None,
+        None,
+    )
+}
+
+/// Create request for the new market order which stops filling once it hits either slippage guard
+pub fn create_market_order_request_with_guard(
+    base_asset_id: AssetId,
+    quote_asset_id: AssetId,
+    side: OrderSide,
+    quantity: u64,
+    client_order_id: ClientOrderId,
+    worst_price: Option<u64>,
+    max_quote_spend: Option<u64>,
 ) -> OrderRequest {
     OrderRequest::Market {
         base_asset_id,
         quote_asset_id,
         quantity,
         side,
-        local_timestamp,
+        client_order_id,
+        worst_price,
+        max_quote_spend,
     }
 }
 
@@ -32,7 +52,31 @@ pub fn create_limit_order_request(
     side: OrderSide,
     price: u64,
     quantity: u64,
-    local_timestamp: SystemTime,
+    client_order_id: ClientOrderId,
+) -> OrderRequest {
+    create_limit_order_request_with_flags(
+        base_asset_id,
+        quote_asset_id,
+        side,
+        price,
+        quantity,
+        client_order_id,
+        TimeInForce::GoodTilCancelled,
+        false,
+    )
+}
+
+/// Create request for the new limit order with explicit execution flags
+#[allow(clippy::too_many_arguments)]
+pub fn create_limit_order_request_with_flags(
+    base_asset_id: AssetId,
+    quote_asset_id: AssetId,
+    side: OrderSide,
+    price: u64,
+    quantity: u64,
+    client_order_id: ClientOrderId,
+    time_in_force: TimeInForce,
+    post_only: bool,
 ) -> OrderRequest {
     OrderRequest::Limit {
         base_asset_id,
@@ -40,7 +84,9 @@ pub fn create_limit_order_request(
         side,
         price,
         quantity,
-        local_timestamp,
+        client_order_id,
+        time_in_force,
+        post_only,
     }
 }
 
@@ -55,7 +101,6 @@ pub fn create_update_order_request(
     side: OrderSide,
     price: u64,
     quantity: u64,
-    local_timestamp: SystemTime,
 ) -> OrderRequest {
     OrderRequest::Update {
         base_asset_id,
@@ -64,7 +109,6 @@ pub fn create_update_order_request(
         side,
         price,
         quantity,
-        local_timestamp,
     }
 }
 
@@ -74,13 +118,11 @@ pub fn create_cancel_order_request(
     quote_asset_id: AssetId,
     order_id: u64,
     side: OrderSide,
-    local_timestamp: SystemTime,
 ) -> OrderRequest {
     OrderRequest::Cancel {
         base_asset_id,
         quote_asset_id,
         order_id,
         side,
-        local_timestamp,
     }
 }
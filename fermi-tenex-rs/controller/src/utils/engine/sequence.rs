@@ -1,34 +1,25 @@
-/// Rotating index sequence
+/// Monotonic index sequence, an issued index is never handed out again
+use fermi_types::error::GDEXError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TradeSequence {
-    min_id: u64,
-    max_id: u64,
     current_idx: u64,
 }
 
 impl TradeSequence {
-    pub fn next_id(&mut self) -> u64 {
+    pub fn next_id(&mut self) -> Result<u64, GDEXError> {
         let next_id = self.current_idx;
 
-        // update index
-        if (next_id + 1) <= self.max_id {
-            self.current_idx += 1;
-        } else {
-            self.current_idx = self.min_id;
-        }
+        // update index, an exhausted sequence rejects the order instead of reissuing an id
+        self.current_idx = next_id.checked_add(1).ok_or(GDEXError::OrderIdExhausted)?;
 
-        next_id
+        Ok(next_id)
     }
 }
 
-pub fn new_sequence_gen(min: u64, max: u64) -> TradeSequence {
-    TradeSequence {
-        min_id: min,
-        max_id: max,
-        current_idx: min,
-    }
+pub fn new_sequence_gen(start: u64) -> TradeSequence {
+    TradeSequence { current_idx: start }
 }
 
 #[cfg(test)]
@@ -37,22 +28,34 @@ mod tests {
 
     #[test]
     fn seq_from_zero() {
-        let mut seq_gen = new_sequence_gen(0, 2);
+        let mut seq_gen = new_sequence_gen(0);
 
         assert_eq!(seq_gen.current_idx, 0);
-        assert_eq!(seq_gen.next_id(), 0);
-        assert_eq!(seq_gen.next_id(), 1);
-        assert_eq!(seq_gen.next_id(), 2);
-        assert_eq!(seq_gen.next_id(), 0);
+        assert_eq!(seq_gen.next_id().unwrap(), 0);
+        assert_eq!(seq_gen.next_id().unwrap(), 1);
+        assert_eq!(seq_gen.next_id().unwrap(), 2);
+        assert_eq!(seq_gen.next_id().unwrap(), 3);
     }
 
     #[test]
     fn seq_from_positive() {
-        let mut seq_gen = new_sequence_gen(1, 2);
+        let mut seq_gen = new_sequence_gen(1);
 
         assert_eq!(seq_gen.current_idx, 1);
-        assert_eq!(seq_gen.next_id(), 1);
-        assert_eq!(seq_gen.next_id(), 2);
-        assert_eq!(seq_gen.next_id(), 1);
+        assert_eq!(seq_gen.next_id().unwrap(), 1);
+        assert_eq!(seq_gen.next_id().unwrap(), 2);
+        assert_eq!(seq_gen.next_id().unwrap(), 3);
+    }
+
+    #[test]
+    fn seq_does_not_wrap() {
+        let mut seq_gen = new_sequence_gen(u64::MAX - 2);
+
+        // the last ids are issued in order, after which the sequence errors instead of wrapping to zero
+        assert_eq!(seq_gen.next_id().unwrap(), u64::MAX - 2);
+        assert_eq!(seq_gen.next_id().unwrap(), u64::MAX - 1);
+        assert!(matches!(seq_gen.next_id(), Err(GDEXError::OrderIdExhausted)));
+        assert!(matches!(seq_gen.next_id(), Err(GDEXError::OrderIdExhausted)));
+        assert_eq!(seq_gen.current_idx, u64::MAX);
     }
 }
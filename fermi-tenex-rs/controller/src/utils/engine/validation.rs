@@ -1,4 +1,7 @@
-use fermi_types::{asset::AssetId, order_book::OrderRequest};
+use fermi_types::{
+    asset::AssetId,
+    order_book::{OrderRequest, OrderSide, TimeInForce},
+};
 use serde::{Deserialize, Serialize};
 
 /// Validation errors
@@ -6,7 +9,8 @@ const ERR_BAD_BASE_ASSET: &str = "bad order asset";
 const ERR_BAD_QUOTE_ASSET: &str = "bad price asset";
 const ERR_BAD_PRICE_VALUE: &str = "price must be non-negative";
 const ERR_BAD_QUANTITY_VALUE: &str = "quantity must be non-negative";
-const ERR_BAD_SEQ_ID: &str = "order ID out of range";
+const ERR_BAD_POST_ONLY_FLAGS: &str = "post-only orders must be good til cancelled";
+const ERR_BAD_QUOTE_SPEND: &str = "max quote spend must be positive and only applies to bids";
 
 /* Validators */
 
@@ -14,22 +18,13 @@ const ERR_BAD_SEQ_ID: &str = "order ID out of range";
 pub struct OrderRequestValidator {
     orderbook_base_asset: AssetId,
     orderbook_quote_asset: AssetId,
-    min_sequence_id: u64,
-    max_sequence_id: u64,
 }
 
 impl OrderRequestValidator {
-    pub fn new(
-        orderbook_base_asset: AssetId,
-        orderbook_quote_asset: AssetId,
-        min_sequence_id: u64,
-        max_sequence_id: u64,
-    ) -> Self {
+    pub fn new(orderbook_base_asset: AssetId, orderbook_quote_asset: AssetId) -> Self {
         OrderRequestValidator {
             orderbook_base_asset,
             orderbook_quote_asset,
-            min_sequence_id,
-            max_sequence_id,
         }
     }
 
@@ -38,32 +33,48 @@ impl OrderRequestValidator {
             OrderRequest::Market {
                 base_asset_id,
                 quote_asset_id,
+                side,
                 quantity,
+                worst_price,
+                max_quote_spend,
                 ..
-            } => self.validate_market(base_asset_id, quote_asset_id, quantity),
+            } => self.validate_market(
+                base_asset_id,
+                quote_asset_id,
+                side,
+                quantity,
+                worst_price,
+                max_quote_spend,
+            ),
 
             OrderRequest::Limit {
                 base_asset_id,
                 quote_asset_id,
                 price,
                 quantity,
+                time_in_force,
+                post_only,
                 ..
-            } => self.validate_limit(base_asset_id, quote_asset_id, price, quantity),
+            } => self.validate_limit(base_asset_id, quote_asset_id, price, quantity, time_in_force, post_only),
 
-            OrderRequest::Update {
-                order_id,
-                price,
-                quantity,
-                ..
-            } => self.validate_update(order_id, price, quantity),
+            OrderRequest::Update { price, quantity, .. } => self.validate_update(price, quantity),
 
-            OrderRequest::Cancel { order_id, .. } => self.validate_cancel(order_id),
+            // order ids are never reused, so an unknown id is reported by the book as not found
+            OrderRequest::Cancel { .. } => Ok(()),
         }
     }
 
     /* Internal validators */
 
-    fn validate_market(&self, base_asset: AssetId, quote_asset: AssetId, quantity: u64) -> Result<(), &str> {
+    fn validate_market(
+        &self,
+        base_asset: AssetId,
+        quote_asset: AssetId,
+        side: OrderSide,
+        quantity: u64,
+        worst_price: Option<u64>,
+        max_quote_spend: Option<u64>,
+    ) -> Result<(), &str> {
         if self.orderbook_base_asset != base_asset {
             return Err(ERR_BAD_BASE_ASSET);
         }
@@ -76,10 +87,28 @@ impl OrderRequestValidator {
             return Err(ERR_BAD_QUANTITY_VALUE);
         }
 
+        if worst_price == Some(0) {
+            return Err(ERR_BAD_PRICE_VALUE);
+        }
+
+        if let Some(max_quote_spend) = max_quote_spend {
+            if max_quote_spend == 0 || side == OrderSide::Ask {
+                return Err(ERR_BAD_QUOTE_SPEND);
+            }
+        }
+
         Ok(())
     }
 
-    fn validate_limit(&self, base_asset: AssetId, quote_asset: AssetId, price: u64, quantity: u64) -> Result<(), &str> {
+    fn validate_limit(
+        &self,
+        base_asset: AssetId,
+        quote_asset: AssetId,
+        price: u64,
+        quantity: u64,
+        time_in_force: TimeInForce,
+        post_only: bool,
+    ) -> Result<(), &str> {
         if self.orderbook_base_asset != base_asset {
             return Err(ERR_BAD_BASE_ASSET);
         }
@@ -96,14 +125,15 @@ impl OrderRequestValidator {
             return Err(ERR_BAD_QUANTITY_VALUE);
         }
 
+        // a post-only order never matches on arrival, so it can only rest
+        if post_only && time_in_force != TimeInForce::GoodTilCancelled {
+            return Err(ERR_BAD_POST_ONLY_FLAGS);
+        }
+
         Ok(())
     }
 
-    fn validate_update(&self, id: u64, price: u64, quantity: u64) -> Result<(), &str> {
-        if self.min_sequence_id > id || self.max_sequence_id < id {
-            return Err(ERR_BAD_SEQ_ID);
-        }
-
+    fn validate_update(&self, price: u64, quantity: u64) -> Result<(), &str> {
         if price == 0 {
             return Err(ERR_BAD_PRICE_VALUE);
         }
@@ -114,12 +144,4 @@ impl OrderRequestValidator {
 
         Ok(())
     }
-
-    fn validate_cancel(&self, id: u64) -> Result<(), &str> {
-        if self.min_sequence_id > id || self.max_sequence_id < id {
-            return Err(ERR_BAD_SEQ_ID);
-        }
-
-        Ok(())
-    }
 }
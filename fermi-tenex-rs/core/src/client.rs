@@ -3,7 +3,11 @@ use crate::{
     multiaddr::{parse_dns, parse_ip4, parse_ip6},
 };
 use anyhow::{anyhow, Context, Result};
+use fermi_types::{account::AccountPubKey, crypto::ToFromBytes, utils};
+use jsonrpsee::{http_client::HttpClientBuilder, rpc_params};
+use jsonrpsee_core::client::ClientT;
 use multiaddr::{Multiaddr, Protocol};
+use std::collections::HashMap;
 use tonic::transport::{Channel, Endpoint, Uri};
 
 pub async fn connect(address: &Multiaddr) -> Result<Channel> {
@@ -169,6 +173,37 @@ fn apply_config_to_endpoint(config: &ServerConfig, mut endpoint: Endpoint) -> En
         .tcp_keepalive(config.tcp_keepalive)
 }
 
+/// Hands out consecutive nonces to the accounts a client signs for, so that several
+/// transactions from one account can be in flight at once
+#[derive(Debug, Default)]
+pub struct NonceTracker {
+    next_nonces: HashMap<AccountPubKey, u64>,
+}
+
+impl NonceTracker {
+    /// Fetch the next nonce of the account over `tenex_getNextNonce` and continue from it
+    pub async fn sync(&mut self, jsonrpc_url: &str, account: &AccountPubKey) -> Result<u64> {
+        let client = HttpClientBuilder::default().build(jsonrpc_url)?;
+        let params = rpc_params![utils::encode_bytes_hex(account.as_bytes().to_vec())];
+        let next_nonce: u64 = client.request("tenex_getNextNonce", params).await?;
+        self.next_nonces.insert(account.clone(), next_nonce);
+        Ok(next_nonce)
+    }
+
+    /// Returns the nonce to sign the account's next transaction with and advances it,
+    /// accounts which were never synced start from zero
+    pub fn next_nonce(&mut self, account: &AccountPubKey) -> u64 {
+        let next_nonce = self.next_nonces.entry(account.clone()).or_insert(0);
+        let nonce = *next_nonce;
+        *next_nonce += 1;
+        nonce
+    }
+
+    pub fn is_synced(&self, account: &AccountPubKey) -> bool {
+        self.next_nonces.contains_key(account)
+    }
+}
+
 #[cfg(test)]
 mod client_tests {
     use super::*;
@@ -73,7 +73,11 @@ pub enum CeremonyCommand {
         narwhal_consensus_addresses: Vec<Multiaddr>,
     },
 
-    AddControllers,
+    AddControllers {
+        /// Hex encoded account allowed to change the consensus batch parameters, defaults to the first validator
+        #[clap(value_parser, long)]
+        governance_account: Option<String>,
+    },
 
     Build,
 
@@ -131,7 +135,7 @@ pub fn run(cmd: Ceremony) -> Result<()> {
         }
 
         // Add the order book controllers
-        CeremonyCommand::AddControllers => {
+        CeremonyCommand::AddControllers { governance_account } => {
             let builder = GenesisStateBuilder::load(&dir)?;
 
             // Initialize controllers to default state
@@ -147,6 +151,39 @@ pub fn run(cmd: Ceremony) -> Result<()> {
                 .unwrap()
                 .create_asset(&null_creator)?;
 
+            // Only the validators of the genesis may stake, consensus has no addresses for anyone else
+            let validator_set = builder
+                .validators
+                .values()
+                .map(|validator| ValidatorPubKey::try_from(validator.public_key).unwrap())
+                .collect();
+            controller_router
+                .stake_controller
+                .lock()
+                .unwrap()
+                .set_validator_set(validator_set);
+
+            // Batch parameters are governed by an account somebody holds the key of
+            let governance_account = match governance_account {
+                Some(governance_account) => {
+                    let governance_bytes: Vec<u8> = utils::decode_bytes_hex(&governance_account)?;
+                    AccountPubKey::from_bytes(&governance_bytes).context("Invalid governance account")?
+                }
+                None => {
+                    let first_validator = builder
+                        .validators
+                        .values()
+                        .next()
+                        .context("Genesis has no validators")?;
+                    ValidatorPubKey::try_from(first_validator.public_key)?
+                }
+            };
+            controller_router
+                .consensus_controller
+                .lock()
+                .unwrap()
+                .set_governance_account(governance_account);
+
             // Fund and stake the validators with the VALIDATOR_FUNDING_AMOUNT
             for (_key, validator) in builder.validators.iter() {
                 let validator_key = ValidatorPubKey::try_from(validator.public_key).unwrap();
@@ -318,9 +355,12 @@ mod test_genesis_ceremony {
             command.run()?;
         }
 
+        let governance_account = utils::encode_bytes_hex(validators[1].1.public_key);
         let command = Ceremony {
             path: Some(dir.path().into()),
-            command: CeremonyCommand::AddControllers,
+            command: CeremonyCommand::AddControllers {
+                governance_account: Some(governance_account),
+            },
         };
 
         command.run()?;
@@ -348,6 +388,19 @@ mod test_genesis_ceremony {
         };
         command.run()?;
 
+        // Batch parameters are governed by the account chosen at genesis
+        let genesis = ValidatorGenesisState::load(dir.path().join(GENESIS_FILENAME))?;
+        let expected_governance_account: AccountPubKey = validators[1].1.public_key.try_into()?;
+        assert_eq!(
+            genesis
+                .controller_router()
+                .consensus_controller
+                .lock()
+                .unwrap()
+                .get_governance_account(),
+            &expected_governance_account
+        );
+
         Ok(())
     }
 }
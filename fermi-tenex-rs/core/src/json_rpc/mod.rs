@@ -1,3 +1,4 @@
 pub mod processor;
 pub mod server;
 pub mod spawner;
+pub mod subscriptions;
@@ -1,18 +1,33 @@
 // fermi
 use crate::client::endpoint_from_multiaddr;
-use fermi_controller::router::ControllerRouter;
+use crate::json_rpc::subscriptions::ProcessedBlock;
+use fermi_controller::router::{ControllerRouter, CATCHUP_STATE_FREQUENCY};
 use fermi_types::{
+    account::AccountPubKey,
     block::{Block, BlockInfo},
-    proto::{BlockRequest, LatestBlockInfoRequest, ValidatorGrpcClient},
-    store::RPCStoreHandle, transaction::SignedTransaction
+    json_rpc::DepthUpdate,
+    order_book::OrderbookDepth,
+    proto::{
+        BlockInfoRequest, BlockRequest, CatchupStateRequest, LatestBlockInfoRequest, ListCatchupStatesRequest,
+        ValidatorGrpcClient,
+    },
+    store::{CatchupManifest, CatchupState, RPCStoreHandle, TransactionLocation},
+    transaction::{SignedTransaction, TransactionDigest},
 };
+// mysten
+use fastcrypto::Hash;
 // external
+use anyhow::{anyhow, bail};
 use multiaddr::Multiaddr;
-use std::sync::{
-    atomic::{AtomicU64, Ordering},
-    Arc, Mutex,
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
 };
-use tokio::task::JoinHandle;
+use tokio::{sync::broadcast, task::JoinHandle};
+use tonic::transport::Channel;
 use tracing::info;
 
 // The BlockProcessor is responsible for listening for new blocks from the
@@ -22,6 +37,8 @@ pub struct BlockProcessor {
     last_block_number: Arc<AtomicU64>,
     grpc_addr: Multiaddr,
     rpc_store_handle: Arc<RPCStoreHandle>,
+    // notifies subscribers of every processed block
+    tx_processed_blocks: broadcast::Sender<ProcessedBlock>,
 }
 
 impl BlockProcessor {
@@ -30,6 +47,7 @@ impl BlockProcessor {
         last_block_number: Arc<AtomicU64>,
         rpc_store_handle: Arc<RPCStoreHandle>,
         grpc_addr: Multiaddr,
+        tx_processed_blocks: broadcast::Sender<ProcessedBlock>,
     ) -> Vec<JoinHandle<()>> {
         let controller_router_clone = Arc::clone(&controller_router);
         let last_block_number_clone = Arc::clone(&last_block_number);
@@ -40,6 +58,7 @@ impl BlockProcessor {
                 last_block_number: last_block_number_clone,
                 grpc_addr,
                 rpc_store_handle,
+                tx_processed_blocks,
             }
             .run()
             .await
@@ -55,6 +74,15 @@ impl BlockProcessor {
             .await
             .unwrap();
 
+        // a node starting from genesis fast-syncs to a recent catchup state rather than replaying every block
+        if self.last_block_number.load(Ordering::SeqCst) == 0 {
+            if let Err(err) = self.fast_sync(&mut grpc_client).await {
+                info!("Fast-sync unavailable, replaying blocks from genesis: {}", err);
+            }
+        }
+
+        // last depth published to subscribers for each orderbook
+        let mut orderbook_depths: HashMap<String, OrderbookDepth> = HashMap::new();
         loop {
             let latest_block_info_request = LatestBlockInfoRequest {};
             let latest_block_info_response = grpc_client.get_latest_block_info(latest_block_info_request).await;
@@ -116,12 +144,37 @@ impl BlockProcessor {
                                     .handle_consensus_transaction(transaction);
                             }
                         }
+                        if let Err(err) = self.index_transactions(target_block_number, &block).await {
+                            info!("Failed to index transactions for block {}: {}", target_block_number, err);
+                        }
                         self.last_block_number.store(target_block_number, Ordering::SeqCst);
                         self.controller_router
                             .lock()
                             .unwrap()
                             .non_critical_process_end_of_block(&self.rpc_store_handle.rpc_store, target_block_number)
                             .unwrap();
+                        if target_block_number % CATCHUP_STATE_FREQUENCY == 0 {
+                            let catchup_state = self
+                                .controller_router
+                                .lock()
+                                .unwrap()
+                                .get_catchup_state(target_block_number, block.block_certificate.digest())
+                                .unwrap();
+                            self.rpc_store_handle
+                                .rpc_store
+                                .catchup_state_store
+                                .write(target_block_number, catchup_state)
+                                .await;
+                        }
+                        // the block number is stored before notifying so that subscribers never miss a block
+                        let depth_updates = self
+                            .collect_depth_updates(&mut orderbook_depths, target_block_number)
+                            .await;
+                        let _ = self.tx_processed_blocks.send(ProcessedBlock {
+                            block_number: target_block_number,
+                            block: Arc::new(block),
+                            depth_updates,
+                        });
                     }
                     Err(_) => {
                         info!(
@@ -134,6 +187,164 @@ impl BlockProcessor {
             }
         }
     }
+
+    // Records where each transaction of the block lives and appends it to the history of its sender
+    async fn index_transactions(&self, block_number: u64, block: &Block) -> anyhow::Result<()> {
+        let rpc_store = &self.rpc_store_handle.rpc_store;
+
+        let mut indexed_transactions: Vec<(TransactionDigest, AccountPubKey, u64)> = Vec::new();
+        for (position, executed_transaction) in block.transactions.iter().enumerate() {
+            let signed_transaction = &executed_transaction.signed_transaction;
+            if let (Ok(digest), Ok(sender)) =
+                (signed_transaction.get_transaction_digest(), signed_transaction.get_sender())
+            {
+                indexed_transactions.push((digest, sender, position as u64));
+            }
+        }
+
+        // a replayed transaction is rejected by its nonce, so only its first execution is indexed
+        let existing_locations = rpc_store
+            .transaction_location_store
+            .read_all(indexed_transactions.iter().map(|(digest, _, _)| *digest))
+            .await?;
+        let mut seen_digests = HashSet::new();
+        let new_transactions: Vec<(TransactionDigest, AccountPubKey, u64)> = indexed_transactions
+            .into_iter()
+            .zip(existing_locations)
+            .filter(|((digest, _, _), location)| location.is_none() && seen_digests.insert(*digest))
+            .map(|(indexed_transaction, _)| indexed_transaction)
+            .collect();
+
+        let mut account_transaction_counts: HashMap<AccountPubKey, u64> = HashMap::new();
+        let mut locations = Vec::new();
+        let mut account_transactions = Vec::new();
+        for (digest, sender, position) in new_transactions {
+            let count = match account_transaction_counts.get(&sender) {
+                Some(count) => *count,
+                None => rpc_store
+                    .account_transaction_count_store
+                    .read(sender.clone())
+                    .await?
+                    .unwrap_or(0),
+            };
+            locations.push((digest, TransactionLocation { block_number, position }));
+            account_transactions.push(((sender.clone(), count), digest));
+            account_transaction_counts.insert(sender, count + 1);
+        }
+
+        rpc_store.transaction_location_store.write_all(locations).await?;
+        rpc_store.account_transactions_store.write_all(account_transactions).await?;
+        rpc_store
+            .account_transaction_count_store
+            .write_all(account_transaction_counts)
+            .await?;
+        Ok(())
+    }
+
+    // Diffs the persisted orderbook depths against the ones last published to subscribers
+    async fn collect_depth_updates(
+        &self,
+        orderbook_depths: &mut HashMap<String, OrderbookDepth>,
+        block_number: u64,
+    ) -> Vec<DepthUpdate> {
+        // the diffs are taken against the last published depths, so skipping blocks without subscribers is safe
+        if self.tx_processed_blocks.receiver_count() == 0 {
+            return Vec::new();
+        }
+        let latest_orderbook_depths = self
+            .rpc_store_handle
+            .rpc_store
+            .latest_orderbook_depth_store
+            .iter(None)
+            .await;
+
+        let mut depth_updates = Vec::new();
+        for (orderbook, orderbook_depth) in latest_orderbook_depths {
+            let previous_depth = orderbook_depths.remove(&orderbook).unwrap_or_default();
+            let depth_diff = orderbook_depth.diff(&previous_depth);
+            if !depth_diff.is_empty() {
+                depth_updates.push(DepthUpdate {
+                    orderbook: orderbook.clone(),
+                    block_number,
+                    is_snapshot: false,
+                    bids: depth_diff.bids,
+                    asks: depth_diff.asks,
+                });
+            }
+            orderbook_depths.insert(orderbook, orderbook_depth);
+        }
+        depth_updates
+    }
+
+    // Restores the controllers from the most recent catchup state advertised by the validator
+    // whose manifest root matches the root published in the block info of its block
+    async fn fast_sync(&self, grpc_client: &mut ValidatorGrpcClient<Channel>) -> anyhow::Result<()> {
+        let list_response = grpc_client
+            .list_catchup_states(ListCatchupStatesRequest {})
+            .await?
+            .into_inner();
+        if !list_response.successful {
+            bail!("Catchup state list request returned unsuccessful");
+        }
+
+        // the newest catchup state can be ahead of the block info, so fall back to older ones
+        for serialized_manifest in list_response.serialized_manifests.iter().rev() {
+            let manifest: CatchupManifest = bincode::deserialize(serialized_manifest)?;
+            let block_number = manifest.block_number;
+
+            let block_info_response = match grpc_client.get_block_info(BlockInfoRequest { block_number }).await {
+                Ok(response) => response.into_inner(),
+                Err(_) => continue,
+            };
+            let block_info: BlockInfo = bincode::deserialize(&block_info_response.serialized_block_info)?;
+            if block_info.block_digest != manifest.block_digest
+                || block_info.catchup_state_root != Some(manifest.root())
+            {
+                bail!("Catchup manifest at block {} does not match its block info", block_number);
+            }
+
+            let catchup_state = Self::fetch_catchup_state(grpc_client, block_number).await?;
+            if catchup_state.manifest != manifest {
+                bail!("Catchup state at block {} does not match its manifest", block_number);
+            }
+            // restoring verifies every controller state against the manifest roots
+            self.controller_router
+                .lock()
+                .unwrap()
+                .restore_catchup_state(&catchup_state)?;
+            self.last_block_number.store(block_number, Ordering::SeqCst);
+            info!("Fast-synced to catchup state at block {}", block_number);
+            return Ok(());
+        }
+        Err(anyhow!("No catchup state with a published block info is available"))
+    }
+
+    async fn fetch_catchup_state(
+        grpc_client: &mut ValidatorGrpcClient<Channel>,
+        block_number: u64,
+    ) -> anyhow::Result<CatchupState> {
+        let mut chunks = grpc_client
+            .get_catchup_state(CatchupStateRequest { block_number })
+            .await?
+            .into_inner();
+
+        let mut serialized_catchup_state = Vec::new();
+        let mut received_chunks = 0;
+        let mut total_chunks = 0;
+        while let Some(chunk) = chunks.message().await? {
+            if chunk.block_number != block_number || chunk.chunk_index != received_chunks {
+                bail!("Received an out of order chunk for the catchup state at block {}", block_number);
+            }
+            serialized_catchup_state.extend_from_slice(&chunk.data);
+            received_chunks += 1;
+            total_chunks = chunk.total_chunks;
+        }
+        if received_chunks == 0 || received_chunks != total_chunks {
+            bail!("Catchup state at block {} was truncated", block_number);
+        }
+
+        Ok(bincode::deserialize(&serialized_catchup_state)?)
+    }
 }
 
 #[cfg(test)]
@@ -147,7 +358,11 @@ mod test_json_rpc_listener {
     use crate::validator::{server::HandledTransaction, state::ValidatorState};
     use fermi_types::store::RPCStore;
     use fermi_types::utils;
-    use fermi_types::{account::AccountKeyPair, crypto::KeypairTraits, transaction::SignedTransaction};
+    use fermi_types::{
+        account::AccountKeyPair,
+        crypto::KeypairTraits,
+        transaction::{ExecutedTransaction, SignedTransaction},
+    };
     // mysten
     use fastcrypto::DIGEST_LEN;
     use narwhal_types::{Certificate, CertificateDigest};
@@ -212,6 +427,65 @@ mod test_json_rpc_listener {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_index_transactions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let rpc_store_handle = Arc::new(RPCStoreHandle {
+            rpc_store: RPCStore::reopen(temp_dir.path().join(JSONRPC_DB_NAME)),
+        });
+        let block_processor = BlockProcessor {
+            controller_router: Arc::new(Mutex::new(ControllerRouter::default())),
+            last_block_number: Arc::new(AtomicU64::new(0)),
+            grpc_addr: utils::new_network_address(),
+            rpc_store_handle: Arc::clone(&rpc_store_handle),
+            tx_processed_blocks: broadcast::channel(1).0,
+        };
+
+        let executed_transaction = |amount: u64| ExecutedTransaction {
+            signed_transaction: get_signed_transaction([0; 32], [1; 32], amount),
+            events: Vec::new(),
+            result: Ok(()),
+        };
+        let first_block = Block {
+            block_certificate: Certificate::default(),
+            transactions: vec![executed_transaction(100), executed_transaction(200)],
+        };
+        // the replayed transaction keeps the location of its first execution
+        let second_block = Block {
+            block_certificate: Certificate::default(),
+            transactions: vec![executed_transaction(100), executed_transaction(300)],
+        };
+        block_processor.index_transactions(1, &first_block).await.unwrap();
+        block_processor.index_transactions(2, &second_block).await.unwrap();
+
+        let rpc_store = &rpc_store_handle.rpc_store;
+        let sender = first_block.transactions[0].signed_transaction.get_sender().unwrap();
+        assert_eq!(
+            rpc_store.account_transaction_count_store.read(sender.clone()).await.unwrap(),
+            Some(3)
+        );
+
+        let expected_locations = [(&first_block, 1, 0), (&first_block, 1, 1), (&second_block, 2, 1)];
+        for (index, (block, block_number, position)) in expected_locations.into_iter().enumerate() {
+            let digest = block.transactions[position as usize]
+                .signed_transaction
+                .get_transaction_digest()
+                .unwrap();
+            assert_eq!(
+                rpc_store
+                    .account_transactions_store
+                    .read((sender.clone(), index as u64))
+                    .await
+                    .unwrap(),
+                Some(digest)
+            );
+            assert_eq!(
+                rpc_store.transaction_location_store.read(digest).await.unwrap(),
+                Some(TransactionLocation { block_number, position })
+            );
+        }
+    }
+
     #[ignore]
     #[tokio::test]
     async fn test_block_listener() {
@@ -230,7 +504,7 @@ mod test_json_rpc_listener {
             relayer_address.clone(),
             Arc::clone(&validator_state),
             vec![utils::new_network_address()],
-            tx_reconfigure_consensus,
+            tx_reconfigure_consensus.clone(),
         );
 
         validator_server.spawn().await.unwrap();
@@ -239,8 +513,12 @@ mod test_json_rpc_listener {
         let last_block_number = Arc::new(AtomicU64::new(0));
 
         let (tx_narwhal_to_post_process, rx_narwhal_to_post_process) = mpsc::channel(1_000);
-        let _post_process_handles =
-            ValidatorPostProcessor::spawn(rx_narwhal_to_post_process, Arc::clone(&validator_state)).unwrap();
+        let _post_process_handles = ValidatorPostProcessor::spawn(
+            rx_narwhal_to_post_process,
+            Arc::clone(&validator_state),
+            tx_reconfigure_consensus,
+        )
+        .unwrap();
 
         let rpc_store_handle = Arc::new(RPCStoreHandle {
             rpc_store: RPCStore::reopen(json_rpc_db_dir),
@@ -251,6 +529,7 @@ mod test_json_rpc_listener {
             Arc::clone(&last_block_number),
             rpc_store_handle,
             relayer_address,
+            broadcast::channel(1).0,
         );
 
         let n_blocks = 200;
@@ -1,12 +1,20 @@
 // fermi
+use fermi_controller::event_registry::EventRegistry;
 use fermi_types::{
-    block::{Block, BlockInfo},
-    json_rpc::{BlockInfoReply, BlockReply},
-    proto::{BlockInfoRequest, LatestBlockInfoRequest, ValidatorGrpcClient},
-    transaction::{deserialize_protobuf, BlockRequest, SignedTransaction},
+    account::AccountPubKey,
+    block::{Block, BlockInfo, BlockNumber},
+    crypto::ToFromBytes,
+    json_rpc::{AccountTransactionsReply, BlockInfoReply, BlockReply, TransactionReply},
+    proto::{BlockInfoRequest, LatestBlockInfoRequest, SubmitTransactionAndWaitRequest, ValidatorGrpcClient},
+    store::{RPCStoreHandle, TransactionLocation},
+    transaction::{
+        deserialize_protobuf, BlockRequest, ExecutedTransaction, QueriedTransaction, SignedTransaction,
+        TransactionDigest,
+    },
     utils,
 };
 // mysten
+use fastcrypto::DIGEST_LEN;
 use sui_json_rpc::SuiRpcModule;
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
@@ -14,6 +22,7 @@ use sui_open_rpc_macros::open_rpc;
 use jsonrpsee::core::{async_trait, Error, RpcResult};
 use jsonrpsee::RpcModule;
 use jsonrpsee_proc_macros::rpc;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
 use tonic::transport::Channel;
 
@@ -27,29 +36,77 @@ use tonic::transport::Channel;
 pub trait Main {
     #[method(name = "submitTransaction")]
     async fn submit_transaction(&self, signed_transaction_bytes: String) -> RpcResult<String>;
+    #[method(name = "submitTransactionAndWait")]
+    async fn submit_transaction_and_wait(
+        &self,
+        signed_transaction_bytes: String,
+        timeout_in_ms: u64,
+    ) -> RpcResult<TransactionReply>;
     #[method(name = "getBlock")]
     async fn get_block(&self, block_number: u64) -> RpcResult<BlockReply>;
     #[method(name = "getBlockInfo")]
     async fn get_block_info(&self, block_number: u64) -> RpcResult<BlockInfoReply>;
     #[method(name = "getLatestBlockInfo")]
     async fn get_latest_block_info(&self) -> RpcResult<BlockInfoReply>;
+    #[method(name = "getTransaction")]
+    async fn get_transaction(&self, transaction_id: String) -> RpcResult<TransactionReply>;
+    #[method(name = "getAccountTransactions")]
+    async fn get_account_transactions(
+        &self,
+        account: String,
+        offset: u64,
+        limit: u64,
+    ) -> RpcResult<AccountTransactionsReply>;
 }
 
+/// Maximum number of transactions returned by a single getAccountTransactions call
+pub const MAX_ACCOUNT_TRANSACTIONS_PAGE: u64 = 100;
+
 // The JSONRPCService struct will implement the RPC server
 // To do so, it must implement the trait `{TraitName}Server`
 // this trait is generated by the rpc method above
 // TODO -  - use RWLock
 pub struct JSONRPCService {
     grpc_client: Mutex<ValidatorGrpcClient<Channel>>,
+    rpc_store_handle: Arc<RPCStoreHandle>,
+    event_registry: EventRegistry,
 }
 
 impl JSONRPCService {
     #[allow(clippy::new_without_default)]
-    pub fn new(grpc_client: ValidatorGrpcClient<Channel>) -> Self {
+    pub fn new(grpc_client: ValidatorGrpcClient<Channel>, rpc_store_handle: Arc<RPCStoreHandle>) -> Self {
         Self {
             grpc_client: Mutex::new(grpc_client),
+            rpc_store_handle,
+            event_registry: EventRegistry::new(),
         }
     }
+
+    async fn fetch_block(&self, block_number: BlockNumber) -> RpcResult<Block> {
+        let request = BlockRequest { block_number };
+        let block_response = self
+            .grpc_client
+            .lock()
+            .await
+            .get_block(request)
+            .await
+            .map_err(|_| Error::Custom("Failed to fetch block from grpc".to_string()))?;
+
+        bincode::deserialize(&block_response.into_inner().serialized_block)
+            .map_err(|_| Error::Custom("Failed to decode block".to_string()))
+    }
+
+    fn get_transaction_reply(&self, block: &Block, location: TransactionLocation) -> RpcResult<TransactionReply> {
+        let executed_transaction = block
+            .transactions
+            .get(location.position as usize)
+            .ok_or_else(|| Error::Custom("Failed to find transaction in block".to_string()))?;
+        Ok(TransactionReply {
+            block_number: location.block_number,
+            position: location.position,
+            transaction: QueriedTransaction::new(executed_transaction.clone(), &self.event_registry),
+        })
+    }
 }
 
 #[async_trait]
@@ -70,19 +127,40 @@ impl MainServer for JSONRPCService {
         Ok("Success".to_string())
     }
 
-    async fn get_block(&self, block_number: u64) -> RpcResult<BlockReply> {
-        let request = BlockRequest { block_number };
-        let block_response = self
-            .grpc_client
-            .lock()
-            .await
-            .get_block(request)
+    async fn submit_transaction_and_wait(
+        &self,
+        signed_transaction_hex: String,
+        timeout_in_ms: u64,
+    ) -> RpcResult<TransactionReply> {
+        let signed_transaction_bytes: Vec<u8> = utils::decode_bytes_hex(&signed_transaction_hex)
+            .map_err(|_| Error::Custom("Invalid transaction hex".to_string()))?;
+
+        let signed_transaction: SignedTransaction = deserialize_protobuf(&signed_transaction_bytes)
+            .map_err(|_| Error::Custom("Invalid transaction bytes".to_string()))?;
+
+        // the wait can be long, so the shared client is cloned rather than held locked
+        let mut grpc_client = self.grpc_client.lock().await.clone();
+        let response = grpc_client
+            .submit_transaction_and_wait(SubmitTransactionAndWaitRequest {
+                signed_transaction: Some(signed_transaction),
+                timeout_in_ms,
+            })
             .await
-            .map_err(|_| Error::Custom("Failed to fetch block from grpc".to_string()))?;
+            .map_err(|e| Error::Custom(e.message().to_string()))?
+            .into_inner();
 
-        let block: Block = bincode::deserialize(&block_response.into_inner().serialized_block)
-            .map_err(|_| Error::Custom("Failed to decode block".to_string()))?;
-        Ok(BlockReply::from(block))
+        let executed_transaction: ExecutedTransaction = bincode::deserialize(&response.serialized_executed_transaction)
+            .map_err(|_| Error::Custom("Failed to decode executed transaction".to_string()))?;
+        Ok(TransactionReply {
+            block_number: response.block_number,
+            position: response.position,
+            transaction: QueriedTransaction::new(executed_transaction, &self.event_registry),
+        })
+    }
+
+    async fn get_block(&self, block_number: u64) -> RpcResult<BlockReply> {
+        let block = self.fetch_block(block_number).await?;
+        Ok(BlockReply::new(block, &self.event_registry))
     }
 
     async fn get_block_info(&self, block_number: u64) -> RpcResult<BlockInfoReply> {
@@ -95,9 +173,13 @@ impl MainServer for JSONRPCService {
             .await
             .map_err(|_| Error::Custom("Failed to fetch block info from grpc".to_string()))?;
 
-        let block_info: BlockInfo = bincode::deserialize(&block_info_response.into_inner().serialized_block_info)
+        let block_info_response = block_info_response.into_inner();
+        let block_info: BlockInfo = bincode::deserialize(&block_info_response.serialized_block_info)
             .map_err(|_| Error::Custom("Failed to decode block".to_string()))?;
-        Ok(BlockInfoReply::from(block_info))
+        Ok(BlockInfoReply {
+            halted: block_info_response.halted,
+            ..BlockInfoReply::from(block_info)
+        })
     }
 
     async fn get_latest_block_info(&self) -> RpcResult<BlockInfoReply> {
@@ -111,10 +193,80 @@ impl MainServer for JSONRPCService {
             .await
             .map_err(|_| Error::Custom("Failed to fetch block info from grpc".to_string()))?;
 
-        let block_info: BlockInfo =
-            bincode::deserialize(&latest_block_info_response.into_inner().serialized_block_info)
-                .map_err(|_| Error::Custom("Failed to decode block".to_string()))?;
-        Ok(BlockInfoReply::from(block_info))
+        let latest_block_info_response = latest_block_info_response.into_inner();
+        let block_info: BlockInfo = bincode::deserialize(&latest_block_info_response.serialized_block_info)
+            .map_err(|_| Error::Custom("Failed to decode block".to_string()))?;
+        Ok(BlockInfoReply {
+            halted: latest_block_info_response.halted,
+            ..BlockInfoReply::from(block_info)
+        })
+    }
+
+    async fn get_transaction(&self, transaction_id: String) -> RpcResult<TransactionReply> {
+        let digest_bytes: [u8; DIGEST_LEN] = utils::decode_bytes_hex(&transaction_id)
+            .map_err(|_| Error::Custom("Invalid transaction id".to_string()))?;
+
+        let location = self
+            .rpc_store_handle
+            .rpc_store
+            .transaction_location_store
+            .read(TransactionDigest::new(digest_bytes))
+            .await
+            .map_err(|_| Error::Custom("Failed to read transaction index".to_string()))?
+            .ok_or_else(|| Error::Custom("Failed to find transaction".to_string()))?;
+
+        let block = self.fetch_block(location.block_number).await?;
+        self.get_transaction_reply(&block, location)
+    }
+
+    async fn get_account_transactions(
+        &self,
+        account: String,
+        offset: u64,
+        limit: u64,
+    ) -> RpcResult<AccountTransactionsReply> {
+        let account_bytes: Vec<u8> = utils::decode_bytes_hex(&account)?;
+        let account: AccountPubKey = AccountPubKey::from_bytes(account_bytes.as_slice())
+            .map_err(|_| Error::Custom("Failed to decode account".to_string()))?;
+        let rpc_store = &self.rpc_store_handle.rpc_store;
+
+        let total = rpc_store
+            .account_transaction_count_store
+            .read(account.clone())
+            .await
+            .map_err(|_| Error::Custom("Failed to read transaction index".to_string()))?
+            .unwrap_or(0);
+        let end = total.min(offset.saturating_add(limit.min(MAX_ACCOUNT_TRANSACTIONS_PAGE)));
+        if offset >= end {
+            return Ok(AccountTransactionsReply {
+                transactions: Vec::new(),
+                total,
+            });
+        }
+
+        let digests = rpc_store
+            .account_transactions_store
+            .read_all((offset..end).map(|index| (account.clone(), index)))
+            .await
+            .map_err(|_| Error::Custom("Failed to read transaction index".to_string()))?;
+        let locations = rpc_store
+            .transaction_location_store
+            .read_all(digests.into_iter().flatten())
+            .await
+            .map_err(|_| Error::Custom("Failed to read transaction index".to_string()))?;
+
+        // transactions of the same block are served from a single block fetch
+        let mut blocks: HashMap<BlockNumber, Block> = HashMap::new();
+        let mut transactions = Vec::new();
+        for location in locations.into_iter().flatten() {
+            if !blocks.contains_key(&location.block_number) {
+                let block = self.fetch_block(location.block_number).await?;
+                blocks.insert(location.block_number, block);
+            }
+            transactions.push(self.get_transaction_reply(&blocks[&location.block_number], location)?);
+        }
+
+        Ok(AccountTransactionsReply { transactions, total })
     }
 }
 
@@ -1,6 +1,12 @@
 // fermi
 use crate::client::endpoint_from_multiaddr;
-use crate::{json_rpc::processor::BlockProcessor, validator::genesis_state::ValidatorGenesisState};
+use crate::{
+    json_rpc::{
+        processor::BlockProcessor,
+        subscriptions::{ProcessedBlock, SubscriptionService, SubscriptionsServer, PROCESSED_BLOCK_CHANNEL_CAPACITY},
+    },
+    validator::genesis_state::ValidatorGenesisState,
+};
 use fermi_controller::router::ControllerRouter;
 use fermi_types::{
     proto::ValidatorGrpcClient,
@@ -9,13 +15,14 @@ use fermi_types::{
 // mysten
 use sui_json_rpc::SuiRpcModule;
 // external
-use jsonrpsee::{http_server::HttpServerBuilder, RpcModule};
+use jsonrpsee::{http_server::HttpServerBuilder, ws_server::WsServerBuilder, RpcModule};
 use multiaddr::Multiaddr;
 use std::{
+    net::SocketAddr,
     path::PathBuf,
     sync::{atomic::AtomicU64, Arc, Mutex},
 };
-use tokio::task::JoinHandle;
+use tokio::{sync::broadcast, task::JoinHandle};
 use tracing::info;
 
 // INTERFACE
@@ -24,8 +31,10 @@ pub struct JSONServiceSpawner {
     controller_router: Arc<Mutex<ControllerRouter>>,
     grpc_addr: Multiaddr,
     jsonrpc_addr: Multiaddr,
+    websocket_addr: SocketAddr,
     latest_listened_block: Arc<AtomicU64>,
     rpc_store_handle: Arc<RPCStoreHandle>,
+    tx_processed_blocks: broadcast::Sender<ProcessedBlock>,
 }
 
 impl JSONServiceSpawner {
@@ -33,19 +42,23 @@ impl JSONServiceSpawner {
         genesis_state: ValidatorGenesisState,
         grpc_addr: Multiaddr,
         jsonrpc_addr: Multiaddr,
+        websocket_addr: SocketAddr,
         json_rpc_db: PathBuf,
     ) -> Self {
         let controller_router = Arc::new(Mutex::new(genesis_state.controller_router().clone()));
         let rpc_store_handle = Arc::new(RPCStoreHandle {
             rpc_store: RPCStore::reopen(json_rpc_db),
         });
+        let (tx_processed_blocks, _rx_processed_blocks) = broadcast::channel(PROCESSED_BLOCK_CHANNEL_CAPACITY);
 
         Self {
             controller_router,
             grpc_addr,
             jsonrpc_addr,
+            websocket_addr,
             latest_listened_block: Arc::new(AtomicU64::new(0)),
             rpc_store_handle,
+            tx_processed_blocks,
         }
     }
 
@@ -54,19 +67,33 @@ impl JSONServiceSpawner {
         rpc_store_handle: Arc<RPCStoreHandle>,
         grpc_addr: &Multiaddr,
     ) -> RpcModule<()> {
-        let mut module = ControllerRouter::generate_rpc_module(controller_router, rpc_store_handle);
+        let mut module = ControllerRouter::generate_rpc_module(controller_router, Arc::clone(&rpc_store_handle));
         let grpc_endpoint = endpoint_from_multiaddr(grpc_addr).unwrap();
         let grpc_client = ValidatorGrpcClient::connect(grpc_endpoint.endpoint().clone())
             .await
             .unwrap();
 
         module
-            .merge(crate::json_rpc::server::JSONRPCService::new(grpc_client).rpc())
+            .merge(crate::json_rpc::server::JSONRPCService::new(grpc_client, rpc_store_handle).rpc())
             .unwrap();
 
         module
     }
 
+    pub async fn construct_subscription_module(
+        rpc_store_handle: Arc<RPCStoreHandle>,
+        last_block_number: Arc<AtomicU64>,
+        tx_processed_blocks: broadcast::Sender<ProcessedBlock>,
+        grpc_addr: &Multiaddr,
+    ) -> RpcModule<SubscriptionService> {
+        let grpc_endpoint = endpoint_from_multiaddr(grpc_addr).unwrap();
+        let grpc_client = ValidatorGrpcClient::connect(grpc_endpoint.endpoint().clone())
+            .await
+            .unwrap();
+
+        SubscriptionService::new(grpc_client, rpc_store_handle, last_block_number, tx_processed_blocks).into_rpc()
+    }
+
     pub async fn spawn_jsonrpc_service(&mut self) -> anyhow::Result<Vec<JoinHandle<()>>> {
         let mut results = Vec::new();
         // block listener
@@ -75,6 +102,7 @@ impl JSONServiceSpawner {
             Arc::clone(&self.latest_listened_block),
             Arc::clone(&self.rpc_store_handle),
             self.grpc_addr.clone(),
+            self.tx_processed_blocks.clone(),
         );
 
         // launch block listener task
@@ -89,6 +117,8 @@ impl JSONServiceSpawner {
         )
         .await;
 
+        // the websocket serves every method along with the subscriptions
+        let mut websocket_module = module.clone();
         let socket_addr = crate::multiaddr::to_socket_addr(&self.jsonrpc_addr).unwrap();
 
         // TODO - the code below is an ugly hack to transport ServerHandle into a JoinHandle<()>
@@ -101,25 +131,56 @@ impl JSONServiceSpawner {
         });
         // spawn rpc server
         results.push(handle);
+
+        info!("Spawning a JSON RPC websocket with address = {}", self.websocket_addr);
+        websocket_module
+            .merge(
+                Self::construct_subscription_module(
+                    Arc::clone(&self.rpc_store_handle),
+                    Arc::clone(&self.latest_listened_block),
+                    self.tx_processed_blocks.clone(),
+                    &self.grpc_addr,
+                )
+                .await,
+            )
+            .unwrap();
+
+        let websocket_addr = self.websocket_addr;
+        let websocket_handle = tokio::spawn(async move {
+            let server = WsServerBuilder::default().build(&websocket_addr).await.unwrap();
+            let _handle = server.start(websocket_module).unwrap();
+            tokio::time::sleep(tokio::time::Duration::from_secs(10_000_000_000_000_000)).await;
+        });
+        results.push(websocket_handle);
         Ok(results)
     }
 }
 
 #[cfg(test)]
 mod test_json_rpc_server {
+    use fermi_controller::bank::proto::create_payment_transaction;
+    use fermi_controller::bank::types::AssetResponse;
     use fermi_controller::futures::test::futures_tests::FuturesControllerTester;
     use fermi_controller::futures::types::{MarketResponse, MarketplaceResponse, MarketplaceUserInfoResponse};
     use fermi_controller::router::ControllerRouter;
+    use fermi_controller::spot::{
+        proto::LimitOrderRequest,
+        types::{SpotOrder, SpotOrderbookResponse},
+    };
+    use fermi_controller::utils::engine::order_book::{get_first_order_id, OrderBookWrapper};
     use fermi_controller::ControllerTestBed;
+    use fermi_types::block::BlockDigest;
     use fermi_types::crypto::KeypairTraits;
-    use fermi_types::order_book::{OrderSide, OrderbookDepth};
+    use fermi_types::order_book::{OrderSide, OrderbookDepth, TimeInForce, Trade, TradeRecord};
     use fermi_types::{
         account::{account_test_functions::generate_keypair_vec, AccountPubKey},
         crypto::ToFromBytes,
         utils,
     };
 
+    use crate::client::NonceTracker;
     use crate::json_rpc::spawner::{RPCStore, RPCStoreHandle};
+    use fastcrypto::DIGEST_LEN;
     use jsonrpsee::http_client::HttpClientBuilder;
     use jsonrpsee::http_server::{HttpServerBuilder, HttpServerHandle};
     use jsonrpsee::rpc_params;
@@ -186,6 +247,134 @@ mod test_json_rpc_server {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_accrued_fees() -> anyhow::Result<()> {
+        let intitializer = generate_keypair_vec([0; 32]).pop().unwrap();
+        let controller_router = local_controller_router(intitializer.public());
+        controller_router
+            .lock()
+            .unwrap()
+            .bank_controller
+            .lock()
+            .unwrap()
+            .collect_fee(intitializer.public(), 0, 1_000)
+            .unwrap();
+
+        let rpc_temp_dir = tempfile::tempdir().unwrap();
+        let rpc_store_handle = Arc::new(RPCStoreHandle {
+            rpc_store: RPCStore::reopen(rpc_temp_dir),
+        });
+        let (server_addr, _handle) = run_server(controller_router, rpc_store_handle).await?;
+        let url = format!("http://{}", server_addr);
+        let client = HttpClientBuilder::default().build(url)?;
+
+        let params = rpc_params![/* asset */ 0];
+        let response: Result<u64, _> = client.request("tenex_getAccruedFees", params).await;
+        assert!(response.unwrap() == 1_000);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_asset() -> anyhow::Result<()> {
+        let intitializer = generate_keypair_vec([0; 32]).pop().unwrap();
+        let controller_router = local_controller_router(intitializer.public());
+
+        let rpc_temp_dir = tempfile::tempdir().unwrap();
+        let rpc_store_handle = Arc::new(RPCStoreHandle {
+            rpc_store: RPCStore::reopen(rpc_temp_dir),
+        });
+        let (server_addr, _handle) = run_server(controller_router, rpc_store_handle).await?;
+        let url = format!("http://{}", server_addr);
+        let client = HttpClientBuilder::default().build(url)?;
+
+        let params = rpc_params![/* asset */ 0];
+        let response: AssetResponse = client.request("tenex_getAsset", params).await?;
+        assert_eq!(response.asset_id, 0);
+        assert_eq!(response.owner, utils::encode_bytes_hex(intitializer.public().as_bytes().to_vec()));
+        assert_eq!(response.total_supply, 10_000_000_000_000_000);
+        assert!(!response.is_frozen);
+
+        let params = rpc_params![/* asset */ 100];
+        let response: Result<AssetResponse, _> = client.request("tenex_getAsset", params).await;
+        assert!(response.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_next_nonce() -> anyhow::Result<()> {
+        let intitializer = generate_keypair_vec([0; 32]).pop().unwrap();
+        let controller_router = local_controller_router(intitializer.public());
+        controller_router
+            .lock()
+            .unwrap()
+            .bank_controller
+            .lock()
+            .unwrap()
+            .increment_nonce(intitializer.public());
+
+        let rpc_temp_dir = tempfile::tempdir().unwrap();
+        let rpc_store_handle = Arc::new(RPCStoreHandle {
+            rpc_store: RPCStore::reopen(rpc_temp_dir),
+        });
+        let (server_addr, _handle) = run_server(controller_router, rpc_store_handle).await?;
+        let url = format!("http://{}", server_addr);
+        let client = HttpClientBuilder::default().build(url)?;
+
+        let params = rpc_params![/* account */ utils::encode_bytes_hex(intitializer.public().as_bytes().to_vec())];
+        let response: u64 = client.request("tenex_getNextNonce", params).await?;
+        assert_eq!(response, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nonce_tracker_sequences_transactions() -> anyhow::Result<()> {
+        let mut keypairs = generate_keypair_vec([0; 32]);
+        let intitializer = keypairs.pop().unwrap();
+        let receiver = keypairs.pop().unwrap();
+        let controller_router = local_controller_router(intitializer.public());
+
+        let rpc_temp_dir = tempfile::tempdir().unwrap();
+        let rpc_store_handle = Arc::new(RPCStoreHandle {
+            rpc_store: RPCStore::reopen(rpc_temp_dir),
+        });
+        let (server_addr, _handle) = run_server(Arc::clone(&controller_router), rpc_store_handle).await?;
+        let url = format!("http://{}", server_addr);
+
+        let mut nonce_tracker = NonceTracker::default();
+        assert_eq!(nonce_tracker.sync(&url, intitializer.public()).await?, 0);
+
+        // every transaction from the account is signed with the tracked nonce and executes
+        for amount in 1..=3 {
+            let mut transaction = create_payment_transaction(
+                intitializer.public(),
+                BlockDigest::new([0; DIGEST_LEN]),
+                receiver.public(),
+                0,
+                amount,
+            );
+            transaction.set_nonce(nonce_tracker.next_nonce(intitializer.public()));
+            controller_router
+                .lock()
+                .unwrap()
+                .handle_consensus_transaction(&transaction)?;
+        }
+        assert_eq!(
+            controller_router
+                .lock()
+                .unwrap()
+                .bank_controller
+                .lock()
+                .unwrap()
+                .get_balance(receiver.public(), 0)?,
+            6
+        );
+
+        // resyncing continues from the nonce the validator expects next
+        assert_eq!(nonce_tracker.sync(&url, intitializer.public()).await?, 3);
+        assert_eq!(nonce_tracker.next_nonce(intitializer.public()), 3);
+        Ok(())
+    }
+
     // TEST FUTURES ENDPOINTS
 
     #[tokio::test]
@@ -352,4 +541,99 @@ mod test_json_rpc_server {
         assert!(response.asks[0].quantity == quantity);
         Ok(())
     }
+
+    // TEST SPOT ENDPOINTS
+
+    #[tokio::test]
+    async fn test_get_spot_endpoints() -> anyhow::Result<()> {
+        let intitializer = generate_keypair_vec([0; 32]).pop().unwrap();
+        let controller_router = local_controller_router(intitializer.public());
+        let (base_asset_id, quote_asset_id, price) = (0, 1, 1_000);
+        {
+            let controller_lock = controller_router.lock().unwrap();
+            controller_lock
+                .bank_controller
+                .lock()
+                .unwrap()
+                .create_asset(intitializer.public())
+                .unwrap();
+            let mut spot_controller = controller_lock.spot_controller.lock().unwrap();
+            spot_controller
+                .create_orderbook(intitializer.public(), base_asset_id, quote_asset_id)
+                .unwrap();
+            let orderbook = spot_controller.get_orderbook(base_asset_id, quote_asset_id).unwrap();
+            // rest two asks and cross the first one
+            for (side, price, quantity) in [
+                (OrderSide::Ask, price, 10),
+                (OrderSide::Ask, price + 1, 10),
+                (OrderSide::Bid, price, 4),
+            ] {
+                let request = LimitOrderRequest::new(
+                    base_asset_id,
+                    quote_asset_id,
+                    side as u64,
+                    price,
+                    quantity,
+                    0,
+                    TimeInForce::GoodTilCancelled as u64,
+                    false,
+                );
+                orderbook.place_limit_order(intitializer.public(), &request).unwrap();
+            }
+        }
+
+        let rpc_temp_dir = tempfile::tempdir().unwrap();
+        let rpc_store_handle = Arc::new(RPCStoreHandle {
+            rpc_store: RPCStore::reopen(rpc_temp_dir),
+        });
+        controller_router
+            .lock()
+            .unwrap()
+            .non_critical_process_end_of_block(&rpc_store_handle.rpc_store, 1)
+            .unwrap();
+        let (server_addr, _handle) = run_server(controller_router, rpc_store_handle).await?;
+        let url = format!("http://{}", server_addr);
+        let client = HttpClientBuilder::default().build(url)?;
+
+        let response: Vec<SpotOrderbookResponse> = client.request("tenex_getSpotOrderbooks", None).await?;
+        assert_eq!(response.len(), 1);
+        assert_eq!((response[0].base_asset_id, response[0].quote_asset_id), (base_asset_id, quote_asset_id));
+        assert_eq!(response[0].admin, utils::encode_bytes_hex(intitializer.public().as_bytes().to_vec()));
+
+        let params = rpc_params![base_asset_id, quote_asset_id, /* depth */ 1];
+        let response: OrderbookDepth = client.request("tenex_getSpotOrderbookDepth", params).await?;
+        assert!(response.bids.is_empty());
+        assert_eq!((response.asks.len(), response.asks[0].price, response.asks[0].quantity), (1, price, 6));
+
+        let params = rpc_params![/* account */ utils::encode_bytes_hex(intitializer.public().as_bytes().to_vec())];
+        let response: Vec<SpotOrder> = client.request("tenex_getSpotOpenOrders", params).await?;
+        let first_order_id = get_first_order_id(base_asset_id, quote_asset_id);
+        assert_eq!(
+            response.iter().map(|order| (order.order_id, order.quantity)).collect::<Vec<_>>(),
+            vec![(first_order_id, 6), (first_order_id + 1, 10)]
+        );
+
+        let params = rpc_params![base_asset_id, quote_asset_id, /* limit */ 10];
+        let response: Vec<Trade> = client.request("tenex_getSpotRecentTrades", params).await?;
+        assert_eq!(response.len(), 1);
+        assert_eq!(
+            (response[0].taker_side, response[0].price, response[0].quantity),
+            (OrderSide::Bid as u64, price, 4)
+        );
+
+        let params = rpc_params![base_asset_id, quote_asset_id, /* limit */ 1_000];
+        let response: Result<Vec<Trade>, _> = client.request("tenex_getSpotRecentTrades", params).await;
+        assert!(response.is_err());
+
+        // the tape records the block of each trade
+        let params = rpc_params![base_asset_id, quote_asset_id, /* before_trade_id */ None::<u64>, /* limit */ 10];
+        let response: Vec<TradeRecord> = client.request("tenex_getSpotTradeHistory", params).await?;
+        assert_eq!(response.len(), 1);
+        assert_eq!((response[0].trade_id, response[0].block_number), (1, 1));
+
+        let params = rpc_params![base_asset_id, quote_asset_id, /* before_trade_id */ Some(1), /* limit */ 10];
+        let response: Vec<TradeRecord> = client.request("tenex_getSpotTradeHistory", params).await?;
+        assert!(response.is_empty());
+        Ok(())
+    }
 }
@@ -0,0 +1,436 @@
+// fermi
+use fermi_controller::event_registry::EventRegistry;
+use fermi_types::{
+    block::{Block, BlockNumber},
+    event::QueriedEvent,
+    json_rpc::{BlockNotification, BlockReply, DepthUpdate, EventFilter, EventNotification},
+    order_book::OrderbookDepth,
+    proto::ValidatorGrpcClient,
+    store::RPCStoreHandle,
+    transaction::BlockRequest,
+    utils,
+};
+// external
+use jsonrpsee::{
+    types::error::{ErrorObject, INVALID_PARAMS_CODE},
+    PendingSubscription, SubscriptionSink,
+};
+use jsonrpsee_proc_macros::rpc;
+use serde::Serialize;
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tonic::transport::Channel;
+
+/// Number of processed blocks buffered for subscribers before the slowest ones lag behind
+pub const PROCESSED_BLOCK_CHANNEL_CAPACITY: usize = 1_000;
+
+/// A block replayed by the BlockProcessor along with the orderbook depth changes it caused
+#[derive(Clone)]
+pub struct ProcessedBlock {
+    pub block_number: BlockNumber,
+    pub block: Arc<Block>,
+    pub depth_updates: Vec<DepthUpdate>,
+}
+
+// Subscriptions are only served over the websocket server
+#[rpc(server, client, namespace = "tenex")]
+pub trait Subscriptions {
+    #[subscription(name = "subscribeBlocks", unsubscribe = "unsubscribeBlocks", item = BlockNotification)]
+    fn subscribe_blocks(&self, start_block_number: Option<u64>);
+
+    #[subscription(name = "subscribeEvents", unsubscribe = "unsubscribeEvents", item = EventNotification)]
+    fn subscribe_events(&self, filter: EventFilter, start_block_number: Option<u64>);
+
+    #[subscription(
+        name = "subscribeOrderbookDepth",
+        unsubscribe = "unsubscribeOrderbookDepth",
+        item = DepthUpdate
+    )]
+    fn subscribe_orderbook_depth(&self, orderbook: String);
+}
+
+pub struct SubscriptionService {
+    grpc_client: ValidatorGrpcClient<Channel>,
+    rpc_store_handle: Arc<RPCStoreHandle>,
+    last_block_number: Arc<AtomicU64>,
+    tx_processed_blocks: broadcast::Sender<ProcessedBlock>,
+    event_registry: Arc<EventRegistry>,
+}
+
+impl SubscriptionService {
+    pub fn new(
+        grpc_client: ValidatorGrpcClient<Channel>,
+        rpc_store_handle: Arc<RPCStoreHandle>,
+        last_block_number: Arc<AtomicU64>,
+        tx_processed_blocks: broadcast::Sender<ProcessedBlock>,
+    ) -> Self {
+        Self {
+            grpc_client,
+            rpc_store_handle,
+            last_block_number,
+            tx_processed_blocks,
+            event_registry: Arc::new(EventRegistry::new()),
+        }
+    }
+
+    // Spawns a task which streams the items of every block from start_block_number onwards,
+    // or of every new block when no start is given
+    fn spawn_block_stream<T, F>(&self, pending: PendingSubscription, start_block_number: Option<u64>, to_items: F)
+    where
+        T: Serialize + Send + 'static,
+        F: Fn(BlockNumber, &Block) -> Vec<T> + Send + 'static,
+    {
+        // subscribe before loading the last block number so that no block falls in between
+        let rx_processed_blocks = self.tx_processed_blocks.subscribe();
+        let latest_block_number = self.last_block_number.load(Ordering::SeqCst);
+        let grpc_client = self.grpc_client.clone();
+        if let Some(sink) = pending.accept() {
+            tokio::spawn(Self::stream_blocks(
+                grpc_client,
+                rx_processed_blocks,
+                start_block_number,
+                latest_block_number,
+                sink,
+                to_items,
+            ));
+        }
+    }
+
+    async fn stream_blocks<T, F>(
+        mut grpc_client: ValidatorGrpcClient<Channel>,
+        mut rx_processed_blocks: broadcast::Receiver<ProcessedBlock>,
+        mut next_block_number: Option<BlockNumber>,
+        latest_block_number: BlockNumber,
+        mut sink: SubscriptionSink,
+        to_items: F,
+    ) where
+        T: Serialize,
+        F: Fn(BlockNumber, &Block) -> Vec<T>,
+    {
+        // a resuming subscriber first catches up to the latest processed block
+        if let Some(start_block_number) = next_block_number {
+            let range = start_block_number.max(1)..latest_block_number + 1;
+            if !Self::backfill(&mut grpc_client, range, &mut sink, &to_items).await {
+                return;
+            }
+            next_block_number = Some(start_block_number.max(latest_block_number + 1));
+        }
+
+        loop {
+            let processed_block = match rx_processed_blocks.recv().await {
+                Ok(processed_block) => processed_block,
+                // the skipped blocks are backfilled from the validator once the next block arrives
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            };
+            let start_block_number = next_block_number.unwrap_or(processed_block.block_number);
+            if processed_block.block_number < start_block_number {
+                continue;
+            }
+            let range = start_block_number..processed_block.block_number;
+            if !Self::backfill(&mut grpc_client, range, &mut sink, &to_items).await {
+                return;
+            }
+            let items = to_items(processed_block.block_number, &processed_block.block);
+            if !Self::send_items(&mut sink, items) {
+                return;
+            }
+            next_block_number = Some(processed_block.block_number + 1);
+        }
+    }
+
+    // Returns false once the subscription is closed or a block cannot be fetched
+    async fn backfill<T, F>(
+        grpc_client: &mut ValidatorGrpcClient<Channel>,
+        block_numbers: Range<BlockNumber>,
+        sink: &mut SubscriptionSink,
+        to_items: &F,
+    ) -> bool
+    where
+        T: Serialize,
+        F: Fn(BlockNumber, &Block) -> Vec<T>,
+    {
+        for block_number in block_numbers {
+            let block_response = match grpc_client.get_block(BlockRequest { block_number }).await {
+                Ok(block_response) => block_response.into_inner(),
+                Err(_) => return false,
+            };
+            let block: Block = match bincode::deserialize(&block_response.serialized_block) {
+                Ok(block) => block,
+                Err(_) => return false,
+            };
+            if !Self::send_items(sink, to_items(block_number, &block)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn send_items<T: Serialize>(sink: &mut SubscriptionSink, items: Vec<T>) -> bool {
+        items.iter().all(|item| matches!(sink.send(item), Ok(true)))
+    }
+
+    fn send_depth_snapshot(
+        sink: &mut SubscriptionSink,
+        orderbook: &str,
+        block_number: BlockNumber,
+        orderbook_depth: Option<OrderbookDepth>,
+    ) -> bool {
+        let orderbook_depth = orderbook_depth.unwrap_or_default();
+        let snapshot = DepthUpdate {
+            orderbook: orderbook.to_string(),
+            block_number,
+            is_snapshot: true,
+            bids: orderbook_depth.bids,
+            asks: orderbook_depth.asks,
+        };
+        matches!(sink.send(&snapshot), Ok(true))
+    }
+}
+
+fn event_notifications(
+    filter: &EventFilter,
+    event_registry: &EventRegistry,
+    block_number: BlockNumber,
+    block: &Block,
+) -> Vec<EventNotification> {
+    let mut notifications = Vec::new();
+    for executed_transaction in &block.transactions {
+        let sender = match executed_transaction.signed_transaction.get_sender() {
+            Ok(sender) => utils::encode_bytes_hex(sender),
+            Err(_) => continue,
+        };
+        if filter.account.as_ref().map_or(false, |account| *account != sender) {
+            continue;
+        }
+        let transaction_id = match executed_transaction.signed_transaction.get_transaction_digest() {
+            Ok(transaction_digest) => utils::encode_bytes_hex(transaction_digest.get_array()),
+            Err(_) => continue,
+        };
+        for event in &executed_transaction.events {
+            if filter.controller_id.map_or(true, |id| id == event.controller_id)
+                && filter
+                    .event_type
+                    .map_or(true, |event_type| event_type == event.event_type)
+            {
+                notifications.push(EventNotification {
+                    block_number,
+                    transaction_id: transaction_id.clone(),
+                    sender: sender.clone(),
+                    event: QueriedEvent::new(event, event_registry),
+                });
+            }
+        }
+    }
+    notifications
+}
+
+impl SubscriptionsServer for SubscriptionService {
+    fn subscribe_blocks(&self, pending: PendingSubscription, start_block_number: Option<u64>) {
+        let event_registry = Arc::clone(&self.event_registry);
+        self.spawn_block_stream(pending, start_block_number, move |block_number, block| {
+            vec![BlockNotification {
+                block_number,
+                block: BlockReply::new(block.clone(), event_registry.as_ref()),
+            }]
+        });
+    }
+
+    fn subscribe_events(&self, pending: PendingSubscription, mut filter: EventFilter, start_block_number: Option<u64>) {
+        // accounts are compared in their canonical hex encoding
+        if let Some(account) = filter.account.take() {
+            match utils::decode_bytes_hex::<Vec<u8>>(&account) {
+                Ok(account_bytes) => filter.account = Some(utils::encode_bytes_hex(account_bytes)),
+                Err(_) => {
+                    pending.reject(ErrorObject::owned(
+                        INVALID_PARAMS_CODE,
+                        "Failed to decode account",
+                        None::<()>,
+                    ));
+                    return;
+                }
+            }
+        }
+        let event_registry = Arc::clone(&self.event_registry);
+        self.spawn_block_stream(pending, start_block_number, move |block_number, block| {
+            event_notifications(&filter, &event_registry, block_number, block)
+        });
+    }
+
+    fn subscribe_orderbook_depth(&self, pending: PendingSubscription, orderbook: String) {
+        let mut rx_processed_blocks = self.tx_processed_blocks.subscribe();
+        let rpc_store_handle = Arc::clone(&self.rpc_store_handle);
+        let last_block_number = Arc::clone(&self.last_block_number);
+        let mut sink = match pending.accept() {
+            Some(sink) => sink,
+            None => return,
+        };
+
+        // depth updates carry absolute quantities, so a diff already contained in the snapshot is harmless
+        tokio::spawn(async move {
+            let depth_store = &rpc_store_handle.rpc_store.latest_orderbook_depth_store;
+            let orderbook_depth = depth_store.read(orderbook.clone()).await.ok().flatten();
+            let block_number = last_block_number.load(Ordering::SeqCst);
+            if !Self::send_depth_snapshot(&mut sink, &orderbook, block_number, orderbook_depth) {
+                return;
+            }
+
+            loop {
+                match rx_processed_blocks.recv().await {
+                    Ok(processed_block) => {
+                        let depth_updates = processed_block
+                            .depth_updates
+                            .into_iter()
+                            .filter(|depth_update| depth_update.orderbook == orderbook)
+                            .collect();
+                        if !Self::send_items(&mut sink, depth_updates) {
+                            return;
+                        }
+                    }
+                    // missed diffs are replaced by a fresh snapshot
+                    Err(RecvError::Lagged(_)) => {
+                        let orderbook_depth = depth_store.read(orderbook.clone()).await.ok().flatten();
+                        let block_number = last_block_number.load(Ordering::SeqCst);
+                        if !Self::send_depth_snapshot(&mut sink, &orderbook, block_number, orderbook_depth) {
+                            return;
+                        }
+                    }
+                    Err(RecvError::Closed) => return,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test_subscriptions {
+    use super::*;
+    use crate::client;
+    use fermi_controller::bank::proto::bank_controller_test_functions::generate_signed_test_transaction;
+    use fermi_types::{
+        account::account_test_functions::generate_keypair_vec,
+        order_book::Depth,
+        store::RPCStore,
+        transaction::{ExecutedTransaction, ExecutionEvent},
+    };
+    use jsonrpsee::{
+        ws_client::WsClientBuilder,
+        ws_server::{WsServerBuilder, WsServerHandle},
+    };
+
+    async fn spawn_subscription_server(
+        tx_processed_blocks: broadcast::Sender<ProcessedBlock>,
+    ) -> (String, WsServerHandle, tempfile::TempDir) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let rpc_store_handle = Arc::new(RPCStoreHandle {
+            rpc_store: RPCStore::reopen(temp_dir.path()),
+        });
+        // subscribers which do not resume never reach the validator
+        let grpc_client = ValidatorGrpcClient::new(client::connect_lazy(&utils::new_network_address()).unwrap());
+        let service = SubscriptionService::new(
+            grpc_client,
+            rpc_store_handle,
+            Arc::new(AtomicU64::new(0)),
+            tx_processed_blocks,
+        );
+
+        let server = WsServerBuilder::default().build("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", server.local_addr().unwrap());
+        let handle = server.start(service.into_rpc()).unwrap();
+        (url, handle, temp_dir)
+    }
+
+    fn processed_block(block_number: BlockNumber, depth_updates: Vec<DepthUpdate>) -> ProcessedBlock {
+        let kp_sender = generate_keypair_vec([0; 32]).pop().unwrap();
+        let kp_receiver = generate_keypair_vec([1; 32]).pop().unwrap();
+        let executed_transaction = ExecutedTransaction {
+            signed_transaction: generate_signed_test_transaction(&kp_sender, &kp_receiver, 10, 0),
+            events: vec![ExecutionEvent {
+                controller_id: 0,
+                event_type: 1,
+                event_bytes: Vec::new(),
+            }],
+            result: Ok(()),
+        };
+        ProcessedBlock {
+            block_number,
+            block: Arc::new(Block {
+                block_certificate: Default::default(),
+                transactions: vec![executed_transaction],
+            }),
+            depth_updates,
+        }
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_blocks_events_and_depth_updates() {
+        let (tx_processed_blocks, _) = broadcast::channel(PROCESSED_BLOCK_CHANNEL_CAPACITY);
+        let (url, _handle, _temp_dir) = spawn_subscription_server(tx_processed_blocks.clone()).await;
+        let client = WsClientBuilder::default().build(&url).await.unwrap();
+
+        let mut blocks = client.subscribe_blocks(None).await.unwrap();
+        let mut events = client
+            .subscribe_events(
+                EventFilter {
+                    controller_id: Some(0),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        let mut filtered_events = client
+            .subscribe_events(
+                EventFilter {
+                    event_type: Some(2),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        let mut depth_updates = client.subscribe_orderbook_depth("BTC-USD".to_string()).await.unwrap();
+
+        // the depth subscription opens with a snapshot of the book
+        let snapshot = depth_updates.next().await.unwrap().unwrap();
+        assert!(snapshot.is_snapshot);
+        assert!(snapshot.bids.is_empty() && snapshot.asks.is_empty());
+
+        let depth_update = DepthUpdate {
+            orderbook: "BTC-USD".to_string(),
+            block_number: 1,
+            is_snapshot: false,
+            bids: vec![Depth {
+                price: 100,
+                quantity: 5,
+            }],
+            asks: Vec::new(),
+        };
+        tx_processed_blocks
+            .send(processed_block(1, vec![depth_update]))
+            .unwrap();
+
+        let block_notification = blocks.next().await.unwrap().unwrap();
+        assert_eq!(block_notification.block_number, 1);
+        assert_eq!(block_notification.block.transactions.len(), 1);
+
+        let event_notification = events.next().await.unwrap().unwrap();
+        assert_eq!(event_notification.block_number, 1);
+        assert_eq!(event_notification.event.event_type, 1);
+        assert!(event_notification.event.decoded_event.is_some());
+
+        let depth_update = depth_updates.next().await.unwrap().unwrap();
+        assert!(!depth_update.is_snapshot);
+        assert_eq!(depth_update.bids[0].quantity, 5);
+
+        // no event of the second subscription matches its filter
+        let no_event = tokio::time::timeout(std::time::Duration::from_millis(100), filtered_events.next()).await;
+        assert!(no_event.is_err());
+    }
+}
@@ -0,0 +1,335 @@
+// IMPORTS
+
+// crate
+use crate::validator::{consensus_adapter::ConsensusAdapter, state::ValidatorState};
+
+// fermi
+use fermi_types::{
+    committee::{EpochId, StakeUnit},
+    utils,
+};
+
+// external
+use axum::{
+    extract::Extension,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+// constants
+pub const HALT_ROUTE: &str = "/halt";
+pub const UNHALT_ROUTE: &str = "/unhalt";
+pub const LOGGING_ROUTE: &str = "/logging";
+pub const CATCHUP_STATE_ROUTE: &str = "/catchup-state";
+pub const COMMITTEE_ROUTE: &str = "/committee";
+pub const FLUSH_BATCH_ROUTE: &str = "/flush-batch";
+
+// INTERFACE
+
+/// Gives the admin interface control over the tracing filter of the running process
+pub trait TracingFilterHandle: Send + Sync {
+    /// Returns the directives of the current filter
+    fn get_filter(&self) -> anyhow::Result<String>;
+
+    /// Replaces the current filter with one parsed from the given directives
+    fn set_filter(&self, directives: &str) -> anyhow::Result<()>;
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CommitteeMemberReply {
+    pub name: Option<String>,
+    pub public_key: String,
+    pub stake: StakeUnit,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CommitteeReply {
+    pub epoch: EpochId,
+    pub total_votes: StakeUnit,
+    pub members: Vec<CommitteeMemberReply>,
+}
+
+#[derive(Clone)]
+struct AdminContext {
+    state: Arc<ValidatorState>,
+    consensus_adapter: Arc<ConsensusAdapter>,
+    tracing_filter_handle: Option<Arc<dyn TracingFilterHandle>>,
+}
+
+/// Serves operator controls for a running validator over http, bound to localhost only
+pub struct AdminServer {
+    port: u16,
+    context: AdminContext,
+}
+
+impl AdminServer {
+    pub fn new(
+        port: u16,
+        state: Arc<ValidatorState>,
+        consensus_adapter: Arc<ConsensusAdapter>,
+        tracing_filter_handle: Option<Arc<dyn TracingFilterHandle>>,
+    ) -> Self {
+        Self {
+            port,
+            context: AdminContext {
+                state,
+                consensus_adapter,
+                tracing_filter_handle,
+            },
+        }
+    }
+
+    pub fn get_address(&self) -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, self.port))
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(HALT_ROUTE, post(halt))
+            .route(UNHALT_ROUTE, post(unhalt))
+            .route(LOGGING_ROUTE, get(get_filter).post(set_filter))
+            .route(CATCHUP_STATE_ROUTE, post(request_catchup_state))
+            .route(COMMITTEE_ROUTE, get(get_committee))
+            .route(FLUSH_BATCH_ROUTE, post(flush_batch))
+            .layer(Extension(self.context.clone()))
+    }
+
+    pub async fn spawn(self) -> Result<JoinHandle<()>, io::Error> {
+        let address = self.get_address();
+        let server = axum::Server::try_bind(&address).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let app = self.router();
+        info!("Admin interface listening on {address}");
+        Ok(tokio::spawn(async move {
+            if let Err(e) = server.serve(app.into_make_service()).await {
+                error!("Admin interface stopped: {:?}", e);
+            }
+        }))
+    }
+}
+
+async fn halt(Extension(context): Extension<AdminContext>) -> (StatusCode, String) {
+    context.state.halt_validator();
+    info!("Validator halted through the admin interface");
+    (StatusCode::OK, "Validator halted".to_string())
+}
+
+async fn unhalt(Extension(context): Extension<AdminContext>) -> (StatusCode, String) {
+    context.state.unhalt_validator();
+    info!("Validator unhalted through the admin interface");
+    (StatusCode::OK, "Validator unhalted".to_string())
+}
+
+async fn get_filter(Extension(context): Extension<AdminContext>) -> (StatusCode, String) {
+    match &context.tracing_filter_handle {
+        Some(handle) => match handle.get_filter() {
+            Ok(filter) => (StatusCode::OK, filter),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        },
+        None => (
+            StatusCode::NOT_IMPLEMENTED,
+            "Tracing filter is not reloadable in this process".to_string(),
+        ),
+    }
+}
+
+async fn set_filter(Extension(context): Extension<AdminContext>, directives: String) -> (StatusCode, String) {
+    let directives = directives.trim();
+    match &context.tracing_filter_handle {
+        Some(handle) => match handle.set_filter(directives) {
+            Ok(()) => {
+                info!("Tracing filter set to {directives} through the admin interface");
+                (StatusCode::OK, directives.to_string())
+            }
+            Err(e) => (StatusCode::BAD_REQUEST, e.to_string()),
+        },
+        None => (
+            StatusCode::NOT_IMPLEMENTED,
+            "Tracing filter is not reloadable in this process".to_string(),
+        ),
+    }
+}
+
+async fn request_catchup_state(Extension(context): Extension<AdminContext>) -> (StatusCode, String) {
+    context.state.request_catchup_state();
+    // a catchup state must reflect whole blocks, so it is taken by execution rather than here
+    let message = if context.state.is_halted() {
+        "Catchup state will be taken at the end of the next block once the validator is unhalted"
+    } else {
+        "Catchup state will be taken at the end of the current block"
+    };
+    (StatusCode::ACCEPTED, message.to_string())
+}
+
+async fn get_committee(Extension(context): Extension<AdminContext>) -> Json<CommitteeReply> {
+    let committee = context.state.committee.load();
+    let members = committee
+        .voting_rights
+        .iter()
+        .map(|(public_key, stake)| CommitteeMemberReply {
+            name: context
+                .state
+                .validator_set
+                .iter()
+                .find(|validator| validator.public_key() == *public_key)
+                .map(|validator| validator.name.clone()),
+            public_key: utils::encode_bytes_hex(public_key),
+            stake: *stake,
+        })
+        .collect();
+    Json(CommitteeReply {
+        epoch: committee.epoch,
+        total_votes: committee.total_votes,
+        members,
+    })
+}
+
+async fn flush_batch(Extension(context): Extension<AdminContext>) -> (StatusCode, String) {
+    match context.consensus_adapter.flush().await {
+        Ok(()) => (StatusCode::OK, "Transaction batch flushed".to_string()),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test_admin {
+    use super::*;
+    use crate::validator::state::test_validator_state::get_test_validator_state;
+    use axum::body::{Body, HttpBody};
+    use http::Request;
+    use std::sync::Mutex;
+    use tower::ServiceExt;
+
+    struct TestTracingFilterHandle {
+        filter: Mutex<String>,
+    }
+
+    impl TracingFilterHandle for TestTracingFilterHandle {
+        fn get_filter(&self) -> anyhow::Result<String> {
+            Ok(self.filter.lock().unwrap().clone())
+        }
+
+        fn set_filter(&self, directives: &str) -> anyhow::Result<()> {
+            if directives.is_empty() {
+                anyhow::bail!("Empty tracing filter");
+            }
+            *self.filter.lock().unwrap() = directives.to_string();
+            Ok(())
+        }
+    }
+
+    fn create_test_admin_server(tracing_filter_handle: Option<Arc<dyn TracingFilterHandle>>) -> AdminServer {
+        let state = Arc::new(get_test_validator_state());
+        let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+        let consensus_adapter = Arc::new(ConsensusAdapter::new(
+            vec![utils::new_network_address()],
+            tx_reconfigure_consensus,
+            Arc::clone(&state.controller_router.consensus_controller),
+        ));
+        AdminServer::new(
+            utils::get_available_port(),
+            state,
+            consensus_adapter,
+            tracing_filter_handle,
+        )
+    }
+
+    async fn send(router: &Router, method: &str, uri: &str, body: &str) -> (StatusCode, String) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let mut response = router.clone().oneshot(request).await.unwrap();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.body_mut().data().await {
+            bytes.extend_from_slice(&chunk.unwrap());
+        }
+        (response.status(), String::from_utf8(bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn halts_and_unhalts_validator() {
+        let admin_server = create_test_admin_server(None);
+        let router = admin_server.router();
+
+        assert_eq!(send(&router, "POST", HALT_ROUTE, "").await.0, StatusCode::OK);
+        assert!(admin_server.context.state.is_halted());
+        assert_eq!(send(&router, "POST", UNHALT_ROUTE, "").await.0, StatusCode::OK);
+        assert!(!admin_server.context.state.is_halted());
+    }
+
+    #[tokio::test]
+    async fn reads_and_updates_tracing_filter() {
+        let admin_server = create_test_admin_server(Some(Arc::new(TestTracingFilterHandle {
+            filter: Mutex::new("info".to_string()),
+        })));
+        let router = admin_server.router();
+
+        assert_eq!(
+            send(&router, "GET", LOGGING_ROUTE, "").await,
+            (StatusCode::OK, "info".to_string())
+        );
+        assert_eq!(
+            send(&router, "POST", LOGGING_ROUTE, "debug,h2=error\n").await.0,
+            StatusCode::OK
+        );
+        assert_eq!(
+            send(&router, "GET", LOGGING_ROUTE, "").await,
+            (StatusCode::OK, "debug,h2=error".to_string())
+        );
+        assert_eq!(
+            send(&router, "POST", LOGGING_ROUTE, "").await.0,
+            StatusCode::BAD_REQUEST
+        );
+
+        // processes without a reloadable filter report it rather than failing silently
+        let router = create_test_admin_server(None).router();
+        assert_eq!(
+            send(&router, "GET", LOGGING_ROUTE, "").await.0,
+            StatusCode::NOT_IMPLEMENTED
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_committee_and_flushes_batch() {
+        let admin_server = create_test_admin_server(None);
+        let router = admin_server.router();
+
+        let (status, body) = send(&router, "GET", COMMITTEE_ROUTE, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let committee: CommitteeReply = serde_json::from_str(&body).unwrap();
+        assert_eq!(committee.epoch, 0);
+        assert_eq!(committee.members.len(), 1);
+        assert_eq!(committee.members[0].name, Some("0".to_string()));
+
+        assert_eq!(
+            send(&router, "POST", CATCHUP_STATE_ROUTE, "").await.0,
+            StatusCode::ACCEPTED
+        );
+        // an empty buffer flushes without contacting consensus
+        assert_eq!(send(&router, "POST", FLUSH_BATCH_ROUTE, "").await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn binds_to_localhost() {
+        let admin_server = create_test_admin_server(None);
+        assert!(admin_server.get_address().ip().is_loopback());
+        let duplicate_admin_server = AdminServer {
+            port: admin_server.port,
+            context: admin_server.context.clone(),
+        };
+        let handle = admin_server.spawn().await.unwrap();
+        // a second server cannot take the same port
+        assert!(duplicate_admin_server.spawn().await.is_err());
+        handle.abort();
+    }
+}
@@ -1,14 +1,23 @@
 // fermi
 use crate::client;
+use fermi_controller::consensus::controller::ConsensusController;
 // mysten
 use narwhal_config::Committee as ConsensusCommittee;
 use narwhal_crypto::KeyPair as ConsensusKeyPair;
 // external
 use multiaddr::Multiaddr;
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
-use tokio::sync::{mpsc::Sender, Mutex};
-
-const BATCH_SIZE: usize = 100;
+use std::{
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc::Sender, Mutex, MutexGuard},
+    task::JoinHandle,
+};
+use tracing::error;
 
 /// Submits transactions to the consensus layer
 pub struct ConsensusAdapter {
@@ -20,7 +29,10 @@ pub struct ConsensusAdapter {
     pub consensus_addresses: Vec<Multiaddr>,
     /// A channel to tell consensus to reconfigure.
     pub tx_reconfigure_consensus: Sender<(ConsensusKeyPair, ConsensusCommittee)>,
-    batch_size: AtomicUsize,
+    /// Source of the batch size and flush delay, which the controller updates at the end of each block
+    consensus_controller: Arc<std::sync::Mutex<ConsensusController>>,
+    /// Local batch size which takes precedence over the controller when non-zero
+    batch_size_override: AtomicUsize,
     submitted_transactions: Mutex<Vec<narwhal_types::TransactionProto>>,
 }
 
@@ -28,6 +40,7 @@ impl ConsensusAdapter {
     pub fn new(
         consensus_addresses: Vec<Multiaddr>,
         tx_reconfigure_consensus: Sender<(ConsensusKeyPair, ConsensusCommittee)>,
+        consensus_controller: Arc<std::sync::Mutex<ConsensusController>>,
     ) -> Self {
         let consensus_clients = consensus_addresses
             .iter()
@@ -42,17 +55,51 @@ impl ConsensusAdapter {
             batch_counter: AtomicU64::new(0),
             consensus_addresses,
             tx_reconfigure_consensus,
-            batch_size: AtomicUsize::new(BATCH_SIZE),
+            consensus_controller,
+            batch_size_override: AtomicUsize::new(0),
             submitted_transactions: Mutex::new(Vec::new()),
         }
     }
 
     pub fn update_batch_size(&self, batch_size: usize) {
-        self.batch_size.store(batch_size, Ordering::SeqCst);
+        self.batch_size_override.store(batch_size, Ordering::SeqCst);
+    }
+
+    pub fn get_batch_size(&self) -> usize {
+        match self.batch_size_override.load(Ordering::SeqCst) {
+            0 => self.consensus_controller.lock().unwrap().batch_size,
+            batch_size => batch_size,
+        }
+    }
+
+    pub fn get_max_batch_delay(&self) -> Duration {
+        self.consensus_controller.lock().unwrap().max_batch_delay
+    }
+
+    /// Spawns a task which flushes partially filled batches every max batch delay,
+    /// the task exits once the adapter has been dropped
+    pub fn spawn_flush_timer(consensus_adapter: &Arc<Self>) -> JoinHandle<()> {
+        let consensus_adapter = Arc::downgrade(consensus_adapter);
+        tokio::spawn(async move {
+            loop {
+                let max_batch_delay = match consensus_adapter.upgrade() {
+                    Some(adapter) => adapter.get_max_batch_delay(),
+                    None => break,
+                };
+                tokio::time::sleep(max_batch_delay).await;
+
+                match consensus_adapter.upgrade() {
+                    Some(adapter) => {
+                        if let Err(e) = adapter.flush().await {
+                            error!("Failed to flush the transaction batch: {:?}", e);
+                        }
+                    }
+                    None => break,
+                }
+            }
+        })
     }
 
-    /// TODO - Add batch timer to force batches to be sent after a certain amount of time
-    /// TODO - move timer and batch size to a config file which specifies fermi params
     pub async fn submit_transaction(
         &self,
         transaction_proto: narwhal_types::TransactionProto,
@@ -60,24 +107,41 @@ impl ConsensusAdapter {
         let mut transaction_buffer_lock = self.submitted_transactions.lock().await;
         transaction_buffer_lock.push(transaction_proto);
 
-        if transaction_buffer_lock.len() >= self.batch_size.load(Ordering::SeqCst) {
-            let worker_index = self.batch_counter.load(Ordering::SeqCst) % (self.consensus_clients.len() as u64);
-            let transactions_copy = tokio_stream::iter(transaction_buffer_lock.clone());
-            *transaction_buffer_lock = Vec::new();
-            // drop the transaction buffer so that other threads do not remain blocked while we submit our batch
-            drop(transaction_buffer_lock);
-            // increment the batch counter so that next batch is sent to a different worker
-            self.batch_counter.fetch_add(1, Ordering::SeqCst);
+        if transaction_buffer_lock.len() >= self.get_batch_size() {
+            self.submit_batch(transaction_buffer_lock).await?;
+        }
+        Ok(())
+    }
 
-            self.consensus_clients
-                .get(worker_index as usize) //worker_index)
-                // safe to unwrap as the client counter is bounded by number of clients
-                .unwrap()
-                .lock()
-                .await
-                .submit_transaction_stream(transactions_copy)
-                .await?;
+    /// Sends any buffered transactions to consensus without waiting for a full batch
+    pub async fn flush(&self) -> Result<(), tonic::Status> {
+        let transaction_buffer_lock = self.submitted_transactions.lock().await;
+        if transaction_buffer_lock.is_empty() {
+            return Ok(());
         }
+        self.submit_batch(transaction_buffer_lock).await
+    }
+
+    async fn submit_batch(
+        &self,
+        mut transaction_buffer_lock: MutexGuard<'_, Vec<narwhal_types::TransactionProto>>,
+    ) -> Result<(), tonic::Status> {
+        let worker_index = self.batch_counter.load(Ordering::SeqCst) % (self.consensus_clients.len() as u64);
+        let transactions_copy = tokio_stream::iter(transaction_buffer_lock.clone());
+        *transaction_buffer_lock = Vec::new();
+        // drop the transaction buffer so that other threads do not remain blocked while we submit our batch
+        drop(transaction_buffer_lock);
+        // increment the batch counter so that next batch is sent to a different worker
+        self.batch_counter.fetch_add(1, Ordering::SeqCst);
+
+        self.consensus_clients
+            .get(worker_index as usize) //worker_index)
+            // safe to unwrap as the client counter is bounded by number of clients
+            .unwrap()
+            .lock()
+            .await
+            .submit_transaction_stream(transactions_copy)
+            .await?;
         Ok(())
     }
 }
@@ -2,7 +2,7 @@
 use crate::builder::genesis_state::GenesisStateBuilder;
 use fermi_controller::router::ControllerRouter;
 use fermi_types::{
-    committee::{Committee, EpochId},
+    committee::{Committee, EpochId, StakeUnit, ValidatorName},
     error::GDEXResult,
     node::ValidatorInfo,
     serialization::{Base64, Encoding},
@@ -11,6 +11,7 @@ use fermi_types::{
 use anyhow::{Context, Result};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
+    collections::BTreeMap,
     convert::TryInto,
     {fs, path::Path},
 };
@@ -35,7 +36,7 @@ impl ValidatorGenesisState {
     }
 
     pub fn epoch(&self) -> EpochId {
-        0
+        self.controller_router.stake_controller.lock().unwrap().get_epoch()
     }
 
     pub fn validator_set(&self) -> &[ValidatorInfo] {
@@ -47,10 +48,28 @@ impl ValidatorGenesisState {
     }
 
     pub fn narwhal_committee(&self) -> narwhal_config::SharedCommittee {
-        let narwhal_committee = self
+        let voting_rights = self
             .validator_set
             .iter()
-            .map(|validator| {
+            .map(|validator| (validator.public_key(), validator.stake))
+            .collect();
+        std::sync::Arc::new(arc_swap::ArcSwap::from_pointee(Self::build_narwhal_committee(
+            &self.validator_set,
+            self.epoch(),
+            &voting_rights,
+        )))
+    }
+
+    /// Builds the consensus committee for an epoch, validators without voting rights are left out
+    pub fn build_narwhal_committee(
+        validator_set: &[ValidatorInfo],
+        epoch: EpochId,
+        voting_rights: &BTreeMap<ValidatorName, StakeUnit>,
+    ) -> narwhal_config::Committee {
+        let narwhal_committee = validator_set
+            .iter()
+            .filter_map(|validator| {
+                let stake = *voting_rights.get(&validator.public_key())?;
                 // Strong requirement here for narwhal and sui to be on the same version of fastcrypto
                 // for AuthorityPublicBytes to cast to type alias PublicKey defined in narwhal to
                 // construct narwhal Committee struct.
@@ -60,17 +79,17 @@ impl ValidatorGenesisState {
                     worker_to_primary: validator.narwhal_worker_to_primary.clone(),
                 };
                 let authority = narwhal_config::Authority {
-                    stake: validator.stake as narwhal_config::Stake, //TODO this should at least be the same size integer
+                    stake: stake as narwhal_config::Stake, //TODO this should at least be the same size integer
                     primary,
                 };
 
-                (name, authority)
+                Some((name, authority))
             })
             .collect();
-        std::sync::Arc::new(arc_swap::ArcSwap::from_pointee(narwhal_config::Committee {
+        narwhal_config::Committee {
             authorities: narwhal_committee,
-            epoch: self.epoch() as narwhal_config::Epoch,
-        }))
+            epoch: epoch as narwhal_config::Epoch,
+        }
     }
 
     pub fn narwhal_worker_cache(&self) -> narwhal_config::SharedWorkerCache {
@@ -30,6 +30,8 @@ pub struct ValidatorMetrics {
     pub block_number: IntCounter,
     /// The validator system epoch time
     pub validator_system_epoch_time_in_micros: IntGauge,
+    /// Set to 1 while the validator is halted
+    pub halted: IntGauge,
     /// The block latency in miliseconds
     pub block_latency_micros: Histogram,
     /// The transactions per second of the cluster
@@ -89,6 +91,12 @@ impl ValidatorMetrics {
                 registry
             )
             .unwrap(),
+            halted: register_int_gauge_with_registry!(
+                "halted",
+                "Whether the validator is halted, 1 if halted and 0 otherwise.",
+                registry
+            )
+            .unwrap(),
             block_latency_micros: register_histogram_with_registry!(
                 "block_latency_micros",
                 "The latency between blocks in microseconds",
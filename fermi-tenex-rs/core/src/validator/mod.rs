@@ -1,8 +1,10 @@
+pub mod admin;
 pub mod consensus_adapter;
 pub mod genesis_state;
 pub mod metrics;
 pub mod post_processor;
 pub mod restarter;
+pub mod scheduler;
 pub mod server;
 pub mod spawner;
 pub mod state;
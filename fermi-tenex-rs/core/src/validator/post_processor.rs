@@ -3,8 +3,15 @@
 // local
 use crate::validator::{server::HandledTransaction, state::ValidatorState};
 
-// external
+// fermi
+use fermi_types::crypto::KeypairTraits;
+
+// mysten
+use narwhal_config::Committee as ConsensusCommittee;
+use narwhal_crypto::KeyPair as ConsensusKeyPair;
 use narwhal_executor::SerializedTransaction;
+
+// external
 use std::sync::Arc;
 use tokio::{
     sync::{broadcast, mpsc},
@@ -20,6 +27,7 @@ impl ValidatorPostProcessor {
     pub fn spawn(
         rx_narwhal_to_post_process: mpsc::Receiver<(HandledTransaction, SerializedTransaction)>,
         validator_state: Arc<ValidatorState>,
+        tx_reconfigure_consensus: mpsc::Sender<(ConsensusKeyPair, ConsensusCommittee)>,
     ) -> anyhow::Result<Vec<JoinHandle<()>>> {
         // channel to communicate from txn processor to block, catchup processors
         let (tx_txn_to_processors, rx_txn_to_block_processor) = broadcast::channel::<u64>(1_000);
@@ -29,7 +37,11 @@ impl ValidatorPostProcessor {
             Arc::clone(&validator_state),
             tx_txn_to_processors,
         );
-        let block_processor_handle = BlockProcessor::spawn(Arc::clone(&validator_state), rx_txn_to_block_processor);
+        let block_processor_handle = BlockProcessor::spawn(
+            Arc::clone(&validator_state),
+            rx_txn_to_block_processor,
+            tx_reconfigure_consensus,
+        );
         Ok(vec![transaction_processor_handle, block_processor_handle])
     }
 }
@@ -69,8 +81,8 @@ impl TransactionProcessor {
                 let (result, _serialized_txn) = message;
 
                 match result {
-                    Ok((consensus_output, execution_indices, execution_result)) => {
-                        executed_transactions.push(execution_result);
+                    Ok((consensus_output, execution_indices, block_transactions)) => {
+                        executed_transactions.extend(block_transactions);
 
                         // if next_transaction_index == 0 then the block is complete and we may write-out
                         if execution_indices.next_transaction_index == 0 {
@@ -105,17 +117,20 @@ impl TransactionProcessor {
 pub struct BlockProcessor {
     validator_state: Arc<ValidatorState>,
     rx_txn_to_block_processor: broadcast::Receiver<u64>,
+    tx_reconfigure_consensus: mpsc::Sender<(ConsensusKeyPair, ConsensusCommittee)>,
 }
 
 impl BlockProcessor {
     pub fn spawn(
         validator_state: Arc<ValidatorState>,
         rx_txn_to_block_processor: broadcast::Receiver<u64>,
+        tx_reconfigure_consensus: mpsc::Sender<(ConsensusKeyPair, ConsensusCommittee)>,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
             Self {
                 validator_state,
                 rx_txn_to_block_processor,
+                tx_reconfigure_consensus,
             }
             .run()
             .await
@@ -143,12 +158,22 @@ impl BlockProcessor {
                     // metrics process end of block
                     metrics.process_end_of_block(block.unwrap(), block_info.unwrap());
                 }
-                // controller logic process end of block
-                let controller_router = &self.validator_state.controller_router;
-
-                controller_router
-                    .critical_process_end_of_block(&store.critical_path_store, block_number)
-                    .unwrap();
+                // controller end of block processing runs during execution, see ValidatorState::process_end_of_block
+
+                // restart consensus with the stake weighted committee when a new epoch begins
+                if let Some(consensus_committee) = self.validator_state.reconfigure_committee() {
+                    let keypair = self.validator_state.secret.copy();
+                    if !consensus_committee.authorities.contains_key(keypair.public()) {
+                        info!(
+                            "Validator is not a member of the epoch {} committee",
+                            consensus_committee.epoch
+                        );
+                        continue;
+                    }
+                    if let Err(e) = self.tx_reconfigure_consensus.send((keypair, consensus_committee)).await {
+                        error!("Failed to reconfigure consensus: {:?}", e);
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,621 @@
+// IMPORTS
+
+// fermi
+use fermi_controller::router::{ControllerRouter, TransactionFootprint};
+use fermi_types::{
+    account::AccountPubKey,
+    asset::AssetPairKey,
+    error::GDEXError,
+    transaction::{ExecutionEvents, Transaction},
+};
+
+// external
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    mem,
+    num::NonZeroUsize,
+    thread,
+};
+
+// INTERFACE
+
+// state a transaction may modify, transactions which share a key conflict
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+enum FootprintKey {
+    Account(AccountPubKey),
+    Orderbook(AssetPairKey),
+    Marketplace(AccountPubKey),
+}
+
+// disjoint sets of footprint keys, the keys of conflicting transactions end up in the same set
+#[derive(Default)]
+struct ConflictSets {
+    key_ids: HashMap<FootprintKey, usize>,
+    keys: Vec<FootprintKey>,
+    parents: Vec<usize>,
+}
+
+impl ConflictSets {
+    fn insert(&mut self, key: FootprintKey) -> usize {
+        if let Some(key_id) = self.key_ids.get(&key) {
+            return *key_id;
+        }
+        let key_id = self.keys.len();
+        self.key_ids.insert(key.clone(), key_id);
+        self.keys.push(key);
+        self.parents.push(key_id);
+        key_id
+    }
+
+    fn find(&mut self, mut key_id: usize) -> usize {
+        while self.parents[key_id] != key_id {
+            self.parents[key_id] = self.parents[self.parents[key_id]];
+            key_id = self.parents[key_id];
+        }
+        key_id
+    }
+
+    fn union(&mut self, key_id_0: usize, key_id_1: usize) {
+        let (root_0, root_1) = (self.find(key_id_0), self.find(key_id_1));
+        if root_0 != root_1 {
+            self.parents[root_1] = root_0;
+        }
+    }
+}
+
+// transactions of a segment which do not conflict with those of any other lane, with the state they may modify
+#[derive(Default)]
+struct Lane {
+    transaction_indices: Vec<usize>,
+    footprint: TransactionFootprint,
+}
+
+impl Lane {
+    fn append(&mut self, lane: Lane) {
+        self.transaction_indices.extend(lane.transaction_indices);
+        self.footprint.accounts.extend(lane.footprint.accounts);
+        self.footprint.orderbook_keys.extend(lane.footprint.orderbook_keys);
+        self.footprint.market_admins.extend(lane.footprint.market_admins);
+    }
+}
+
+/// Executes the transactions of a block, running transactions whose footprints do not overlap in parallel
+/// each transaction's result and events, as well as the final state, are those of executing the block in order
+pub struct BlockScheduler {
+    n_workers: usize,
+}
+
+impl Default for BlockScheduler {
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+}
+
+impl BlockScheduler {
+    pub fn new(n_workers: usize) -> Self {
+        Self {
+            n_workers: n_workers.max(1),
+        }
+    }
+
+    /// Executes the transactions on the router and returns their results in block order
+    pub fn execute_block(
+        &self,
+        controller_router: &ControllerRouter,
+        transactions: &[&Transaction],
+    ) -> Vec<Result<ExecutionEvents, GDEXError>> {
+        if self.n_workers < 2 {
+            return transactions
+                .iter()
+                .map(|transaction| controller_router.handle_consensus_transaction(transaction))
+                .collect();
+        }
+
+        // a transaction without a footprint ends the segment before it and executes on its own
+        let mut results = Vec::with_capacity(transactions.len());
+        let mut segment = Vec::new();
+        for transaction in transactions {
+            match controller_router.get_footprint(transaction) {
+                Some(footprint) => segment.push((*transaction, footprint)),
+                None => {
+                    results.extend(self.execute_segment(controller_router, mem::take(&mut segment)));
+                    results.push(controller_router.handle_consensus_transaction(transaction));
+                }
+            }
+        }
+        results.extend(self.execute_segment(controller_router, segment));
+        results
+    }
+
+    // executes transactions which all have a footprint, each lane on its own thread
+    fn execute_segment(
+        &self,
+        controller_router: &ControllerRouter,
+        segment: Vec<(&Transaction, TransactionFootprint)>,
+    ) -> Vec<Result<ExecutionEvents, GDEXError>> {
+        let lanes = self.get_lanes(controller_router, &segment);
+        if lanes.len() < 2 {
+            return segment
+                .iter()
+                .map(|(transaction, _)| controller_router.handle_consensus_transaction(transaction))
+                .collect();
+        }
+
+        let lane_routers: Vec<(Vec<usize>, ControllerRouter)> = lanes
+            .into_iter()
+            .map(|lane| (lane.transaction_indices, controller_router.split_lane(&lane.footprint)))
+            .collect();
+        let lane_results: Vec<Vec<(usize, Result<ExecutionEvents, GDEXError>)>> = thread::scope(|scope| {
+            let segment = &segment;
+            let handles: Vec<_> = lane_routers
+                .iter()
+                .map(|(transaction_indices, lane_router)| {
+                    scope.spawn(move || {
+                        transaction_indices
+                            .iter()
+                            .map(|index| (*index, lane_router.handle_consensus_transaction(segment[*index].0)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Lane execution panicked"))
+                .collect()
+        });
+        for (_, lane_router) in lane_routers {
+            controller_router.merge_lane(lane_router);
+        }
+
+        let mut results: Vec<Option<Result<ExecutionEvents, GDEXError>>> = segment.iter().map(|_| None).collect();
+        for (index, result) in lane_results.into_iter().flatten() {
+            results[index] = Some(result);
+        }
+        results
+            .into_iter()
+            .map(|result| result.expect("Every transaction of a segment belongs to a lane"))
+            .collect()
+    }
+
+    // groups conflicting transactions into lanes and packs them onto at most n_workers lanes
+    fn get_lanes(
+        &self,
+        controller_router: &ControllerRouter,
+        segment: &[(&Transaction, TransactionFootprint)],
+    ) -> Vec<Lane> {
+        let mut conflict_sets = ConflictSets::default();
+        let mut transaction_key_ids = Vec::with_capacity(segment.len());
+        for (_, footprint) in segment {
+            // the sender is always part of the footprint, so every transaction has a key
+            let key_ids: Vec<usize> = footprint
+                .accounts
+                .iter()
+                .cloned()
+                .map(FootprintKey::Account)
+                .chain(footprint.orderbook_keys.iter().cloned().map(FootprintKey::Orderbook))
+                .chain(footprint.market_admins.iter().cloned().map(FootprintKey::Marketplace))
+                .map(|key| conflict_sets.insert(key))
+                .collect();
+            for key_id in &key_ids[1..] {
+                conflict_sets.union(key_ids[0], *key_id);
+            }
+            transaction_key_ids.push(key_ids[0]);
+        }
+
+        // a fill settles with the accounts resting on the book, which are not part of the taker's footprint
+        let orderbook_keys: HashSet<&AssetPairKey> = segment
+            .iter()
+            .flat_map(|(_, footprint)| footprint.orderbook_keys.iter())
+            .collect();
+        for orderbook_key in orderbook_keys {
+            let orderbook_key_id = conflict_sets.insert(FootprintKey::Orderbook(orderbook_key.clone()));
+            for account in controller_router.get_resting_accounts(orderbook_key) {
+                let account_key_id = conflict_sets.insert(FootprintKey::Account(account));
+                conflict_sets.union(orderbook_key_id, account_key_id);
+            }
+        }
+
+        let mut lane_ids: HashMap<usize, usize> = HashMap::new();
+        let mut lanes: Vec<Lane> = Vec::new();
+        for (index, key_id) in transaction_key_ids.into_iter().enumerate() {
+            let root = conflict_sets.find(key_id);
+            let lane_id = *lane_ids.entry(root).or_insert_with(|| {
+                lanes.push(Lane::default());
+                lanes.len() - 1
+            });
+            lanes[lane_id].transaction_indices.push(index);
+        }
+        if lanes.len() < 2 {
+            return lanes;
+        }
+        for key_id in 0..conflict_sets.keys.len() {
+            let lane_id = lane_ids[&conflict_sets.find(key_id)];
+            match &conflict_sets.keys[key_id] {
+                FootprintKey::Account(account) => lanes[lane_id].footprint.accounts.push(account.clone()),
+                FootprintKey::Orderbook(orderbook_key) => {
+                    lanes[lane_id].footprint.orderbook_keys.push(orderbook_key.clone())
+                }
+                FootprintKey::Marketplace(market_admin) => {
+                    lanes[lane_id].footprint.market_admins.push(market_admin.clone())
+                }
+            }
+        }
+
+        // the largest lanes are placed first, each onto the worker with the fewest transactions so far
+        lanes.sort_by_key(|lane| Reverse(lane.transaction_indices.len()));
+        let mut workers: Vec<Lane> = (0..self.n_workers.min(lanes.len())).map(|_| Lane::default()).collect();
+        for lane in lanes {
+            workers
+                .iter_mut()
+                .min_by_key(|worker| worker.transaction_indices.len())
+                .expect("There is at least one worker")
+                .append(lane);
+        }
+        for worker in &mut workers {
+            worker.transaction_indices.sort_unstable();
+        }
+        workers
+    }
+}
+
+#[cfg(test)]
+mod test_scheduler {
+    use super::*;
+    use fastcrypto::DIGEST_LEN;
+    use fermi_controller::{
+        bank::proto::{create_create_asset_transaction, create_payment_transaction},
+        controller::Controller,
+        futures::proto::{AccountDepositRequest, CreateMarketplaceRequest},
+        spot::proto::{
+            create_create_orderbook_transaction, create_limit_order_transaction, create_update_fee_schedule_transaction,
+        },
+        stake::proto::create_stake_transaction,
+    };
+    use fermi_types::{
+        account::{account_test_functions::generate_keypair_vec, AccountKeyPair},
+        asset::AssetId,
+        block::BlockDigest,
+        crypto::KeypairTraits,
+        order_book::{OrderSide, TimeInForce},
+    };
+
+    const QUOTE_ASSET_ID: AssetId = 0;
+    const N_ORDERBOOKS: u64 = 3;
+    const TRANSFER_AMOUNT: u64 = 1_000_000;
+
+    fn create_router() -> ControllerRouter {
+        let controller_router = ControllerRouter::default();
+        controller_router.initialize_controllers();
+        controller_router.initialize_controller_accounts();
+        controller_router
+    }
+
+    // the admin, one maker per orderbook and one taker per orderbook
+    fn get_keys() -> (AccountKeyPair, Vec<AccountKeyPair>, Vec<AccountKeyPair>) {
+        let mut keys = generate_keypair_vec([0; 32]);
+        let admin = keys.remove(0);
+        let mut takers = generate_keypair_vec([1; 32]);
+        takers.truncate(N_ORDERBOOKS as usize);
+        (admin, keys, takers)
+    }
+
+    // creates the quote asset and a base asset per orderbook, makers are funded in base and quote, takers in quote
+    fn create_genesis_block() -> Vec<Transaction> {
+        let (admin, makers, takers) = get_keys();
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let mut transactions = Vec::new();
+        for _ in 0..=N_ORDERBOOKS {
+            transactions.push(create_create_asset_transaction(admin.public(), recent_block_hash, 0));
+        }
+        for base_asset_id in 1..=N_ORDERBOOKS {
+            transactions.push(create_create_orderbook_transaction(
+                admin.public(),
+                recent_block_hash,
+                base_asset_id,
+                QUOTE_ASSET_ID,
+            ));
+            let (maker, taker) = (&makers[base_asset_id as usize - 1], &takers[base_asset_id as usize - 1]);
+            for (user, asset_id) in [(maker, base_asset_id), (maker, QUOTE_ASSET_ID), (taker, QUOTE_ASSET_ID)] {
+                transactions.push(create_payment_transaction(
+                    admin.public(),
+                    recent_block_hash,
+                    user.public(),
+                    asset_id,
+                    TRANSFER_AMOUNT,
+                ));
+            }
+        }
+        transactions
+    }
+
+    // asks on every book, a payment which links two books, a stake which executes on its own,
+    // then bids which fill against the asks and asks of a base asset the taker does not hold
+    fn create_test_block() -> Vec<Transaction> {
+        let (_admin, makers, takers) = get_keys();
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let limit_order = |user: &AccountKeyPair, base_asset_id: AssetId, side: OrderSide, price: u64| {
+            create_limit_order_transaction(
+                user.public(),
+                recent_block_hash,
+                base_asset_id,
+                QUOTE_ASSET_ID,
+                side as u64,
+                price,
+                10,
+                0,
+                TimeInForce::GoodTilCancelled as u64,
+                false,
+            )
+        };
+        let mut transactions = Vec::new();
+        for (maker, base_asset_id) in makers.iter().zip(1..=N_ORDERBOOKS) {
+            transactions.push(limit_order(maker, base_asset_id, OrderSide::Ask, 100));
+            transactions.push(limit_order(maker, base_asset_id, OrderSide::Ask, 110));
+        }
+        transactions.push(create_payment_transaction(
+            takers[0].public(),
+            recent_block_hash,
+            takers[1].public(),
+            QUOTE_ASSET_ID,
+            1_000,
+        ));
+        transactions.push(create_stake_transaction(takers[2].public(), recent_block_hash, 1_000));
+        for (taker, base_asset_id) in takers.iter().zip(1..=N_ORDERBOOKS) {
+            transactions.push(limit_order(taker, base_asset_id, OrderSide::Bid, 120));
+            transactions.push(limit_order(taker, base_asset_id, OrderSide::Ask, 1_000));
+        }
+        transactions
+    }
+
+    // gives each transaction the nonce its sender will have when it executes
+    fn execute(
+        scheduler: &BlockScheduler,
+        controller_router: &ControllerRouter,
+        mut transactions: Vec<Transaction>,
+    ) -> Vec<Result<ExecutionEvents, GDEXError>> {
+        let mut nonces = HashMap::new();
+        for transaction in &mut transactions {
+            let sender = transaction.get_sender().unwrap();
+            let nonce = nonces
+                .entry(sender.clone())
+                .or_insert_with(|| controller_router.get_next_nonce(&sender));
+            transaction.set_nonce(*nonce);
+            *nonce += 1;
+        }
+        let transactions: Vec<&Transaction> = transactions.iter().collect();
+        scheduler.execute_block(controller_router, &transactions)
+    }
+
+    #[test]
+    fn parallel_execution_matches_serial_execution() {
+        let serial_scheduler = BlockScheduler::new(1);
+        let parallel_scheduler = BlockScheduler::new(4);
+        let (serial_router, parallel_router) = (create_router(), create_router());
+        for (scheduler, controller_router) in [
+            (&serial_scheduler, &serial_router),
+            (&parallel_scheduler, &parallel_router),
+        ] {
+            for result in execute(scheduler, controller_router, create_genesis_block()) {
+                result.unwrap();
+            }
+        }
+
+        // the asks and the payment before the stake split into a lane per book plus one for the payment
+        let test_block = create_test_block();
+        let segment: Vec<(&Transaction, TransactionFootprint)> = test_block[..2 * N_ORDERBOOKS as usize + 1]
+            .iter()
+            .map(|transaction| (transaction, parallel_router.get_footprint(transaction).unwrap()))
+            .collect();
+        assert_eq!(parallel_scheduler.get_lanes(&parallel_router, &segment).len(), 4);
+        assert!(parallel_router
+            .get_footprint(&test_block[2 * N_ORDERBOOKS as usize + 1])
+            .is_none());
+
+        let serial_results = execute(&serial_scheduler, &serial_router, create_test_block());
+        let parallel_results = execute(&parallel_scheduler, &parallel_router, test_block);
+        assert_eq!(serial_results, parallel_results);
+        assert_eq!(
+            serial_results.iter().filter(|result| result.is_err()).count(),
+            N_ORDERBOOKS as usize
+        );
+
+        let (admin, makers, takers) = get_keys();
+        let mut accounts: Vec<AccountPubKey> = [&admin]
+            .into_iter()
+            .chain(makers.iter())
+            .chain(takers.iter())
+            .map(|keypair| keypair.public().clone())
+            .collect();
+        accounts.extend(serial_router.get_shared_accounts());
+        for account in &accounts {
+            assert_eq!(
+                serial_router.get_next_nonce(account),
+                parallel_router.get_next_nonce(account)
+            );
+            for asset_id in 0..=N_ORDERBOOKS {
+                assert_eq!(
+                    serial_router
+                        .bank_controller
+                        .lock()
+                        .unwrap()
+                        .get_balance(account, asset_id),
+                    parallel_router
+                        .bank_controller
+                        .lock()
+                        .unwrap()
+                        .get_balance(account, asset_id)
+                );
+            }
+        }
+        let serial_spot_controller = serial_router.spot_controller.lock().unwrap();
+        let parallel_spot_controller = parallel_router.spot_controller.lock().unwrap();
+        assert_eq!(parallel_spot_controller.get_orderbooks().len(), N_ORDERBOOKS as usize);
+        for (orderbook_key, orderbook) in serial_spot_controller.get_orderbooks() {
+            let parallel_orderbook = &parallel_spot_controller.get_orderbooks()[orderbook_key];
+            assert_eq!(
+                orderbook.get_orderbook_depth(),
+                parallel_orderbook.get_orderbook_depth()
+            );
+            assert_eq!(orderbook.get_recent_trades(), parallel_orderbook.get_recent_trades());
+            assert_eq!(orderbook.get_recent_trades().len(), 1);
+        }
+    }
+
+    #[test]
+    fn parallel_rebates_match_serial_execution() {
+        let serial_scheduler = BlockScheduler::new(1);
+        let parallel_scheduler = BlockScheduler::new(4);
+        let (serial_router, parallel_router) = (create_router(), create_router());
+
+        // every book rebates its makers, and no fee has been collected before the test block
+        let (admin, makers, takers) = get_keys();
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let mut setup_block = create_genesis_block();
+        for base_asset_id in 1..=N_ORDERBOOKS {
+            setup_block.push(create_update_fee_schedule_transaction(
+                admin.public(),
+                recent_block_hash,
+                base_asset_id,
+                QUOTE_ASSET_ID,
+                -10,
+                30,
+            ));
+        }
+        for (scheduler, controller_router) in [
+            (&serial_scheduler, &serial_router),
+            (&parallel_scheduler, &parallel_router),
+        ] {
+            for result in execute(scheduler, controller_router, setup_block.clone()) {
+                result.unwrap();
+            }
+            assert_eq!(
+                controller_router
+                    .bank_controller
+                    .lock()
+                    .unwrap()
+                    .get_accrued_fees(QUOTE_ASSET_ID),
+                0
+            );
+        }
+
+        let serial_results = execute(&serial_scheduler, &serial_router, create_test_block());
+        let parallel_results = execute(&parallel_scheduler, &parallel_router, create_test_block());
+        assert_eq!(serial_results, parallel_results);
+        assert_eq!(
+            serial_results.iter().filter(|result| result.is_err()).count(),
+            N_ORDERBOOKS as usize
+        );
+
+        let mut accounts: Vec<AccountPubKey> = makers
+            .iter()
+            .chain(takers.iter())
+            .map(|keypair| keypair.public().clone())
+            .collect();
+        accounts.extend(serial_router.get_shared_accounts());
+        for account in &accounts {
+            assert_eq!(
+                serial_router
+                    .bank_controller
+                    .lock()
+                    .unwrap()
+                    .get_balance(account, QUOTE_ASSET_ID),
+                parallel_router
+                    .bank_controller
+                    .lock()
+                    .unwrap()
+                    .get_balance(account, QUOTE_ASSET_ID)
+            );
+        }
+
+        // each book fills 10 at 100, the taker pays 3 and the maker is rebated 1
+        assert_eq!(
+            parallel_router
+                .bank_controller
+                .lock()
+                .unwrap()
+                .get_accrued_fees(QUOTE_ASSET_ID),
+            2 * N_ORDERBOOKS
+        );
+    }
+
+    #[test]
+    fn futures_marketplaces_execute_in_lanes() {
+        let serial_scheduler = BlockScheduler::new(1);
+        let parallel_scheduler = BlockScheduler::new(4);
+        let (serial_router, parallel_router) = (create_router(), create_router());
+        for (scheduler, controller_router) in [
+            (&serial_scheduler, &serial_router),
+            (&parallel_scheduler, &parallel_router),
+        ] {
+            for result in execute(scheduler, controller_router, create_genesis_block()) {
+                result.unwrap();
+            }
+        }
+
+        // every taker opens a marketplace and deposits into it, the second also deposits into the first's
+        let (_admin, _makers, takers) = get_keys();
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let mut test_block: Vec<Transaction> = takers
+            .iter()
+            .map(|taker| {
+                Transaction::new(
+                    taker.public(),
+                    recent_block_hash,
+                    &CreateMarketplaceRequest::new(QUOTE_ASSET_ID),
+                )
+            })
+            .collect();
+        for taker in &takers {
+            test_block.push(Transaction::new(
+                taker.public(),
+                recent_block_hash,
+                &AccountDepositRequest::new(1_000, taker.public()),
+            ));
+        }
+        test_block.push(Transaction::new(
+            takers[1].public(),
+            recent_block_hash,
+            &AccountDepositRequest::new(1_000, takers[0].public()),
+        ));
+
+        let segment: Vec<(&Transaction, TransactionFootprint)> = test_block
+            .iter()
+            .map(|transaction| (transaction, parallel_router.get_footprint(transaction).unwrap()))
+            .collect();
+        assert_eq!(
+            parallel_scheduler.get_lanes(&parallel_router, &segment).len(),
+            N_ORDERBOOKS as usize - 1
+        );
+
+        let serial_results = execute(&serial_scheduler, &serial_router, test_block.clone());
+        let parallel_results = execute(&parallel_scheduler, &parallel_router, test_block);
+        assert_eq!(serial_results, parallel_results);
+        assert!(serial_results.iter().all(|result| result.is_ok()));
+
+        let mut accounts: Vec<AccountPubKey> = takers.iter().map(|keypair| keypair.public().clone()).collect();
+        accounts.extend(serial_router.get_shared_accounts());
+        for account in &accounts {
+            assert_eq!(
+                serial_router.get_next_nonce(account),
+                parallel_router.get_next_nonce(account)
+            );
+            assert_eq!(
+                serial_router
+                    .bank_controller
+                    .lock()
+                    .unwrap()
+                    .get_balance(account, QUOTE_ASSET_ID),
+                parallel_router
+                    .bank_controller
+                    .lock()
+                    .unwrap()
+                    .get_balance(account, QUOTE_ASSET_ID)
+            );
+        }
+        assert_eq!(
+            serial_router.futures_controller.lock().unwrap().get_catchup_state(),
+            parallel_router.futures_controller.lock().unwrap().get_catchup_state()
+        );
+    }
+}
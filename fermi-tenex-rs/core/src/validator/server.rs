@@ -10,9 +10,12 @@ use crate::{
 use fermi_types::{
     crypto::KeypairTraits,
     proto::{
-        BlockInfoRequest, BlockInfoResponse, BlockRequest, BlockResponse, Empty, LatestBlockInfoRequest,
-        MetricsRequest, MetricsResponse, ValidatorGrpc, ValidatorGrpcServer,
+        BlockInfoRequest, BlockInfoResponse, BlockRequest, BlockResponse, CatchupStateChunk, CatchupStateRequest,
+        Empty, ExecutedTransactionResponse, LatestBlockInfoRequest, ListCatchupStatesRequest,
+        ListCatchupStatesResponse, MetricsRequest, MetricsResponse, SubmitTransactionAndWaitRequest, ValidatorGrpc,
+        ValidatorGrpcServer,
     },
+    store::CATCHUP_STATE_CHUNK_SIZE,
     transaction::{ExecutedTransaction, SignedTransaction},
 };
 
@@ -30,7 +33,11 @@ use bytes::Bytes;
 use futures::StreamExt;
 use multiaddr::Multiaddr;
 use prometheus::Registry;
-use std::{io, sync::Arc, time::SystemTime};
+use std::{
+    io,
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, SystemTime},
+};
 use tokio::{
     sync::mpsc::{Receiver, Sender},
     task::JoinHandle,
@@ -39,8 +46,12 @@ use tonic::{Request, Response, Status};
 use tracing::{info, trace};
 
 // constants
-// frequency of orderbook depth writes (rounds)
-pub type HandledTransaction = Result<(ConsensusOutput, ExecutionIndices, ExecutedTransaction), SubscriberError>;
+// number of recent catchup states advertised to syncing nodes
+pub const MAX_LISTED_CATCHUP_STATES: usize = 10;
+// longest a submitter may wait for its transaction to be finalized
+pub const MAX_SUBMIT_AND_WAIT_TIMEOUT_IN_MS: u64 = 30_000;
+
+pub type HandledTransaction = Result<(ConsensusOutput, ExecutionIndices, Vec<ExecutedTransaction>), SubscriberError>;
 
 /// Contains and orchestrates a tokio handle where the validator server runs
 pub struct ValidatorServerHandle {
@@ -78,7 +89,11 @@ impl ValidatorServer {
         consensus_addresses: Vec<Multiaddr>,
         tx_reconfigure_consensus: Sender<(ConsensusKeyPair, ConsensusCommittee)>,
     ) -> Self {
-        let consensus_adapter = Arc::new(ConsensusAdapter::new(consensus_addresses, tx_reconfigure_consensus));
+        let consensus_adapter = Arc::new(ConsensusAdapter::new(
+            consensus_addresses,
+            tx_reconfigure_consensus,
+            Arc::clone(&state.controller_router.consensus_controller),
+        ));
 
         Self {
             grpc_address,
@@ -109,6 +124,8 @@ impl ValidatorServer {
             .unwrap();
         let local_addr = server.local_addr().to_owned();
         info!("Listening to traffic on {local_addr}");
+        // flush partially filled batches so that transactions do not sit in the buffer indefinitely
+        ConsensusAdapter::spawn_flush_timer(&self.consensus_adapter);
         let handle = ValidatorServerHandle {
             local_addr,
             handle: tokio::spawn(server.serve()),
@@ -139,7 +156,8 @@ impl ValidatorService {
             .consensus_config()
             .ok_or_else(|| anyhow!("Validator is missing consensus config"))?;
         let consensus_keypair = config.key_pair().copy();
-        let consensus_committee = config.genesis()?.narwhal_committee().load();
+        // after a restart the restored stake may already be past the genesis epoch
+        let consensus_committee = state.get_consensus_committee();
         let consensus_worker_cache = config.genesis()?.narwhal_worker_cache();
         let consensus_execution_state = Arc::clone(&state);
         let consensus_storage_base_path = consensus_config.db_path().to_path_buf();
@@ -151,7 +169,7 @@ impl ValidatorService {
         let restarter_handle = tokio::spawn(async move {
             NodeRestarter::watch(
                 consensus_keypair,
-                &*consensus_committee,
+                &consensus_committee,
                 consensus_worker_cache,
                 consensus_storage_base_path,
                 consensus_execution_state,
@@ -175,6 +193,11 @@ impl ValidatorService {
         trace!("Handling a new transaction with ValidatorService",);
         state.metrics.transactions_received.inc();
 
+        if state.is_halted() {
+            state.metrics.transactions_received_failed.inc();
+            return Err(tonic::Status::unavailable("Validator is halted"));
+        }
+
         signed_transaction
             .verify_signature()
             .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
@@ -197,22 +220,11 @@ impl ValidatorService {
             }
         }
 
-        // check transaction is not a duplicate
-        let transaction = signed_transaction
-            .get_transaction()
-            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
-        if state.validator_store.cache_contains_transaction(transaction) {
+        // check the transaction nonce has not been used and it carries no more requests than allowed
+        // execution enforces the exact nonce sequence
+        if let Err(err) = state.handle_pre_consensus_transaction(&signed_transaction) {
             state.metrics.transactions_received_failed.inc();
-            // TODO - find cleaner way to represent this logic
-            // TODO - make sure benchmark flag is removed from node Cargo.toml in the future
-            let digest = "TEST"; // TODO impl txn to string
-            cfg_if::cfg_if! {
-                if #[cfg(feature = "benchmark")] {
-                    trace!("Duplicate transaction id = {}", digest);
-                } else {
-                    return Err(tonic::Status::internal("Duplicate transaction ".to_owned() + &digest));
-                }
-            }
+            return Err(tonic::Status::invalid_argument(err.to_string()));
         }
 
         // submit transaction
@@ -226,11 +238,6 @@ impl ValidatorService {
             .submit_transaction(consensus_transaction_wrapper)
             .await?;
 
-        state
-            .handle_pre_consensus_transaction(&signed_transaction)
-            // .instrument(span)
-            .map_err(|e| tonic::Status::internal(e.to_string()))?;
-
         let processing_time_in_micros: u64 = SystemTime::now()
             .duration_since(start)
             .unwrap()
@@ -277,6 +284,7 @@ impl ValidatorGrpc for ValidatorService {
                     Ok(Response::new(BlockInfoResponse {
                         successful: true,
                         serialized_block_info,
+                        halted: validator_state.is_halted(),
                     }))
                 } else {
                     Err(Status::not_found("Latest block info was not found."))
@@ -307,6 +315,7 @@ impl ValidatorGrpc for ValidatorService {
                     Ok(Response::new(BlockInfoResponse {
                         successful: true,
                         serialized_block_info,
+                        halted: validator_state.is_halted(),
                     }))
                 } else {
                     Err(Status::not_found("Block info was not found."))
@@ -353,9 +362,76 @@ impl ValidatorGrpc for ValidatorService {
         Ok(Response::new(MetricsResponse {
             average_latency: metrics.get_average_latency_in_micros(),
             average_tps: metrics.get_average_tps(),
+            halted: validator_state.is_halted(),
         }))
     }
 
+    async fn list_catchup_states(
+        &self,
+        _request: Request<ListCatchupStatesRequest>,
+    ) -> Result<Response<ListCatchupStatesResponse>, Status> {
+        let validator_state = &self.state;
+        let critical_path_store = &validator_state.validator_store.critical_path_store;
+        let executed_block_number = validator_state.executed_block_number.load(Ordering::SeqCst);
+
+        // list the latest scheduled or requested catchup states, oldest first
+        let mut serialized_manifests = Vec::new();
+        for (_, manifest) in critical_path_store
+            .get_catchup_manifests()
+            .await
+            .into_iter()
+            .filter(|(block_number, _)| *block_number <= executed_block_number)
+            .take(MAX_LISTED_CATCHUP_STATES)
+        {
+            serialized_manifests.push(Bytes::from(
+                bincode::serialize(&manifest).map_err(|_| Status::unknown("Failed to serialize catchup manifest"))?,
+            ));
+        }
+        serialized_manifests.reverse();
+
+        Ok(Response::new(ListCatchupStatesResponse {
+            successful: true,
+            serialized_manifests,
+        }))
+    }
+
+    type GetCatchupStateStream = tokio_stream::Iter<std::vec::IntoIter<Result<CatchupStateChunk, Status>>>;
+
+    async fn get_catchup_state(
+        &self,
+        request: Request<CatchupStateRequest>,
+    ) -> Result<Response<Self::GetCatchupStateStream>, Status> {
+        let validator_state = &self.state;
+        let block_number = request.into_inner().block_number;
+
+        let catchup_state = validator_state
+            .validator_store
+            .critical_path_store
+            .catchup_state_store
+            .read(block_number)
+            .await
+            .map_err(|err| Status::unknown(err.to_string()))?
+            .ok_or_else(|| Status::not_found("Catchup state was not found."))?;
+        let serialized_catchup_state =
+            bincode::serialize(&catchup_state).map_err(|_| Status::unknown("Failed to serialize catchup state"))?;
+
+        let total_chunks = serialized_catchup_state.chunks(CATCHUP_STATE_CHUNK_SIZE).len() as u64;
+        let chunks: Vec<Result<CatchupStateChunk, Status>> = serialized_catchup_state
+            .chunks(CATCHUP_STATE_CHUNK_SIZE)
+            .enumerate()
+            .map(|(chunk_index, data)| {
+                Ok(CatchupStateChunk {
+                    block_number,
+                    chunk_index: chunk_index as u64,
+                    total_chunks,
+                    data: Bytes::copy_from_slice(data),
+                })
+            })
+            .collect();
+
+        Ok(Response::new(tokio_stream::iter(chunks)))
+    }
+
     // PUT REQUESTS
 
     async fn submit_transaction(
@@ -393,6 +469,68 @@ impl ValidatorGrpc for ValidatorService {
         }
         Ok(tonic::Response::new(Empty {}))
     }
+
+    async fn submit_transaction_and_wait(
+        &self,
+        request: tonic::Request<SubmitTransactionAndWaitRequest>,
+    ) -> Result<tonic::Response<ExecutedTransactionResponse>, tonic::Status> {
+        trace!("Handling a new transaction in ValidatorGrpc submit_transaction_and_wait",);
+        let request = request.into_inner();
+        let signed_transaction = request
+            .signed_transaction
+            .ok_or_else(|| Status::invalid_argument("Missing signed transaction"))?;
+        let transaction_digest = signed_transaction
+            .get_transaction_digest()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let timeout = Duration::from_millis(request.timeout_in_ms.min(MAX_SUBMIT_AND_WAIT_TIMEOUT_IN_MS));
+
+        let state = self.state.clone();
+        let consensus_adapter = self.consensus_adapter.clone();
+        tokio::spawn(async move { Self::handle_transaction(consensus_adapter, state, signed_transaction).await })
+            .await
+            .unwrap()?;
+
+        // the digest index is written once the block holding the transaction is stored
+        let critical_path_store = &self.state.validator_store.critical_path_store;
+        tokio::time::timeout(
+            timeout,
+            critical_path_store
+                .transaction_location_store
+                .notify_read(transaction_digest),
+        )
+        .await
+        .map_err(|_| Status::deadline_exceeded("Transaction was not finalized before the timeout."))?
+        .map_err(|err| Status::unknown(err.to_string()))?;
+
+        // batch writes notify without the value, so the location is read back
+        let location = critical_path_store
+            .transaction_location_store
+            .read(transaction_digest)
+            .await
+            .map_err(|err| Status::unknown(err.to_string()))?
+            .ok_or_else(|| Status::not_found("Transaction location was not found."))?;
+        let block = critical_path_store
+            .block_store
+            .read(location.block_number)
+            .await
+            .map_err(|err| Status::unknown(err.to_string()))?
+            .ok_or_else(|| Status::not_found("Block was not found."))?;
+        let executed_transaction = block
+            .transactions
+            .get(location.position as usize)
+            .ok_or_else(|| Status::not_found("Transaction was not found in its block."))?;
+        let serialized_executed_transaction = Bytes::from(
+            bincode::serialize(executed_transaction)
+                .map_err(|_| Status::unknown("Failed to serialize executed transaction"))?,
+        );
+
+        Ok(Response::new(ExecutedTransactionResponse {
+            successful: true,
+            block_number: location.block_number,
+            position: location.position,
+            serialized_executed_transaction,
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -402,17 +540,19 @@ mod test_validator_server {
         builder::genesis_state::GenesisStateBuilder,
         client,
         genesis_ceremony::{VALIDATOR_BALANCE, VALIDATOR_FUNDING_AMOUNT},
-        validator::metrics::ValidatorMetrics,
+        validator::{metrics::ValidatorMetrics, state::test_validator_state::get_test_validator_state},
     };
     use fermi_controller::bank::proto::bank_controller_test_functions::generate_signed_test_transaction;
-    use fermi_controller::router::ControllerRouter;
+    use fermi_controller::router::{ControllerRouter, CATCHUP_STATE_FREQUENCY};
     use fermi_types::{
         account::{account_test_functions::generate_keypair_vec, ValidatorKeyPair, ValidatorPubKeyBytes},
         crypto::{get_key_pair_from_rng, KeypairTraits},
         node::ValidatorInfo,
         proto::ValidatorGrpcClient,
+        store::{CatchupManifest, CatchupState},
         utils,
     };
+    use narwhal_types::CertificateDigest;
 
     async fn spawn_test_validator_server() -> Result<ValidatorServerHandle, io::Error> {
         let controller_router = ControllerRouter::default();
@@ -474,7 +614,7 @@ mod test_validator_server {
 
         let kp_sender = generate_keypair_vec([0; 32]).pop().unwrap();
         let kp_receiver = generate_keypair_vec([1; 32]).pop().unwrap();
-        let signed_transaction = generate_signed_test_transaction(&kp_sender, &kp_receiver, 10);
+        let signed_transaction = generate_signed_test_transaction(&kp_sender, &kp_receiver, 10, 0);
 
         let _resp1 = client
             .submit_transaction(signed_transaction)
@@ -482,6 +622,38 @@ mod test_validator_server {
             .map_err(|e| io::Error::new(io::ErrorKind::Other, e));
     }
 
+    #[tokio::test]
+    pub async fn halted_server_rejects_transactions() {
+        let validator_state = Arc::new(get_test_validator_state());
+        validator_state.halt_validator();
+
+        let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+        let handle = ValidatorServer::new(
+            utils::new_network_address(),
+            validator_state.clone(),
+            vec![utils::new_network_address()],
+            tx_reconfigure_consensus,
+        )
+        .spawn()
+        .await
+        .unwrap();
+        let mut client = ValidatorGrpcClient::new(client::connect_lazy(handle.grpc_address()).unwrap());
+
+        let kp_sender = generate_keypair_vec([0; 32]).pop().unwrap();
+        let kp_receiver = generate_keypair_vec([1; 32]).pop().unwrap();
+        let signed_transaction = generate_signed_test_transaction(&kp_sender, &kp_receiver, 10, 0);
+        let status = client.submit_transaction(signed_transaction).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+        assert_eq!(validator_state.metrics.transactions_received_failed.get(), 1);
+
+        let metrics = client.get_latest_metrics(MetricsRequest {}).await.unwrap().into_inner();
+        assert!(metrics.halted);
+
+        validator_state.unhalt_validator();
+        let metrics = client.get_latest_metrics(MetricsRequest {}).await.unwrap().into_inner();
+        assert!(!metrics.halted);
+    }
+
     #[tokio::test]
     pub async fn spawn() {
         let controller_router = ControllerRouter::default();
@@ -529,4 +701,72 @@ mod test_validator_server {
         );
         validator_server.spawn().await.unwrap();
     }
+
+    #[tokio::test]
+    pub async fn server_lists_and_streams_catchup_states() {
+        let validator_state = Arc::new(get_test_validator_state());
+        // a requested catchup state is taken off the CATCHUP_STATE_FREQUENCY schedule
+        let requested_catchup_state = validator_state
+            .controller_router
+            .get_catchup_state(1, CertificateDigest::new([0; 32]))
+            .unwrap();
+        let catchup_state = validator_state
+            .controller_router
+            .get_catchup_state(CATCHUP_STATE_FREQUENCY, CertificateDigest::new([0; 32]))
+            .unwrap();
+        let critical_path_store = &validator_state.validator_store.critical_path_store;
+        critical_path_store
+            .write_catchup_state(1, requested_catchup_state.clone())
+            .await;
+        critical_path_store
+            .write_catchup_state(CATCHUP_STATE_FREQUENCY, catchup_state.clone())
+            .await;
+        validator_state
+            .executed_block_number
+            .store(CATCHUP_STATE_FREQUENCY + 1, Ordering::SeqCst);
+
+        let (tx_reconfigure_consensus, _rx_reconfigure_consensus) = tokio::sync::mpsc::channel(10);
+        let handle = ValidatorServer::new(
+            utils::new_network_address(),
+            validator_state,
+            vec![utils::new_network_address()],
+            tx_reconfigure_consensus,
+        )
+        .spawn()
+        .await
+        .unwrap();
+        let mut client = ValidatorGrpcClient::new(client::connect_lazy(handle.grpc_address()).unwrap());
+
+        let list_response = client
+            .list_catchup_states(ListCatchupStatesRequest {})
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(list_response.serialized_manifests.len(), 2);
+        let manifest: CatchupManifest = bincode::deserialize(&list_response.serialized_manifests[0]).unwrap();
+        assert_eq!(manifest, requested_catchup_state.manifest);
+        let manifest: CatchupManifest = bincode::deserialize(&list_response.serialized_manifests[1]).unwrap();
+        assert_eq!(manifest, catchup_state.manifest);
+
+        let mut chunks = client
+            .get_catchup_state(CatchupStateRequest {
+                block_number: CATCHUP_STATE_FREQUENCY,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        let mut serialized_catchup_state = Vec::new();
+        while let Some(chunk) = chunks.message().await.unwrap() {
+            serialized_catchup_state.extend_from_slice(&chunk.data);
+        }
+        let streamed_catchup_state: CatchupState = bincode::deserialize(&serialized_catchup_state).unwrap();
+        streamed_catchup_state.verify().unwrap();
+        assert_eq!(streamed_catchup_state.manifest.root(), catchup_state.manifest.root());
+
+        // missing catchup states are reported as not found
+        assert!(client
+            .get_catchup_state(CatchupStateRequest { block_number: 2 })
+            .await
+            .is_err());
+    }
 }
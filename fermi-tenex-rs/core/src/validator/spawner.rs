@@ -6,8 +6,13 @@ use crate::{
     genesis_ceremony::GENESIS_FILENAME,
     json_rpc::spawner::JSONServiceSpawner,
     validator::{
-        consensus_adapter::ConsensusAdapter, genesis_state::ValidatorGenesisState, metrics::ValidatorMetrics,
-        post_processor::ValidatorPostProcessor, server::ValidatorServer, server::ValidatorService,
+        admin::{AdminServer, TracingFilterHandle},
+        consensus_adapter::ConsensusAdapter,
+        genesis_state::ValidatorGenesisState,
+        metrics::ValidatorMetrics,
+        post_processor::ValidatorPostProcessor,
+        server::ValidatorServer,
+        server::ValidatorService,
         state::ValidatorState,
     },
 };
@@ -19,12 +24,12 @@ use fermi_types::{node::ValidatorInfo, utils};
 use futures::future::join_all;
 use multiaddr::Multiaddr;
 use prometheus::Registry;
-use std::{path::PathBuf, sync::Arc};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 use tokio::{
     sync::mpsc::{channel, Receiver, Sender},
     task::JoinHandle,
 };
-use tracing::info;
+use tracing::{error, info};
 
 // mysten
 use narwhal_config::{Committee as ConsensusCommittee, Parameters as ConsensusParameters};
@@ -54,8 +59,14 @@ pub struct ValidatorSpawner {
     /// Address for communication to the validator JSON RPC server
     // TODO - make this a multiaddr
     jsonrpc_address: Multiaddr,
+    /// Address for JSON RPC subscriptions over websocket
+    websocket_address: SocketAddr,
     /// Address for communication to the metrics server
     metrics_address: Multiaddr,
+    /// Localhost port of the admin interface
+    admin_interface_port: u16,
+    /// Lets the admin interface change the tracing filter, when the process installed a reloadable one
+    tracing_filter_handle: Option<Arc<dyn TracingFilterHandle>>,
 
     /// Begin objects initialized after calling spawn_validator_service
 
@@ -104,7 +115,10 @@ impl ValidatorSpawner {
             grpc_address,
             // TODO - make this configurable
             jsonrpc_address,
+            websocket_address: utils::available_local_socket_address(),
             metrics_address,
+            admin_interface_port: utils::get_available_port(),
+            tracing_filter_handle: None,
             validator_state: None,
             consensus_adapter: None,
             tx_reconfigure_consensus: None,
@@ -122,6 +136,14 @@ impl ValidatorSpawner {
         &self.jsonrpc_address
     }
 
+    pub fn get_websocket_address(&self) -> &SocketAddr {
+        &self.websocket_address
+    }
+
+    pub fn get_admin_interface_port(&self) -> u16 {
+        self.admin_interface_port
+    }
+
     pub fn get_validator_info(&self) -> &ValidatorInfo {
         &self.validator_info
     }
@@ -155,6 +177,18 @@ impl ValidatorSpawner {
     }
 
     // SETTERS
+    pub fn set_websocket_address(&mut self, websocket_address: SocketAddr) {
+        self.websocket_address = websocket_address;
+    }
+
+    pub fn set_admin_interface_port(&mut self, admin_interface_port: u16) {
+        self.admin_interface_port = admin_interface_port;
+    }
+
+    pub fn set_tracing_filter_handle(&mut self, tracing_filter_handle: Arc<dyn TracingFilterHandle>) {
+        self.tracing_filter_handle = Some(tracing_filter_handle);
+    }
+
     pub fn halt_validator(&mut self) {
         self.validator_state.as_mut().unwrap().halt_validator();
     }
@@ -220,9 +254,9 @@ impl ValidatorSpawner {
             consensus_db_path,
             grpc_db_path: grpc_db_path.clone(),
             metrics_address: self.metrics_address.clone(),
-            admin_interface_port: utils::get_available_port(),
+            admin_interface_port: self.admin_interface_port,
             json_rpc_address: self.jsonrpc_address.clone(),
-            websocket_address: Some(utils::available_local_socket_address()),
+            websocket_address: Some(self.websocket_address),
             consensus_config: Some(consensus_config),
             enable_event_processing: true,
             enable_gossip: true,
@@ -243,6 +277,11 @@ impl ValidatorSpawner {
             &grpc_db_path,
             metrics,
         ));
+        // rebuild controller state from the local store before narwhal asks where to resume execution
+        validator_state
+            .restore_from_store()
+            .await
+            .expect("Failed to restore the validator state");
 
         // channel to communicate between narwhal + post process service
         let (tx_narwhal_to_post_process, rx_narwhal_to_post_process) = channel(1_000);
@@ -262,9 +301,15 @@ impl ValidatorSpawner {
         validator_handles.extend(narwhal_handles);
         validator_handles.extend(prometheus_server_handle);
 
-        // spawn post process service
-        let post_process_handles =
-            ValidatorPostProcessor::spawn(rx_narwhal_to_post_process, Arc::clone(&validator_state)).unwrap();
+        // spawn post process service, which restarts consensus through the reconfigure channel at epoch boundaries
+        // unwrapping is safe as the reconfigure channel is created in spawn_validator
+        let tx_reconfigure_consensus = self.tx_reconfigure_consensus.as_ref().unwrap().clone();
+        let post_process_handles = ValidatorPostProcessor::spawn(
+            rx_narwhal_to_post_process,
+            Arc::clone(&validator_state),
+            tx_reconfigure_consensus,
+        )
+        .unwrap();
         validator_handles.extend(post_process_handles);
 
         self.service_handles = Some(validator_handles);
@@ -298,12 +343,25 @@ impl ValidatorSpawner {
             self.genesis_state.clone(),
             self.grpc_address.clone(),
             self.jsonrpc_address.clone(),
+            self.websocket_address,
             jsonrpc_db_path.clone(),
         );
 
         let mut jsonrpc_handle = jsonrpc_spawner.spawn_jsonrpc_service().await.unwrap();
         jsonrpc_handle.push(validator_server_handle.get_handle());
 
+        // the validator keeps running without its admin interface, e.g. when the port is taken
+        let admin_server = AdminServer::new(
+            self.admin_interface_port,
+            Arc::clone(self.validator_state.as_ref().unwrap()),
+            Arc::clone(self.consensus_adapter.as_ref().unwrap()),
+            self.tracing_filter_handle.clone(),
+        );
+        match admin_server.spawn().await {
+            Ok(admin_handle) => jsonrpc_handle.push(admin_handle),
+            Err(e) => error!("Failed to spawn the admin interface: {:?}", e),
+        }
+
         self.server_handles = Some(jsonrpc_handle);
     }
 
@@ -313,8 +371,6 @@ impl ValidatorSpawner {
         self.tx_reconfigure_consensus = Some(tx_reconfigure_consensus);
         self.spawn_validator_service(rx_reconfigure_consensus).await;
         self.spawn_validator_server().await;
-        // Unwrap is safe since we have already launched the validator service
-        self.validator_state.as_ref().unwrap().halt_validator();
     }
 
     pub async fn await_handles(&mut self) {
@@ -1,17 +1,19 @@
 use super::genesis_state::ValidatorGenesisState;
-use crate::validator::metrics::ValidatorMetrics;
+use crate::validator::{metrics::ValidatorMetrics, scheduler::BlockScheduler};
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use fastcrypto::Hash;
-use fermi_controller::router::ControllerRouter;
+use fermi_controller::router::{ControllerRouter, CATCHUP_STATE_FREQUENCY};
 use fermi_types::{
     account::ValidatorKeyPair,
     block::{Block, BlockCertificate, BlockDigest, BlockInfo, BlockNumber},
     committee::{Committee, ValidatorName},
     error::GDEXError,
-    store::CriticalPathStore,
+    node::ValidatorInfo,
+    store::{CriticalPathStore, StateRoot, TransactionLocation},
     transaction::{ExecutedTransaction, SignedTransaction, Transaction, TransactionDigest},
 };
+use narwhal_config::Committee as ConsensusCommittee;
 use narwhal_consensus::ConsensusOutput;
 use narwhal_executor::{ExecutionIndices, ExecutionState};
 use narwhal_types::CertificateDigest;
@@ -25,13 +27,16 @@ use std::{
     },
     time::{SystemTime, UNIX_EPOCH},
 };
-use tracing::{info, trace};
+use tokio::sync::Notify;
+use tracing::{error, info, trace};
 
-/// Tracks recently submitted transactions to implement transaction gating
+/// Tracks recent blocks to implement transaction gating
+/// replay protection is enforced at execution through the per-account transaction nonce
 pub struct ValidatorStore {
-    /// The transaction map tracks recently submitted transactions
-    transaction_cache: Mutex<HashMap<TransactionDigest, Option<BlockDigest>>>,
+    /// The block digest map tracks recent blocks which transactions may reference
     block_digest_cache: Mutex<HashMap<BlockDigest, BlockNumber>>,
+    // roots of catchup states taken during execution, waiting for their block info to be written
+    catchup_state_roots: Mutex<HashMap<BlockNumber, StateRoot>>,
     // garbage collection depth
     gc_depth: u64,
     pub block_number: AtomicU64,
@@ -43,12 +48,11 @@ impl ValidatorStore {
         let critical_path_store = CriticalPathStore::reopen(store_path);
         let last_block_info = critical_path_store.last_block_info.clone();
 
-        // TODO load the state if last block is not 0, i.e. not at genesis
+        // controller state up to this block is rebuilt by ValidatorState::restore_from_store
         let block_number = match last_block_info {
             Ok(o) => {
                 if let Some(v) = o {
                     v.block_number
-                    // mark for replay somehow here
                 } else {
                     0
                 }
@@ -66,57 +70,39 @@ impl ValidatorStore {
         }
 
         Self {
-            transaction_cache: Mutex::new(HashMap::new()),
             block_digest_cache,
+            catchup_state_roots: Mutex::new(HashMap::new()),
             gc_depth: 50,
             critical_path_store,
             block_number: AtomicU64::new(block_number),
         }
     }
 
-    pub fn cache_contains_transaction(&self, transaction: &Transaction) -> bool {
-        return self
-            .transaction_cache
-            .lock()
-            .unwrap()
-            .contains_key(&transaction.digest());
-    }
-
     pub fn cache_contains_block_digest(&self, block_digest: &BlockDigest) -> bool {
         return self.block_digest_cache.lock().unwrap().contains_key(block_digest);
     }
 
-    pub fn insert_unconfirmed_transaction(&self, transaction: &Transaction) {
-        self.transaction_cache.lock().unwrap().insert(
-            transaction.digest(),
-            None, // Insert with no block digest, a digest will be added after confirmation
-        );
+    pub fn insert_confirmed_block(&self, consensus_output: &ConsensusOutput) {
+        self.block_digest_cache
+            .lock()
+            .unwrap()
+            .insert(consensus_output.certificate.digest(), consensus_output.consensus_index);
     }
 
-    pub fn insert_confirmed_transaction(
-        &self,
-        transaction: &Transaction,
-        consensus_output: &ConsensusOutput,
-    ) -> Result<(), GDEXError> {
-        let block_digest = consensus_output.certificate.digest();
-        let transaction_digest = transaction.digest();
-
-        // return an error if transaction has already been seen before
-        if let Some(digest) = self.transaction_cache.lock().unwrap().get(&transaction_digest) {
-            if digest.is_some() {
-                return Err(GDEXError::TransactionDuplicate);
-            }
-        }
-
-        self.transaction_cache
+    /// Caches a block replayed from the block store so that transactions may reference it
+    pub fn insert_replayed_block(&self, block_digest: BlockDigest, block_number: BlockNumber) {
+        self.block_digest_cache
             .lock()
             .unwrap()
-            .insert(transaction_digest, Some(block_digest));
-        self.block_digest_cache
+            .insert(block_digest, block_number);
+    }
+
+    /// Records the root of a catchup state so that it is published in the block info of its block
+    pub fn insert_catchup_state_root(&self, block_number: BlockNumber, catchup_state_root: StateRoot) {
+        self.catchup_state_roots
             .lock()
             .unwrap()
-            .insert(block_digest, consensus_output.consensus_index);
-        Ok(())
+            .insert(block_number, catchup_state_root);
     }
 
     pub async fn write_latest_block(
@@ -145,10 +131,13 @@ impl ValidatorStore {
             .try_into()
             .unwrap();
 
+        let catchup_state_root = self.catchup_state_roots.lock().unwrap().remove(&block_number);
+
         let block_info = BlockInfo {
             block_number,
             block_digest,
             validator_system_epoch_time_in_micros,
+            catchup_state_root,
         };
 
         // write-out the block information to associated stores
@@ -164,18 +153,61 @@ impl ValidatorStore {
             .last_block_info_store
             .write(0, block_info.clone())
             .await;
+        // indexed once the block is stored so that waiters can read the transaction from its block
+        self.write_transaction_locations(&block, block_number).await;
         (block, block_info)
     }
 
+    /// Indexes the transactions of a block by digest, keeping the first execution of a replayed transaction
+    async fn write_transaction_locations(&self, block: &Block, block_number: BlockNumber) {
+        let digests: Vec<(TransactionDigest, u64)> = block
+            .transactions
+            .iter()
+            .enumerate()
+            .filter_map(|(position, executed_transaction)| {
+                executed_transaction
+                    .signed_transaction
+                    .get_transaction_digest()
+                    .ok()
+                    .map(|digest| (digest, position as u64))
+            })
+            .collect();
+        let transaction_location_store = &self.critical_path_store.transaction_location_store;
+        let existing_locations = match transaction_location_store
+            .read_all(digests.iter().map(|(digest, _)| *digest))
+            .await
+        {
+            Ok(existing_locations) => existing_locations,
+            Err(err) => {
+                error!(
+                    "Failed to read transaction locations for block {}: {}",
+                    block_number, err
+                );
+                return;
+            }
+        };
+
+        let mut locations: HashMap<TransactionDigest, TransactionLocation> = HashMap::new();
+        for ((digest, position), existing_location) in digests.into_iter().zip(existing_locations) {
+            if existing_location.is_none() {
+                locations
+                    .entry(digest)
+                    .or_insert(TransactionLocation { block_number, position });
+            }
+        }
+        if let Err(err) = transaction_location_store.write_all(locations).await {
+            error!(
+                "Failed to write transaction locations for block {}: {}",
+                block_number, err
+            );
+        }
+    }
+
     pub fn prune(&self) {
         let mut locked_block_digest_cache = self.block_digest_cache.lock().unwrap();
         if locked_block_digest_cache.len() > self.gc_depth as usize {
             let mut threshold = locked_block_digest_cache.values().max().unwrap() - self.gc_depth;
             locked_block_digest_cache.retain(|_k, v| v > &mut threshold);
-            self.transaction_cache
-                .lock()
-                .unwrap()
-                .retain(|_k, v| v.is_none() || locked_block_digest_cache.contains_key(&v.unwrap()));
         }
     }
 }
@@ -201,9 +233,21 @@ pub struct ValidatorState {
     pub secret: StableSyncValidatorSigner,
     /// A global lock to halt all transaction/cert processing.
     halted: AtomicBool,
+    /// Wakes execution once the validator is unhalted
+    unhalted: Notify,
+    /// Whether execution is inside a block, halting only takes effect between blocks
+    block_in_progress: AtomicBool,
+    /// Set by operators to take a catchup state at the end of the current block
+    catchup_state_requested: AtomicBool,
+    /// Transactions of the current block, executed together once consensus delivers the last of them
+    pending_transactions: Mutex<Vec<SignedTransaction>>,
+    /// Executes the transactions of a block, in parallel where they do not conflict
+    scheduler: BlockScheduler,
     // Epoch related information.
     /// Committee of this GDEX instance.
     pub committee: ArcSwap<Committee>,
+    /// Validators known from genesis, the source of network addresses for new committees
+    pub validator_set: Vec<ValidatorInfo>,
     /// NodeConfig for this node
     /// Controller of various blockchain modules
     pub controller_router: ControllerRouter,
@@ -211,6 +255,8 @@ pub struct ValidatorState {
     pub catchup_router: ControllerRouter,
     /// A map of transactions which have been seen
     pub validator_store: ValidatorStore,
+    /// Number of the last block whose transactions and end of block processing have been executed
+    pub executed_block_number: AtomicU64,
     /// Metrics around blockchain operations
     pub metrics: Arc<ValidatorMetrics>,
 }
@@ -225,37 +271,219 @@ impl ValidatorState {
         store_db_path: &PathBuf,
         metrics: Arc<ValidatorMetrics>,
     ) -> Self {
+        let validator_store = ValidatorStore::reopen(store_db_path);
+        let executed_block_number = AtomicU64::new(validator_store.block_number.load(Ordering::SeqCst));
         ValidatorState {
             name,
             secret,
             halted: AtomicBool::new(false),
+            unhalted: Notify::new(),
+            block_in_progress: AtomicBool::new(false),
+            catchup_state_requested: AtomicBool::new(false),
+            pending_transactions: Mutex::new(Vec::new()),
+            scheduler: BlockScheduler::default(),
             committee: ArcSwap::from(Arc::new(genesis.committee().unwrap())),
+            validator_set: genesis.validator_set().to_vec(),
             controller_router: genesis.controller_router().clone(),
             catchup_router: genesis.controller_router().clone(),
-            validator_store: ValidatorStore::reopen(store_db_path),
+            validator_store,
+            executed_block_number,
             metrics,
         }
     }
 
+    /// Stops accepting new transactions and pauses execution at the next block boundary
     pub fn halt_validator(&self) {
-        self.halted.store(true, Ordering::Relaxed);
+        self.halted.store(true, Ordering::SeqCst);
+        self.metrics.halted.set(1);
     }
 
     pub fn unhalt_validator(&self) {
-        self.halted.store(false, Ordering::Relaxed);
+        self.halted.store(false, Ordering::SeqCst);
+        self.metrics.halted.set(0);
+        self.unhalted.notify_waiters();
     }
 
     pub fn is_halted(&self) -> bool {
-        self.halted.load(Ordering::Relaxed)
+        self.halted.load(Ordering::SeqCst)
+    }
+
+    /// Resolves immediately unless the validator is halted, in which case it waits for unhalt_validator
+    pub async fn wait_until_unhalted(&self) {
+        loop {
+            // register interest before checking the flag so an unhalt in between is not missed
+            let unhalted = self.unhalted.notified();
+            if !self.is_halted() {
+                return;
+            }
+            unhalted.await;
+        }
+    }
+
+    /// Requests a catchup state outside of the regular schedule, taken once the current block has executed
+    pub fn request_catchup_state(&self) {
+        self.catchup_state_requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Rebuilds the committee from stake once the stake controller has entered a new epoch,
+    /// returning the consensus committee that narwhal should be restarted with
+    pub fn reconfigure_committee(&self) -> Option<ConsensusCommittee> {
+        let (epoch, voting_rights) = {
+            let stake_controller = self.controller_router.stake_controller.lock().unwrap();
+            (stake_controller.get_epoch(), stake_controller.get_voting_rights())
+        };
+        if epoch <= self.committee.load().epoch() {
+            return None;
+        }
+
+        // stake is only accepted from the genesis validator set, this guards states built without one
+        let voting_rights = voting_rights
+            .into_iter()
+            .filter(|(name, _)| self.validator_set.iter().any(|v| v.public_key() == *name))
+            .collect();
+        let committee = match Committee::new(epoch, voting_rights) {
+            Ok(committee) => committee,
+            Err(e) => {
+                error!("Failed to build the committee for epoch {epoch}: {:?}", e);
+                return None;
+            }
+        };
+        let consensus_committee = ValidatorGenesisState::build_narwhal_committee(
+            &self.validator_set,
+            epoch,
+            &committee.voting_rights.iter().cloned().collect(),
+        );
+        info!("Entering epoch {epoch} with committee {:?}", committee.voting_rights);
+        self.committee.store(Arc::new(committee));
+        Some(consensus_committee)
+    }
+
+    /// Returns the consensus committee of the current epoch, which narwhal is started with
+    pub fn get_consensus_committee(&self) -> ConsensusCommittee {
+        let committee = self.committee.load();
+        ValidatorGenesisState::build_narwhal_committee(
+            &self.validator_set,
+            committee.epoch(),
+            &committee.voting_rights.iter().cloned().collect(),
+        )
     }
 }
 
 impl ValidatorState {
-    /// Initiate a new transaction.
+    /// Rebuilds the controller state after a restart by loading the latest catchup snapshot
+    /// and replaying the stored blocks which follow it
+    pub async fn restore_from_store(&self) -> Result<(), GDEXError> {
+        let critical_path_store = &self.validator_store.critical_path_store;
+        let last_block_number = self.validator_store.block_number.load(Ordering::SeqCst);
+        if last_block_number == 0 {
+            return Ok(());
+        }
+
+        // snapshots are taken ahead of the block store, so start from the latest one up to the last stored block
+        // scheduled and requested snapshots are both found through the manifest index
+        let mut snapshot_block_number = 0;
+        for (block_number, _) in critical_path_store.get_catchup_manifests().await {
+            if block_number > last_block_number {
+                continue;
+            }
+            let catchup_state = critical_path_store
+                .catchup_state_store
+                .read(block_number)
+                .await
+                .map_err(|_| GDEXError::StateRestore)?;
+            if let Some(catchup_state) = catchup_state {
+                if catchup_state.manifest.block_number != block_number {
+                    error!("Catchup state stored at block {block_number} was taken at a different block");
+                    continue;
+                }
+                match self.controller_router.restore_catchup_state(&catchup_state) {
+                    Ok(()) => {
+                        snapshot_block_number = block_number;
+                        break;
+                    }
+                    // fall back to an older snapshot, e.g. one written before an upgrade
+                    Err(e) => error!("Failed to restore catchup state at block {block_number}: {:?}", e),
+                }
+            }
+        }
+        info!(
+            "Restoring validator state from block {snapshot_block_number} and replaying up to block {last_block_number}"
+        );
+
+        for block_number in snapshot_block_number + 1..=last_block_number {
+            let block = critical_path_store
+                .block_store
+                .read(block_number)
+                .await
+                .map_err(|_| GDEXError::StateRestore)?
+                .ok_or(GDEXError::StateRestore)?;
+            self.validator_store
+                .insert_replayed_block(block.block_certificate.digest(), block_number);
+
+            // replayed transactions are rejected by their nonce exactly as during execution
+            let transactions = block
+                .transactions
+                .iter()
+                .map(|executed_transaction| executed_transaction.signed_transaction.get_transaction())
+                .collect::<Result<Vec<&Transaction>, GDEXError>>()?;
+            let _ = self.scheduler.execute_block(&self.controller_router, &transactions);
+            self.controller_router
+                .critical_process_end_of_block(critical_path_store, block_number)?;
+        }
+        self.executed_block_number.store(last_block_number, Ordering::SeqCst);
+
+        // the restored stake may be in a later epoch than genesis, consensus must start with its committee
+        self.reconfigure_committee();
+        Ok(())
+    }
+
+    /// Runs end of block processing once execution reaches the end of a block, persisting the execution
+    /// indices and, every CATCHUP_STATE_FREQUENCY blocks or on request, a snapshot of the controllers
+    async fn process_end_of_block(
+        &self,
+        consensus_output: &ConsensusOutput,
+        execution_indices: &ExecutionIndices,
+    ) -> Result<(), GDEXError> {
+        let critical_path_store = &self.validator_store.critical_path_store;
+        let block_number = self.executed_block_number.fetch_add(1, Ordering::SeqCst) + 1;
+
+        critical_path_store
+            .execution_indices_store
+            .write_all([(block_number, execution_indices.clone())])
+            .await
+            .map_err(|_| GDEXError::ExecError)?;
+        self.controller_router
+            .critical_process_end_of_block(critical_path_store, block_number)?;
+
+        let catchup_state_requested = self.catchup_state_requested.swap(false, Ordering::SeqCst);
+        if catchup_state_requested || block_number % CATCHUP_STATE_FREQUENCY == 0 {
+            if catchup_state_requested {
+                info!("Taking a requested catchup state at block {block_number}");
+            }
+            let catchup_state = self
+                .controller_router
+                .get_catchup_state(block_number, consensus_output.certificate.digest())?;
+            self.validator_store
+                .insert_catchup_state_root(block_number, catchup_state.manifest.root());
+            critical_path_store
+                .write_catchup_state(block_number, catchup_state)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Initiate a new transaction, rejecting it early if the sender has already used its nonce
+    /// or pays less than the minimum fee
     pub fn handle_pre_consensus_transaction(&self, signed_transaction: &SignedTransaction) -> Result<(), GDEXError> {
         trace!("Handling a new pre-consensus transaction with the ValidatorState",);
         let transaction = signed_transaction.get_transaction()?;
-        self.validator_store.insert_unconfirmed_transaction(transaction);
+        // oversized batches and underpaying transactions would fail at execution anyway, so they are not sequenced
+        transaction.validate_request_count()?;
+        transaction.validate_fee()?;
+        let next_nonce = self.controller_router.get_next_nonce(&transaction.get_sender()?);
+        if transaction.nonce < next_nonce {
+            return Err(GDEXError::TransactionNonce);
+        }
         Ok(())
     }
 }
@@ -264,7 +492,8 @@ impl ValidatorState {
 impl ExecutionState for ValidatorState {
     type Transaction = SignedTransaction;
     type Error = GDEXError;
-    type Outcome = (ConsensusOutput, ExecutionIndices, ExecutedTransaction);
+    /// The executed transactions of a block, returned with its last transaction and empty before it
+    type Outcome = (ConsensusOutput, ExecutionIndices, Vec<ExecutedTransaction>);
 
     async fn handle_consensus_transaction(
         &self,
@@ -272,6 +501,15 @@ impl ExecutionState for ValidatorState {
         execution_indices: ExecutionIndices,
         signed_transaction: Self::Transaction,
     ) -> Result<Self::Outcome, Self::Error> {
+        // a halted validator pauses before starting a new block, consensus holds the remaining output until it resumes
+        if !self.block_in_progress.load(Ordering::SeqCst) {
+            if self.is_halted() {
+                info!("Validator is halted, pausing execution at the block boundary");
+            }
+            self.wait_until_unhalted().await;
+            self.block_in_progress.store(true, Ordering::SeqCst);
+        }
+
         self.metrics.transactions_executed.inc();
 
         // verify signed transaction signature
@@ -279,51 +517,55 @@ impl ExecutionState for ValidatorState {
             .verify_signature()
             .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
 
-        // get transaction
-        let transaction = signed_transaction.get_transaction()?;
+        // a transaction without a body is rejected before it is buffered
+        signed_transaction.get_transaction()?;
 
         // TODO - https://github.com/fermiorg/fermi/issues/162 - verify transaction signature prior to handling
 
-        // cache confirmed transaction
-        let uniqueness_check = self
-            .validator_store
-            .insert_confirmed_transaction(transaction, consensus_output);
-
-        // if transaction is not unique stop execution
-        if uniqueness_check.is_err() {
-            self.metrics.transactions_executed_failed.inc();
-            // safe to unwrap error
-            return Ok((
-                consensus_output.clone(),
-                execution_indices,
-                ExecutedTransaction {
-                    signed_transaction,
-                    events: Vec::new(),
-                    result: Err(uniqueness_check.err().unwrap()),
-                },
-            ));
-        }
-
-        let execution_result = self.controller_router.handle_consensus_transaction(transaction);
+        // cache confirmed block
+        self.validator_store.insert_confirmed_block(consensus_output);
 
-        let executed_transaction = match execution_result {
-            Ok(executed_events) => ExecutedTransaction {
-                signed_transaction,
-                events: executed_events,
-                result: Ok(()),
-            },
-            Err(e) => {
-                self.metrics.transactions_executed_failed.inc();
+        // the block executes once all of its transactions are known, so that independent ones can run in parallel
+        // the post processor closes the block on the same condition
+        let signed_transactions = {
+            let mut pending_transactions = self.pending_transactions.lock().unwrap();
+            pending_transactions.push(signed_transaction);
+            if execution_indices.next_transaction_index != 0 {
+                return Ok((consensus_output.clone(), execution_indices, Vec::new()));
+            }
+            std::mem::take(&mut *pending_transactions)
+        };
 
-                ExecutedTransaction {
+        let transactions = signed_transactions
+            .iter()
+            .map(|signed_transaction| signed_transaction.get_transaction())
+            .collect::<Result<Vec<&Transaction>, GDEXError>>()?;
+        let execution_results = self.scheduler.execute_block(&self.controller_router, &transactions);
+        let executed_transactions = signed_transactions
+            .into_iter()
+            .zip(execution_results)
+            .map(|(signed_transaction, execution_result)| match execution_result {
+                Ok(executed_events) => ExecutedTransaction {
                     signed_transaction,
-                    events: Vec::new(),
-                    result: Err(e),
+                    events: executed_events,
+                    result: Ok(()),
+                },
+                Err(e) => {
+                    self.metrics.transactions_executed_failed.inc();
+
+                    ExecutedTransaction {
+                        signed_transaction,
+                        events: Vec::new(),
+                        result: Err(e),
+                    }
                 }
-            }
-        };
+            })
+            .collect();
+
+        self.block_in_progress.store(false, Ordering::SeqCst);
+        self.process_end_of_block(consensus_output, &execution_indices).await?;
 
-        Ok((consensus_output.clone(), execution_indices, executed_transaction))
+        Ok((consensus_output.clone(), execution_indices, executed_transactions))
     }
 
     fn ask_consensus_write_lock(&self) -> bool {
@@ -336,7 +578,25 @@ impl ExecutionState for ValidatorState {
     }
 
     async fn load_execution_indices(&self) -> Result<ExecutionIndices, Self::Error> {
-        Ok(ExecutionIndices::default())
+        // resume narwhal right after the last block reflected in the controller state
+        // narwhal restarts on a fresh store at each epoch change, so an epoch without executed blocks starts from the beginning
+        let block_number = self.executed_block_number.load(Ordering::SeqCst);
+        let epoch_start_block = self
+            .controller_router
+            .stake_controller
+            .lock()
+            .unwrap()
+            .get_epoch_start_block();
+        if block_number <= epoch_start_block {
+            return Ok(ExecutionIndices::default());
+        }
+        self.validator_store
+            .critical_path_store
+            .execution_indices_store
+            .read(block_number)
+            .await
+            .map_err(|_| GDEXError::StateRestore)?
+            .ok_or(GDEXError::StateRestore)
     }
 
     fn deserialize(bytes: &[u8]) -> Result<Self::Transaction, bincode::Error> {
@@ -358,12 +618,14 @@ pub mod test_validator_state {
             create_cancel_order_transaction, create_create_orderbook_transaction, create_limit_order_transaction,
             create_update_order_transaction,
         },
+        utils::engine::order_book::get_first_order_id,
     };
     use fermi_types::{
         account::ValidatorPubKeyBytes,
         crypto::{get_key_pair_from_rng, KeypairTraits},
         node::ValidatorInfo,
-        order_book::OrderSide,
+        order_book::{OrderSide, TimeInForce},
+        transaction::Transaction,
         utils,
     };
     use narwhal_consensus::ConsensusOutput;
@@ -453,6 +715,11 @@ pub mod test_validator_state {
         ValidatorState::new(public_key, secret, &genesis, &store_path, metrics)
     }
 
+    fn sign_with_nonce(mut transaction: Transaction, nonce: u64, sender_kp: &KeyPair) -> SignedTransaction {
+        transaction.set_nonce(nonce);
+        transaction.sign(sender_kp).unwrap()
+    }
+
     fn create_test_execution_indices() -> ExecutionIndices {
         ExecutionIndices {
             next_certificate_index: 1,
@@ -461,6 +728,15 @@ pub mod test_validator_state {
         }
     }
 
+    // closes the block, so that its transactions are executed
+    fn create_end_of_block_execution_indices() -> ExecutionIndices {
+        ExecutionIndices {
+            next_certificate_index: 2,
+            next_batch_index: 0,
+            next_transaction_index: 0,
+        }
+    }
+
     fn create_test_consensus_output() -> ConsensusOutput {
         let dummy_header = Header::default();
         let dummy_certificate = Certificate {
@@ -478,7 +754,7 @@ pub mod test_validator_state {
     pub async fn process_create_asset_txn() {
         let validator: ValidatorState = create_test_validator();
         let dummy_consensus_output = create_test_consensus_output();
-        let dummy_execution_indices = create_test_execution_indices();
+        let dummy_execution_indices = create_end_of_block_execution_indices();
 
         // create asset transaction
         let sender_kp = generate_production_keypair::<KeyPair>();
@@ -501,7 +777,7 @@ pub mod test_validator_state {
     pub async fn process_payment_txn() {
         let validator: ValidatorState = create_test_validator();
         let dummy_consensus_output = create_test_consensus_output();
-        let dummy_execution_indices = create_test_execution_indices();
+        let dummy_execution_indices = create_end_of_block_execution_indices();
 
         // create asset transaction
         let sender_kp = generate_production_keypair::<KeyPair>();
@@ -529,7 +805,7 @@ pub mod test_validator_state {
             TEST_ASSET_ID,
             TEST_AMOUNT,
         );
-        let signed_transaction = transaction.sign(&sender_kp).unwrap();
+        let signed_transaction = sign_with_nonce(transaction, 1, &sender_kp);
 
         validator
             .handle_consensus_transaction(
@@ -541,12 +817,378 @@ pub mod test_validator_state {
             .unwrap();
     }
 
+    #[tokio::test]
+    pub async fn halted_validator_pauses_at_block_boundary() {
+        let validator = Arc::new(create_test_validator());
+        let dummy_consensus_output = create_test_consensus_output();
+        let end_of_block_indices = ExecutionIndices {
+            next_certificate_index: 2,
+            next_batch_index: 0,
+            next_transaction_index: 0,
+        };
+
+        let sender_kp = generate_production_keypair::<KeyPair>();
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let transaction = create_create_asset_transaction(sender_kp.public(), recent_block_hash, 0);
+        validator
+            .handle_consensus_transaction(
+                &dummy_consensus_output,
+                create_test_execution_indices(),
+                transaction.sign(&sender_kp).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // halting inside a block lets the block finish
+        validator.halt_validator();
+        assert_eq!(validator.metrics.halted.get(), 1);
+        let transaction = create_create_asset_transaction(sender_kp.public(), recent_block_hash, 0);
+        validator
+            .handle_consensus_transaction(
+                &dummy_consensus_output,
+                end_of_block_indices.clone(),
+                sign_with_nonce(transaction, 1, &sender_kp),
+            )
+            .await
+            .unwrap();
+        assert_eq!(validator.executed_block_number.load(Ordering::SeqCst), 1);
+
+        // the next block waits until the validator is unhalted
+        let transaction = create_create_asset_transaction(sender_kp.public(), recent_block_hash, 0);
+        let signed_transaction = sign_with_nonce(transaction, 2, &sender_kp);
+        let mut execution = {
+            let validator = validator.clone();
+            tokio::spawn(async move {
+                validator
+                    .handle_consensus_transaction(&dummy_consensus_output, end_of_block_indices, signed_transaction)
+                    .await
+            })
+        };
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(100), &mut execution)
+                .await
+                .is_err()
+        );
+        assert_eq!(validator.executed_block_number.load(Ordering::SeqCst), 1);
+
+        validator.unhalt_validator();
+        assert_eq!(validator.metrics.halted.get(), 0);
+        execution.await.unwrap().unwrap();
+        assert_eq!(validator.executed_block_number.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    pub async fn requested_catchup_state_taken_at_block_end() {
+        let validator: ValidatorState = create_test_validator();
+        let dummy_consensus_output = create_test_consensus_output();
+        let end_of_block_indices = ExecutionIndices {
+            next_certificate_index: 2,
+            next_batch_index: 0,
+            next_transaction_index: 0,
+        };
+
+        validator.request_catchup_state();
+        let sender_kp = generate_production_keypair::<KeyPair>();
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let transaction = create_create_asset_transaction(sender_kp.public(), recent_block_hash, 0);
+        let (_, _, block_transactions) = validator
+            .handle_consensus_transaction(
+                &dummy_consensus_output,
+                end_of_block_indices,
+                transaction.sign(&sender_kp).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let catchup_state = validator
+            .validator_store
+            .critical_path_store
+            .catchup_state_store
+            .read(1)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(catchup_state.manifest.block_number, 1);
+        let manifests = validator
+            .validator_store
+            .critical_path_store
+            .get_catchup_manifests()
+            .await;
+        assert_eq!(manifests, vec![(1, catchup_state.manifest.clone())]);
+
+        // the requested snapshot is used on restart, so the block itself is not needed for replay
+        validator
+            .validator_store
+            .write_latest_block(dummy_consensus_output.certificate.clone(), block_transactions)
+            .await;
+        validator
+            .validator_store
+            .critical_path_store
+            .block_store
+            .remove(1)
+            .await;
+        let ValidatorState { validator_store, .. } = validator;
+        let restarted_validator = ValidatorState {
+            validator_store,
+            executed_block_number: AtomicU64::new(0),
+            ..create_test_validator()
+        };
+        restarted_validator.restore_from_store().await.unwrap();
+        assert_eq!(restarted_validator.executed_block_number.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            restarted_validator.controller_router.get_next_nonce(sender_kp.public()),
+            1
+        );
+    }
+
+    #[tokio::test]
+    pub async fn restore_replays_stored_blocks() {
+        let validator: ValidatorState = create_test_validator();
+        let dummy_consensus_output = create_test_consensus_output();
+        // the payment closes the block
+        let end_of_block_indices = ExecutionIndices {
+            next_certificate_index: 2,
+            next_batch_index: 0,
+            next_transaction_index: 0,
+        };
+
+        let sender_kp = generate_production_keypair::<KeyPair>();
+        let receiver_kp = generate_production_keypair::<KeyPair>();
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        const TEST_AMOUNT: u64 = 1000000;
+        let signed_transactions = vec![
+            create_create_asset_transaction(sender_kp.public(), recent_block_hash, 0)
+                .sign(&sender_kp)
+                .unwrap(),
+            sign_with_nonce(
+                create_payment_transaction(
+                    sender_kp.public(),
+                    recent_block_hash,
+                    receiver_kp.public(),
+                    0,
+                    TEST_AMOUNT,
+                ),
+                1,
+                &sender_kp,
+            ),
+        ];
+
+        let mut executed_transactions = Vec::new();
+        for (i, signed_transaction) in signed_transactions.into_iter().enumerate() {
+            let execution_indices = if i == 1 {
+                end_of_block_indices.clone()
+            } else {
+                create_test_execution_indices()
+            };
+            let (_, _, block_transactions) = validator
+                .handle_consensus_transaction(&dummy_consensus_output, execution_indices, signed_transaction)
+                .await
+                .unwrap();
+            executed_transactions.extend(block_transactions);
+        }
+        assert_eq!(executed_transactions.len(), 2);
+        assert_eq!(validator.executed_block_number.load(Ordering::SeqCst), 1);
+        validator
+            .validator_store
+            .write_latest_block(dummy_consensus_output.certificate.clone(), executed_transactions)
+            .await;
+        let receiver_balance = validator
+            .controller_router
+            .bank_controller
+            .lock()
+            .unwrap()
+            .get_balance(receiver_kp.public(), 0)
+            .unwrap();
+
+        // a fresh validator on top of the same store replays the block and resumes after it
+        let ValidatorState { validator_store, .. } = validator;
+        let restarted_validator = ValidatorState {
+            validator_store,
+            executed_block_number: AtomicU64::new(0),
+            ..create_test_validator()
+        };
+        restarted_validator.restore_from_store().await.unwrap();
+
+        assert_eq!(restarted_validator.executed_block_number.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            restarted_validator
+                .controller_router
+                .bank_controller
+                .lock()
+                .unwrap()
+                .get_balance(receiver_kp.public(), 0)
+                .unwrap(),
+            receiver_balance
+        );
+        // the replayed transactions consumed their nonces, so they cannot be executed again
+        assert_eq!(
+            restarted_validator.controller_router.get_next_nonce(sender_kp.public()),
+            2
+        );
+        let execution_indices = restarted_validator.load_execution_indices().await.unwrap();
+        assert_eq!(execution_indices.next_certificate_index, 2);
+        assert_eq!(execution_indices.next_transaction_index, 0);
+    }
+
+    #[tokio::test]
+    pub async fn restart_resumes_committee_of_restored_epoch() {
+        let key: ValidatorKeyPair =
+            get_key_pair_from_rng::<ValidatorKeyPair, rand::rngs::OsRng>(&mut rand::rngs::OsRng);
+        let public_key = ValidatorPubKeyBytes::from(key.public());
+
+        // the validator stakes at genesis and every block ends an epoch
+        // each validator is built from its own genesis, as routers share their controllers when cloned
+        let validator_public_key = key.public().clone();
+        let secret = Arc::pin(key);
+        let new_validator = || {
+            let controller_router = ControllerRouter::default();
+            controller_router.initialize_controllers();
+            controller_router.initialize_controller_accounts();
+            controller_router
+                .bank_controller
+                .lock()
+                .unwrap()
+                .create_asset(&validator_public_key)
+                .unwrap();
+            {
+                let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+                stake_controller
+                    .stake(&validator_public_key, VALIDATOR_FUNDING_AMOUNT)
+                    .unwrap();
+                stake_controller.set_epoch_length(1);
+            }
+            let validator_info = ValidatorInfo {
+                name: "0".into(),
+                public_key,
+                stake: VALIDATOR_FUNDING_AMOUNT,
+                balance: VALIDATOR_BALANCE,
+                delegation: 0,
+                narwhal_primary_to_primary: utils::new_network_address(),
+                narwhal_worker_to_primary: utils::new_network_address(),
+                narwhal_primary_to_worker: vec![utils::new_network_address()],
+                narwhal_worker_to_worker: vec![utils::new_network_address()],
+                narwhal_consensus_addresses: vec![utils::new_network_address()],
+            };
+            let genesis = GenesisStateBuilder::new()
+                .set_master_controller(controller_router)
+                .add_validator(validator_info)
+                .build();
+            let store_path = tempfile::tempdir()
+                .expect("Failed to open temporary directory")
+                .into_path();
+            let metrics = Arc::new(ValidatorMetrics::new(&Registry::default()));
+            ValidatorState::new(public_key, secret.clone(), &genesis, &store_path, metrics)
+        };
+
+        let validator = new_validator();
+        let dummy_consensus_output = create_test_consensus_output();
+        let sender_kp = generate_production_keypair::<KeyPair>();
+        let signed_transaction = sign_with_nonce(
+            create_create_asset_transaction(sender_kp.public(), BlockDigest::new([0; DIGEST_LEN]), 0),
+            0,
+            &sender_kp,
+        );
+        let (_, _, executed_transactions) = validator
+            .handle_consensus_transaction(
+                &dummy_consensus_output,
+                create_end_of_block_execution_indices(),
+                signed_transaction,
+            )
+            .await
+            .unwrap();
+        validator
+            .validator_store
+            .write_latest_block(dummy_consensus_output.certificate.clone(), executed_transactions)
+            .await;
+
+        // a validator restarted after the epoch change starts consensus with the committee of that epoch
+        let ValidatorState { validator_store, .. } = validator;
+        let restarted_validator = ValidatorState {
+            validator_store,
+            executed_block_number: AtomicU64::new(0),
+            ..new_validator()
+        };
+        assert_eq!(restarted_validator.committee.load().epoch(), 0);
+        restarted_validator.restore_from_store().await.unwrap();
+
+        assert_eq!(restarted_validator.committee.load().epoch(), 1);
+        let consensus_committee = restarted_validator.get_consensus_committee();
+        assert_eq!(consensus_committee.epoch, 1);
+        assert_eq!(consensus_committee.authorities.len(), 1);
+        // narwhal starts the new epoch on a fresh store, so execution resumes from its beginning
+        let execution_indices = restarted_validator.load_execution_indices().await.unwrap();
+        assert_eq!(execution_indices.next_certificate_index, 0);
+    }
+
+    #[tokio::test]
+    pub async fn pre_consensus_rejects_zero_fee() {
+        let validator: ValidatorState = create_test_validator();
+
+        let sender_kp = generate_production_keypair::<KeyPair>();
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let mut transaction = create_create_asset_transaction(sender_kp.public(), recent_block_hash, 0);
+        assert!(validator
+            .handle_pre_consensus_transaction(&transaction.clone().sign(&sender_kp).unwrap())
+            .is_ok());
+
+        transaction.set_fee(0);
+        assert_eq!(
+            validator.handle_pre_consensus_transaction(&transaction.sign(&sender_kp).unwrap()),
+            Err(GDEXError::TransactionFeeTooLow)
+        );
+    }
+
+    #[tokio::test]
+    pub async fn write_latest_block_indexes_first_execution() {
+        let validator: ValidatorState = create_test_validator();
+        let dummy_consensus_output = create_test_consensus_output();
+
+        let sender_kp = generate_production_keypair::<KeyPair>();
+        let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+        let signed_transaction = create_create_asset_transaction(sender_kp.public(), recent_block_hash, 0)
+            .sign(&sender_kp)
+            .unwrap();
+        let transaction_digest = signed_transaction.get_transaction_digest().unwrap();
+
+        // the replayed transaction is rejected by its nonce in the second block
+        for _ in 0..2 {
+            let (_, _, executed_transactions) = validator
+                .handle_consensus_transaction(
+                    &dummy_consensus_output,
+                    create_end_of_block_execution_indices(),
+                    signed_transaction.clone(),
+                )
+                .await
+                .unwrap();
+            validator
+                .validator_store
+                .write_latest_block(dummy_consensus_output.certificate.clone(), executed_transactions)
+                .await;
+        }
+
+        let critical_path_store = &validator.validator_store.critical_path_store;
+        let location = critical_path_store
+            .transaction_location_store
+            .notify_read(transaction_digest)
+            .await
+            .unwrap();
+        assert_eq!(
+            location,
+            Some(TransactionLocation {
+                block_number: 1,
+                position: 0
+            })
+        );
+        let block = critical_path_store.block_store.read(1).await.unwrap().unwrap();
+        assert!(block.transactions[0].result.is_ok());
+    }
+
     #[allow(unused_must_use)]
     #[tokio::test]
     pub async fn process_create_orderbook_transaction() {
         let validator: ValidatorState = create_test_validator();
         let dummy_consensus_output = create_test_consensus_output();
-        let dummy_execution_indices = create_test_execution_indices();
+        let dummy_execution_indices = create_end_of_block_execution_indices();
 
         // create asset transaction
         let sender_kp = generate_production_keypair::<KeyPair>();
@@ -554,7 +1196,7 @@ pub mod test_validator_state {
 
         for asset_number in 0..5 {
             let transaction = create_create_asset_transaction(sender_kp.public(), recent_block_hash, asset_number);
-            let signed_transaction = transaction.sign(&sender_kp).unwrap();
+            let signed_transaction = sign_with_nonce(transaction, asset_number, &sender_kp);
 
             validator
                 .handle_consensus_transaction(
@@ -594,7 +1236,7 @@ pub mod test_validator_state {
     pub async fn process_place_limit_order_and_cancel_transaction() {
         let validator: ValidatorState = create_test_validator();
         let dummy_consensus_output = create_test_consensus_output();
-        let dummy_execution_indices = create_test_execution_indices();
+        let dummy_execution_indices = create_end_of_block_execution_indices();
 
         // create asset transaction
         let sender_kp = generate_production_keypair::<KeyPair>();
@@ -602,7 +1244,7 @@ pub mod test_validator_state {
 
         for asset_number in 0..5 {
             let transaction = create_create_asset_transaction(sender_kp.public(), recent_block_hash, asset_number);
-            let signed_transaction = transaction.sign(&sender_kp).unwrap();
+            let signed_transaction = sign_with_nonce(transaction, asset_number, &sender_kp);
 
             validator
                 .handle_consensus_transaction(
@@ -624,7 +1266,7 @@ pub mod test_validator_state {
             TEST_BASE_ASSET_ID,
             TEST_QUOTE_ASSET_ID,
         );
-        let signed_transaction = transaction.sign(&sender_kp).unwrap();
+        let signed_transaction = sign_with_nonce(transaction, 5, &sender_kp);
 
         validator
             .handle_consensus_transaction(
@@ -645,8 +1287,11 @@ pub mod test_validator_state {
             OrderSide::Bid as u64,
             TEST_PRICE,
             TEST_QUANTITY,
+            0,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
         );
-        let signed_transaction = transaction.sign(&sender_kp).unwrap();
+        let signed_transaction = sign_with_nonce(transaction, 6, &sender_kp);
 
         validator
             .handle_consensus_transaction(
@@ -658,7 +1303,7 @@ pub mod test_validator_state {
             .unwrap();
 
         // cancel order
-        const TEST_ORDER_ID: u64 = 1;
+        const TEST_ORDER_ID: u64 = get_first_order_id(TEST_BASE_ASSET_ID, TEST_QUOTE_ASSET_ID);
         let transaction = create_cancel_order_transaction(
             sender_kp.public(),
             recent_block_hash,
@@ -667,7 +1312,7 @@ pub mod test_validator_state {
             OrderSide::Bid as u64,
             TEST_ORDER_ID,
         );
-        let signed_transaction = transaction.sign(&sender_kp).unwrap();
+        let signed_transaction = sign_with_nonce(transaction, 7, &sender_kp);
 
         validator
             .handle_consensus_transaction(
@@ -684,7 +1329,7 @@ pub mod test_validator_state {
     pub async fn process_place_limit_order_and_update_transaction() {
         let validator: ValidatorState = create_test_validator();
         let dummy_consensus_output = create_test_consensus_output();
-        let dummy_execution_indices = create_test_execution_indices();
+        let dummy_execution_indices = create_end_of_block_execution_indices();
 
         // create asset transaction
         let sender_kp = generate_production_keypair::<KeyPair>();
@@ -692,7 +1337,7 @@ pub mod test_validator_state {
 
         for asset_number in 0..5 {
             let transaction = create_create_asset_transaction(sender_kp.public(), recent_block_hash, asset_number);
-            let signed_transaction = transaction.sign(&sender_kp).unwrap();
+            let signed_transaction = sign_with_nonce(transaction, asset_number, &sender_kp);
 
             validator
                 .handle_consensus_transaction(
@@ -714,7 +1359,7 @@ pub mod test_validator_state {
             TEST_BASE_ASSET_ID,
             TEST_QUOTE_ASSET_ID,
         );
-        let signed_transaction = transaction.sign(&sender_kp).unwrap();
+        let signed_transaction = sign_with_nonce(transaction, 5, &sender_kp);
 
         validator
             .handle_consensus_transaction(
@@ -736,8 +1381,11 @@ pub mod test_validator_state {
             OrderSide::Bid as u64,
             TEST_PRICE,
             TEST_QUANTITY,
+            0,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
         );
-        let signed_transaction = transaction.sign(&sender_kp).unwrap();
+        let signed_transaction = sign_with_nonce(transaction, 6, &sender_kp);
 
         validator
             .handle_consensus_transaction(
@@ -749,7 +1397,7 @@ pub mod test_validator_state {
             .unwrap();
 
         // cancel order
-        const TEST_ORDER_ID: u64 = 1;
+        const TEST_ORDER_ID: u64 = get_first_order_id(TEST_BASE_ASSET_ID, TEST_QUOTE_ASSET_ID);
         let transaction = create_update_order_transaction(
             sender_kp.public(),
             recent_block_hash,
@@ -760,7 +1408,7 @@ pub mod test_validator_state {
             TEST_QUANTITY,
             TEST_ORDER_ID,
         );
-        let signed_transaction = transaction.sign(&sender_kp).unwrap();
+        let signed_transaction = sign_with_nonce(transaction, 7, &sender_kp);
 
         validator
             .handle_consensus_transaction(
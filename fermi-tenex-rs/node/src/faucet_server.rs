@@ -1,6 +1,6 @@
 // fermi
 use fermi_controller::bank::proto::create_payment_transaction;
-use fermi_core::client;
+use fermi_core::{client, client::NonceTracker, multiaddr::to_socket_addr};
 use fermi_types::{
     account::AccountKeyPair,
     crypto::KeypairTraits,
@@ -18,6 +18,7 @@ use std::{
 };
 // external
 use multiaddr::Multiaddr;
+use tokio::sync::Mutex;
 use tonic::{transport::Server, Request, Response, Status};
 pub const PRIMARY_ASSET_ID: u64 = 0;
 pub const FAUCET_PORT: u32 = 8080;
@@ -27,22 +28,26 @@ pub struct FaucetService {
     pub validator_index: u64,
     pub key_path: PathBuf,
     pub validator_addr: Multiaddr,
+    pub jsonrpc_addr: Multiaddr,
+    pub nonce_tracker: Mutex<NonceTracker>,
 }
 
 fn generate_signed_airdrop_transaction_for_faucet(
     kp_sender: &AccountKeyPair,
     kp_receiver_public_key: &Ed25519PublicKey,
     amount: u64,
+    nonce: u64,
 ) -> SignedTransaction {
     // Setting a certificate_digest
     let recent_certificate_digest = CertificateDigest::new([0; DIGEST_LEN]);
-    let transaction = create_payment_transaction(
+    let mut transaction = create_payment_transaction(
         kp_sender.public(),
         recent_certificate_digest,
         kp_receiver_public_key,
         PRIMARY_ASSET_ID,
         amount,
     );
+    transaction.set_nonce(nonce);
     match transaction.sign(kp_sender) {
         Ok(t) => t,
         _ => panic!("Error signing transaction"),
@@ -65,9 +70,22 @@ impl Faucet for FaucetService {
         let kp_sender: AccountKeyPair = utils::read_keypair_from_file(&key_file).unwrap();
         let kp_receiver_public_key = kp_public_airdrop_to;
 
+        // Fetch the faucet's next nonce once, afterwards airdrops are numbered locally
+        let nonce = {
+            let mut nonce_tracker = self.nonce_tracker.lock().await;
+            if !nonce_tracker.is_synced(kp_sender.public()) {
+                let jsonrpc_url = format!("http://{}", to_socket_addr(&self.jsonrpc_addr).unwrap());
+                nonce_tracker
+                    .sync(&jsonrpc_url, kp_sender.public())
+                    .await
+                    .map_err(|e| Status::unavailable(e.to_string()))?;
+            }
+            nonce_tracker.next_nonce(kp_sender.public())
+        };
+
         // Creating signed transaction and proto
         let signed_transaction =
-            generate_signed_airdrop_transaction_for_faucet(&kp_sender, &kp_receiver_public_key, 100);
+            generate_signed_airdrop_transaction_for_faucet(&kp_sender, &kp_receiver_public_key, 100, nonce);
 
         // Getting the validator port from the second cli argument
         // The port for the validator that we will send the transaction to is passed in as the second cli argument when the server is starting
@@ -98,11 +116,15 @@ impl Default for FaucetService {
         // TODO - take validator addr directly as multiaddr as in spawn node
         let validator_port = env::args().nth(1).unwrap();
         let validator_addr = format!("/dns/localhost/tcp/{}/http", validator_port).parse().unwrap();
+        let jsonrpc_port = env::args().nth(2).unwrap();
+        let jsonrpc_addr = format!("/ip4/127.0.0.1/tcp/{}/http", jsonrpc_port).parse().unwrap();
 
         Self {
             validator_index: 0,
             key_path,
             validator_addr,
+            jsonrpc_addr,
+            nonce_tracker: Mutex::new(NonceTracker::default()),
         }
     }
 }
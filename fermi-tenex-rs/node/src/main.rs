@@ -1,11 +1,15 @@
 // fermi
-use fermi_core::validator::spawner::ValidatorSpawner;
+use fermi_core::{
+    config::node::default_websocket_address,
+    validator::{admin::TracingFilterHandle, spawner::ValidatorSpawner},
+};
 // external
 use anyhow::Result;
 use clap::{crate_name, crate_version, App, AppSettings, ArgMatches, SubCommand};
 use multiaddr::Multiaddr;
-use std::{path::Path, str::FromStr};
+use std::{net::SocketAddr, path::Path, str::FromStr, sync::Arc};
 use tracing::info;
+use tracing_subscriber::{reload, EnvFilter};
 
 const DEFAULT_VALIDATOR_MULTIADDR: &str = "/dns/localhost/tcp/62000/http";
 const DEFAULT_METRICS_MULTIADDR: &str = "/dns/localhost/tcp/63000/http";
@@ -25,7 +29,9 @@ async fn main() -> Result<()> {
                 .args_from_usage("--name=<NAME> 'The validator name'")
                 .args_from_usage("--grpc-address=<ADDR> 'The validator grpc address'")
                 .args_from_usage("--jsonrpc-address=<ADDR> 'The validator jsonrpc address'")
-                .args_from_usage("--metrics-address=<ADDR> 'The metrics address'"),
+                .args_from_usage("--websocket-address=[ADDR] 'The validator jsonrpc websocket address'")
+                .args_from_usage("--metrics-address=<ADDR> 'The metrics address'")
+                .args_from_usage("--admin-port=[PORT] 'The localhost port of the admin interface'"),
         )
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .get_matches();
@@ -52,21 +58,37 @@ async fn main() -> Result<()> {
 
     let log_filter = format!("{tracing_level},h2={network_tracing_level},tower={network_tracing_level},hyper={network_tracing_level},tonic::transport={network_tracing_level}");
 
-    let _guard = telemetry_subscribers::TelemetryConfig::new("fermi-node")
-        // load env variables
-        .with_env()
-        // load special log filter
-        .with_log_level(&log_filter)
-        .init();
+    // the filter is reloadable so that operators can change it through the admin interface
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&log_filter));
+    let subscriber_builder = tracing_subscriber::fmt::Subscriber::builder()
+        .with_env_filter(env_filter)
+        .with_filter_reloading();
+    let tracing_filter_handle = Arc::new(NodeTracingFilterHandle(subscriber_builder.reload_handle()));
+    tracing::subscriber::set_global_default(subscriber_builder.finish()).expect("Failed to set subscriber");
 
     match matches.subcommand() {
-        ("run", Some(sub_matches)) => run(sub_matches).await,
+        ("run", Some(sub_matches)) => run(sub_matches, tracing_filter_handle).await,
         _ => unreachable!(),
     }
     Ok(())
 }
 
-async fn run(matches: &ArgMatches<'_>) {
+/// Exposes the reloadable filter of the node subscriber to the admin interface
+struct NodeTracingFilterHandle<S>(reload::Handle<EnvFilter, S>);
+
+impl<S: 'static> TracingFilterHandle for NodeTracingFilterHandle<S> {
+    fn get_filter(&self) -> Result<String> {
+        Ok(self.0.with_current(|filter| filter.to_string())?)
+    }
+
+    fn set_filter(&self, directives: &str) -> Result<()> {
+        let filter = EnvFilter::try_new(directives)?;
+        self.0.reload(filter)?;
+        Ok(())
+    }
+}
+
+async fn run(matches: &ArgMatches<'_>, tracing_filter_handle: Arc<dyn TracingFilterHandle>) {
     let db_dir = matches.value_of("db-dir").unwrap();
     let db_path = Path::new(db_dir).to_path_buf();
 
@@ -85,6 +107,11 @@ async fn run(matches: &ArgMatches<'_>) {
         .unwrap_or(DEFAULT_VALIDATOR_MULTIADDR);
     let jsonrpc_address = Multiaddr::from_str(jsonrpc_address).unwrap();
 
+    let websocket_address = match matches.value_of("websocket-address") {
+        Some(websocket_address) => SocketAddr::from_str(websocket_address).unwrap(),
+        None => default_websocket_address().unwrap(),
+    };
+
     let metrics_address = matches.value_of("metrics-address").unwrap_or(DEFAULT_METRICS_MULTIADDR);
     let metrics_address = Multiaddr::from_str(metrics_address).unwrap();
 
@@ -98,6 +125,15 @@ async fn run(matches: &ArgMatches<'_>) {
         /* metrics_address */ metrics_address,
         /* validator_name */ name.to_string(),
     );
+    validator_spawner.set_websocket_address(websocket_address);
+    if let Some(admin_port) = matches.value_of("admin-port") {
+        validator_spawner.set_admin_interface_port(admin_port.parse().expect("Invalid admin port"));
+    }
+    info!(
+        "Admin interface will listen on localhost port {}",
+        validator_spawner.get_admin_interface_port()
+    );
+    validator_spawner.set_tracing_filter_handle(tracing_filter_handle);
     validator_spawner.spawn_validator().await;
 
     validator_spawner.await_handles().await;
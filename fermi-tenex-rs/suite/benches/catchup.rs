@@ -9,13 +9,17 @@ use fermi_controller::{
     },
     utils::engine::order_book::OrderBookWrapper,
 };
-use fermi_types::{account::AccountPubKey, crypto::ToFromBytes, order_book::OrderSide};
+use fermi_types::{
+    account::AccountPubKey,
+    crypto::ToFromBytes,
+    order_book::{OrderSide, TimeInForce},
+};
 // narwhal
 use fastcrypto::{generate_production_keypair, traits::KeyPair as _};
 use narwhal_crypto::KeyPair;
 // external
 use criterion::*;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -68,7 +72,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         .unwrap()
         .create_account(&controller_account)
         .unwrap();
-    let orderbooks = HashMap::new();
+    let orderbooks = BTreeMap::new();
     let spot_controller = Arc::new(Mutex::new(SpotController::new(
         controller_account,
         orderbooks,
@@ -95,7 +99,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         spot_controller
             .lock()
             .unwrap()
-            .create_orderbook(base_asset_id, quote_asset_id)
+            .create_orderbook(creator_kp.public(), base_asset_id, quote_asset_id)
             .unwrap();
 
         for k in 1..n_users {
@@ -120,6 +124,9 @@ fn criterion_benchmark(c: &mut Criterion) {
                 OrderSide::Bid as u64,
                 n_users as u64 % 100 + 1,
                 1,
+                0,
+                TimeInForce::GoodTilCancelled as u64,
+                false,
             );
             let ask_request = LimitOrderRequest::new(
                 base_asset_id,
@@ -127,6 +134,9 @@ fn criterion_benchmark(c: &mut Criterion) {
                 OrderSide::Ask as u64,
                 n_users as u64 % 100 + 101,
                 1,
+                0,
+                TimeInForce::GoodTilCancelled as u64,
+                false,
             );
             spot_controller
                 .lock()
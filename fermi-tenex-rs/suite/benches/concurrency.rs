@@ -1,5 +1,21 @@
 // fermi
-use fermi_controller::bank::controller::BankController;
+use fermi_controller::{
+    bank::{
+        controller::BankController,
+        proto::{create_create_asset_transaction, create_payment_transaction},
+    },
+    router::ControllerRouter,
+    spot::proto::{create_create_orderbook_transaction, create_limit_order_transaction},
+};
+use fermi_core::validator::scheduler::BlockScheduler;
+use fermi_types::{
+    block::BlockDigest,
+    order_book::{OrderSide, TimeInForce},
+    transaction::Transaction,
+};
+// narwhal
+use fastcrypto::{generate_production_keypair, traits::KeyPair as _, DIGEST_LEN};
+use narwhal_crypto::KeyPair;
 // external
 use criterion::*;
 use std::sync::{Arc, Mutex};
@@ -107,5 +123,94 @@ fn criterion_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, criterion_benchmark);
+// a block of crossing orders spread evenly over independent orderbooks, each with its own maker and taker
+fn block_execution_benchmark(c: &mut Criterion) {
+    const N_ORDERBOOKS: u64 = 8;
+    const N_ORDERS_PER_ORDERBOOK: u64 = 256;
+    const QUOTE_ASSET_ID: u64 = 0;
+
+    let admin = generate_production_keypair::<KeyPair>();
+    let users: Vec<(KeyPair, KeyPair)> = (0..N_ORDERBOOKS)
+        .map(|_| {
+            (
+                generate_production_keypair::<KeyPair>(),
+                generate_production_keypair::<KeyPair>(),
+            )
+        })
+        .collect();
+    let recent_block_hash = BlockDigest::new([0; DIGEST_LEN]);
+
+    // executes genesis and returns the router with a block whose nonces follow it
+    let setup = || {
+        let controller_router = ControllerRouter::default();
+        controller_router.initialize_controllers();
+        controller_router.initialize_controller_accounts();
+        let mut genesis = Vec::new();
+        for _ in 0..=N_ORDERBOOKS {
+            genesis.push(create_create_asset_transaction(admin.public(), recent_block_hash, 0));
+        }
+        for ((maker, taker), base_asset_id) in users.iter().zip(1..) {
+            genesis.push(create_create_orderbook_transaction(
+                admin.public(),
+                recent_block_hash,
+                base_asset_id,
+                QUOTE_ASSET_ID,
+            ));
+            for (user, asset_id) in [(maker, base_asset_id), (maker, QUOTE_ASSET_ID), (taker, QUOTE_ASSET_ID)] {
+                genesis.push(create_payment_transaction(
+                    admin.public(),
+                    recent_block_hash,
+                    user.public(),
+                    asset_id,
+                    1_000_000_000,
+                ));
+            }
+        }
+        for (nonce, mut transaction) in genesis.into_iter().enumerate() {
+            transaction.set_nonce(nonce as u64);
+            controller_router.handle_consensus_transaction(&transaction).unwrap();
+        }
+
+        let mut block = Vec::new();
+        for nonce in 0..N_ORDERS_PER_ORDERBOOK {
+            for ((maker, taker), base_asset_id) in users.iter().zip(1..) {
+                for (user, side) in [(maker, OrderSide::Ask), (taker, OrderSide::Bid)] {
+                    let mut transaction = create_limit_order_transaction(
+                        user.public(),
+                        recent_block_hash,
+                        base_asset_id,
+                        QUOTE_ASSET_ID,
+                        side as u64,
+                        100,
+                        10,
+                        0,
+                        TimeInForce::GoodTilCancelled as u64,
+                        false,
+                    );
+                    transaction.set_nonce(nonce);
+                    block.push(transaction);
+                }
+            }
+        }
+        (controller_router, block)
+    };
+
+    for (name, scheduler) in [
+        ("concurrency_execute_block_serial", BlockScheduler::new(1)),
+        ("concurrency_execute_block_parallel", BlockScheduler::default()),
+    ] {
+        c.bench_function(name, |b| {
+            b.iter_batched(
+                &setup,
+                |(controller_router, block): (ControllerRouter, Vec<Transaction>)| {
+                    let block: Vec<&Transaction> = block.iter().collect();
+                    scheduler.execute_block(&controller_router, &block)
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+}
+
+criterion_group!(benches, criterion_benchmark, block_execution_benchmark);
 criterion_main!(benches);
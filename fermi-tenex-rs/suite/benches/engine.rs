@@ -12,16 +12,13 @@ use fermi_controller::{
 use fermi_types::{
     account::{account_test_functions::generate_keypair_vec, AccountPubKey},
     crypto::{KeypairTraits, ToFromBytes},
-    order_book::{OrderProcessingResult, OrderRequest, OrderSide, Success},
+    order_book::{OrderProcessingResult, OrderRequest, OrderSide, Success, TimeInForce},
 };
 // external
 use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use rocksdb::{ColumnFamilyDescriptor, DBWithThreadMode, MultiThreaded, Options, DB};
-use std::{
-    sync::{Arc, Mutex},
-    time::SystemTime,
-};
+use std::sync::{Arc, Mutex};
 
 const N_ORDERS_BENCH: u64 = 1_024;
 const N_ACCOUNTS: u64 = 1_024;
@@ -70,15 +67,9 @@ fn place_orders_engine(
         let price: u64 = rng.gen_range(1..100);
 
         // order construction & submission
-        let order: OrderRequest = create_limit_order_request(
-            base_asset_id,
-            quote_asset_id,
-            order_type,
-            price,
-            quantity,
-            SystemTime::now(),
-        );
-        let res: OrderProcessingResult = orderbook.process_order(order);
+        let order: OrderRequest =
+            create_limit_order_request(base_asset_id, quote_asset_id, order_type, price, quantity, 0);
+        let res: OrderProcessingResult = orderbook.process_order(order).unwrap();
         if persist {
             persist_result(db, &res);
         }
@@ -115,8 +106,16 @@ fn place_orders_engine_account(
         let quantity = rng.gen_range(1..100);
         let price = rng.gen_range(1..100);
 
-        let limit_order_request =
-            LimitOrderRequest::new(base_asset_id, quote_asset_id, order_type as u64, price, quantity);
+        let limit_order_request = LimitOrderRequest::new(
+            base_asset_id,
+            quote_asset_id,
+            order_type as u64,
+            price,
+            quantity,
+            0,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
+        );
         let res = spot_orderbook.place_limit_order(primary, &limit_order_request).unwrap();
         if persist {
             persist_result(db, &res);
@@ -29,7 +29,7 @@ use tracing::info;
 
 // HELPER FUNCTIONS
 
-async fn create_genesis_state(dir: &Path, validator_count: usize) -> ValidatorGenesisState {
+async fn create_genesis_state(dir: &Path, validator_count: usize, epoch_length: Option<u64>) -> ValidatorGenesisState {
     // initialize validator info
     let validators_info = (0..validator_count)
         .map(|i| {
@@ -66,6 +66,13 @@ async fn create_genesis_state(dir: &Path, validator_count: usize) -> ValidatorGe
         .create_asset(&validator_creator_pubkey)
         .unwrap();
 
+    // the first validator governs the batch parameters
+    controller_router
+        .consensus_controller
+        .lock()
+        .unwrap()
+        .set_governance_account(validator_creator_pubkey.clone());
+
     // fund validators
     let transfer_amount: u64 = CREATED_ASSET_BALANCE / (validator_count as u64);
     for validator_info in &validators_info {
@@ -83,6 +90,16 @@ async fn create_genesis_state(dir: &Path, validator_count: usize) -> ValidatorGe
             .unwrap();
     }
 
+    // the committee is only rebuilt from stake when epochs are enabled, so validators must be staked
+    if let Some(epoch_length) = epoch_length {
+        let mut stake_controller = controller_router.stake_controller.lock().unwrap();
+        stake_controller.set_epoch_length(epoch_length);
+        for validator_info in &validators_info {
+            let validator_pubkey = ValidatorPubKey::try_from(validator_info.public_key).unwrap();
+            stake_controller.stake(&validator_pubkey, validator_info.stake).unwrap();
+        }
+    }
+
     ValidatorGenesisState::new(controller_router, validators_info)
 }
 
@@ -95,12 +112,21 @@ pub struct TestCluster {
 
 impl TestCluster {
     pub async fn spawn(validator_count: usize, max_spawn: Option<usize>) -> Self {
+        Self::spawn_inner(validator_count, max_spawn, None).await
+    }
+
+    /// Spawns a cluster of staked validators whose committee is rebuilt from stake every epoch_length blocks
+    pub async fn spawn_with_epoch_length(validator_count: usize, max_spawn: Option<usize>, epoch_length: u64) -> Self {
+        Self::spawn_inner(validator_count, max_spawn, Some(epoch_length)).await
+    }
+
+    async fn spawn_inner(validator_count: usize, max_spawn: Option<usize>, epoch_length: Option<u64>) -> Self {
         // get temp dirs
         let temp_working_dir = tempfile::tempdir().unwrap();
         let working_dir = temp_working_dir.path().to_path_buf();
 
         // create and save genesis state
-        let genesis_state = create_genesis_state(working_dir.as_path(), validator_count).await;
+        let genesis_state = create_genesis_state(working_dir.as_path(), validator_count, epoch_length).await;
         let _save_result = genesis_state.save(working_dir.join(GENESIS_FILENAME));
 
         // create and spawn validators
@@ -123,7 +149,6 @@ impl TestCluster {
             if validator_counter <= max_spawn.unwrap_or(validator_count) {
                 info!("Spawning validator {}", validator_counter);
                 validator_spawner.spawn_validator().await;
-                validator_spawner.get_validator_state().unwrap().unhalt_validator();
             }
 
             validator_spawners.push(validator_spawner);
@@ -163,7 +188,6 @@ impl TestCluster {
         let spawner = self.get_validator_spawner(index);
         // start the validator back up
         spawner.spawn_validator().await;
-        spawner.get_validator_state().unwrap().unhalt_validator();
     }
 
     pub fn get_validator_client(&mut self, index: usize) -> ValidatorGrpcClient<Channel> {
@@ -197,6 +221,12 @@ impl TestCluster {
 
         let kp_sender: ValidatorKeyPair = utils::read_keypair_from_file(&key_file).unwrap();
         let kp_receiver = generate_keypair_vec([1; 32]).pop().unwrap();
+        // continue from the sender's next nonce, as seen by the sending validator
+        let mut nonce = sender
+            .get_validator_state()
+            .unwrap()
+            .controller_router
+            .get_next_nonce(kp_sender.public());
 
         let receiver = self.get_validator_spawner(receiving_validator);
         let receiver_address = receiver.get_grpc_address().clone();
@@ -208,7 +238,8 @@ impl TestCluster {
         let mut i = 1;
         while i < n_transactions + 1 {
             let amount = i;
-            let signed_transaction = generate_signed_test_transaction(&kp_sender, &kp_receiver, amount);
+            let signed_transaction = generate_signed_test_transaction(&kp_sender, &kp_receiver, amount, nonce);
+            nonce += 1;
             signed_transactions.push(signed_transaction.clone());
             let _resp1 = client
                 .submit_transaction(signed_transaction)
@@ -233,6 +264,12 @@ impl TestCluster {
 
         let kp_sender: ValidatorKeyPair = utils::read_keypair_from_file(&key_file).unwrap();
         let kp_receiver = generate_keypair_vec([1; 32]).pop().unwrap();
+        // continue from the sender's next nonce, as seen by the sending validator
+        let mut nonce = sender
+            .get_validator_state()
+            .unwrap()
+            .controller_router
+            .get_next_nonce(kp_sender.public());
 
         let receiver = self.get_validator_spawner(receiving_validator);
         let receiver_address = receiver.get_grpc_address().clone();
@@ -245,7 +282,8 @@ impl TestCluster {
         tokio::spawn(async move {
             while i < n_transactions + 1 {
                 let amount = fixed_amount.unwrap_or(i);
-                let signed_transaction = generate_signed_test_transaction(&kp_sender, &kp_receiver, amount);
+                let signed_transaction = generate_signed_test_transaction(&kp_sender, &kp_receiver, amount, nonce);
+                nonce += 1;
                 signed_transactions.push(signed_transaction.clone());
                 let _resp1 = client
                     .submit_transaction(signed_transaction)
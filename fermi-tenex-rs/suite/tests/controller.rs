@@ -10,8 +10,12 @@ pub mod process_tests {
         utils::engine::order_book::OrderBookWrapper,
     };
     use fermi_types::{
-        account::account_test_functions::generate_keypair_vec, account::AccountPubKey, asset::AssetId,
-        crypto::KeypairTraits, crypto::ToFromBytes, order_book::OrderSide,
+        account::account_test_functions::generate_keypair_vec,
+        account::AccountPubKey,
+        asset::AssetId,
+        crypto::KeypairTraits,
+        crypto::ToFromBytes,
+        order_book::{OrderSide, TimeInForce},
     };
 
     use std::sync::{Arc, Mutex};
@@ -27,7 +31,16 @@ pub mod process_tests {
         price: u64,
         quantity: u64,
     ) {
-        let limit_order_request = LimitOrderRequest::new(BASE_ASSET_ID, QUOTE_ASSET_ID, side as u64, price, quantity);
+        let limit_order_request = LimitOrderRequest::new(
+            BASE_ASSET_ID,
+            QUOTE_ASSET_ID,
+            side as u64,
+            price,
+            quantity,
+            0,
+            TimeInForce::GoodTilCancelled as u64,
+            false,
+        );
         orderbook_interface
             .place_limit_order(account, &limit_order_request)
             .unwrap();
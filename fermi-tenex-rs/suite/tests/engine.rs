@@ -1,24 +1,42 @@
 #[cfg(test)]
 mod tests {
 
-    use fermi_controller::utils::engine::{order_book::Orderbook, orders};
-    use fermi_types::order_book::{Failed, OrderSide, Success};
-    use std::time::SystemTime;
+    use fermi_controller::utils::engine::{
+        order_book::{get_first_order_id, OrderId, Orderbook},
+        orders,
+    };
+    use fermi_types::order_book::{OrderSide, Success};
 
     const BASE_ASSET_ID: u64 = 0;
     const QUOTE_ASSET_ID: u64 = 1;
+    const ORDER_ID_1: OrderId = get_first_order_id(BASE_ASSET_ID, QUOTE_ASSET_ID);
+    const ORDER_ID_2: OrderId = ORDER_ID_1 + 1;
+    const ORDER_ID_3: OrderId = ORDER_ID_1 + 2;
 
     #[test]
     fn market_order_on_empty_orderbook() {
         let mut orderbook = Orderbook::new(BASE_ASSET_ID, QUOTE_ASSET_ID);
 
-        let order1 =
-            orders::create_market_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 2, SystemTime::now());
+        let order1 = orders::create_market_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 2, 0);
 
         // process market order
-        let res = orderbook.process_order(order1);
+        let res = orderbook.process_order(order1).unwrap();
 
-        if !matches!(res[0], Ok(Success::Accepted { order_id: 1, .. })) || !matches!(res[1], Err(Failed::NoMatch(1))) {
+        // the unfilled quantity is reported as cancelled rather than dropped
+        if !matches!(
+            res[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_1,
+                ..
+            })
+        ) || !matches!(
+            res[1],
+            Ok(Success::Cancelled {
+                order_id: ORDER_ID_1,
+                quantity: 2,
+                ..
+            })
+        ) {
             panic!("unexpected event sequence: {:?}", res)
         }
     }
@@ -27,24 +45,27 @@ mod tests {
     fn market_order_partial_match() {
         let mut orderbook = Orderbook::new(BASE_ASSET_ID, QUOTE_ASSET_ID);
 
-        let order1 =
-            orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 10, 2, SystemTime::now());
+        let order1 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 10, 2, 0);
 
-        let order2 =
-            orders::create_market_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 1, SystemTime::now());
+        let order2 = orders::create_market_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 1, 0);
 
-        orderbook.process_order(order1);
-        let res = orderbook.process_order(order2);
+        orderbook.process_order(order1).unwrap();
+        let res = orderbook.process_order(order2).unwrap();
 
-        if !matches!(res[0], Ok(Success::Accepted { order_id: 2, .. }))
-            || !matches!(res[1], Ok(Success::Filled {
-                order_id: 2,
+        if !matches!(
+            res[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_2,
+                ..
+            })
+        ) || !matches!(res[1], Ok(Success::Filled {
+                order_id: ORDER_ID_2,
                 price,
                 quantity,
                 ..
             }) if price == 10 && quantity == 1)
             || !matches!(res[2], Ok(Success::PartiallyFilled {
-                order_id: 1,
+                order_id: ORDER_ID_1,
                 price,
                 quantity,
                 ..
@@ -58,52 +79,42 @@ mod tests {
     fn market_order_two_orders_match() {
         let mut orderbook = Orderbook::new(BASE_ASSET_ID, QUOTE_ASSET_ID);
 
-        let order1 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Bid,
-            10,
-            10,
-            SystemTime::now(),
-        );
-
-        let order2 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Bid,
-            12,
-            10,
-            SystemTime::now(),
-        );
-
-        let order3 =
-            orders::create_market_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 15, SystemTime::now());
-
-        orderbook.process_order(order1);
-        orderbook.process_order(order2);
-        let res = orderbook.process_order(order3);
-
-        if !matches!(res[0], Ok(Success::Accepted { order_id: 3, .. }))
-            || !matches!(res[1], Ok(Success::PartiallyFilled {
-                order_id: 3,
+        let order1 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 10, 10, 0);
+
+        let order2 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 12, 10, 0);
+
+        let order3 = orders::create_market_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 15, 0);
+
+        orderbook.process_order(order1).unwrap();
+        orderbook.process_order(order2).unwrap();
+        let res = orderbook.process_order(order3).unwrap();
+
+        if !matches!(
+            res[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_3,
+                ..
+            })
+        ) || !matches!(res[1], Ok(Success::PartiallyFilled {
+                order_id: ORDER_ID_3,
                 price,
                 quantity,
                 ..
             }) if price == 12 && quantity == 10)
             || !matches!(res[2], Ok(Success::Filled {
-                order_id: 2,
+                order_id: ORDER_ID_2,
                 price,
                 quantity,
                 ..
             }) if price == 12 && quantity == 10)
             || !matches!(res[3], Ok(Success::Filled {
-                order_id: 3,
+                order_id: ORDER_ID_3,
                 price,
                 quantity,
                 ..
             }) if price == 10 && quantity == 5)
             || !matches!(res[4], Ok(Success::PartiallyFilled {
-                order_id: 1,
+                order_id: ORDER_ID_1,
                 price,
                 quantity,
                 ..
@@ -117,19 +128,18 @@ mod tests {
     fn limit_order_on_empty_orderbook() {
         let mut orderbook = Orderbook::new(BASE_ASSET_ID, QUOTE_ASSET_ID);
 
-        let order1 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Bid,
-            100,
-            20,
-            SystemTime::now(),
-        );
+        let order1 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 100, 20, 0);
 
         // process order
-        let res = orderbook.process_order(order1);
+        let res = orderbook.process_order(order1).unwrap();
 
-        if !matches!(res[0], Ok(Success::Accepted { order_id: 1, .. })) {
+        if !matches!(
+            res[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_1,
+                ..
+            })
+        ) {
             panic!("unexpected event sequence: {:?}", res)
         }
     }
@@ -138,36 +148,27 @@ mod tests {
     fn limit_order_partial_match() {
         let mut orderbook = Orderbook::new(BASE_ASSET_ID, QUOTE_ASSET_ID);
 
-        let order1 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Bid,
-            100,
-            100,
-            SystemTime::now(),
-        );
-
-        let order2 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Ask,
-            90,
-            50,
-            SystemTime::now(),
-        );
-
-        orderbook.process_order(order1);
-        let res = orderbook.process_order(order2);
-
-        if !matches!(res[0], Ok(Success::Accepted { order_id: 2, .. }))
-            || !matches!(res[1], Ok(Success::Filled {
-                order_id: 2,
+        let order1 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 100, 100, 0);
+
+        let order2 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 90, 50, 0);
+
+        orderbook.process_order(order1).unwrap();
+        let res = orderbook.process_order(order2).unwrap();
+
+        if !matches!(
+            res[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_2,
+                ..
+            })
+        ) || !matches!(res[1], Ok(Success::Filled {
+                order_id: ORDER_ID_2,
                 price,
                 quantity,
                 ..
             }) if price == 100 && quantity == 50)
             || !matches!(res[2], Ok(Success::PartiallyFilled {
-                order_id: 1,
+                order_id: ORDER_ID_1,
                 price,
                 quantity,
                 ..
@@ -181,30 +182,27 @@ mod tests {
     fn limit_order_exact_match() {
         let mut orderbook = Orderbook::new(BASE_ASSET_ID, QUOTE_ASSET_ID);
 
-        let order1 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Bid,
-            100,
-            10,
-            SystemTime::now(),
-        );
+        let order1 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 100, 10, 0);
 
-        let order2 =
-            orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 90, 5, SystemTime::now());
+        let order2 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 90, 5, 0);
 
-        orderbook.process_order(order1);
-        let res = orderbook.process_order(order2);
+        orderbook.process_order(order1).unwrap();
+        let res = orderbook.process_order(order2).unwrap();
 
-        if !matches!(res[0], Ok(Success::Accepted { order_id: 2, .. }))
-            || !matches!(res[1], Ok(Success::Filled {
-                order_id: 2,
+        if !matches!(
+            res[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_2,
+                ..
+            })
+        ) || !matches!(res[1], Ok(Success::Filled {
+                order_id: ORDER_ID_2,
                 price,
                 quantity,
                 ..
             }) if price == 100 && quantity == 5)
             || !matches!(res[2], Ok(Success::PartiallyFilled {
-                order_id: 1,
+                order_id: ORDER_ID_1,
                 price,
                 quantity,
                 ..
@@ -213,20 +211,24 @@ mod tests {
             panic!("unexpected event sequence: {:?}", res)
         }
 
-        let order3 =
-            orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 80, 5, SystemTime::now());
+        let order3 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 80, 5, 0);
 
-        let res2 = orderbook.process_order(order3);
+        let res2 = orderbook.process_order(order3).unwrap();
 
-        if !matches!(res2[0], Ok(Success::Accepted { order_id: 3, .. }))
-            || !matches!(res2[1], Ok(Success::Filled {
-                order_id: 3,
+        if !matches!(
+            res2[0],
+            Ok(Success::Accepted {
+                order_id: ORDER_ID_3,
+                ..
+            })
+        ) || !matches!(res2[1], Ok(Success::Filled {
+                order_id: ORDER_ID_3,
                 price,
                 quantity,
                 ..
             }) if price == 100 && quantity == 5)
             || !matches!(res2[2], Ok(Success::Filled {
-                order_id: 1,
+                order_id: ORDER_ID_1,
                 price,
                 quantity,
                 ..
@@ -242,52 +244,24 @@ mod tests {
     fn current_spread() {
         let mut orderbook = Orderbook::new(BASE_ASSET_ID, QUOTE_ASSET_ID);
 
-        let order1 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Bid,
-            100,
-            10,
-            SystemTime::now(),
-        );
+        let order1 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 100, 10, 0);
 
         // not enough orders to calculate
         assert_eq!(orderbook.current_spread(), None);
 
-        let order2 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Ask,
-            120,
-            5,
-            SystemTime::now(),
-        );
-
-        let order3 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Ask,
-            125,
-            25,
-            SystemTime::now(),
-        );
-
-        orderbook.process_order(order1);
-        orderbook.process_order(order2);
-        orderbook.process_order(order3);
+        let order2 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 120, 5, 0);
+
+        let order3 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Ask, 125, 25, 0);
+
+        orderbook.process_order(order1).unwrap();
+        orderbook.process_order(order2).unwrap();
+        orderbook.process_order(order3).unwrap();
 
         assert_eq!(orderbook.current_spread(), Some((100, 120)));
 
         // wider spread
-        let order4 = orders::create_limit_order_request(
-            BASE_ASSET_ID,
-            QUOTE_ASSET_ID,
-            OrderSide::Bid,
-            140,
-            15,
-            SystemTime::now(),
-        );
-        orderbook.process_order(order4);
+        let order4 = orders::create_limit_order_request(BASE_ASSET_ID, QUOTE_ASSET_ID, OrderSide::Bid, 140, 15, 0);
+        orderbook.process_order(order4).unwrap();
 
         assert_eq!(orderbook.current_spread(), Some((100, 125)));
     }
@@ -13,9 +13,20 @@ pub type AssetPairKey = String;
 pub type FuturesOrderbookKey = String;
 
 pub const PRIMARY_ASSET_ID: u64 = 0;
+// decimals given to assets created without metadata, e.g. the primary asset at genesis
+pub const DEFAULT_ASSET_DECIMALS: u32 = 6;
+pub const MAX_ASSET_DECIMALS: u32 = 18;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Asset {
     pub asset_id: AssetId,
     pub owner_pubkey: AccountPubKey,
+    pub name: String,
+    pub ticker: String,
+    pub decimals: u32,
+    pub total_supply: AssetAmount,
+    // None leaves the supply uncapped
+    pub max_supply: Option<AssetAmount>,
+    // a frozen asset can no longer be minted, burned or sent in payments
+    pub is_frozen: bool,
 }
@@ -1,5 +1,5 @@
 // crate
-use crate::transaction::ExecutedTransaction;
+use crate::{store::StateRoot, transaction::ExecutedTransaction};
 // mysten
 use narwhal_types::{Certificate, CertificateDigest};
 // external
@@ -21,4 +21,6 @@ pub struct BlockInfo {
     pub block_digest: BlockDigest,
     // TODO - change to consensus time when implemented
     pub validator_system_epoch_time_in_micros: u64,
+    // root of the catchup state manifest taken at this block, if any
+    pub catchup_state_root: Option<StateRoot>,
 }
@@ -20,6 +20,14 @@ pub enum GDEXError {
     AccountLookup,
     #[error("Failed to find asset")]
     AssetLookup,
+    #[error("Sender is not the asset owner")]
+    AssetOwner,
+    #[error("Asset is frozen")]
+    AssetFrozen,
+    #[error("Asset supply would exceed its max supply")]
+    AssetMaxSupply,
+    #[error("Asset metadata is invalid")]
+    InvalidAssetMetadata,
     #[error("Not implemented")]
     NotImplemented,
 
@@ -51,24 +59,62 @@ pub enum GDEXError {
     CannotLiquidatePosition,
     #[error("Order request failed")]
     OrderRequest,
+    #[error("Orderbook has issued every available order id")]
+    OrderIdExhausted,
+    #[error("Order quote value overflows")]
+    OrderValueOverflow,
     #[error("Orderbook creation failed")]
     OrderBookCreation,
+    #[error("Sender is not the orderbook admin")]
+    OrderbookAdmin,
     #[error("Insufficient balance to place order")]
     OrderExceedsBalance,
+    #[error("Fee rates are out of range or the maker rebate exceeds the taker fee")]
+    InvalidFeeSchedule,
+    #[error("Post-only order would take liquidity")]
+    PostOnlyWouldTake,
+    #[error("Fill-or-kill order could not be filled in full")]
+    FillOrKillUnfilled,
+    #[error("Reduce-only order would increase the position")]
+    ReduceOnlyIncreasesPosition,
+    #[error("Market bid requires a worst price or max quote spend")]
+    MarketOrderUnguarded,
+    #[error("Unstake amount exceeds the staked balance")]
+    InsufficientStake,
+    #[error("Delegation target is not a staked validator")]
+    DelegationTarget,
+    #[error("Only members of the validator set may stake")]
+    StakeIneligible,
+    #[error("Sender is not the governance account")]
+    GovernanceAccount,
+    #[error("Batch size and batch delay must be positive")]
+    InvalidBatchParams,
     #[error("Payment request failed")]
     PaymentRequest,
     #[error("Failed to serialize the signed transaction")]
     TransactionSerialization,
     #[error("Failed to deserialize into a signed transaction")]
     TransactionDeserialization,
-    #[error("Failed to process duplicate transaction")]
-    TransactionDuplicate,
+    #[error("Sender balance cannot cover the transaction fee")]
+    TransactionFeeUnderfunded,
+    #[error("Transaction fee is below the protocol minimum")]
+    TransactionFeeTooLow,
+    #[error("Transaction nonce does not match the next nonce of the sender")]
+    TransactionNonce,
+    #[error("Transaction holds more requests than a single transaction may carry")]
+    TransactionRequestLimit,
     // other errors
     #[error("Error while converting type")]
     Conversion,
     // Consensus output errors
     #[error("Failed to execute transaction")]
     ExecError,
+    #[error("Failed to restore state from the catchup snapshot and block store")]
+    StateRestore,
+    #[error("Catchup state was written by an incompatible version")]
+    CatchupStateVersion,
+    #[error("Catchup state does not match its manifest")]
+    CatchupStateVerification,
 
     // server errors
     #[error("Failed to process the inbound transaction")]
@@ -87,6 +133,8 @@ pub enum GDEXError {
     InvalidControllerError,
     #[error("Controller can not handle request type")]
     InvalidRequestTypeError,
+    #[error("No decoder is registered for the event type")]
+    UnknownEventType,
 
     #[error("Failed to verify transaction signature")]
     TransactionSignatureVerificationError,
@@ -0,0 +1,176 @@
+// fermi
+use crate::{error::GDEXError, transaction::ExecutionEvent, utils};
+// external
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// JSON view of every event emitted by the controllers, account keys are hex encoded
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(tag = "event")]
+pub enum DecodedEvent {
+    // bank
+    AssetCreated {
+        asset_id: u64,
+    },
+    PaymentSuccess {
+        sender: String,
+        receiver: String,
+        asset_id: u64,
+        quantity: u64,
+    },
+    AssetMinted {
+        asset_id: u64,
+        quantity: u64,
+    },
+    AssetBurned {
+        asset_id: u64,
+        quantity: u64,
+    },
+    AssetFrozen {
+        asset_id: u64,
+        frozen: bool,
+    },
+    AssetOwnershipTransferred {
+        asset_id: u64,
+        previous_owner: String,
+        new_owner: String,
+    },
+    // stake
+    Staked {
+        account: String,
+        amount: u64,
+    },
+    UnstakeStarted {
+        account: String,
+        amount: u64,
+        release_block: u64,
+    },
+    Delegated {
+        delegator: String,
+        validator: String,
+        amount: u64,
+    },
+    // spot
+    SpotOrderNew {
+        account: String,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+    },
+    SpotOrderFill {
+        account: String,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+        fee: i64,
+    },
+    SpotOrderPartialFill {
+        account: String,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+        fee: i64,
+    },
+    SpotOrderUpdate {
+        account: String,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+    },
+    SpotOrderCancel {
+        account: String,
+        base_asset_id: u64,
+        quote_asset_id: u64,
+        order_id: u64,
+        client_order_id: u64,
+    },
+    // consensus
+    BatchParamsUpdated {
+        batch_size: u64,
+        max_batch_delay_millis: u64,
+    },
+    // futures
+    FuturesOrderNew {
+        account: String,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+    },
+    FuturesOrderFill {
+        account: String,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        fee: i64,
+    },
+    FuturesOrderPartialFill {
+        account: String,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+        fee: i64,
+    },
+    FuturesOrderUpdate {
+        account: String,
+        order_id: u64,
+        side: u64,
+        price: u64,
+        quantity: u64,
+    },
+    FuturesOrderCancel {
+        account: String,
+        order_id: u64,
+    },
+    FuturesLiquidate {
+        sender: String,
+        target_account: String,
+        side: u64,
+        price: u64,
+        quantity: u64,
+    },
+}
+
+/// Decodes the protobuf payload of an execution event, implemented by the controller event registry
+pub trait EventDecoder {
+    fn decode_event(&self, event: &ExecutionEvent) -> Result<DecodedEvent, GDEXError>;
+}
+
+/// An execution event as returned over JSON RPC, with its decoded view next to the raw bytes
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+pub struct QueriedEvent {
+    pub controller_id: i32,
+    pub event_type: i32,
+    pub event_bytes: String,
+    // None when no decoder is registered for the event
+    pub decoded_event: Option<DecodedEvent>,
+}
+
+impl QueriedEvent {
+    pub fn new(event: &ExecutionEvent, decoder: &dyn EventDecoder) -> Self {
+        QueriedEvent {
+            controller_id: event.controller_id,
+            event_type: event.event_type,
+            event_bytes: utils::encode_bytes_hex(&event.event_bytes),
+            decoded_event: decoder.decode_event(event).ok(),
+        }
+    }
+}
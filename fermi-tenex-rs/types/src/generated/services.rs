@@ -19,6 +19,8 @@ pub struct BlockInfoResponse {
     pub successful: bool,
     #[prost(bytes="bytes", tag="2")]
     pub serialized_block_info: ::prost::bytes::Bytes,
+    #[prost(bool, tag="3")]
+    pub halted: bool,
 }
 // block
 
@@ -45,6 +47,58 @@ pub struct MetricsResponse {
     pub average_latency: u64,
     #[prost(double, tag="2")]
     pub average_tps: f64,
+    #[prost(bool, tag="3")]
+    pub halted: bool,
+}
+// catchup state
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListCatchupStatesRequest {
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListCatchupStatesResponse {
+    #[prost(bool, tag="1")]
+    pub successful: bool,
+    /// bincode serialized catchup manifests, ordered by block number
+    #[prost(bytes="bytes", repeated, tag="2")]
+    pub serialized_manifests: ::prost::alloc::vec::Vec<::prost::bytes::Bytes>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CatchupStateRequest {
+    #[prost(uint64, tag="1")]
+    pub block_number: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CatchupStateChunk {
+    #[prost(uint64, tag="1")]
+    pub block_number: u64,
+    #[prost(uint64, tag="2")]
+    pub chunk_index: u64,
+    #[prost(uint64, tag="3")]
+    pub total_chunks: u64,
+    #[prost(bytes="bytes", tag="4")]
+    pub data: ::prost::bytes::Bytes,
+}
+// submit and wait
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubmitTransactionAndWaitRequest {
+    #[prost(message, optional, tag="1")]
+    pub signed_transaction: ::core::option::Option<super::transaction::SignedTransaction>,
+    #[prost(uint64, tag="2")]
+    pub timeout_in_ms: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecutedTransactionResponse {
+    #[prost(bool, tag="1")]
+    pub successful: bool,
+    #[prost(uint64, tag="2")]
+    pub block_number: u64,
+    #[prost(uint64, tag="3")]
+    pub position: u64,
+    /// bincode serialized executed transaction
+    #[prost(bytes="bytes", tag="4")]
+    pub serialized_executed_transaction: ::prost::bytes::Bytes,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FaucetAirdropRequest {
@@ -202,6 +256,49 @@ pub mod validator_grpc_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        /// request the catchup states available for fast-sync
+        pub async fn list_catchup_states(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListCatchupStatesRequest>,
+        ) -> Result<tonic::Response<super::ListCatchupStatesResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.ValidatorGRPC/ListCatchupStates",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// request a catchup state by block number, streamed in chunks
+        pub async fn get_catchup_state(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CatchupStateRequest>,
+        ) -> Result<
+            tonic::Response<tonic::codec::Streaming<super::CatchupStateChunk>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.ValidatorGRPC/GetCatchupState",
+            );
+            self.inner.server_streaming(request.into_request(), path, codec).await
+        }
         /// submit a transaction
         pub async fn submit_transaction(
             &mut self,
@@ -248,6 +345,26 @@ pub mod validator_grpc_client {
                 .client_streaming(request.into_streaming_request(), path, codec)
                 .await
         }
+        /// submit a transaction and wait until it is finalized in a block
+        pub async fn submit_transaction_and_wait(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SubmitTransactionAndWaitRequest>,
+        ) -> Result<tonic::Response<super::ExecutedTransactionResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/services.ValidatorGRPC/SubmitTransactionAndWait",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
     }
 }
 /// Generated client implementations.
@@ -361,6 +478,22 @@ pub mod validator_grpc_server {
             &self,
             request: tonic::Request<super::MetricsRequest>,
         ) -> Result<tonic::Response<super::MetricsResponse>, tonic::Status>;
+        /// request the catchup states available for fast-sync
+        async fn list_catchup_states(
+            &self,
+            request: tonic::Request<super::ListCatchupStatesRequest>,
+        ) -> Result<tonic::Response<super::ListCatchupStatesResponse>, tonic::Status>;
+        ///Server streaming response type for the GetCatchupState method.
+        type GetCatchupStateStream: futures_core::Stream<
+                Item = Result<super::CatchupStateChunk, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        /// request a catchup state by block number, streamed in chunks
+        async fn get_catchup_state(
+            &self,
+            request: tonic::Request<super::CatchupStateRequest>,
+        ) -> Result<tonic::Response<Self::GetCatchupStateStream>, tonic::Status>;
         /// submit a transaction
         async fn submit_transaction(
             &self,
@@ -373,6 +506,11 @@ pub mod validator_grpc_server {
                 tonic::Streaming<super::super::transaction::SignedTransaction>,
             >,
         ) -> Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// submit a transaction and wait until it is finalized in a block
+        async fn submit_transaction_and_wait(
+            &self,
+            request: tonic::Request<super::SubmitTransactionAndWaitRequest>,
+        ) -> Result<tonic::Response<super::ExecutedTransactionResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct ValidatorGrpcServer<T: ValidatorGrpc> {
@@ -579,6 +717,87 @@ pub mod validator_grpc_server {
                     };
                     Box::pin(fut)
                 }
+                "/services.ValidatorGRPC/ListCatchupStates" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListCatchupStatesSvc<T: ValidatorGrpc>(pub Arc<T>);
+                    impl<
+                        T: ValidatorGrpc,
+                    > tonic::server::UnaryService<super::ListCatchupStatesRequest>
+                    for ListCatchupStatesSvc<T> {
+                        type Response = super::ListCatchupStatesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListCatchupStatesRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).list_catchup_states(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListCatchupStatesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/services.ValidatorGRPC/GetCatchupState" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetCatchupStateSvc<T: ValidatorGrpc>(pub Arc<T>);
+                    impl<
+                        T: ValidatorGrpc,
+                    > tonic::server::ServerStreamingService<super::CatchupStateRequest>
+                    for GetCatchupStateSvc<T> {
+                        type Response = super::CatchupStateChunk;
+                        type ResponseStream = T::GetCatchupStateStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CatchupStateRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_catchup_state(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetCatchupStateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/services.ValidatorGRPC/SubmitTransaction" => {
                     #[allow(non_camel_case_types)]
                     struct SubmitTransactionSvc<T: ValidatorGrpc>(pub Arc<T>);
@@ -622,6 +841,46 @@ pub mod validator_grpc_server {
                     };
                     Box::pin(fut)
                 }
+                "/services.ValidatorGRPC/SubmitTransactionAndWait" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubmitTransactionAndWaitSvc<T: ValidatorGrpc>(pub Arc<T>);
+                    impl<
+                        T: ValidatorGrpc,
+                    > tonic::server::UnaryService<super::SubmitTransactionAndWaitRequest>
+                    for SubmitTransactionAndWaitSvc<T> {
+                        type Response = super::ExecutedTransactionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SubmitTransactionAndWaitRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).submit_transaction_and_wait(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SubmitTransactionAndWaitSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/services.ValidatorGRPC/SubmitTransactionStream" => {
                     #[allow(non_camel_case_types)]
                     struct SubmitTransactionStreamSvc<T: ValidatorGrpc>(pub Arc<T>);
@@ -32,8 +32,24 @@ pub struct Transaction {
     pub request_type: i32,
     #[prost(bytes="bytes", tag="5")]
     pub recent_block_hash: ::prost::bytes::Bytes,
+    /// fee paid for each request of the transaction
     #[prost(uint64, tag="6")]
     pub fee: u64,
     #[prost(bytes="bytes", tag="7")]
     pub request_bytes: ::prost::bytes::Bytes,
+    /// sequence number of the transaction among those sent by the sender
+    #[prost(uint64, tag="8")]
+    pub nonce: u64,
+    /// when set, these requests are executed in order and all-or-nothing in place of the single request above
+    #[prost(message, repeated, tag="9")]
+    pub requests: ::prost::alloc::vec::Vec<ControllerRequest>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ControllerRequest {
+    #[prost(int32, tag="1")]
+    pub target_controller: i32,
+    #[prost(int32, tag="2")]
+    pub request_type: i32,
+    #[prost(bytes="bytes", tag="3")]
+    pub request_bytes: ::prost::bytes::Bytes,
 }
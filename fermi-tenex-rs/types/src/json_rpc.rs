@@ -1,6 +1,8 @@
 // fermi
 use crate::{
     block::{Block, BlockInfo, BlockNumber},
+    event::{EventDecoder, QueriedEvent},
+    order_book::Depth,
     transaction::QueriedTransaction,
     utils,
 };
@@ -16,13 +18,12 @@ pub struct BlockReply {
     pub block_id: String,
 }
 
-impl From<Block> for BlockReply {
-    fn from(block: Block) -> Self {
-        #[allow(clippy::redundant_closure)]
+impl BlockReply {
+    pub fn new(block: Block, decoder: &dyn EventDecoder) -> Self {
         let transactions = block
             .transactions
             .into_iter()
-            .map(|transaction| QueriedTransaction::from(transaction))
+            .map(|transaction| QueriedTransaction::new(transaction, decoder))
             .collect();
         Self {
             transactions,
@@ -36,6 +37,9 @@ pub struct BlockInfoReply {
     pub validator_system_epoch_time_in_micros: u64,
     pub block_number: BlockNumber,
     pub block_id: String,
+    pub catchup_state_root: Option<String>,
+    /// Whether the serving validator is currently halted
+    pub halted: bool,
 }
 
 impl From<BlockInfo> for BlockInfoReply {
@@ -44,6 +48,59 @@ impl From<BlockInfo> for BlockInfoReply {
             block_number: block_info.block_number,
             validator_system_epoch_time_in_micros: block_info.validator_system_epoch_time_in_micros,
             block_id: utils::encode_bytes_hex(Digest::from(block_info.block_digest).to_vec()),
+            catchup_state_root: block_info.catchup_state_root.map(utils::encode_bytes_hex),
+            halted: false,
         }
     }
 }
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct TransactionReply {
+    pub block_number: BlockNumber,
+    pub position: u64,
+    pub transaction: QueriedTransaction,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, JsonSchema)]
+pub struct AccountTransactionsReply {
+    pub transactions: Vec<TransactionReply>,
+    // total number of transactions sent by the account
+    pub total: u64,
+}
+
+// subscriptions
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct BlockNotification {
+    pub block_number: BlockNumber,
+    pub block: BlockReply,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct EventNotification {
+    pub block_number: BlockNumber,
+    pub transaction_id: String,
+    // hex encoded sender of the transaction which emitted the event
+    pub sender: String,
+    pub event: QueriedEvent,
+}
+
+/// Price levels of an orderbook which changed in a block, a removed level has a zero quantity
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DepthUpdate {
+    pub orderbook: String,
+    pub block_number: BlockNumber,
+    // true when the update carries the whole book rather than a diff
+    pub is_snapshot: bool,
+    pub bids: Vec<Depth>,
+    pub asks: Vec<Depth>,
+}
+
+/// Selects the events forwarded to a subscriber, every unset field matches all events
+#[derive(Clone, Debug, Deserialize, Serialize, Default, JsonSchema)]
+pub struct EventFilter {
+    pub controller_id: Option<i32>,
+    pub event_type: Option<i32>,
+    // hex encoded sender of the transaction which emitted the event
+    pub account: Option<String>,
+}
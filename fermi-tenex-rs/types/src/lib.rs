@@ -4,6 +4,7 @@ pub mod block;
 pub mod committee;
 pub mod crypto;
 pub mod error;
+pub mod event;
 pub mod json_rpc;
 pub mod node;
 pub mod order_book;
@@ -3,9 +3,13 @@ use crate::asset::{AssetAmount, AssetId, AssetPrice};
 // external
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::{fmt::Debug, time::SystemTime};
+use std::{collections::BTreeMap, fmt::Debug};
 
 pub type OrderId = u64;
+/// Opaque tag attached by the client to a new order and echoed in every event for that order
+pub type ClientOrderId = u64;
+/// Per-book logical clock which orders requests in the sequence they are executed by consensus
+pub type SequenceNumber = u64;
 
 #[derive(Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Debug)]
 #[repr(u64)]
@@ -14,12 +18,78 @@ pub enum OrderSide {
     Ask = 2,
 }
 
+/// Governs what happens to the part of a limit order which does not match on arrival
+#[derive(Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Debug)]
+#[repr(u64)]
+pub enum TimeInForce {
+    /// rest the remainder on the book until it is filled or cancelled
+    GoodTilCancelled = 0,
+    /// match what is immediately available and cancel the remainder
+    ImmediateOrCancel = 1,
+    /// match the full quantity immediately or reject the order
+    FillOrKill = 2,
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum OrderType {
     Market,
     Limit,
 }
 
+/// Which side of a fill an order was on, resting orders make liquidity and incoming orders take it
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Liquidity {
+    Maker,
+    Taker,
+}
+
+/// Denominator of every fee rate, one basis point is 1/10_000 of the traded notional
+pub const FEE_RATE_DENOMINATOR: i64 = 10_000;
+
+/// Maker and taker fee rates of an orderbook, quoted in basis points of the traded quote notional
+///
+/// A negative maker rate is a rebate which is paid out of the fees charged to the taker of the same fill
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct FeeSchedule {
+    pub maker_fee_bps: i64,
+    pub taker_fee_bps: i64,
+}
+
+impl FeeSchedule {
+    pub fn new(maker_fee_bps: i64, taker_fee_bps: i64) -> Self {
+        FeeSchedule {
+            maker_fee_bps,
+            taker_fee_bps,
+        }
+    }
+
+    /// Rates must stay within 100% and a maker rebate can never exceed what the taker pays
+    pub fn is_valid(&self) -> bool {
+        self.maker_fee_bps.abs() <= FEE_RATE_DENOMINATOR
+            && (0..=FEE_RATE_DENOMINATOR).contains(&self.taker_fee_bps)
+            && self.maker_fee_bps + self.taker_fee_bps >= 0
+    }
+
+    /// Fee owed on a fill, positive values are charged to the account and negative values are rebated to it
+    ///
+    /// Charges round up and rebates round down so that the fee account can never be overdrawn
+    pub fn compute_fee(&self, liquidity: Liquidity, price: AssetPrice, quantity: AssetAmount) -> Option<i64> {
+        let rate = match liquidity {
+            Liquidity::Maker => self.maker_fee_bps,
+            Liquidity::Taker => self.taker_fee_bps,
+        };
+        let notional = i128::from(price).checked_mul(i128::from(quantity))?;
+        let scaled = notional.checked_mul(i128::from(rate))?;
+        let denominator = i128::from(FEE_RATE_DENOMINATOR);
+        let fee = if scaled >= 0 {
+            (scaled + denominator - 1) / denominator
+        } else {
+            scaled / denominator
+        };
+        i64::try_from(fee).ok()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Order {
     pub order_id: u64,
@@ -63,7 +133,11 @@ pub enum OrderRequest {
         quote_asset_id: AssetId,
         side: OrderSide,
         quantity: AssetAmount,
-        local_timestamp: SystemTime,
+        client_order_id: ClientOrderId,
+        /// least favourable price the order may be filled at
+        worst_price: Option<AssetPrice>,
+        /// most quote a bid may spend across all of its fills
+        max_quote_spend: Option<AssetAmount>,
     },
     Limit {
         base_asset_id: AssetId,
@@ -71,7 +145,9 @@ pub enum OrderRequest {
         side: OrderSide,
         price: AssetPrice,
         quantity: AssetAmount,
-        local_timestamp: SystemTime,
+        client_order_id: ClientOrderId,
+        time_in_force: TimeInForce,
+        post_only: bool,
     },
     Update {
         base_asset_id: AssetId,
@@ -80,14 +156,12 @@ pub enum OrderRequest {
         side: OrderSide,
         price: AssetPrice,
         quantity: AssetAmount,
-        local_timestamp: SystemTime,
     },
     Cancel {
         base_asset_id: AssetId,
         quote_asset_id: AssetId,
         order_id: OrderId,
         side: OrderSide,
-        local_timestamp: SystemTime,
     },
 }
 
@@ -99,23 +173,26 @@ pub enum Success {
         price: u64,
         quantity: u64,
         order_type: OrderType,
-        timestamp: SystemTime,
+        client_order_id: ClientOrderId,
+        sequence_number: SequenceNumber,
     },
     Filled {
         order_id: u64,
         side: OrderSide,
         order_type: OrderType,
+        liquidity: Liquidity,
         price: u64,
         quantity: u64,
-        timestamp: SystemTime,
+        sequence_number: SequenceNumber,
     },
     PartiallyFilled {
         order_id: u64,
         side: OrderSide,
         order_type: OrderType,
+        liquidity: Liquidity,
         price: u64,
         quantity: u64,
-        timestamp: SystemTime,
+        sequence_number: SequenceNumber,
     },
     Updated {
         order_id: u64,
@@ -124,14 +201,15 @@ pub enum Success {
         previous_quantity: u64,
         price: u64,
         quantity: u64,
-        timestamp: SystemTime,
+        sequence_number: SequenceNumber,
     },
     Cancelled {
         order_id: u64,
         side: OrderSide,
+        order_type: OrderType,
         price: u64,
         quantity: u64,
-        timestamp: SystemTime,
+        sequence_number: SequenceNumber,
     },
 }
 
@@ -141,18 +219,82 @@ pub enum Failed {
     DuplicateOrderID(u64),
     NoMatch(u64),
     OrderNotFound(u64),
+    PostOnlyWouldTake,
+    FillOrKillUnfilled,
 }
 
 pub type OrderProcessingResult = Vec<Result<Success, Failed>>;
 
-#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct Depth {
     pub price: u64,
     pub quantity: u64,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct OrderbookDepth {
     pub bids: Vec<Depth>,
     pub asks: Vec<Depth>,
 }
+
+impl OrderbookDepth {
+    /// Returns the price levels which changed since the previous depth, a removed level has a zero quantity
+    pub fn diff(&self, previous: &OrderbookDepth) -> OrderbookDepth {
+        OrderbookDepth {
+            bids: diff_levels(&self.bids, &previous.bids),
+            asks: diff_levels(&self.asks, &previous.asks),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+}
+
+/// A fill between an incoming order and a resting order, numbered in execution order within its book
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct Trade {
+    pub trade_id: u64,
+    pub taker_side: u64,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+/// A trade on the persisted tape of a book, tagged with the block it was executed in
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TradeRecord {
+    pub trade_id: u64,
+    pub taker_side: u64,
+    pub price: u64,
+    pub quantity: u64,
+    pub block_number: u64,
+}
+
+impl TradeRecord {
+    pub fn new(trade: &Trade, block_number: u64) -> Self {
+        TradeRecord {
+            trade_id: trade.trade_id,
+            taker_side: trade.taker_side,
+            price: trade.price,
+            quantity: trade.quantity,
+            block_number,
+        }
+    }
+}
+
+fn diff_levels(current: &[Depth], previous: &[Depth]) -> Vec<Depth> {
+    let mut levels: BTreeMap<u64, u64> = previous.iter().map(|depth| (depth.price, 0)).collect();
+    for depth in current {
+        levels.insert(depth.price, depth.quantity);
+    }
+    levels
+        .into_iter()
+        .filter(|(price, quantity)| {
+            previous
+                .iter()
+                .find(|depth| depth.price == *price)
+                .map_or(true, |depth| depth.quantity != *quantity)
+        })
+        .map(|(price, quantity)| Depth { price, quantity })
+        .collect()
+}
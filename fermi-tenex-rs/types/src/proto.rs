@@ -12,12 +12,18 @@ pub use services::{
     BlockInfoResponse,
     BlockRequest,
     BlockResponse,
+    CatchupStateChunk,
+    CatchupStateRequest,
     Empty,
+    ExecutedTransactionResponse,
     FaucetAirdropRequest,
     FaucetAirdropResponse,
     LatestBlockInfoRequest,
+    ListCatchupStatesRequest,
+    ListCatchupStatesResponse,
     MetricsRequest,
     MetricsResponse,
+    SubmitTransactionAndWaitRequest,
 };
 
 #[path = "generated/transaction.rs"]
@@ -1,20 +1,27 @@
 // fermi
 use crate::{
-    block::{Block, BlockInfo, BlockNumber},
-    order_book::OrderbookDepth,
+    account::AccountPubKey,
+    block::{Block, BlockDigest, BlockInfo, BlockNumber},
+    error::GDEXError,
+    order_book::{OrderbookDepth, Trade, TradeRecord},
+    transaction::{ControllerType, TransactionDigest},
 };
 // mysten
+use fastcrypto::DIGEST_LEN;
 use mysten_store::{
     reopen, rocks,
     rocks::{open_cf, DBMap, TypedStoreError},
     traits::Map,
 };
+use narwhal_executor::ExecutionIndices;
+use narwhal_types::CertificateDigestProto;
 // external
+use blake2::digest::Update;
 use eyre::Result;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     cmp::Eq,
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
     hash::Hash,
 };
 use tokio::sync::{
@@ -166,6 +173,17 @@ where
             .expect("Failed to receive reply to WriteAll command from store")
     }
 
+    /// Writes all the key-value pairs without waiting for the result, for callers outside of an async context
+    pub fn try_write_all(&self, key_value_pairs: impl IntoIterator<Item = (Key, Value)>) {
+        let (sender, _receiver) = oneshot::channel();
+        if let Err(e) = self
+            .channel
+            .try_send(StoreCommand::WriteAll(key_value_pairs.into_iter().collect(), sender))
+        {
+            panic!("Failed to send WriteAll command to store: {e}");
+        }
+    }
+
     pub async fn remove(&self, key: Key) {
         if let Err(e) = self.channel.send(StoreCommand::Delete(key)).await {
             panic!("Failed to send Delete command to store: {e}");
@@ -189,6 +207,17 @@ where
             .expect("Failed to receive reply to RemoveAll command from store")
     }
 
+    /// Removes all the keys without waiting for the result, for callers outside of an async context
+    pub fn try_remove_all(&self, keys: impl IntoIterator<Item = Key>) {
+        let (sender, _receiver) = oneshot::channel();
+        if let Err(e) = self
+            .channel
+            .try_send(StoreCommand::DeleteAll(keys.into_iter().collect(), sender))
+        {
+            panic!("Failed to send DeleteAll command to store: {e}");
+        }
+    }
+
     pub async fn read(&self, key: Key) -> StoreResult<Option<Value>> {
         let (sender, receiver) = oneshot::channel();
         if let Err(e) = self.channel.send(StoreCommand::Read(key, sender)).await {
@@ -243,23 +272,55 @@ pub struct CriticalPathStore {
     pub last_block_info_store: Store<BlockNumber, BlockInfo>,
     pub block_store: Store<BlockNumber, Block>,
     pub block_info_store: Store<BlockNumber, BlockInfo>,
+    // narwhal execution indices reached at the end of each block
+    pub execution_indices_store: Store<BlockNumber, ExecutionIndices>,
+    // controller snapshots taken every CATCHUP_STATE_FREQUENCY blocks or on request
+    pub catchup_state_store: Store<BlockNumber, CatchupState>,
+    // manifests of the stored controller snapshots, the index of catchup_state_store
+    pub catchup_manifest_store: Store<BlockNumber, CatchupManifest>,
+    // location of the first execution of each transaction
+    pub transaction_location_store: Store<TransactionDigest, TransactionLocation>,
 }
 
 impl CriticalPathStore {
     const BLOCKS_CF: &'static str = "blocks";
     const BLOCK_INFO_CF: &'static str = "block_info";
     const LAST_BLOCK_CF: &'static str = "last_block";
+    const EXECUTION_INDICES_CF: &'static str = "execution_indices";
+    const CATCHUP_STATE_CF: &'static str = "catchup_state";
+    const CATCHUP_MANIFEST_CF: &'static str = "catchup_manifest";
+    const TRANSACTION_LOCATION_CF: &'static str = "transaction_location";
     pub fn reopen<Path: AsRef<std::path::Path>>(store_path: Path) -> Self {
         let rocksdb = open_cf(
             store_path,
             None,
-            &[Self::BLOCKS_CF, Self::BLOCK_INFO_CF, Self::LAST_BLOCK_CF],
+            &[
+                Self::BLOCKS_CF,
+                Self::BLOCK_INFO_CF,
+                Self::LAST_BLOCK_CF,
+                Self::EXECUTION_INDICES_CF,
+                Self::CATCHUP_STATE_CF,
+                Self::CATCHUP_MANIFEST_CF,
+                Self::TRANSACTION_LOCATION_CF,
+            ],
         )
         .expect("Cannot open database");
-        let (block_map, block_info_map, last_block_map) = reopen!(&rocksdb,
+        let (
+            block_map,
+            block_info_map,
+            last_block_map,
+            execution_indices_map,
+            catchup_state_map,
+            catchup_manifest_map,
+            transaction_location_map,
+        ) = reopen!(&rocksdb,
             Self::BLOCKS_CF;<BlockNumber, Block>,
             Self::BLOCK_INFO_CF;<BlockNumber, BlockInfo>,
-            Self::LAST_BLOCK_CF;<u64, BlockInfo>
+            Self::LAST_BLOCK_CF;<u64, BlockInfo>,
+            Self::EXECUTION_INDICES_CF;<BlockNumber, ExecutionIndices>,
+            Self::CATCHUP_STATE_CF;<BlockNumber, CatchupState>,
+            Self::CATCHUP_MANIFEST_CF;<BlockNumber, CatchupManifest>,
+            Self::TRANSACTION_LOCATION_CF;<TransactionDigest, TransactionLocation>
         );
 
         let last_block_info = last_block_map.get(&0_u64);
@@ -267,14 +328,37 @@ impl CriticalPathStore {
         let last_block_info_store = Store::new(last_block_map);
         let block_store = Store::new(block_map);
         let block_info_store = Store::new(block_info_map);
+        let execution_indices_store = Store::new(execution_indices_map);
+        let catchup_state_store = Store::new(catchup_state_map);
+        let catchup_manifest_store = Store::new(catchup_manifest_map);
+        let transaction_location_store = Store::new(transaction_location_map);
 
         Self {
             last_block_info,
             last_block_info_store,
             block_store,
             block_info_store,
+            execution_indices_store,
+            catchup_state_store,
+            catchup_manifest_store,
+            transaction_location_store,
         }
     }
+
+    /// Stores a catchup state and indexes it by its manifest
+    pub async fn write_catchup_state(&self, block_number: BlockNumber, catchup_state: CatchupState) {
+        let manifest = catchup_state.manifest.clone();
+        self.catchup_state_store.write(block_number, catchup_state).await;
+        self.catchup_manifest_store.write(block_number, manifest).await;
+    }
+
+    /// Returns the manifests of the stored catchup states, latest first
+    pub async fn get_catchup_manifests(&self) -> Vec<(BlockNumber, CatchupManifest)> {
+        let mut manifests: Vec<(BlockNumber, CatchupManifest)> =
+            self.catchup_manifest_store.iter(None).await.into_iter().collect();
+        manifests.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+        manifests
+    }
 }
 
 // TODO - Find intelligent way to make the JSON RPC Store modular across controllers
@@ -284,39 +368,198 @@ impl CriticalPathStore {
 pub struct RPCStoreHandle {
     pub rpc_store: RPCStore,
 }
+
 // catchup state
+
+/// Version of the catchup state layout, bumped whenever the serialized form of a controller changes
+pub const CATCHUP_STATE_VERSION: u32 = 2;
+
+pub type StateRoot = [u8; DIGEST_LEN];
+
+/// Size of the chunks a serialized catchup state is split into when streamed to a syncing node
+pub const CATCHUP_STATE_CHUNK_SIZE: usize = 1 << 20;
+
+/// Describes a catchup state without its payload, so that snapshots can be verified and compared
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CatchupManifest {
+    pub version: u32,
+    pub block_number: BlockNumber,
+    pub block_digest: BlockDigest,
+    // blake2b-256 digest of each serialized controller
+    pub controller_roots: BTreeMap<ControllerType, StateRoot>,
+}
+
+impl CatchupManifest {
+    pub fn is_compatible(&self) -> bool {
+        self.version == CATCHUP_STATE_VERSION
+    }
+
+    /// Digest which commits to the version, block and every controller root
+    pub fn root(&self) -> StateRoot {
+        fastcrypto::blake2b_256(|hasher| {
+            hasher.update(self.version.to_le_bytes());
+            hasher.update(self.block_number.to_le_bytes());
+            hasher.update(CertificateDigestProto::from(self.block_digest).digest);
+            for (controller_type, controller_root) in &self.controller_roots {
+                hasher.update((*controller_type as i32).to_le_bytes());
+                hasher.update(controller_root);
+            }
+        })
+    }
+
+    /// Returns the controllers whose state differs between the two manifests
+    pub fn diff(&self, other: &CatchupManifest) -> Vec<ControllerType> {
+        let controller_types: BTreeSet<&ControllerType> = self
+            .controller_roots
+            .keys()
+            .chain(other.controller_roots.keys())
+            .collect();
+        controller_types
+            .into_iter()
+            .filter(|controller_type| {
+                self.controller_roots.get(controller_type) != other.controller_roots.get(controller_type)
+            })
+            .copied()
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CatchupState {
-    pub state: Vec<Vec<u8>>,
+    pub manifest: CatchupManifest,
+    pub controller_states: BTreeMap<ControllerType, Vec<u8>>,
+}
+
+impl CatchupState {
+    pub fn new(block_number: BlockNumber, block_digest: BlockDigest) -> Self {
+        CatchupState {
+            manifest: CatchupManifest {
+                version: CATCHUP_STATE_VERSION,
+                block_number,
+                block_digest,
+                controller_roots: BTreeMap::new(),
+            },
+            controller_states: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert_controller_state(&mut self, controller_type: ControllerType, state: Vec<u8>) {
+        self.manifest
+            .controller_roots
+            .insert(controller_type, compute_state_root(&state));
+        self.controller_states.insert(controller_type, state);
+    }
+
+    /// Returns the state of a controller after checking the snapshot version and the controller root
+    pub fn get_controller_state(&self, controller_type: ControllerType) -> Result<&[u8], GDEXError> {
+        if !self.manifest.is_compatible() {
+            return Err(GDEXError::CatchupStateVersion);
+        }
+        let state = self
+            .controller_states
+            .get(&controller_type)
+            .ok_or(GDEXError::CatchupStateVerification)?;
+        match self.manifest.controller_roots.get(&controller_type) {
+            Some(controller_root) if *controller_root == compute_state_root(state) => Ok(state),
+            _ => Err(GDEXError::CatchupStateVerification),
+        }
+    }
+
+    /// Checks that every controller state matches the root recorded in the manifest
+    pub fn verify(&self) -> Result<(), GDEXError> {
+        if self.controller_states.len() != self.manifest.controller_roots.len() {
+            return Err(GDEXError::CatchupStateVerification);
+        }
+        for controller_type in self.controller_states.keys() {
+            self.get_controller_state(*controller_type)?;
+        }
+        Ok(())
+    }
+}
+
+fn compute_state_root(state: &[u8]) -> StateRoot {
+    fastcrypto::blake2b_256(|hasher| hasher.update(state))
+}
+
+/// Position of an executed transaction inside the block store
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TransactionLocation {
+    pub block_number: BlockNumber,
+    pub position: u64,
 }
 
 pub struct RPCStore {
     pub latest_orderbook_depth_store: Store<String, OrderbookDepth>,
+    // most recent trades of each orderbook, oldest first
+    pub latest_trades_store: Store<String, Vec<Trade>>,
+    // (orderbook, trade id) -> trade, bounded to the most recent trades of each orderbook
+    pub trade_history_store: Store<(String, u64), TradeRecord>,
     // catchup store
     pub catchup_state_store: Store<BlockNumber, CatchupState>,
+    // transaction indices
+    pub transaction_location_store: Store<TransactionDigest, TransactionLocation>,
+    // (sender, sequence number of the transaction for that sender) -> digest
+    pub account_transactions_store: Store<(AccountPubKey, u64), TransactionDigest>,
+    pub account_transaction_count_store: Store<AccountPubKey, u64>,
 }
 
 impl RPCStore {
     const LAST_ORDERBOOK_DEPTH_CF: &'static str = "last_orderbook_depth";
+    const LATEST_TRADES_CF: &'static str = "latest_trades";
+    const TRADE_HISTORY_CF: &'static str = "trade_history";
     const CATCHUP_STATE_CF: &'static str = "catchup_state";
+    const TRANSACTION_LOCATION_CF: &'static str = "transaction_location";
+    const ACCOUNT_TRANSACTIONS_CF: &'static str = "account_transactions";
+    const ACCOUNT_TRANSACTION_COUNT_CF: &'static str = "account_transaction_count";
 
     pub fn reopen<Path: AsRef<std::path::Path>>(store_path: Path) -> Self {
         let rocksdb = open_cf(
             store_path,
             None,
-            &[Self::LAST_ORDERBOOK_DEPTH_CF, Self::CATCHUP_STATE_CF],
+            &[
+                Self::LAST_ORDERBOOK_DEPTH_CF,
+                Self::LATEST_TRADES_CF,
+                Self::TRADE_HISTORY_CF,
+                Self::CATCHUP_STATE_CF,
+                Self::TRANSACTION_LOCATION_CF,
+                Self::ACCOUNT_TRANSACTIONS_CF,
+                Self::ACCOUNT_TRANSACTION_COUNT_CF,
+            ],
         )
         .expect("Cannot open database");
-        let (orderbook_depth_map, catchup_state_map) = reopen!(&rocksdb,
+        let (
+            orderbook_depth_map,
+            latest_trades_map,
+            trade_history_map,
+            catchup_state_map,
+            transaction_location_map,
+            account_transactions_map,
+            account_transaction_count_map,
+        ) = reopen!(&rocksdb,
             Self::LAST_ORDERBOOK_DEPTH_CF;<String, OrderbookDepth>,
-            Self::CATCHUP_STATE_CF;<BlockNumber, CatchupState>
+            Self::LATEST_TRADES_CF;<String, Vec<Trade>>,
+            Self::TRADE_HISTORY_CF;<(String, u64), TradeRecord>,
+            Self::CATCHUP_STATE_CF;<BlockNumber, CatchupState>,
+            Self::TRANSACTION_LOCATION_CF;<TransactionDigest, TransactionLocation>,
+            Self::ACCOUNT_TRANSACTIONS_CF;<(AccountPubKey, u64), TransactionDigest>,
+            Self::ACCOUNT_TRANSACTION_COUNT_CF;<AccountPubKey, u64>
         );
         let latest_orderbook_depth_store = Store::new(orderbook_depth_map);
+        let latest_trades_store = Store::new(latest_trades_map);
+        let trade_history_store = Store::new(trade_history_map);
         let catchup_state_store = Store::new(catchup_state_map);
+        let transaction_location_store = Store::new(transaction_location_map);
+        let account_transactions_store = Store::new(account_transactions_map);
+        let account_transaction_count_store = Store::new(account_transaction_count_map);
 
         Self {
             latest_orderbook_depth_store,
+            latest_trades_store,
+            trade_history_store,
             catchup_state_store,
+            transaction_location_store,
+            account_transactions_store,
+            account_transaction_count_store,
         }
     }
 }
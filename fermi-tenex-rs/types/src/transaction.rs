@@ -4,7 +4,8 @@ use crate::{
     account::{AccountKeyPair, AccountPubKey, AccountSignature},
     crypto::ToFromBytes,
     error::GDEXError,
-    order_book::OrderSide,
+    event::{EventDecoder, QueriedEvent},
+    order_book::{OrderSide, TimeInForce},
     serialization::{Base64, Encoding},
     utils,
 };
@@ -30,10 +31,39 @@ pub const PROTO_VERSION: Version = Version {
 };
 
 pub const DEFAULT_TRANSACTION_FEE: u64 = 1000;
+// lowest fee a transaction may pay per request, so that sequencing a transaction is never free
+pub const MIN_TRANSACTION_FEE: u64 = DEFAULT_TRANSACTION_FEE;
+// most requests a single transaction may carry
+pub const MAX_TRANSACTION_REQUESTS: usize = 32;
+
+// ENUMS
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[repr(i32)]
+pub enum ControllerType {
+    Bank = 0,
+    Stake = 1,
+    Spot = 2,
+    Consensus = 3,
+    Futures = 4,
+}
+
+impl ControllerType {
+    pub fn from_i32(value: i32) -> Result<Self, GDEXError> {
+        match value {
+            0 => Ok(ControllerType::Bank),
+            1 => Ok(ControllerType::Stake),
+            2 => Ok(ControllerType::Spot),
+            3 => Ok(ControllerType::Consensus),
+            4 => Ok(ControllerType::Futures),
+            _ => Err(GDEXError::DeserializationError),
+        }
+    }
+}
 
 // DIGEST TYPES
 
-#[derive(Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct TransactionDigest([u8; DIGEST_LEN]);
 
 impl fmt::Display for TransactionDigest {
@@ -187,6 +217,27 @@ impl Transaction {
             recent_block_hash: CertificateDigestProto::from(recent_block_hash).digest,
             fee: DEFAULT_TRANSACTION_FEE,
             request_bytes: Bytes::from(serialize_protobuf(request)),
+            nonce: 0,
+            requests: Vec::new(),
+        }
+    }
+
+    /// Creates a transaction which executes the requests in order, either all of them take effect or none do
+    pub fn new_batch(
+        sender: &AccountPubKey,
+        recent_block_hash: CertificateDigest,
+        requests: Vec<ControllerRequest>,
+    ) -> Self {
+        Transaction {
+            version: Some(PROTO_VERSION),
+            sender: Bytes::from(sender.as_ref().to_vec()),
+            target_controller: 0,
+            request_type: 0,
+            recent_block_hash: CertificateDigestProto::from(recent_block_hash).digest,
+            fee: DEFAULT_TRANSACTION_FEE,
+            request_bytes: Bytes::new(),
+            nonce: 0,
+            requests,
         }
     }
 
@@ -194,6 +245,66 @@ impl Transaction {
         self.fee = fee;
     }
 
+    pub fn is_batch(&self) -> bool {
+        !self.requests.is_empty()
+    }
+
+    pub fn get_request_count(&self) -> usize {
+        if self.is_batch() {
+            self.requests.len()
+        } else {
+            1
+        }
+    }
+
+    pub fn validate_request_count(&self) -> Result<(), GDEXError> {
+        if self.get_request_count() > MAX_TRANSACTION_REQUESTS {
+            return Err(GDEXError::TransactionRequestLimit);
+        }
+        Ok(())
+    }
+
+    pub fn validate_fee(&self) -> Result<(), GDEXError> {
+        if self.fee < MIN_TRANSACTION_FEE {
+            return Err(GDEXError::TransactionFeeTooLow);
+        }
+        Ok(())
+    }
+
+    /// Returns the fee charged for the transaction, which pays the fee once for each request
+    pub fn get_total_fee(&self) -> Result<u64, GDEXError> {
+        self.fee
+            .checked_mul(self.get_request_count() as u64)
+            .ok_or(GDEXError::Conversion)
+    }
+
+    /// Splits the transaction into single request transactions of the same sender, in execution order
+    pub fn get_requests(&self) -> Result<Vec<Transaction>, GDEXError> {
+        self.validate_request_count()?;
+        if !self.is_batch() {
+            return Ok(vec![self.clone()]);
+        }
+        Ok(self
+            .requests
+            .iter()
+            .map(|request| Transaction {
+                version: self.version.clone(),
+                sender: self.sender.clone(),
+                target_controller: request.target_controller,
+                request_type: request.request_type,
+                recent_block_hash: self.recent_block_hash.clone(),
+                fee: self.fee,
+                request_bytes: request.request_bytes.clone(),
+                nonce: self.nonce,
+                requests: Vec::new(),
+            })
+            .collect())
+    }
+
+    pub fn set_nonce(&mut self, nonce: u64) {
+        self.nonce = nonce;
+    }
+
     pub fn get_sender(&self) -> Result<AccountPubKey, GDEXError> {
         AccountPubKey::from_bytes(&self.sender).map_err(|_e| GDEXError::DeserializationError)
     }
@@ -230,6 +341,18 @@ impl Hash for Transaction {
     }
 }
 
+// CONTROLLER REQUEST
+
+impl ControllerRequest {
+    pub fn new<T: Request + Message + std::default::Default>(request: &T) -> Self {
+        ControllerRequest {
+            target_controller: T::get_controller_id(),
+            request_type: T::get_request_type_id(),
+            request_bytes: Bytes::from(serialize_protobuf(request)),
+        }
+    }
+}
+
 // EXECUTION RESULT
 
 pub type ExecutionEvents = Vec<ExecutionEvent>;
@@ -259,21 +382,25 @@ pub struct ExecutedTransaction {
 pub struct QueriedTransaction {
     pub executed_transaction: ExecutedTransaction,
     pub transaction_id: String,
+    // events of the transaction decoded next to their raw bytes
+    pub events: Vec<QueriedEvent>,
 }
 
-impl From<ExecutedTransaction> for QueriedTransaction {
-    fn from(executed_transaction: ExecutedTransaction) -> Self {
-        if let Ok(transaction) = executed_transaction.signed_transaction.get_transaction() {
-            let transaction_digest = transaction.digest().get_array();
-            QueriedTransaction {
-                executed_transaction,
-                transaction_id: utils::encode_bytes_hex(transaction_digest),
-            }
-        } else {
-            QueriedTransaction {
-                executed_transaction,
-                transaction_id: "".to_string(),
-            }
+impl QueriedTransaction {
+    pub fn new(executed_transaction: ExecutedTransaction, decoder: &dyn EventDecoder) -> Self {
+        let transaction_id = match executed_transaction.signed_transaction.get_transaction() {
+            Ok(transaction) => utils::encode_bytes_hex(transaction.digest().get_array()),
+            Err(_) => "".to_string(),
+        };
+        let events = executed_transaction
+            .events
+            .iter()
+            .map(|event| QueriedEvent::new(event, decoder))
+            .collect();
+        QueriedTransaction {
+            executed_transaction,
+            transaction_id,
+            events,
         }
     }
 }
@@ -325,3 +452,12 @@ pub fn parse_order_side(side: u64) -> Result<OrderSide, GDEXError> {
         _ => Err(GDEXError::DeserializationError),
     }
 }
+
+pub fn parse_time_in_force(time_in_force: u64) -> Result<TimeInForce, GDEXError> {
+    match time_in_force {
+        0 => Ok(TimeInForce::GoodTilCancelled),
+        1 => Ok(TimeInForce::ImmediateOrCancel),
+        2 => Ok(TimeInForce::FillOrKill),
+        _ => Err(GDEXError::DeserializationError),
+    }
+}